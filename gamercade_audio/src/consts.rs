@@ -1,9 +1,12 @@
 /// How many channels are available for a song.
 pub const SONG_TRACK_CHANNELS: usize = 8;
 
-/// How many channels are available for sfx.
+/// How many channels are available for sfx, unless the rom sets its own count.
 pub const SFX_CHANNELS: usize = 8;
 
+/// The most sfx channels a rom can have.
+pub const SFX_CHANNELS_MAX: usize = 16;
+
 /// How many effects are available.
 pub const EFFECT_COUNT: usize = 3;
 
@@ -44,3 +47,16 @@ pub const SFX_MAX_COUNT: usize = 256;
 pub const WAVETABLE_MAX_LENGTH: usize = 2048;
 
 pub const DEFAULT_BPM: f32 = 120.0;
+
+/// The frequency of A4, before the tuning reference is changed.
+pub const DEFAULT_TUNING_REFERENCE: f32 = 440.0;
+
+/// The lowest and highest frequencies A4 can be tuned to.
+pub const TUNING_REFERENCE_MIN: f32 = 400.0;
+pub const TUNING_REFERENCE_MAX: f32 = 480.0;
+
+/// How far every note can be transposed up or down, in semitones.
+pub const TRANSPOSE_MAX_SEMITONES: i32 = 24;
+
+/// The most swing a song can have, delaying every other step by 75% of a step.
+pub const SWING_MAX: f32 = 0.75;
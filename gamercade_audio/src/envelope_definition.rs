@@ -34,8 +34,56 @@ impl EnvelopeValue {
     }
 }
 
+/// The default coefficient used by exponential envelope curves.
+/// Closely matches the original fixed exponential ramp.
+pub const ENVELOPE_DEFAULT_CURVE_COEFFICIENT: f32 = 6.9;
+
+/// The shape of each ramp within an envelope.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum EnvelopeCurve {
+    /// Travels to the target at a constant rate.
+    Linear,
+
+    /// Moves quickly at first and slows down as it reaches the target.
+    /// Larger coefficients result in a snappier curve.
+    Exponential(f32),
+
+    /// Moves slowly at first and speeds up as it reaches the target.
+    Logarithmic,
+}
+
+impl Default for EnvelopeCurve {
+    fn default() -> Self {
+        Self::Exponential(ENVELOPE_DEFAULT_CURVE_COEFFICIENT)
+    }
+}
+
+impl EnvelopeCurve {
+    /// Shapes the progress of a ramp, where progress is between 0.0 and 1.0.
+    /// Returns the fraction of the distance travelled to the target.
+    pub fn apply(self, progress: f32) -> f32 {
+        let progress = progress.clamp(0.0, 1.0);
+
+        match self {
+            EnvelopeCurve::Linear => progress,
+            EnvelopeCurve::Exponential(coefficient) => exponential(progress, coefficient),
+            EnvelopeCurve::Logarithmic => {
+                1.0 - exponential(1.0 - progress, ENVELOPE_DEFAULT_CURVE_COEFFICIENT)
+            }
+        }
+    }
+}
+
+fn exponential(progress: f32, coefficient: f32) -> f32 {
+    if coefficient <= f32::EPSILON {
+        progress
+    } else {
+        (1.0 - (-coefficient * progress).exp()) / (1.0 - (-coefficient).exp())
+    }
+}
+
 /// Definition of an Envelope. Controls the ADSR and volume levels.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct EnvelopeDefinition {
     /// The max level
     pub total_level: EnvelopeValue,
@@ -54,6 +102,10 @@ pub struct EnvelopeDefinition {
 
     /// The the length of decay after the key is released.
     pub release_time: EnvelopeValue,
+
+    /// The shape of each of the ramps.
+    #[serde(default)]
+    pub curve: EnvelopeCurve,
 }
 
 impl Default for EnvelopeDefinition {
@@ -66,6 +118,7 @@ impl Default for EnvelopeDefinition {
             decay_attack_time: EnvelopeValue::zero(),
             decay_sustain_time: EnvelopeValue::max(),
             release_time: EnvelopeValue::zero(),
+            curve: EnvelopeCurve::default(),
         }
     }
 }
@@ -80,6 +133,7 @@ impl EnvelopeDefinition {
             decay_attack_time: EnvelopeValue::max(),
             decay_sustain_time: EnvelopeValue::max(),
             release_time: EnvelopeValue::max(),
+            curve: EnvelopeCurve::default(),
         }
     }
 
@@ -93,6 +147,7 @@ impl EnvelopeDefinition {
             decay_attack_time: EnvelopeValue::zero(),
             decay_sustain_time: EnvelopeValue::zero(),
             release_time: EnvelopeValue::zero(),
+            curve: EnvelopeCurve::default(),
         }
     }
 
@@ -106,6 +161,34 @@ impl EnvelopeDefinition {
             decay_attack_time: EnvelopeValue::zero(),
             decay_sustain_time: EnvelopeValue(64),
             release_time: EnvelopeValue(64),
+            curve: EnvelopeCurve::Exponential(4.0),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve_endpoints() {
+        let curves = [
+            EnvelopeCurve::Linear,
+            EnvelopeCurve::Exponential(ENVELOPE_DEFAULT_CURVE_COEFFICIENT),
+            EnvelopeCurve::Exponential(0.0),
+            EnvelopeCurve::Logarithmic,
+        ];
+
+        curves.into_iter().for_each(|curve| {
+            assert!(curve.apply(0.0).abs() < 0.0001);
+            assert!((curve.apply(1.0) - 1.0).abs() < 0.0001);
+        });
+    }
+
+    #[test]
+    fn test_curve_shapes() {
+        assert!(EnvelopeCurve::Exponential(4.0).apply(0.5) > 0.5);
+        assert!(EnvelopeCurve::Logarithmic.apply(0.5) < 0.5);
+        assert!((EnvelopeCurve::Linear.apply(0.5) - 0.5).abs() < f32::EPSILON);
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// The lowest cutoff frequency a filter can be set to.
+pub const FILTER_MIN_CUTOFF: f32 = 20.0;
+
+/// The highest cutoff frequency a filter can be set to.
+pub const FILTER_MAX_CUTOFF: f32 = 20_000.0;
+
+/// How many octaves the cutoff will travel when the envelope amount is at 1.0.
+pub const FILTER_ENVELOPE_OCTAVES: f32 = 8.0;
+
+/// Definition of a resonant low-pass filter applied to an instrument.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FilterDefinition {
+    /// The cutoff frequency in Hz.
+    pub cutoff: f32,
+
+    /// The amount of resonance, between 0.0 and 1.0.
+    pub resonance: f32,
+
+    /// How much the amplitude envelope moves the cutoff, between -1.0 and 1.0.
+    pub envelope_amount: f32,
+}
+
+impl Default for FilterDefinition {
+    fn default() -> Self {
+        Self {
+            cutoff: FILTER_MAX_CUTOFF,
+            resonance: 0.0,
+            envelope_amount: 0.0,
+        }
+    }
+}
+
+impl FilterDefinition {
+    /// Returns the cutoff after applying the envelope routing.
+    pub fn modulated_cutoff(&self, envelope: f32) -> f32 {
+        let octaves = self.envelope_amount * envelope * FILTER_ENVELOPE_OCTAVES;
+        (self.cutoff * octaves.exp2()).clamp(FILTER_MIN_CUTOFF, FILTER_MAX_CUTOFF)
+    }
+}
@@ -20,7 +20,7 @@ impl Algorithm {
     }
 
     pub const fn max() -> u8 {
-        11
+        13
     }
 
     pub fn get_definition(self) -> &'static AlgorithmDefinition {
@@ -33,6 +33,7 @@ impl Algorithm {
                     ModulatedBy::Single(1),
                     ModulatedBy::Single(2),
                 ],
+                ring_modulated: NO_RING_MODULATION,
             },
 
             // [A + B] > C > D
@@ -43,6 +44,7 @@ impl Algorithm {
                     ModulatedBy::Double(0, 1),
                     ModulatedBy::Single(2),
                 ],
+                ring_modulated: NO_RING_MODULATION,
             },
 
             // [[A > B] + C] > D
@@ -53,6 +55,7 @@ impl Algorithm {
                     ModulatedBy::None,
                     ModulatedBy::Double(1, 2),
                 ],
+                ring_modulated: NO_RING_MODULATION,
             },
 
             // [[A > B] + [A > C]] > D
@@ -63,6 +66,7 @@ impl Algorithm {
                     ModulatedBy::Single(0),
                     ModulatedBy::Double(1, 2),
                 ],
+                ring_modulated: NO_RING_MODULATION,
             },
 
             // [A + B + C] > D
@@ -73,6 +77,7 @@ impl Algorithm {
                     ModulatedBy::None,
                     ModulatedBy::Triple(0, 1, 2),
                 ],
+                ring_modulated: NO_RING_MODULATION,
             },
 
             // [A > B > C] + D
@@ -83,6 +88,7 @@ impl Algorithm {
                     ModulatedBy::Single(1),
                     ModulatedBy::None,
                 ],
+                ring_modulated: NO_RING_MODULATION,
             },
 
             // [A > B > C] + [A > B > D]
@@ -93,6 +99,7 @@ impl Algorithm {
                     ModulatedBy::Single(1),
                     ModulatedBy::Single(1),
                 ],
+                ring_modulated: NO_RING_MODULATION,
             },
 
             // [A > B] + [C > D]
@@ -103,6 +110,7 @@ impl Algorithm {
                     ModulatedBy::Single(0),
                     ModulatedBy::Single(1),
                 ],
+                ring_modulated: NO_RING_MODULATION,
             },
 
             // [A > B] + [A > C] + [A > D]
@@ -113,6 +121,7 @@ impl Algorithm {
                     ModulatedBy::Single(0),
                     ModulatedBy::Single(0),
                 ],
+                ring_modulated: NO_RING_MODULATION,
             },
 
             // [A > B] + [A > C] + D
@@ -123,26 +132,58 @@ impl Algorithm {
                     ModulatedBy::Single(0),
                     ModulatedBy::None,
                 ],
+                ring_modulated: NO_RING_MODULATION,
             },
 
             // [A > B] + C + D
             10 => &AlgorithmDefinition {
                 carriers: [false, true, true, true],
                 modulators: [ModulatedBy::Single(0), ModulatedBy::None, ModulatedBy::None],
+                ring_modulated: NO_RING_MODULATION,
             },
 
             // A + B + C + D
             11 => &AlgorithmDefinition {
                 carriers: [true, true, true, true],
                 modulators: [ModulatedBy::None, ModulatedBy::None, ModulatedBy::None],
+                ring_modulated: NO_RING_MODULATION,
+            },
+
+            // [A * B] + [C * D]
+            12 => &AlgorithmDefinition {
+                carriers: [false, true, false, true],
+                modulators: [
+                    ModulatedBy::Single(0),
+                    ModulatedBy::None,
+                    ModulatedBy::Single(2),
+                ],
+                ring_modulated: [true, false, true],
+            },
+
+            // [A > B > C] * D
+            13 => &AlgorithmDefinition {
+                carriers: [false, false, false, true],
+                modulators: [
+                    ModulatedBy::Single(0),
+                    ModulatedBy::Single(1),
+                    ModulatedBy::Single(2),
+                ],
+                ring_modulated: [false, false, true],
             },
             _ => panic!("invalid algorithm value"),
         }
     }
 }
 
+const NO_RING_MODULATION: [bool; OPERATOR_COUNT - 1] = [false; OPERATOR_COUNT - 1];
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AlgorithmDefinition {
     pub carriers: [bool; OPERATOR_COUNT],
     pub modulators: [ModulatedBy; OPERATOR_COUNT - 1],
+
+    /// If set, the operator's output is multiplied by its modulators,
+    /// instead of them modulating its phase.
+    #[serde(default)]
+    pub ring_modulated: [bool; OPERATOR_COUNT - 1],
 }
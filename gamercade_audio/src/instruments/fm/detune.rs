@@ -1,17 +1,15 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+/// Detunes an operator away from its frequency ratio, in cents.
+/// Spreading operators apart gives a wider, chorus-like sound.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Detune(pub i8);
 
 impl Detune {
     pub fn as_multiplier(self) -> f32 {
         assert!(Self::max() <= 100);
         assert!(Self::min() >= -100);
-        if self.0 >= 0 {
-            1.0 + ((self.0 as f32 / Self::max() as f32) * 0.059_463_095)
-        } else {
-            1.0 + ((self.0 as f32 / Self::max() as f32) * (1.0 - 0.943_874_3))
-        }
+        2.0_f32.powf(self.0 as f32 / 1200.0)
     }
 
     pub fn min() -> i8 {
@@ -22,3 +20,20 @@ impl Detune {
         100
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detune_in_cents() {
+        assert_eq!(Detune(0).as_multiplier(), 1.0);
+
+        // A full semitone in either direction.
+        assert!((Detune(100).as_multiplier() - 1.059_463_1).abs() < 0.000_01);
+        assert!((Detune(-100).as_multiplier() - 0.943_874_3).abs() < 0.000_01);
+
+        // An octave is 1200 cents, so 12 semitones up doubles the frequency.
+        assert!((Detune(100).as_multiplier().powi(12) - 2.0).abs() < 0.000_1);
+    }
+}
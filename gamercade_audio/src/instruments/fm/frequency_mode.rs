@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+use super::FrequencyMultiplier;
+
+/// How an operator picks its frequency.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FrequencyMode {
+    /// Follows the played note, scaled by the operator's frequency multiplier.
+    #[default]
+    Ratio,
+
+    /// Always plays at this frequency in Hz, ignoring the played note.
+    /// Useful for formants and noisy, inharmonic sounds.
+    Fixed(f32),
+}
+
+impl FrequencyMode {
+    pub fn min_fixed() -> f32 {
+        1.0
+    }
+
+    pub fn max_fixed() -> f32 {
+        10_000.0
+    }
+
+    /// Returns the operator's frequency for the played note's frequency.
+    pub fn frequency(self, multiplier: FrequencyMultiplier, note_frequency: f32) -> f32 {
+        match self {
+            Self::Ratio => multiplier.multiply(note_frequency),
+            Self::Fixed(frequency) => frequency.clamp(Self::min_fixed(), Self::max_fixed()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_ignores_note() {
+        let multiplier = FrequencyMultiplier { top: 3, bottom: 2 };
+
+        assert_eq!(FrequencyMode::Ratio.frequency(multiplier, 200.0), 300.0);
+        assert_eq!(
+            FrequencyMode::Fixed(50.0).frequency(multiplier, 200.0),
+            50.0
+        );
+        assert_eq!(
+            FrequencyMode::Fixed(50.0).frequency(multiplier, 800.0),
+            50.0
+        );
+        assert_eq!(
+            FrequencyMode::Fixed(f32::MAX).frequency(multiplier, 800.0),
+            FrequencyMode::max_fixed()
+        );
+    }
+}
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 // 5/2, 10/4,
 // There are ~255 unique fractions from 1-20
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct FrequencyMultiplier {
     pub top: u8,
     pub bottom: u8,
@@ -2,6 +2,7 @@ mod algorithm;
 mod detune;
 mod feedback;
 mod fm_waveform;
+mod frequency_mode;
 mod frequency_multiplier;
 mod operator_definition;
 mod patch_definition;
@@ -10,6 +11,7 @@ pub use algorithm::*;
 pub use detune::*;
 pub use feedback::*;
 pub use fm_waveform::*;
+pub use frequency_mode::*;
 pub use frequency_multiplier::*;
 pub use operator_definition::*;
 pub use patch_definition::*;
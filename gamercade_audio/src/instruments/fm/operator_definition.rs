@@ -1,11 +1,11 @@
 use serde::{Deserialize, Serialize};
 
-use super::FrequencyMultiplier;
+use super::{FrequencyMode, FrequencyMultiplier};
 use crate::{
     Detune, EnvelopeDefinition, EnvelopeValue, FMWaveform, IndexInterpolator, OPERATOR_COUNT,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OperatorDefinitionBundle {
     pub operators: [OperatorDefinition; OPERATOR_COUNT],
 }
@@ -20,6 +20,7 @@ impl Default for OperatorDefinitionBundle {
         let silent_modulator = OperatorDefinition {
             waveform: FMWaveform::Sine,
             frequency_multiplier: FrequencyMultiplier::one(),
+            frequency_mode: FrequencyMode::Ratio,
             detune: Detune(0),
             envlope_definition: modulators_envelope,
             interpolator: IndexInterpolator::default(),
@@ -33,6 +34,7 @@ impl Default for OperatorDefinitionBundle {
         let modulator = OperatorDefinition {
             waveform: FMWaveform::Sine,
             frequency_multiplier: FrequencyMultiplier::one(),
+            frequency_mode: FrequencyMode::Ratio,
             detune: Detune(0),
             envlope_definition: modulator_envelope,
             interpolator: IndexInterpolator::default(),
@@ -41,6 +43,7 @@ impl Default for OperatorDefinitionBundle {
         let carrier = OperatorDefinition {
             waveform: FMWaveform::Sine,
             frequency_multiplier: FrequencyMultiplier::one(),
+            frequency_mode: FrequencyMode::Ratio,
             detune: Detune(0),
             envlope_definition: EnvelopeDefinition::interesting(),
             interpolator: IndexInterpolator::default(),
@@ -57,10 +60,12 @@ impl Default for OperatorDefinitionBundle {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OperatorDefinition {
     pub waveform: FMWaveform,
     pub frequency_multiplier: FrequencyMultiplier,
+    #[serde(default)]
+    pub frequency_mode: FrequencyMode,
     pub detune: Detune,
     pub envlope_definition: EnvelopeDefinition,
     pub interpolator: IndexInterpolator,
@@ -4,7 +4,7 @@ use crate::OperatorDefinitionBundle;
 
 use super::{Algorithm, FeedbackLevel};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct PatchDefinition {
     pub operators: OperatorDefinitionBundle,
     pub algorithm: Algorithm,
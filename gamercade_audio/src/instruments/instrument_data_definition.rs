@@ -7,7 +7,7 @@ use crate::{InstrumentKind, PatchDefinition, SampleDefinition, WavetableDefiniti
 pub struct InstrumentId(pub usize);
 
 /// The types of instruments the tracker can use
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum InstrumentDataDefinition {
     Wavetable(WavetableDefinition),
     FMSynth(PatchDefinition),
@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    de_audio_data, ser_audio_data, EnvelopeDefinition, IndexInterpolator, LoopMode, SampleBitDepth,
+    de_audio_data, ser_audio_data, EnvelopeDefinition, FilterDefinition, IndexInterpolator,
+    LoopMode, SampleBitDepth,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SampleDefinition {
     #[serde(serialize_with = "ser_audio_data", deserialize_with = "de_audio_data")]
     pub data: Box<[SampleBitDepth]>,
@@ -13,6 +14,8 @@ pub struct SampleDefinition {
     pub envelope_definition: EnvelopeDefinition,
     pub interpolator: IndexInterpolator,
     pub loop_mode: LoopMode,
+    #[serde(default)]
+    pub filter: Option<FilterDefinition>,
 }
 
 impl Default for SampleDefinition {
@@ -24,6 +27,7 @@ impl Default for SampleDefinition {
             envelope_definition: Default::default(),
             interpolator: IndexInterpolator::default(),
             loop_mode: LoopMode::Oneshot,
+            filter: None,
         }
     }
 }
@@ -1,9 +1,11 @@
 mod wavetable_definition;
 mod wavetable_generator;
+mod wavetable_resampler;
 mod wavetable_waveform;
 
 pub use wavetable_definition::*;
 pub use wavetable_generator::*;
+pub use wavetable_resampler::*;
 pub use wavetable_waveform::*;
 
 pub type WavetableBitDepth = i16;
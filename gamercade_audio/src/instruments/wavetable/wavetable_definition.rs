@@ -1,14 +1,32 @@
 use serde::{Deserialize, Serialize};
 
-use super::WavetableBitDepth;
-use crate::{de_audio_data, ser_audio_data, EnvelopeDefinition, IndexInterpolator};
+use super::{resize_wavetable, WavetableBitDepth};
+use crate::{
+    de_audio_data, ser_audio_data, EnvelopeDefinition, FilterDefinition, IndexInterpolator, Lfo,
+};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WavetableDefinition {
     #[serde(serialize_with = "ser_audio_data", deserialize_with = "de_audio_data")]
     pub data: Box<[WavetableBitDepth]>,
     pub envelope: EnvelopeDefinition,
     pub interpolator: IndexInterpolator,
+    #[serde(default)]
+    pub filter: Option<FilterDefinition>,
+    #[serde(default)]
+    pub lfo: Option<Lfo>,
+
+    /// A second table to crossfade into. Empty if the wavetable doesn't morph.
+    #[serde(
+        default,
+        serialize_with = "ser_audio_data",
+        deserialize_with = "de_audio_data"
+    )]
+    pub morph_data: Box<[WavetableBitDepth]>,
+
+    /// How far to crossfade from data into morph_data, from 0.0 to 1.0.
+    #[serde(default)]
+    pub morph: f32,
 }
 
 impl Default for WavetableDefinition {
@@ -17,6 +35,10 @@ impl Default for WavetableDefinition {
             data: vec![0].into_boxed_slice(),
             envelope: Default::default(),
             interpolator: IndexInterpolator::default(),
+            filter: None,
+            lfo: None,
+            morph_data: Box::default(),
+            morph: 0.0,
         }
     }
 }
@@ -29,4 +51,13 @@ impl WavetableDefinition {
     pub fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
+
+    /// Returns a copy with the table resampled to a new size, keeping the shape of
+    /// the waveform. Everything else, including the morph table, is left as it is.
+    pub fn resample(&self, new_size: usize) -> Self {
+        Self {
+            data: resize_wavetable(&self.data, new_size),
+            ..self.clone()
+        }
+    }
 }
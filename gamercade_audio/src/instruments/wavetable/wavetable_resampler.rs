@@ -0,0 +1,196 @@
+use super::WavetableBitDepth;
+use crate::WAVETABLE_MAX_LENGTH;
+
+/// Which part of a recording should be turned into a wavetable.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum WavetableResampleSource {
+    /// Only the first full cycle of the waveform, found by its zero crossings.
+    #[default]
+    SingleCycle,
+
+    /// The entire recording is squeezed into a single table.
+    WholeClip,
+}
+
+/// Resamples mono audio into wavetable data. Samples are expected to be
+/// between -1.0 and 1.0. The table wraps around, so the last entry
+/// is interpolated back into the first.
+pub fn resample_wavetable(
+    samples: &[f32],
+    source: WavetableResampleSource,
+    size: usize,
+) -> Box<[WavetableBitDepth]> {
+    let samples = match source {
+        WavetableResampleSource::SingleCycle => find_single_cycle(samples),
+        WavetableResampleSource::WholeClip => samples,
+    };
+
+    let len = samples.len();
+
+    if len == 0 {
+        return vec![WavetableBitDepth::default(); size].into_boxed_slice();
+    }
+
+    (0..size)
+        .map(|index| {
+            let position = index as f32 * len as f32 / size as f32;
+            let from = position as usize;
+            let to = (from + 1) % len;
+            let fraction = position - from as f32;
+
+            let value = samples[from] + (samples[to] - samples[from]) * fraction;
+            (value.clamp(-1.0, 1.0) * WavetableBitDepth::MAX as f32) as WavetableBitDepth
+        })
+        .collect::<Vec<_>>()
+        .into_boxed_slice()
+}
+
+/// Resizes a table by interpolating between its entries, wrapping around like it does
+/// when played. The size is kept between 1 and the longest a table can be.
+pub fn resize_wavetable(data: &[WavetableBitDepth], size: usize) -> Box<[WavetableBitDepth]> {
+    let size = size.clamp(1, WAVETABLE_MAX_LENGTH);
+    let len = data.len();
+
+    if len == 0 {
+        return vec![WavetableBitDepth::default(); size].into_boxed_slice();
+    }
+
+    (0..size)
+        .map(|index| {
+            let position = index as f32 * len as f32 / size as f32;
+            let from = position as usize;
+            let to = (from + 1) % len;
+            let fraction = position - from as f32;
+
+            let (from, to) = (data[from] as f32, data[to] as f32);
+            (from + (to - from) * fraction).round() as WavetableBitDepth
+        })
+        .collect::<Vec<_>>()
+        .into_boxed_slice()
+}
+
+/// Rescales the table so its loudest entry reaches full amplitude.
+/// Silent tables are left as they are.
+pub fn normalize_wavetable(data: &mut [WavetableBitDepth]) {
+    let peak = data
+        .iter()
+        .map(|value| (*value as i32).abs())
+        .max()
+        .unwrap_or_default();
+
+    if peak == 0 {
+        return;
+    }
+
+    let scale = WavetableBitDepth::MAX as f32 / peak as f32;
+    data.iter_mut().for_each(|value| {
+        *value = (*value as f32 * scale)
+            .round()
+            .clamp(WavetableBitDepth::MIN as f32, WavetableBitDepth::MAX as f32)
+            as WavetableBitDepth
+    });
+}
+
+/// Returns the samples between the first two rising zero crossings,
+/// or all of them if a full cycle couldn't be found.
+fn find_single_cycle(samples: &[f32]) -> &[f32] {
+    let mut crossings = samples
+        .windows(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[0] < 0.0 && pair[1] >= 0.0)
+        .map(|(index, _)| index + 1);
+
+    match (crossings.next(), crossings.next()) {
+        (Some(start), Some(end)) => &samples[start..end],
+        _ => samples,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use super::*;
+    use crate::{WavetableGenerator, WavetableWaveform};
+
+    fn sine(cycles: usize, samples_per_cycle: usize, phase: f32) -> Vec<f32> {
+        (0..cycles * samples_per_cycle)
+            .map(|index| (TAU * index as f32 / samples_per_cycle as f32 + phase).sin())
+            .collect()
+    }
+
+    fn assert_close(left: &[WavetableBitDepth], right: &[WavetableBitDepth]) {
+        assert_eq!(left.len(), right.len());
+        left.iter().zip(right.iter()).for_each(|(left, right)| {
+            assert!((*left as i32 - *right as i32).abs() < WavetableBitDepth::MAX as i32 / 50)
+        });
+    }
+
+    #[test]
+    fn test_resample_matches_generator() {
+        let expected = WavetableGenerator {
+            waveform: WavetableWaveform::Sine,
+            size: 64,
+        }
+        .generate();
+
+        let whole = resample_wavetable(&sine(1, 300, 0.0), WavetableResampleSource::WholeClip, 64);
+        assert_close(&whole, &expected);
+
+        // Starting partway through a cycle, the first full cycle is found.
+        let single = resample_wavetable(
+            &sine(5, 300, -1.0),
+            WavetableResampleSource::SingleCycle,
+            64,
+        );
+        assert_close(&single, &expected);
+    }
+
+    #[test]
+    fn test_resize_keeps_the_waveform() {
+        let sine = |size| {
+            WavetableGenerator {
+                waveform: WavetableWaveform::Sine,
+                size,
+            }
+            .generate()
+        };
+
+        assert_close(&resize_wavetable(&sine(64), 256), &sine(256));
+        assert_close(&resize_wavetable(&sine(256), 64), &sine(64));
+        assert_eq!(resize_wavetable(&sine(64), 64), sine(64));
+
+        // Upsampling interpolates between neighbours, including the last and first
+        assert_eq!(&*resize_wavetable(&[0, 100], 4), &[0, 50, 100, 50]);
+        assert_eq!(resize_wavetable(&[5, 5], 0).len(), 1);
+    }
+
+    #[test]
+    fn test_normalize_reaches_full_amplitude() {
+        let mut data = [100, -200, 50, 0];
+        normalize_wavetable(&mut data);
+
+        assert_eq!(data[1], -WavetableBitDepth::MAX);
+        assert_eq!(data[0], WavetableBitDepth::MAX / 2 + 1);
+        assert_eq!(data[3], 0);
+
+        let mut silent = [0; 4];
+        normalize_wavetable(&mut silent);
+        assert_eq!(silent, [0; 4]);
+    }
+
+    #[test]
+    fn test_single_cycle_falls_back_to_whole_clip() {
+        let samples = [0.5; 16];
+
+        assert_eq!(
+            resample_wavetable(&samples, WavetableResampleSource::SingleCycle, 32),
+            resample_wavetable(&samples, WavetableResampleSource::WholeClip, 32),
+        );
+        assert!(
+            resample_wavetable(&[], WavetableResampleSource::WholeClip, 8)
+                .iter()
+                .all(|value| *value == 0)
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use std::f32::consts::{FRAC_PI_2, PI, TAU};
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum WavetableWaveform {
     // Basics
     Sine,
@@ -25,6 +25,10 @@ pub enum WavetableWaveform {
 
     // Noise
     Noise,
+
+    /// Sums sine waves at each harmonic, starting from the fundamental,
+    /// scaled by the given amplitudes.
+    Additive(Vec<f32>),
 }
 
 impl Default for WavetableWaveform {
@@ -102,11 +106,17 @@ impl WavetableWaveform {
         Self::LogarithmicSaw
     }
 
+    /// Generates an Additive wave oscilator, with one
+    /// amplitude per harmonic.
+    pub fn additive(harmonics: Vec<f32>) -> Self {
+        Self::Additive(harmonics)
+    }
+
     /// Generates the result of waveform
-    pub fn func(self, value: f32) -> f32 {
+    pub fn func(&self, value: f32) -> f32 {
         match self {
             Self::Sine => value.sin(),
-            Self::Pulse(duty) => pulse(value, duty),
+            Self::Pulse(duty) => pulse(value, *duty),
             Self::Square => square(value),
             Self::Saw => ((value % TAU) / PI) - 1.0,
             Self::Triangle => value.sin().asin() / FRAC_PI_2,
@@ -121,6 +131,7 @@ impl WavetableWaveform {
             Self::InvertedAlternatingSine => inverted_alternating_sine(value),
             Self::InvertedCamelSine => inverted_camel_sine(value),
             Self::Noise => (fastrand::f32() * 2.0) - 1.0,
+            Self::Additive(harmonics) => additive(value, harmonics),
         }
     }
 }
@@ -200,3 +211,52 @@ fn inverted_alternating_sine(value: f32) -> f32 {
 fn inverted_camel_sine(value: f32) -> f32 {
     inverted_alternating_sine(value).abs()
 }
+
+/// Scaled by the total amplitude, so the output never clips.
+fn additive(value: f32, harmonics: &[f32]) -> f32 {
+    let total = harmonics
+        .iter()
+        .map(|amplitude| amplitude.abs())
+        .sum::<f32>();
+
+    if total == 0.0 {
+        return 0.0;
+    }
+
+    harmonics
+        .iter()
+        .enumerate()
+        .map(|(index, amplitude)| (value * (index + 1) as f32).sin() * amplitude)
+        .sum::<f32>()
+        / total
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::WavetableGenerator;
+
+    use super::*;
+
+    #[test]
+    fn test_additive_fundamental_matches_sine() {
+        let generator = |waveform| WavetableGenerator { waveform, size: 64 }.generate();
+
+        assert_eq!(
+            generator(WavetableWaveform::additive(vec![0.5, 0.0, 0.0])),
+            generator(WavetableWaveform::Sine)
+        );
+        assert!(generator(WavetableWaveform::additive(vec![0.0; 4]))
+            .iter()
+            .all(|value| *value == 0));
+    }
+
+    #[test]
+    fn test_additive_stays_in_range() {
+        let waveform = WavetableWaveform::additive(vec![1.0, 0.5, -0.75, 0.25, 1.0]);
+
+        (0..256).for_each(|index| {
+            let value = waveform.func(index as f32 * 0.1);
+            assert!((-1.0..=1.0).contains(&value));
+        });
+    }
+}
@@ -0,0 +1,152 @@
+use std::f32::consts::TAU;
+
+use serde::{Deserialize, Serialize};
+
+/// The fastest rate an lfo can be set to, in Hz.
+pub const LFO_MAX_RATE: f32 = 20.0;
+
+/// The longest delay an lfo can be set to, in seconds.
+pub const LFO_MAX_DELAY: f32 = 4.0;
+
+/// How many semitones the pitch will move when the depth is at 1.0.
+pub const LFO_PITCH_SEMITONES: f32 = 2.0;
+
+/// The shape of the lfo.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LfoWaveform {
+    #[default]
+    Sine,
+    Triangle,
+    Square,
+    Saw,
+}
+
+/// Where the lfo output is routed to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LfoTarget {
+    /// Modulates the frequency, producing vibrato.
+    #[default]
+    Pitch,
+
+    /// Modulates the amplitude, producing tremolo.
+    Amplitude,
+
+    /// Moves the morph position of a wavetable back and forth.
+    Morph,
+}
+
+/// Definition of a low frequency oscillator used to modulate an instrument.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Lfo {
+    /// The rate in Hz.
+    pub rate: f32,
+
+    /// The amount of modulation, between 0.0 and 1.0.
+    pub depth: f32,
+
+    pub waveform: LfoWaveform,
+
+    /// How long to wait after a note starts before modulating, in seconds.
+    pub delay: f32,
+
+    pub target: LfoTarget,
+}
+
+impl Default for Lfo {
+    fn default() -> Self {
+        Self {
+            rate: 5.0,
+            depth: 0.5,
+            waveform: LfoWaveform::default(),
+            delay: 0.0,
+            target: LfoTarget::default(),
+        }
+    }
+}
+
+impl LfoWaveform {
+    /// Returns the value of the waveform, between -1.0 and 1.0.
+    /// Phase is expected to be between 0.0 and 1.0.
+    pub fn func(self, phase: f32) -> f32 {
+        match self {
+            Self::Sine => (phase * TAU).sin(),
+            Self::Triangle => 1.0 - (4.0 * ((phase + 0.25).fract() - 0.5).abs()),
+            Self::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Self::Saw => (2.0 * phase) - 1.0,
+        }
+    }
+}
+
+impl Lfo {
+    /// Returns the frequency multiplier for the given lfo value.
+    pub fn pitch_ratio(&self, value: f32) -> f32 {
+        (value * self.depth * LFO_PITCH_SEMITONES / 12.0).exp2()
+    }
+
+    /// Returns the amplitude multiplier for the given lfo value.
+    pub fn amplitude(&self, value: f32) -> f32 {
+        1.0 - (self.depth * (1.0 - value) * 0.5)
+    }
+
+    /// Returns the amount added to the morph position for the given lfo value.
+    pub fn morph_offset(&self, value: f32) -> f32 {
+        value * self.depth
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waveform_range() {
+        [
+            LfoWaveform::Sine,
+            LfoWaveform::Triangle,
+            LfoWaveform::Square,
+            LfoWaveform::Saw,
+        ]
+        .into_iter()
+        .for_each(|waveform| {
+            (0..100).for_each(|step| {
+                let value = waveform.func(step as f32 / 100.0);
+                assert!((-1.0..=1.0).contains(&value), "{:?}: {}", waveform, value);
+            })
+        });
+    }
+
+    #[test]
+    fn test_triangle_shape() {
+        let triangle = LfoWaveform::Triangle;
+        assert!(triangle.func(0.0).abs() < f32::EPSILON);
+        assert!((triangle.func(0.25) - 1.0).abs() < f32::EPSILON);
+        assert!(triangle.func(0.5).abs() < f32::EPSILON);
+        assert!((triangle.func(0.75) + 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_modulation_depth() {
+        let lfo = Lfo {
+            depth: 1.0,
+            ..Default::default()
+        };
+
+        assert_eq!(lfo.amplitude(1.0), 1.0);
+        assert_eq!(lfo.amplitude(-1.0), 0.0);
+        assert_eq!(lfo.pitch_ratio(0.0), 1.0);
+        assert!((lfo.pitch_ratio(1.0) - (2.0 / 12.0f32).exp2()).abs() < f32::EPSILON);
+
+        let off = Lfo {
+            depth: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(off.amplitude(-1.0), 1.0);
+        assert_eq!(off.pitch_ratio(1.0), 1.0);
+    }
+}
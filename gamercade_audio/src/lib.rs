@@ -1,13 +1,19 @@
 mod consts;
 mod envelope_definition;
+mod filter_definition;
 mod instruments;
+mod lfo_definition;
 mod notes;
 mod sound_rom;
+mod sound_rom_v0;
 mod tracker;
 
 pub use consts::*;
 pub use envelope_definition::*;
+pub use filter_definition::*;
 pub use instruments::*;
+pub use lfo_definition::*;
 pub use notes::*;
 pub use sound_rom::*;
+pub use sound_rom_v0::SoundRomV0;
 pub use tracker::*;
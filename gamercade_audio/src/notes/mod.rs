@@ -1,7 +1,9 @@
 mod note;
 mod note_name;
 mod octave;
+mod tuning;
 
 pub use note::*;
 pub use note_name::*;
 pub use octave::*;
+pub use tuning::*;
@@ -7,7 +7,9 @@ use serde::{Deserialize, Serialize};
 use strum::{EnumCount, IntoEnumIterator};
 use tinystr::TinyAsciiStr;
 
-use crate::{NoteName, NoteNameIter, Octave, OctaveIter, TOTAL_NOTES_COUNT};
+use crate::{
+    NoteName, NoteNameIter, Octave, OctaveIter, DEFAULT_TUNING_REFERENCE, TOTAL_NOTES_COUNT,
+};
 
 /// Newtype Note Id
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -43,6 +45,9 @@ pub struct Note {
 static mut NOTES_LUT: MaybeUninit<[Note; TOTAL_NOTES_COUNT]> = MaybeUninit::uninit();
 pub const FIRST_NOTE_OFFSET: usize = 2;
 
+/// The midi note number of the first note, C1 is 45 notes away from A4. (69 - 45 = 24)
+pub const MIDI_NOTE_OFFSET: usize = 24;
+
 /// A type which implements .iter() which
 /// goes through all valid notes for this sound engine.
 pub struct NotesIter {
@@ -93,8 +98,7 @@ pub fn initialize_notes() {
 
     unsafe {
         NOTES_LUT.write(std::array::from_fn(|index| {
-            // C1 is 45 notes away from A4. (69 - 45 = 24)
-            let index = index + 24;
+            let index = index + MIDI_NOTE_OFFSET;
 
             let (name, octave) = note_iter.next().unwrap();
 
@@ -117,7 +121,7 @@ pub fn get_note(index: NoteId) -> &'static Note {
 
 /// Converts a note index to a frequency, based on how far from A4 it is
 fn note_to_frequency(offset: isize) -> f32 {
-    440.0 * 2.0_f32.powf((offset - 69) as f32 / 12.0)
+    DEFAULT_TUNING_REFERENCE * 2.0_f32.powf((offset - 69) as f32 / 12.0)
 }
 
 pub fn name_octave_to_index(name: NoteName, octave: Octave) -> Option<NoteId> {
@@ -139,10 +143,18 @@ pub fn from_name_octave(name: NoteName, octave: Octave) -> Option<&'static Note>
     name_octave_to_index(name, octave).map(get_note)
 }
 
+/// Converts a midi note number into a note index, or None
+/// if the note is outside of the range of the sound engine.
+pub fn midi_note_to_index(midi_note: u8) -> Option<NoteId> {
+    let index = (midi_note as usize).checked_sub(MIDI_NOTE_OFFSET)?;
+    (index < TOTAL_NOTES_COUNT).then_some(NoteId(index))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        from_name_octave, get_note, initialize_notes, NoteId, NoteName, Octave, TOTAL_NOTES_COUNT,
+        from_name_octave, get_note, initialize_notes, midi_note_to_index, NoteId, NoteName, Octave,
+        TOTAL_NOTES_COUNT,
     };
 
     #[test]
@@ -171,4 +183,16 @@ mod tests {
         assert!(from_name_octave(NoteName::B, Octave::One).is_none());
         assert!(from_name_octave(NoteName::C, Octave::Nine).is_none());
     }
+
+    #[test]
+    fn test_midi_note_to_index() {
+        initialize_notes();
+
+        assert!(midi_note_to_index(0).is_none());
+        assert!(midi_note_to_index(127).is_none());
+        assert_eq!(midi_note_to_index(24), Some(NoteId(0)));
+
+        let a440 = midi_note_to_index(69).unwrap();
+        assert!((get_note(a440).frequency - 440.0).abs() < 0.001);
+    }
 }
@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{get_note, NoteId, DEFAULT_TUNING_REFERENCE, MIDI_NOTE_OFFSET};
+
+/// The note index of A4 (midi note 69), which every tuning is built around.
+pub const TUNING_ROOT_NOTE: NoteId = NoteId(69 - MIDI_NOTE_OFFSET);
+
+/// The fewest and most steps an octave can be divided into. Fewer steps would
+/// spread the notes across so many octaves that most of them couldn't be heard.
+pub const TUNING_MIN_DIVISIONS: u8 = 5;
+pub const TUNING_MAX_DIVISIONS: u8 = 72;
+
+/// How note indices are turned into frequencies. Note names always
+/// follow the twelve tone names, even when the tuning doesn't.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Tuning {
+    /// The standard twelve tone equal temperament.
+    #[default]
+    EqualTemperament,
+
+    /// Divides each octave into this many equal steps, such as 19-EDO.
+    EqualDivision(u8),
+
+    /// A scale of ratios from the root note, in the style of a Scala file.
+    /// The root itself is left out, and the last ratio is the period the
+    /// scale repeats at, which is usually the octave (2.0).
+    Ratios(Vec<f32>),
+}
+
+impl Tuning {
+    /// Returns the frequency of the note. Invalid tunings fall back to equal temperament.
+    pub fn frequency(&self, note: NoteId) -> f32 {
+        match self {
+            Self::EqualTemperament => get_note(note).frequency,
+            _ => DEFAULT_TUNING_REFERENCE * self.ratio_from_root(steps_from_root(note)),
+        }
+    }
+
+    /// Returns the frequency ratio between the note and the one this many steps away,
+    /// such as 1.5 for a fifth in just intonation. Steps can go past the last note.
+    pub fn interval(&self, note: NoteId, steps: i32) -> f32 {
+        let from = steps_from_root(note);
+        self.ratio_from_root(from + steps) / self.ratio_from_root(from)
+    }
+
+    /// The frequency ratio of the note this many steps from the root.
+    fn ratio_from_root(&self, steps: i32) -> f32 {
+        match self {
+            Self::EqualDivision(divisions) => {
+                let divisions = (*divisions).clamp(TUNING_MIN_DIVISIONS, TUNING_MAX_DIVISIONS);
+                2.0_f32.powf(steps as f32 / divisions as f32)
+            }
+            Self::Ratios(ratios) if is_valid_scale(ratios) => {
+                let period = ratios[ratios.len() - 1];
+                let octave = steps.div_euclid(ratios.len() as i32);
+                let degree = steps.rem_euclid(ratios.len() as i32) as usize;
+                let ratio = match degree {
+                    0 => 1.0,
+                    degree => ratios[degree - 1],
+                };
+
+                period.powi(octave) * ratio
+            }
+            _ => 2.0_f32.powf(steps as f32 / 12.0),
+        }
+    }
+
+    /// Parses a list of ratios, separated by spaces, commas or new lines.
+    /// Like Scala files, values containing a period are in cents, while
+    /// others are fractions such as 3/2, or whole numbers.
+    pub fn parse_ratios(text: &str) -> Result<Vec<f32>, String> {
+        let ratios = text
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|value| !value.is_empty())
+            .map(parse_ratio)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if is_valid_scale(&ratios) {
+            Ok(ratios)
+        } else {
+            Err(String::from(
+                "Scale needs at least one ratio, and the last must be greater than 1",
+            ))
+        }
+    }
+}
+
+fn steps_from_root(note: NoteId) -> i32 {
+    note.0 as i32 - TUNING_ROOT_NOTE.0 as i32
+}
+
+fn parse_ratio(value: &str) -> Result<f32, String> {
+    let invalid = || format!("Invalid ratio: {}", value);
+
+    let ratio = if value.contains('.') {
+        let cents = value.parse::<f32>().map_err(|_| invalid())?;
+        2.0_f32.powf(cents / 1200.0)
+    } else if let Some((top, bottom)) = value.split_once('/') {
+        let top = top.parse::<u32>().map_err(|_| invalid())?;
+        let bottom = bottom.parse::<u32>().map_err(|_| invalid())?;
+        top as f32 / bottom as f32
+    } else {
+        value.parse::<u32>().map_err(|_| invalid())? as f32
+    };
+
+    if ratio.is_finite() && ratio > 0.0 {
+        Ok(ratio)
+    } else {
+        Err(invalid())
+    }
+}
+
+/// The scale must have a period which goes up, or notes wouldn't rise.
+fn is_valid_scale(ratios: &[f32]) -> bool {
+    ratios
+        .last()
+        .is_some_and(|period| period.is_finite() && *period > 1.0)
+        && ratios.iter().all(|ratio| ratio.is_finite() && *ratio > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::initialize_notes;
+
+    #[test]
+    fn test_tunings_match_at_root() {
+        initialize_notes();
+
+        let twelve = Tuning::EqualTemperament;
+        let nineteen = Tuning::EqualDivision(19);
+        let just = Tuning::Ratios(Tuning::parse_ratios("9/8 5/4 4/3 3/2 5/3 15/8 2/1").unwrap());
+
+        [twelve, nineteen, just].iter().for_each(|tuning| {
+            assert!((tuning.frequency(TUNING_ROOT_NOTE) - 440.0).abs() < 0.001);
+        });
+    }
+
+    #[test]
+    fn test_custom_scales() {
+        initialize_notes();
+
+        let twelve = Tuning::EqualDivision(12);
+        (0..48).for_each(|note| {
+            let expected = Tuning::EqualTemperament.frequency(NoteId(note));
+            assert!((twelve.frequency(NoteId(note)) - expected).abs() < 0.01);
+        });
+
+        let nineteen = Tuning::EqualDivision(19);
+        assert!((nineteen.frequency(NoteId(45 + 19)) - 880.0).abs() < 0.01);
+
+        let just = Tuning::Ratios(vec![1.5, 2.0]);
+        assert_eq!(just.frequency(NoteId(46)), 660.0);
+        assert_eq!(just.frequency(NoteId(47)), 880.0);
+        assert_eq!(just.frequency(NoteId(44)), 330.0);
+        assert_eq!(just.frequency(NoteId(43)), 220.0);
+    }
+
+    #[test]
+    fn test_intervals_follow_the_tuning() {
+        initialize_notes();
+
+        let twelve = Tuning::EqualTemperament;
+        assert!((twelve.interval(NoteId(24), 7) - 1.4983).abs() < 0.001);
+        assert!((twelve.interval(NoteId(24), -12) - 0.5).abs() < 0.001);
+
+        let nineteen = Tuning::EqualDivision(19);
+        assert!((nineteen.interval(NoteId(24), 19) - 2.0).abs() < 0.001);
+
+        // Intervals of a scale depend on where they start from
+        let just = Tuning::Ratios(vec![1.25, 1.5, 2.0]);
+        assert_eq!(just.interval(TUNING_ROOT_NOTE, 2), 1.5);
+        assert_eq!(just.interval(NoteId(TUNING_ROOT_NOTE.0 + 1), 1), 1.2);
+        assert_eq!(just.interval(NoteId(TUNING_ROOT_NOTE.0 + 2), 1), 2.0 / 1.5);
+    }
+
+    #[test]
+    fn test_divisions_are_clamped() {
+        initialize_notes();
+
+        let octave_up = NoteId(TUNING_ROOT_NOTE.0 + TUNING_MIN_DIVISIONS as usize);
+        [0, 1, TUNING_MIN_DIVISIONS]
+            .into_iter()
+            .for_each(|divisions| {
+                let tuning = Tuning::EqualDivision(divisions);
+                assert!((tuning.frequency(octave_up) - 880.0).abs() < 0.01);
+            });
+
+        let octave_up = NoteId(TUNING_ROOT_NOTE.0 + TUNING_MAX_DIVISIONS as usize);
+        let tuning = Tuning::EqualDivision(u8::MAX);
+        assert!((tuning.frequency(octave_up) - 880.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parse_ratios() {
+        let ratios = Tuning::parse_ratios("3/2, 1200.0\n").unwrap();
+        assert_eq!(ratios, vec![1.5, 2.0]);
+        assert_eq!(Tuning::parse_ratios("3").unwrap(), vec![3.0]);
+
+        assert!(Tuning::parse_ratios("").is_err());
+        assert!(Tuning::parse_ratios("3/0").is_err());
+        assert!(Tuning::parse_ratios("1/2").is_err());
+        assert!(Tuning::parse_ratios("abc").is_err());
+    }
+}
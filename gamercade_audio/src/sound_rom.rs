@@ -4,7 +4,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     Chain, ChainId, EnvelopeDefinition, IndexInterpolator, InstrumentDataDefinition, InstrumentId,
-    Phrase, Song, SongId, WavetableDefinition, WavetableGenerator, WavetableWaveform,
+    Phrase, Song, SongId, Tuning, WavetableDefinition, WavetableGenerator, WavetableWaveform,
+    DEFAULT_BPM, SFX_CHANNELS, SFX_CHANNELS_MAX,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,17 +15,129 @@ pub struct SoundRom {
     pub phrases: Box<[Option<Phrase>]>,
     pub instruments: Box<[Option<InstrumentDataDefinition>]>,
     pub sfx: Box<[Sfx]>,
+    #[serde(default)]
+    pub bus_gains: BusGains,
+    #[serde(default)]
+    pub delay: DelayDefinition,
+    #[serde(default = "default_sfx_channels")]
+    pub sfx_channels: usize,
+    #[serde(default)]
+    pub tuning: Tuning,
+}
+
+/// Used as the serde default for gain values.
+pub fn unity_gain() -> f32 {
+    1.0
+}
+
+/// Used as the serde default for the sfx channel count.
+pub fn default_sfx_channels() -> usize {
+    SFX_CHANNELS
+}
+
+/// Clamps the sfx channel count into the supported range.
+pub fn clamp_sfx_channels(channels: usize) -> usize {
+    channels.clamp(1, SFX_CHANNELS_MAX)
+}
+
+/// Project wide gains for the music and sound effect buses.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BusGains {
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl Default for BusGains {
+    fn default() -> Self {
+        Self {
+            music: unity_gain(),
+            sfx: unity_gain(),
+        }
+    }
+}
+
+/// The longest time a delay can echo after.
+pub const DELAY_MAX_SECONDS: f32 = 1.0;
+
+/// The most feedback a delay can have, any more and it would never fade out.
+pub const DELAY_MAX_FEEDBACK: f32 = 0.95;
+
+/// A project wide echo, which the music and sound effect buses send into.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DelayDefinition {
+    pub enabled: bool,
+
+    /// How long until the echo is heard, from 0.0 to DELAY_MAX_SECONDS.
+    pub time: f32,
+
+    /// How much of the echo is fed back into the delay, from 0.0 to DELAY_MAX_FEEDBACK.
+    pub feedback: f32,
+
+    /// The volume of the echo.
+    pub wet: f32,
+
+    /// How much of each bus is sent into the delay.
+    pub music_send: f32,
+    pub sfx_send: f32,
+}
+
+impl Default for DelayDefinition {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            time: 0.25,
+            feedback: 0.4,
+            wet: 0.5,
+            music_send: 0.0,
+            sfx_send: 0.0,
+        }
+    }
+}
+
+impl DelayDefinition {
+    /// Returns a copy with every parameter clamped into its valid range.
+    /// Invalid values are replaced by zero.
+    pub fn clamped(self) -> Self {
+        let clamp = |value: f32, max: f32| {
+            if value.is_finite() {
+                value.clamp(0.0, max)
+            } else {
+                0.0
+            }
+        };
+
+        Self {
+            enabled: self.enabled,
+            time: clamp(self.time, DELAY_MAX_SECONDS),
+            feedback: clamp(self.feedback, DELAY_MAX_FEEDBACK),
+            wet: clamp(self.wet, 1.0),
+            music_send: clamp(self.music_send, 1.0),
+            sfx_send: clamp(self.sfx_send, 1.0),
+        }
+    }
 }
 
 /// Represents a singular sound effect
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Sfx {
     pub bpm: f32,
     pub chain: ChainId,
+    #[serde(default = "unity_gain")]
+    pub gain: f32,
     // TODO: Should we include other data here, like
     // loop style? or should this be handled by game code?
 }
 
+impl Default for Sfx {
+    fn default() -> Self {
+        Self {
+            bpm: DEFAULT_BPM,
+            chain: ChainId::default(),
+            gain: unity_gain(),
+        }
+    }
+}
+
 impl Default for SoundRom {
     fn default() -> Self {
         let default_sine_wave = InstrumentDataDefinition::Wavetable(WavetableDefinition {
@@ -35,6 +148,10 @@ impl Default for SoundRom {
             .generate(),
             envelope: EnvelopeDefinition::interesting(),
             interpolator: IndexInterpolator::default(),
+            filter: None,
+            lfo: None,
+            morph_data: Box::default(),
+            morph: 0.0,
         });
 
         let default_phrase = Phrase::c_scale(InstrumentId(0));
@@ -44,6 +161,7 @@ impl Default for SoundRom {
         let default_sfx = Sfx {
             bpm: 120.0,
             chain: ChainId::default(),
+            gain: unity_gain(),
         };
 
         Self {
@@ -52,6 +170,10 @@ impl Default for SoundRom {
             phrases: vec![Some(default_phrase)].into_boxed_slice(),
             instruments: vec![Some(default_sine_wave)].into_boxed_slice(),
             sfx: vec![default_sfx].into_boxed_slice(),
+            bus_gains: BusGains::default(),
+            delay: DelayDefinition::default(),
+            sfx_channels: default_sfx_channels(),
+            tuning: Tuning::default(),
         }
     }
 }
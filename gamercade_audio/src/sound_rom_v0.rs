@@ -0,0 +1,189 @@
+use serde::Deserialize;
+
+use crate::{
+    de_audio_data, default_sfx_channels, unity_gain, Algorithm, BusGains, Chain, ChainId,
+    DelayDefinition, Detune, EnvelopeCurve, EnvelopeDefinition, EnvelopeValue, FMWaveform,
+    FeedbackLevel, FrequencyMode, FrequencyMultiplier, IndexInterpolator, InstrumentDataDefinition,
+    LoopMode, OperatorDefinition, OperatorDefinitionBundle, PatchDefinition, Phrase,
+    SampleBitDepth, SampleDefinition, Sfx, Song, SoundRom, Tuning, WavetableBitDepth,
+    WavetableDefinition, OPERATOR_COUNT, SONG_TRACK_CHANNELS,
+};
+
+/// The layout of the sound rom before roms had a format version. Bincode can't skip
+/// missing fields, so it's frozen as it was, and upgraded to the current types.
+/// Only the types which have changed since are kept here.
+#[derive(Deserialize)]
+pub struct SoundRomV0 {
+    songs: Box<[SongV0]>,
+    chains: Box<[Option<Chain>]>,
+    phrases: Box<[Option<Phrase>]>,
+    instruments: Box<[Option<InstrumentDataDefinitionV0>]>,
+    sfx: Box<[SfxV0]>,
+}
+
+#[derive(Deserialize)]
+struct SongV0 {
+    bpm: f32,
+    tracks: Box<[[Option<ChainId>; SONG_TRACK_CHANNELS]]>,
+}
+
+#[derive(Deserialize)]
+struct SfxV0 {
+    bpm: f32,
+    chain: ChainId,
+}
+
+#[derive(Deserialize)]
+enum InstrumentDataDefinitionV0 {
+    Wavetable(WavetableDefinitionV0),
+    FMSynth(PatchDefinitionV0),
+    Sampler(SampleDefinitionV0),
+}
+
+#[derive(Deserialize)]
+struct WavetableDefinitionV0 {
+    #[serde(deserialize_with = "de_audio_data")]
+    data: Box<[WavetableBitDepth]>,
+    envelope: EnvelopeDefinitionV0,
+    interpolator: IndexInterpolator,
+}
+
+#[derive(Deserialize)]
+struct PatchDefinitionV0 {
+    operators: [OperatorDefinitionV0; OPERATOR_COUNT],
+    algorithm: Algorithm,
+    feedback: FeedbackLevel,
+}
+
+#[derive(Deserialize)]
+struct OperatorDefinitionV0 {
+    waveform: FMWaveform,
+    frequency_multiplier: FrequencyMultiplier,
+    detune: Detune,
+    envlope_definition: EnvelopeDefinitionV0,
+    interpolator: IndexInterpolator,
+}
+
+#[derive(Deserialize)]
+struct SampleDefinitionV0 {
+    #[serde(deserialize_with = "de_audio_data")]
+    data: Box<[SampleBitDepth]>,
+    source_sample_rate: usize,
+    sample_frequency: Option<f32>,
+    envelope_definition: EnvelopeDefinitionV0,
+    interpolator: IndexInterpolator,
+    loop_mode: LoopMode,
+}
+
+#[derive(Deserialize)]
+struct EnvelopeDefinitionV0 {
+    total_level: EnvelopeValue,
+    sustain_level: EnvelopeValue,
+    attack_time: EnvelopeValue,
+    decay_attack_time: EnvelopeValue,
+    decay_sustain_time: EnvelopeValue,
+    release_time: EnvelopeValue,
+}
+
+impl From<SoundRomV0> for SoundRom {
+    fn from(rom: SoundRomV0) -> Self {
+        Self {
+            songs: rom.songs.into_vec().into_iter().map(Song::from).collect(),
+            chains: rom.chains,
+            phrases: rom.phrases,
+            instruments: rom
+                .instruments
+                .into_vec()
+                .into_iter()
+                .map(|instrument| instrument.map(InstrumentDataDefinition::from))
+                .collect(),
+            sfx: rom.sfx.into_vec().into_iter().map(Sfx::from).collect(),
+            bus_gains: BusGains::default(),
+            delay: DelayDefinition::default(),
+            sfx_channels: default_sfx_channels(),
+            tuning: Tuning::default(),
+        }
+    }
+}
+
+impl From<SongV0> for Song {
+    fn from(song: SongV0) -> Self {
+        Self {
+            bpm: song.bpm,
+            tracks: song.tracks,
+            gain: unity_gain(),
+            swing: 0.0,
+        }
+    }
+}
+
+impl From<SfxV0> for Sfx {
+    fn from(sfx: SfxV0) -> Self {
+        Self {
+            bpm: sfx.bpm,
+            chain: sfx.chain,
+            gain: unity_gain(),
+        }
+    }
+}
+
+impl From<InstrumentDataDefinitionV0> for InstrumentDataDefinition {
+    fn from(instrument: InstrumentDataDefinitionV0) -> Self {
+        match instrument {
+            InstrumentDataDefinitionV0::Wavetable(wavetable) => {
+                Self::Wavetable(WavetableDefinition {
+                    data: wavetable.data,
+                    envelope: wavetable.envelope.into(),
+                    interpolator: wavetable.interpolator,
+                    filter: None,
+                    lfo: None,
+                    morph_data: Box::default(),
+                    morph: 0.0,
+                })
+            }
+            InstrumentDataDefinitionV0::FMSynth(patch) => Self::FMSynth(PatchDefinition {
+                operators: OperatorDefinitionBundle {
+                    operators: patch.operators.map(OperatorDefinition::from),
+                },
+                algorithm: patch.algorithm,
+                feedback: patch.feedback,
+            }),
+            InstrumentDataDefinitionV0::Sampler(sample) => Self::Sampler(SampleDefinition {
+                data: sample.data,
+                source_sample_rate: sample.source_sample_rate,
+                sample_frequency: sample.sample_frequency,
+                envelope_definition: sample.envelope_definition.into(),
+                interpolator: sample.interpolator,
+                loop_mode: sample.loop_mode,
+                filter: None,
+            }),
+        }
+    }
+}
+
+impl From<OperatorDefinitionV0> for OperatorDefinition {
+    fn from(operator: OperatorDefinitionV0) -> Self {
+        Self {
+            waveform: operator.waveform,
+            frequency_multiplier: operator.frequency_multiplier,
+            frequency_mode: FrequencyMode::Ratio,
+            detune: operator.detune,
+            envlope_definition: operator.envlope_definition.into(),
+            interpolator: operator.interpolator,
+        }
+    }
+}
+
+impl From<EnvelopeDefinitionV0> for EnvelopeDefinition {
+    fn from(envelope: EnvelopeDefinitionV0) -> Self {
+        Self {
+            total_level: envelope.total_level,
+            sustain_level: envelope.sustain_level,
+            attack_time: envelope.attack_time,
+            decay_attack_time: envelope.decay_attack_time,
+            decay_sustain_time: envelope.decay_sustain_time,
+            release_time: envelope.release_time,
+            curve: EnvelopeCurve::default(),
+        }
+    }
+}
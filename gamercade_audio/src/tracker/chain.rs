@@ -6,11 +6,11 @@ use crate::{CHAIN_MAX_PHRASE_COUNT, PHRASE_STEPS_PER_BEAT};
 use super::phrase::PhraseId;
 
 /// Newtype Chain Identifier
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
 pub struct ChainId(pub usize);
 
 /// A chain is a series of phrases, which when combined together form a song.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Chain {
     pub entries: ArrayVec<Option<PhraseId>, CHAIN_MAX_PHRASE_COUNT>,
 }
@@ -1,5 +1,223 @@
+use std::{fmt, str::FromStr};
+
 use serde::{Deserialize, Serialize};
 
-// TODO: This
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Effect {}
+use crate::{Lfo, LfoTarget, LfoWaveform, LFO_MAX_RATE};
+
+/// The largest value of a single effect parameter, a single hex digit.
+pub const EFFECT_PARAMETER_MAX: u8 = 0xF;
+
+/// How many seconds each step of a glide effect lasts.
+pub const GLIDE_SECONDS_PER_STEP: f32 = 0.01;
+
+/// An effect command placed in a phrase row. Effects start on the row they
+/// are placed, and keep going until replaced by another of the same kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Effect {
+    /// Modulates the pitch of the channel. A depth of zero cancels the vibrato.
+    /// Written as `Vxy`, where x is the depth and y is the speed.
+    Vibrato { depth: u8, speed: u8 },
+
+    /// Slides the pitch from the previous note into the next ones, instead of
+    /// jumping to them. A time of zero cancels the glide.
+    /// Written as `Gxx`, where xx is the time in hundredths of a second.
+    Glide { time: u8 },
+
+    /// Cycles between the note, and the note raised by each offset, moving on once a frame.
+    /// Offsets are steps of the tuning, usually semitones. Offsets of zero cancel the arpeggio.
+    /// Written as `Axy`, so `A47` plays a major chord.
+    Arpeggio { first: u8, second: u8 },
+}
+
+impl Effect {
+    /// Returns the lfo used for a vibrato, or None if this cancels it.
+    pub fn vibrato_lfo(&self) -> Option<Lfo> {
+        match *self {
+            Effect::Vibrato { depth: 0, .. } => None,
+            Effect::Vibrato { depth, speed } => Some(Lfo {
+                rate: LFO_MAX_RATE * speed as f32 / EFFECT_PARAMETER_MAX as f32,
+                depth: depth as f32 / EFFECT_PARAMETER_MAX as f32,
+                waveform: LfoWaveform::Sine,
+                delay: 0.0,
+                target: LfoTarget::Pitch,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns how long a glide lasts in seconds, or None if this isn't a glide.
+    pub fn glide_seconds(&self) -> Option<f32> {
+        match *self {
+            Effect::Glide { time } => Some(time as f32 * GLIDE_SECONDS_PER_STEP),
+            _ => None,
+        }
+    }
+
+    /// Returns the step offsets of an arpeggio, or None if this cancels it.
+    pub fn arpeggio_offsets(&self) -> Option<[u8; 2]> {
+        match *self {
+            Effect::Arpeggio {
+                first: 0,
+                second: 0,
+            } => None,
+            Effect::Arpeggio { first, second } => Some([first, second]),
+            _ => None,
+        }
+    }
+
+    /// Returns a different kind of effect with the same value, used to cycle
+    /// between the effects in the editor.
+    pub fn next_kind(&self) -> Self {
+        let mut next = match self {
+            Effect::Vibrato { .. } => Effect::Glide { time: 0 },
+            Effect::Glide { .. } => Effect::Arpeggio {
+                first: 0,
+                second: 0,
+            },
+            Effect::Arpeggio { .. } => Effect::Vibrato { depth: 0, speed: 0 },
+        };
+        next.set_value(self.value());
+        next
+    }
+
+    /// Returns the effect parameters packed into a single byte.
+    pub fn value(&self) -> u8 {
+        match *self {
+            Effect::Vibrato { depth, speed } => (depth << 4) | speed,
+            Effect::Glide { time } => time,
+            Effect::Arpeggio { first, second } => (first << 4) | second,
+        }
+    }
+
+    /// Sets the effect parameters from a single byte.
+    pub fn set_value(&mut self, value: u8) {
+        match self {
+            Effect::Vibrato { depth, speed } => {
+                *depth = value >> 4;
+                *speed = value & EFFECT_PARAMETER_MAX;
+            }
+            Effect::Glide { time } => *time = value,
+            Effect::Arpeggio { first, second } => {
+                *first = value >> 4;
+                *second = value & EFFECT_PARAMETER_MAX;
+            }
+        }
+    }
+}
+
+impl fmt::Display for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Effect::Vibrato { .. } => write!(f, "V{:02X}", self.value()),
+            Effect::Glide { .. } => write!(f, "G{:02X}", self.value()),
+            Effect::Arpeggio { .. } => write!(f, "A{:02X}", self.value()),
+        }
+    }
+}
+
+impl FromStr for Effect {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut chars = text.chars();
+        let command = chars.next();
+        let value = chars.as_str();
+
+        if value.len() != 2 {
+            return Err(format!("Invalid effect: {}", text));
+        }
+
+        let value =
+            u8::from_str_radix(value, 16).map_err(|_| format!("Invalid effect: {}", text))?;
+
+        let mut effect = match command {
+            Some('V') => Effect::Vibrato { depth: 0, speed: 0 },
+            Some('G') => Effect::Glide { time: 0 },
+            Some('A') => Effect::Arpeggio {
+                first: 0,
+                second: 0,
+            },
+            _ => return Err(format!("Unknown effect: {}", text)),
+        };
+        effect.set_value(value);
+        Ok(effect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effect_text_round_trip() {
+        let effect = Effect::Vibrato {
+            depth: 4,
+            speed: 0xA,
+        };
+
+        assert_eq!(effect.to_string(), "V4A");
+        assert_eq!("V4A".parse::<Effect>(), Ok(effect));
+        assert!("X4A".parse::<Effect>().is_err());
+        assert!("V4".parse::<Effect>().is_err());
+        assert!("VZZ".parse::<Effect>().is_err());
+        assert!("éAA".parse::<Effect>().is_err());
+
+        let glide = Effect::Glide { time: 0x2C };
+        assert_eq!(glide.to_string(), "G2C");
+        assert_eq!("G2C".parse::<Effect>(), Ok(glide));
+    }
+
+    #[test]
+    fn test_glide_seconds() {
+        assert_eq!(Effect::Glide { time: 0 }.glide_seconds(), Some(0.0));
+        assert_eq!(
+            Effect::Glide { time: 50 }.glide_seconds(),
+            Some(50.0 * GLIDE_SECONDS_PER_STEP)
+        );
+        assert!(Effect::Vibrato { depth: 1, speed: 1 }
+            .glide_seconds()
+            .is_none());
+        assert!(Effect::Glide { time: 0xFF }.vibrato_lfo().is_none());
+
+        let vibrato = Effect::Vibrato { depth: 2, speed: 3 };
+        assert_eq!(vibrato.next_kind(), Effect::Glide { time: 0x23 });
+        assert_eq!(vibrato.next_kind().next_kind().next_kind(), vibrato);
+    }
+
+    #[test]
+    fn test_arpeggio_offsets() {
+        let major: Effect = "A47".parse().unwrap();
+        assert_eq!(
+            major,
+            Effect::Arpeggio {
+                first: 4,
+                second: 7
+            }
+        );
+        assert_eq!(major.to_string(), "A47");
+        assert_eq!(major.arpeggio_offsets(), Some([4, 7]));
+
+        assert!("A00"
+            .parse::<Effect>()
+            .unwrap()
+            .arpeggio_offsets()
+            .is_none());
+        assert!(Effect::Glide { time: 0x47 }.arpeggio_offsets().is_none());
+    }
+
+    #[test]
+    fn test_zero_depth_cancels_vibrato() {
+        assert!(Effect::Vibrato { depth: 0, speed: 5 }
+            .vibrato_lfo()
+            .is_none());
+
+        let lfo = Effect::Vibrato {
+            depth: 0xF,
+            speed: 0xF,
+        }
+        .vibrato_lfo()
+        .unwrap();
+        assert_eq!(lfo.depth, 1.0);
+        assert_eq!(lfo.rate, LFO_MAX_RATE);
+    }
+}
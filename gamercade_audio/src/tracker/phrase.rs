@@ -8,11 +8,11 @@ use crate::{
 };
 
 /// Newtype Chain Identifier
-#[derive(Debug, Copy, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct PhraseId(pub usize);
 
 /// A phrase is a series of notes tied to instruments, which when combined together form a chain.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Phrase {
     pub entries: ArrayVec<Option<PhraseStorageType>, PHRASE_MAX_ENTRIES>,
 }
@@ -70,7 +70,7 @@ impl Default for Phrase {
 
 pub type PhraseStorageType = PhraseEntry<NoteId, InstrumentId>;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// An entry in the phrase, contains all data necessary to produce a sound
 pub struct PhraseEntry<N, T> {
     pub note: N,
@@ -1,15 +1,22 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{Chain, ChainId, DEFAULT_BPM, PHRASE_STEPS_PER_BEAT, SONG_TRACK_CHANNELS};
+use crate::{unity_gain, Chain, ChainId, DEFAULT_BPM, PHRASE_STEPS_PER_BEAT, SONG_TRACK_CHANNELS};
 
 #[derive(Debug, Clone, Copy)]
 pub struct SongId(pub usize);
 
 /// A song is a series of chains
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Song {
     pub bpm: f32,
     pub tracks: Box<[[Option<ChainId>; SONG_TRACK_CHANNELS]]>,
+    #[serde(default = "unity_gain")]
+    pub gain: f32,
+
+    /// How far every other step is delayed, as a fraction of a step,
+    /// from 0.0 up to SWING_MAX. Doesn't change the length of the song.
+    #[serde(default)]
+    pub swing: f32,
 }
 
 impl Default for Song {
@@ -17,6 +24,8 @@ impl Default for Song {
         Self {
             bpm: DEFAULT_BPM,
             tracks: vec![std::array::from_fn(|_| None)].into_boxed_slice(),
+            gain: unity_gain(),
+            swing: 0.0,
         }
     }
 }
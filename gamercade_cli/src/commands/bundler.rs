@@ -20,6 +20,26 @@ pub(crate) struct BundleArgs {
     /// Path of the output file.
     #[clap(short, long, value_parser)]
     output: PathBuf,
+
+    /// The zstd compression level. Higher is smaller but slower to bundle.
+    #[clap(short = 'l', long, value_parser)]
+    compression_level: Option<i32>,
+
+    /// The title of the game, replacing the one from the assets.
+    #[clap(long, value_parser)]
+    title: Option<String>,
+
+    /// An author of the game. Can be given more than once, replacing the ones from the assets.
+    #[clap(long = "author", value_parser)]
+    authors: Vec<String>,
+
+    /// The version of the game, replacing the one from the assets.
+    #[clap(long = "game-version", value_parser)]
+    game_version: Option<String>,
+
+    /// A description of the game, replacing the one from the assets.
+    #[clap(long, value_parser)]
+    description: Option<String>,
 }
 
 impl Watchable for BundleArgs {
@@ -59,8 +79,26 @@ pub(crate) fn run(args: &BundleArgs) -> Result<Option<Child>, String> {
         _ => args.output.clone().with_extension("gcrom"),
     };
 
-    let bundled_rom = try_bundle_files(&code, &assets)?;
-    bundled_rom.try_save(&path)?;
+    let mut bundled_rom = try_bundle_files(&code, &assets)?;
+    bundled_rom.validate()?;
+
+    let meta = &mut bundled_rom.meta;
+    if let Some(title) = &args.title {
+        meta.title = title.clone();
+    }
+    if !args.authors.is_empty() {
+        meta.authors = args.authors.clone();
+    }
+    if let Some(version) = &args.game_version {
+        meta.version = version.clone();
+    }
+    if let Some(description) = &args.description {
+        meta.description = description.clone();
+    }
+    match args.compression_level {
+        Some(level) => bundled_rom.try_save_with_level(&path, level)?,
+        None => bundled_rom.try_save(&path)?,
+    }
 
     println!("Bundled rom output to: {}", path.to_string_lossy());
     Ok(None)
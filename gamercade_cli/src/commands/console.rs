@@ -69,11 +69,16 @@ impl Watchable for ConsoleArgs {
     }
 }
 
-pub(crate) fn run(args: &ConsoleArgs) -> Result<Option<Child>, String> {
-    let console_bin = CONSOLE_NAMES
+/// Finds the console binary in the working directory.
+pub(crate) fn find_console_bin() -> Result<&'static str, String> {
+    CONSOLE_NAMES
         .into_iter()
         .find(|name| Path::new(name).exists())
-        .ok_or("Unable to find console binary.")?;
+        .ok_or_else(|| String::from("Unable to find console binary."))
+}
+
+pub(crate) fn run(args: &ConsoleArgs) -> Result<Option<Child>, String> {
+    let console_bin = find_console_bin()?;
 
     let child = match &args.mode {
         Some(ConsoleCommand::Rom { rom }) => std::process::Command::new(console_bin)
@@ -0,0 +1,47 @@
+use std::{path::PathBuf, process::Child};
+
+use clap::Args;
+use gamercade_fs::Rom;
+
+use crate::watch::Watchable;
+
+use super::console::find_console_bin;
+
+#[derive(Args, Debug, Clone)]
+pub(crate) struct ExportArgs {
+    /// Path to the .gcrom to export.
+    #[clap(short, long, value_parser)]
+    rom: PathBuf,
+
+    /// Path of the console binary to embed the rom in. Defaults to the one in the working directory.
+    #[clap(short, long, value_parser)]
+    console: Option<PathBuf>,
+
+    /// Path of the output executable.
+    #[clap(short, long, value_parser)]
+    output: PathBuf,
+}
+
+impl Watchable for ExportArgs {
+    fn get_watch_list(&self) -> Vec<PathBuf> {
+        vec![self.rom.clone()]
+    }
+
+    fn watchable(&self) -> bool {
+        true
+    }
+}
+
+pub(crate) fn run(args: &ExportArgs) -> Result<Option<Child>, String> {
+    Rom::try_load(&args.rom)?.validate()?;
+
+    let console = match &args.console {
+        Some(console) => console.clone(),
+        None => PathBuf::from(find_console_bin()?),
+    };
+
+    gamercade_fs::embed_rom(&console, &args.rom, &args.output)?;
+
+    println!("Exported game to: {}", args.output.to_string_lossy());
+    Ok(None)
+}
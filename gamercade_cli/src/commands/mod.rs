@@ -1,5 +1,6 @@
 pub(crate) mod bundler;
 pub(crate) mod console;
+pub(crate) mod export;
 
 use std::path::PathBuf;
 
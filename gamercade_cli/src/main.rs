@@ -5,7 +5,7 @@ use clap::{Parser, Subcommand};
 mod watch;
 
 mod commands;
-use commands::{bundler::BundleArgs, console::ConsoleArgs, *};
+use commands::{bundler::BundleArgs, console::ConsoleArgs, export::ExportArgs, *};
 use notify::{Config, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use watch::Watchable;
 
@@ -29,6 +29,9 @@ enum Command {
 
     /// Run the console with optional parameters.
     Console(ConsoleArgs),
+
+    /// Export a .gcrom as a standalone game, which launches straight into it.
+    Export(ExportArgs),
 }
 
 impl Watchable for Command {
@@ -36,6 +39,7 @@ impl Watchable for Command {
         match self {
             Command::Bundle(bundle_args) => bundle_args.get_watch_list(),
             Command::Console(console_args) => console_args.get_watch_list(),
+            Command::Export(export_args) => export_args.get_watch_list(),
         }
     }
 
@@ -43,6 +47,7 @@ impl Watchable for Command {
         match self {
             Command::Bundle(bundle_args) => bundle_args.watchable(),
             Command::Console(console_args) => console_args.watchable(),
+            Command::Export(export_args) => export_args.watchable(),
         }
     }
 }
@@ -52,6 +57,7 @@ impl Command {
         match self {
             Command::Bundle(bundle_args) => bundler::run(bundle_args),
             Command::Console(console_args) => console::run(console_args),
+            Command::Export(export_args) => export::run(export_args),
         }
     }
 }
@@ -7,6 +7,16 @@ pub trait AudioApi {
 
     fn play_note(&mut self, note_id: i32, instrument_index: i32, channel: i32);
     fn play_frequency(&mut self, frequency: f32, instrument_index: i32, channel: i32);
+
+    fn set_music_gain(&mut self, gain: f32);
+    fn set_sfx_gain(&mut self, gain: f32);
+
+    fn set_pan(&mut self, channel: i32, pan: f32);
+    fn set_master_volume(&mut self, volume: f32);
+
+    fn set_delay_enabled(&mut self, enabled: i32);
+    fn set_delay_parameters(&mut self, time: f32, feedback: f32, wet: f32);
+    fn set_delay_send(&mut self, channel: i32, send: f32);
 }
 
 macro_rules! derive_bind_audio_api {
@@ -28,4 +38,11 @@ derive_bind_audio_api! {
     bind_stop_channel,
     bind_play_note,
     bind_play_frequency,
+    bind_set_music_gain,
+    bind_set_sfx_gain,
+    bind_set_pan,
+    bind_set_master_volume,
+    bind_set_delay_enabled,
+    bind_set_delay_parameters,
+    bind_set_delay_send,
 }
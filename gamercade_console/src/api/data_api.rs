@@ -11,6 +11,11 @@ pub trait DataApi {
     fn sprite_width(&self, sheet_index: i32) -> i32;
     fn sprite_count(&self, sheet_index: i32) -> i32;
 
+    fn tilemap_count(&self) -> i32;
+    fn tilemap_width(&self, tilemap_index: i32) -> i32;
+    fn tilemap_height(&self, tilemap_index: i32) -> i32;
+    fn tilemap_sprite_sheet(&self, tilemap_index: i32) -> i32;
+
     fn bgm_length_secs(&self, bgm_index: i32) -> f32;
     fn bgm_length_frames(&self, bgm_index: i32) -> i32;
     fn sfx_length_secs(&self, sfx_index: i32) -> f32;
@@ -39,6 +44,10 @@ derive_bind_data_api! {
     bind_sprite_height,
     bind_sprite_width,
     bind_sprite_count,
+    bind_tilemap_count,
+    bind_tilemap_width,
+    bind_tilemap_height,
+    bind_tilemap_sprite_sheet,
     bind_bgm_length_secs,
     bind_bgm_length_frames,
     bind_sfx_length_secs,
@@ -18,6 +18,8 @@ pub trait DrawApi {
 
     fn circle_filled(&mut self, graphics_parameters: i32, x: i32, y: i32, radius: i32);
 
+    fn ellipse(&mut self, graphics_parameters: i32, x: i32, y: i32, radius_x: i32, radius_y: i32);
+
     fn rect(&mut self, graphics_parameters: i32, x: i32, y: i32, width: i32, height: i32);
 
     fn rect_filled(&mut self, graphics_parameters: i32, x: i32, y: i32, width: i32, height: i32);
@@ -25,6 +27,49 @@ pub trait DrawApi {
     fn line(&mut self, graphics_parameters: i32, x0: i32, y0: i32, x1: i32, y1: i32);
 
     fn sprite(&mut self, graphics_parameters: i32, transparency_mask: i64, x: i32, y: i32);
+
+    fn sprite_rotated(
+        &mut self,
+        graphics_parameters: i32,
+        transparency_mask: i64,
+        x: i32,
+        y: i32,
+        angle: f32,
+    );
+
+    fn sprite_scaled(
+        &mut self,
+        graphics_parameters: i32,
+        transparency_mask: i64,
+        x: i32,
+        y: i32,
+        scale_x: f32,
+        scale_y: f32,
+    );
+
+    fn sprite_absolute(&mut self, graphics_parameters: i32, transparency_mask: i64, x: i32, y: i32);
+
+    fn draw_tilemap(
+        &mut self,
+        graphics_parameters: i32,
+        transparency_mask: i64,
+        tilemap_index: i32,
+        camera_x: i32,
+        camera_y: i32,
+    );
+
+    fn set_camera(&mut self, x: i32, y: i32);
+
+    fn set_clip_rect(&mut self, x: i32, y: i32, width: i32, height: i32);
+
+    fn clear_clip(&mut self);
+
+    /// Bound along with the other text functions, since it reads text from the game.
+    fn draw_text(&mut self, text: &str, graphics_parameters: i32, x: i32, y: i32, spacing: i32);
+
+    fn set_palette_remap(&mut self, from_color_index: i32, to_color_index: i32);
+
+    fn clear_palette_remap(&mut self);
 }
 
 derive_bind_draw_api! {
@@ -32,8 +77,18 @@ derive_bind_draw_api! {
     bind_set_pixel,
     bind_circle,
     bind_circle_filled,
+    bind_ellipse,
     bind_rect,
     bind_rect_filled,
     bind_line,
     bind_sprite,
+    bind_sprite_rotated,
+    bind_sprite_scaled,
+    bind_sprite_absolute,
+    bind_draw_tilemap,
+    bind_set_camera,
+    bind_set_clip_rect,
+    bind_clear_clip,
+    bind_set_palette_remap,
+    bind_clear_palette_remap,
 }
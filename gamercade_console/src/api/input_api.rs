@@ -24,6 +24,22 @@ macro_rules! derive_bind_input_api {
                 )*
 
                 fn raw_input_state(&self, player_id: i32) -> i64;
+
+                fn mouse_x(&self, player_id: i32) -> i32;
+                fn mouse_y(&self, player_id: i32) -> i32;
+                fn mouse_buttons(&self, player_id: i32) -> i32;
+
+                fn input_history_len(&self, player_id: i32) -> i32;
+                fn input_history_buttons(&self, player_id: i32, frames_ago: i32) -> i32;
+
+                $(
+                    fn [<input_history_analog_ $anlg_name _x>](&self, player_id: i32, frames_ago: i32) -> f32;
+                    fn [<input_history_analog_ $anlg_name _y>](&self, player_id: i32, frames_ago: i32) -> f32;
+                )*
+
+                $(
+                    fn [<input_history_trigger_ $trg_name>](&self, player_id: i32, frames_ago: i32) -> f32;
+                )*
             }
 
             pub trait InputApiBinding {
@@ -44,6 +60,22 @@ macro_rules! derive_bind_input_api {
 
                 fn bind_raw_input_state(&mut self);
 
+                fn bind_mouse_x(&mut self);
+                fn bind_mouse_y(&mut self);
+                fn bind_mouse_buttons(&mut self);
+
+                fn bind_input_history_len(&mut self);
+                fn bind_input_history_buttons(&mut self);
+
+                $(
+                    fn [<bind_input_history_analog_ $anlg_name _x>](&mut self);
+                    fn [<bind_input_history_analog_ $anlg_name _y>](&mut self);
+                )*
+
+                $(
+                    fn [<bind_input_history_trigger_ $trg_name>](&mut self);
+                )*
+
                 fn bind_input_api(&mut self) {
                     $(
                         self.[<bind_button_ $btn_name _pressed>]();
@@ -61,6 +93,22 @@ macro_rules! derive_bind_input_api {
                     )*
 
                     self.bind_raw_input_state();
+
+                    self.bind_mouse_x();
+                    self.bind_mouse_y();
+                    self.bind_mouse_buttons();
+
+                    self.bind_input_history_len();
+                    self.bind_input_history_buttons();
+
+                    $(
+                        self.[<bind_input_history_analog_ $anlg_name _x>]();
+                        self.[<bind_input_history_analog_ $anlg_name _y>]();
+                    )*
+
+                    $(
+                        self.[<bind_input_history_trigger_ $trg_name>]();
+                    )*
                 }
             }
         }
@@ -6,6 +6,8 @@ mod graphics_parameter_api;
 mod input_api;
 mod multiplayer_api;
 mod random_api;
+mod rumble_api;
+mod save_api;
 mod text_api;
 
 pub use api_example::*;
@@ -16,4 +18,6 @@ pub use graphics_parameter_api::*;
 pub use input_api::*;
 pub use multiplayer_api::*;
 pub use random_api::*;
+pub use rumble_api::*;
+pub use save_api::*;
 pub use text_api::*;
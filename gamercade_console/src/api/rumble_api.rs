@@ -0,0 +1,19 @@
+pub trait RumbleApi {
+    fn set_rumble(&mut self, player_id: i32, strength: f32, frames: i32);
+}
+
+macro_rules! derive_bind_rumble_api {
+    ($($name:ident,)*) => {
+        pub trait RumbleApiBinding {
+            $(fn $name(&mut self);)*
+
+            fn bind_rumble_api(&mut self) {
+                $(self.$name();)*
+            }
+        }
+    };
+}
+
+derive_bind_rumble_api! {
+    bind_set_rumble,
+}
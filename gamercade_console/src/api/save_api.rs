@@ -0,0 +1,26 @@
+/// The maximum size, in bytes, of a game's save data.
+pub const SAVE_DATA_MAX_SIZE: usize = 4096;
+
+pub trait SaveApi {
+    fn save_data_len(&self) -> i32;
+    fn read_save_data(&self, buffer: &mut [u8]) -> i32;
+    fn write_save_data(&mut self, data: &[u8]) -> i32;
+}
+
+macro_rules! derive_bind_save_api {
+    ($($name:ident,)*) => {
+        pub trait SaveApiBinding {
+            $(fn $name(&mut self);)*
+
+            fn bind_save_api(&mut self) {
+                $(self.$name();)*
+            }
+        }
+    };
+}
+
+derive_bind_save_api! {
+    bind_save_data_len,
+    bind_read_save_data,
+    bind_write_save_data,
+}
@@ -26,4 +26,5 @@ macro_rules! derive_bind_text_api {
 
 derive_bind_text_api! {
     bind_console_log,
+    bind_draw_text,
 }
@@ -34,4 +34,13 @@ derive_audio_api_binding! {
 
     play_note(note_id: i32, instrument_index: i32, channel: i32),
     play_frequency(frequency: f32, instrument_index: i32, channel: i32),
+
+    set_music_gain(gain: f32),
+    set_sfx_gain(gain: f32),
+    set_pan(channel: i32, pan: f32),
+    set_master_volume(volume: f32),
+
+    set_delay_enabled(enabled: i32),
+    set_delay_parameters(time: f32, feedback: f32, wet: f32),
+    set_delay_send(channel: i32, send: f32),
 }
@@ -32,6 +32,10 @@ derive_data_api_binding! {
     sprite_height(sprite_sheet: i32),
     sprite_width(sprite_sheet: i32),
     sprite_count(sprite_sheet: i32),
+    tilemap_count(),
+    tilemap_width(tilemap_index: i32),
+    tilemap_height(tilemap_index: i32),
+    tilemap_sprite_sheet(tilemap_index: i32),
     bgm_length_secs(bgm_index: i32),
     bgm_length_frames(bgm_index: i32),
     sfx_length_secs(sfx_index: i32),
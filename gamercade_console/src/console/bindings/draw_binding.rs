@@ -28,6 +28,7 @@ derive_draw_api_binding! {
 
     circle(graphics_parameters: i32, x: i32, y: i32, radius: i32),
     circle_filled(graphics_parameters: i32, x: i32, y: i32, radius: i32),
+    ellipse(graphics_parameters: i32, x: i32, y: i32, radius_x: i32, radius_y: i32),
 
     rect(
         graphics_parameters: i32,
@@ -53,4 +54,42 @@ derive_draw_api_binding! {
         x: i32,
         y: i32
     ),
+
+    sprite_rotated(
+        graphics_parameters: i32,
+        transparency_mask: i64,
+        x: i32,
+        y: i32,
+        angle: f32,
+    ),
+
+    sprite_scaled(
+        graphics_parameters: i32,
+        transparency_mask: i64,
+        x: i32,
+        y: i32,
+        scale_x: f32,
+        scale_y: f32,
+    ),
+
+    sprite_absolute(
+        graphics_parameters: i32,
+        transparency_mask: i64,
+        x: i32,
+        y: i32
+    ),
+
+    draw_tilemap(
+        graphics_parameters: i32,
+        transparency_mask: i64,
+        tilemap_index: i32,
+        camera_x: i32,
+        camera_y: i32
+    ),
+
+    set_camera(x: i32, y: i32),
+    set_clip_rect(x: i32, y: i32, width: i32, height: i32),
+    clear_clip(),
+    set_palette_remap(from_color_index: i32, to_color_index: i32),
+    clear_palette_remap(),
 }
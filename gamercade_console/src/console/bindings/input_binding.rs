@@ -82,6 +82,72 @@ macro_rules! derive_bind_wasm_input_api {
                         caller.data().input_context.raw_input_state(id)
                     }).unwrap();
                 }
+
+                fn bind_mouse_x(&mut self) {
+                    self.func_wrap("env", "mouse_x", |caller: Caller<'_, Contexts>, id: i32| {
+                        caller.data().input_context.mouse_x(id)
+                    }).unwrap();
+                }
+
+                fn bind_mouse_y(&mut self) {
+                    self.func_wrap("env", "mouse_y", |caller: Caller<'_, Contexts>, id: i32| {
+                        caller.data().input_context.mouse_y(id)
+                    }).unwrap();
+                }
+
+                fn bind_mouse_buttons(&mut self) {
+                    self.func_wrap("env", "mouse_buttons", |caller: Caller<'_, Contexts>, id: i32| {
+                        caller.data().input_context.mouse_buttons(id)
+                    }).unwrap();
+                }
+
+                // INPUT HISTORY MACRO
+                fn bind_input_history_len(&mut self) {
+                    self.func_wrap("env", "input_history_len", |caller: Caller<'_, Contexts>, id: i32| {
+                        caller.data().input_context.input_history_len(id)
+                    }).unwrap();
+                }
+
+                fn bind_input_history_buttons(&mut self) {
+                    self.func_wrap(
+                        "env",
+                        "input_history_buttons",
+                        |caller: Caller<'_, Contexts>, id: i32, frames_ago: i32| {
+                            caller.data().input_context.input_history_buttons(id, frames_ago)
+                    }).unwrap();
+                }
+
+                $(
+                    fn [<bind_input_history_analog_ $anlg_name _x>](&mut self) {
+                        self.func_wrap(
+                            "env",
+                            stringify!([<input_history_analog_ $anlg_name _x>]),
+                            |caller: Caller<'_, Contexts>, id: i32, frames_ago: i32| {
+                                caller.data().input_context.[<input_history_analog_ $anlg_name _x>](id, frames_ago)
+                        }).unwrap();
+                    }
+
+                    fn [<bind_input_history_analog_ $anlg_name _y>](&mut self) {
+                        self.func_wrap(
+                            "env",
+                            stringify!([<input_history_analog_ $anlg_name _y>]),
+                            |caller: Caller<'_, Contexts>, id: i32, frames_ago: i32| {
+                                caller.data().input_context.[<input_history_analog_ $anlg_name _y>](id, frames_ago)
+                        }).unwrap();
+                    }
+                )*
+
+                $(
+                    fn [<bind_input_history_trigger_ $trg_name>](&mut self) {
+                        self.func_wrap(
+                            "env",
+                            stringify!([<input_history_trigger_ $trg_name>]),
+                            |caller: Caller<'_, Contexts>, id: i32, frames_ago: i32| {
+                                caller.data().input_context.[<input_history_trigger_ $trg_name>](id, frames_ago)
+                        }).unwrap();
+                    }
+                )*
+                // END INPUT HISTORY MACRO
             }
         }
     };
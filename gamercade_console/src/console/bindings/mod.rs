@@ -7,6 +7,8 @@ mod graphics_parameter_binding;
 mod input_binding;
 mod multiplayer_binding;
 mod random_binding;
+mod rumble_binding;
+mod save_binding;
 mod text_binding;
 
 pub fn bind_all_apis(linker: &mut wasmtime::Linker<super::Contexts>) {
@@ -18,4 +20,6 @@ pub fn bind_all_apis(linker: &mut wasmtime::Linker<super::Contexts>) {
     linker.bind_text_api();
     linker.bind_multiplayer_api();
     linker.bind_audio_api();
+    linker.bind_save_api();
+    linker.bind_rumble_api();
 }
@@ -0,0 +1,20 @@
+use crate::api::{RumbleApi, RumbleApiBinding};
+use wasmtime::{Caller, Linker};
+
+use crate::console::Contexts;
+
+impl RumbleApiBinding for Linker<Contexts> {
+    fn bind_set_rumble(&mut self) {
+        self.func_wrap(
+            "env",
+            "set_rumble",
+            |mut caller: Caller<'_, Contexts>, player_id: i32, strength: f32, frames: i32| {
+                caller
+                    .data_mut()
+                    .rumble_context
+                    .set_rumble(player_id, strength, frames)
+            },
+        )
+        .unwrap();
+    }
+}
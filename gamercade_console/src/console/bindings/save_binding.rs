@@ -0,0 +1,62 @@
+use crate::api::{SaveApi, SaveApiBinding};
+use wasmtime::{Caller, Extern, Linker, Memory, Trap};
+
+use crate::console::Contexts;
+
+fn get_memory(caller: &mut Caller<'_, Contexts>) -> Result<Memory, Trap> {
+    match caller.get_export("memory") {
+        Some(Extern::Memory(mem)) => Ok(mem),
+        _ => Err(Trap::new("failed to find host memory")),
+    }
+}
+
+impl SaveApiBinding for Linker<Contexts> {
+    fn bind_save_data_len(&mut self) {
+        self.func_wrap("env", "save_data_len", |caller: Caller<'_, Contexts>| {
+            caller.data().save_context.save_data_len()
+        })
+        .unwrap();
+    }
+
+    fn bind_read_save_data(&mut self) {
+        self.func_wrap(
+            "env",
+            "read_save_data",
+            |mut caller: Caller<'_, Contexts>, ptr: i32, len: i32| {
+                let mem = get_memory(&mut caller)?;
+                let (data, contexts) = mem.data_and_store_mut(&mut caller);
+                let buffer = match data
+                    .get_mut(ptr as u32 as usize..)
+                    .and_then(|arr| arr.get_mut(..len as u32 as usize))
+                {
+                    Some(buffer) => buffer,
+                    None => return Err(Trap::new("invalid data")),
+                };
+
+                Ok(contexts.save_context.read_save_data(buffer))
+            },
+        )
+        .unwrap();
+    }
+
+    fn bind_write_save_data(&mut self) {
+        self.func_wrap(
+            "env",
+            "write_save_data",
+            |mut caller: Caller<'_, Contexts>, ptr: i32, len: i32| {
+                let mem = get_memory(&mut caller)?;
+                let (data, contexts) = mem.data_and_store_mut(&mut caller);
+                let data = match data
+                    .get(ptr as u32 as usize..)
+                    .and_then(|arr| arr.get(..len as u32 as usize))
+                {
+                    Some(data) => data,
+                    None => return Err(Trap::new("invalid data")),
+                };
+
+                Ok(contexts.save_context.write_save_data(data))
+            },
+        )
+        .unwrap();
+    }
+}
@@ -1,4 +1,4 @@
-use crate::api::{TextApi, TextApiBinding};
+use crate::api::{DrawApi, TextApi, TextApiBinding};
 use paste::paste;
 use std::str;
 use wasmtime::{Caller, Extern, Linker, Trap};
@@ -6,7 +6,7 @@ use wasmtime::{Caller, Extern, Linker, Trap};
 use crate::console::Contexts;
 
 macro_rules! derive_text_api_binding {
-    ($($ident:ident (text_ptr: i32, len: i32, $($name:ident:$args:ty $(,)? )*) $(,)?)*) => {
+    ($($context:ident.$ident:ident (text_ptr: i32, len: i32, $($name:ident:$args:ty $(,)? )*) $(,)?)*) => {
         paste! {
             impl TextApiBinding for Linker<Contexts> {
                 $(
@@ -20,8 +20,8 @@ macro_rules! derive_text_api_binding {
                                     _ => return Err(Trap::new("failed to find host memory")),
                                 };
 
-                                let data = match mem
-                                    .data(&caller)
+                                let (data, contexts) = mem.data_and_store_mut(&mut caller);
+                                let data = match data
                                     .get(text_ptr as u32 as usize..)
                                     .and_then(|arr| arr.get(..len as u32 as usize))
                                 {
@@ -34,7 +34,7 @@ macro_rules! derive_text_api_binding {
                                     Err(_) => return Err(Trap::new("string is not valid utf-8")),
                                 };
 
-                                Ok(caller.data().text_context.$ident(text, $($name as $args,)*))
+                                Ok(contexts.$context.$ident(text, $($name as $args,)*))
                         }).unwrap();
                     }
 
@@ -48,8 +48,8 @@ macro_rules! derive_text_api_binding {
                                     _ => return Err(Trap::new("failed to find host memory")),
                                 };
 
-                                let data = match mem
-                                    .data(&caller)
+                                let (data, contexts) = mem.data_and_store_mut(&mut caller);
+                                let data = match data
                                     .get(text_ptr as u32 as usize..)
                                     .and_then(|arr| arr.get(..len as u32 as usize))
                                 {
@@ -64,7 +64,7 @@ macro_rules! derive_text_api_binding {
                                     Err(_) => return Err(Trap::new("string is not valid utf-16")),
                                 };
 
-                                Ok(caller.data().text_context.$ident(&text, $($name as $args,)*))
+                                Ok(contexts.$context.$ident(&text, $($name as $args,)*))
                         }).unwrap();
                     }
                 )*
@@ -74,5 +74,13 @@ macro_rules! derive_text_api_binding {
 }
 
 derive_text_api_binding! {
-    console_log(text_ptr: i32, len: i32,),
+    text_context.console_log(text_ptr: i32, len: i32,),
+    draw_context.draw_text(
+        text_ptr: i32,
+        len: i32,
+        graphics_parameters: i32,
+        x: i32,
+        y: i32,
+        spacing: i32,
+    ),
 }
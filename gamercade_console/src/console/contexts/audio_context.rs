@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
-use gamercade_audio::{SongId, SFX_CHANNELS, SONG_TRACK_CHANNELS, TOTAL_NOTES_COUNT};
+use gamercade_audio::{DelayDefinition, PhraseVolumeType, SongId, TOTAL_NOTES_COUNT};
+use gamercade_core::FrameRate;
 use gamercade_sound_engine::{SoundEngineData, SoundRomInstance};
 
 use crate::api::AudioApi;
@@ -12,10 +13,14 @@ pub struct AudioContext {
 }
 
 impl AudioContext {
-    pub fn new(sound_rom: &Arc<SoundRomInstance>, output_sample_rate: usize) -> Self {
+    pub fn new(
+        sound_rom: &Arc<SoundRomInstance>,
+        frame_rate: FrameRate,
+        output_sample_rate: usize,
+    ) -> Self {
         Self {
             sound_rom: sound_rom.clone(),
-            sound_engine_data: SoundEngineData::new(output_sample_rate, sound_rom),
+            sound_engine_data: SoundEngineData::new(output_sample_rate, frame_rate, sound_rom),
             changed: false,
         }
     }
@@ -33,7 +38,7 @@ impl AudioApi for AudioContext {
     fn play_sfx(&mut self, sfx_index: i32, channel: i32) {
         if let (Ok(sfx_index), Ok(channel)) = (usize::try_from(sfx_index), usize::try_from(channel))
         {
-            if channel < SONG_TRACK_CHANNELS {
+            if channel < self.sound_engine_data.channel_count() {
                 self.sound_engine_data
                     .play_sfx(self.sound_rom.sfx.get(sfx_index).cloned(), channel);
             }
@@ -46,7 +51,7 @@ impl AudioApi for AudioContext {
 
     fn stop_channel(&mut self, channel: i32) {
         if let Ok(channel) = usize::try_from(channel) {
-            if channel < SONG_TRACK_CHANNELS {
+            if channel < self.sound_engine_data.channel_count() {
                 self.sound_engine_data.play_sfx(None, channel)
             }
         }
@@ -59,9 +64,13 @@ impl AudioApi for AudioContext {
         let channel = usize::try_from(channel);
 
         if let (true, Ok(instrument_index), Ok(channel)) = (valid_note, instrument_index, channel) {
-            if channel < SFX_CHANNELS {
-                self.sound_engine_data
-                    .play_note(note_id, instrument_index, channel);
+            if channel < self.sound_engine_data.channel_count() {
+                self.sound_engine_data.play_note(
+                    note_id,
+                    instrument_index,
+                    channel,
+                    PhraseVolumeType::MAX,
+                );
             }
         };
     }
@@ -70,10 +79,52 @@ impl AudioApi for AudioContext {
         if let (Ok(instrument_index), Ok(channel)) =
             (usize::try_from(instrument_index), usize::try_from(channel))
         {
-            if channel < SFX_CHANNELS {
+            if channel < self.sound_engine_data.channel_count() {
                 self.sound_engine_data
                     .play_frequency(frequency, instrument_index, channel);
             }
         }
     }
+
+    fn set_music_gain(&mut self, gain: f32) {
+        self.sound_engine_data.set_music_gain(gain);
+    }
+
+    fn set_sfx_gain(&mut self, gain: f32) {
+        self.sound_engine_data.set_sfx_gain(gain);
+    }
+
+    fn set_pan(&mut self, channel: i32, pan: f32) {
+        if let Ok(channel) = usize::try_from(channel) {
+            self.sound_engine_data.set_pan(channel, pan)
+        }
+    }
+
+    fn set_master_volume(&mut self, volume: f32) {
+        self.sound_engine_data.set_master_volume(volume);
+    }
+
+    fn set_delay_enabled(&mut self, enabled: i32) {
+        let delay = DelayDefinition {
+            enabled: enabled != 0,
+            ..*self.sound_engine_data.delay()
+        };
+        self.sound_engine_data.set_delay(delay);
+    }
+
+    fn set_delay_parameters(&mut self, time: f32, feedback: f32, wet: f32) {
+        let delay = DelayDefinition {
+            time,
+            feedback,
+            wet,
+            ..*self.sound_engine_data.delay()
+        };
+        self.sound_engine_data.set_delay(delay);
+    }
+
+    fn set_delay_send(&mut self, channel: i32, send: f32) {
+        if let Ok(channel) = usize::try_from(channel) {
+            self.sound_engine_data.set_delay_send(channel, send)
+        }
+    }
 }
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use gamercade_core::SpriteSheet;
+use gamercade_core::{SpriteSheet, Tilemap};
 use gamercade_fs::Rom;
 
 use crate::api::DataApi;
@@ -59,6 +59,28 @@ impl DataApi for DataContext {
             .unwrap_or(-1)
     }
 
+    fn tilemap_count(&self) -> i32 {
+        self.rom.graphics.tilemaps.len() as i32
+    }
+
+    fn tilemap_width(&self, tilemap_index: i32) -> i32 {
+        self.get_tilemap(tilemap_index)
+            .map(|tilemap| tilemap.width as i32)
+            .unwrap_or(-1)
+    }
+
+    fn tilemap_height(&self, tilemap_index: i32) -> i32 {
+        self.get_tilemap(tilemap_index)
+            .map(|tilemap| tilemap.height as i32)
+            .unwrap_or(-1)
+    }
+
+    fn tilemap_sprite_sheet(&self, tilemap_index: i32) -> i32 {
+        self.get_tilemap(tilemap_index)
+            .map(|tilemap| tilemap.sprite_sheet.0 as i32)
+            .unwrap_or(-1)
+    }
+
     fn bgm_length_secs(&self, bgm_index: i32) -> f32 {
         self.get_bgm_length_secs(bgm_index).unwrap_or(f32::NAN)
     }
@@ -90,6 +112,15 @@ impl DataContext {
             .flatten()
     }
 
+    fn get_tilemap(&self, tilemap_index: i32) -> Option<&Tilemap> {
+        self.rom
+            .graphics
+            .validate_tilemap_index(tilemap_index)
+            .map(|index| self.rom.graphics.tilemap(index))
+            .ok()
+            .flatten()
+    }
+
     fn get_bgm_length_secs(&self, bgm_index: i32) -> Option<f32> {
         let song = self.rom.sounds.songs.get(bgm_index as usize)?;
         Some(song.song_length_seconds(&self.rom.sounds.chains))
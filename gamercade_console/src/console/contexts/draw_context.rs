@@ -1,5 +1,11 @@
-use crate::{api::DrawApi, pixel_buffer::PixelBuffer};
-use gamercade_core::{Color, GraphicsParameters, XCord, YCord, BYTES_PER_PIXEL};
+use crate::{
+    api::DrawApi,
+    pixel_buffer::{PixelBuffer, SpriteBlit},
+};
+use gamercade_core::{
+    font_glyph_pixel, Color, ColorIndex, GraphicsParameters, XCord, YCord, BYTES_PER_PIXEL,
+    FONT_GLYPH_HEIGHT, FONT_GLYPH_WIDTH, PALETTE_COLORS,
+};
 use gamercade_fs::Rom;
 use std::{
     ops::{Add, Sub},
@@ -10,6 +16,12 @@ use std::{
 pub struct DrawContext {
     pub(crate) frame_buffer: PixelBuffer,
     pub(crate) rom: Arc<Rom>,
+
+    /// The color each sprite color index is drawn with, set by the game.
+    palette_remap: [ColorIndex; PALETTE_COLORS],
+
+    /// Subtracted from the position of everything drawn, except absolute sprites.
+    camera: (i32, i32),
 }
 
 impl DrawContext {
@@ -17,9 +29,19 @@ impl DrawContext {
         Self {
             frame_buffer: PixelBuffer::init_from_rom(&rom),
             rom,
+            palette_remap: no_palette_remap(),
+            camera: (0, 0),
         }
     }
 
+    /// Converts a position in the world into a position on the screen.
+    fn camera_offset(&self, x: i32, y: i32) -> (i32, i32) {
+        (
+            x.saturating_sub(self.camera.0),
+            y.saturating_sub(self.camera.1),
+        )
+    }
+
     pub fn try_get_xcord<T: Into<i32>>(&self, x: T) -> Option<XCord> {
         self.rom.resolution.try_get_xcord(x)
     }
@@ -29,34 +51,196 @@ impl DrawContext {
     }
 }
 
+fn no_palette_remap() -> [ColorIndex; PALETTE_COLORS] {
+    std::array::from_fn(|index| ColorIndex(index as u8))
+}
+
+/// Looks up the sprite, palette and flipping from the graphics parameters.
+fn sprite_blit<'a>(
+    rom: &'a Rom,
+    palette_remap: &'a [ColorIndex; PALETTE_COLORS],
+    graphics_parameters: i32,
+    transparency_mask: i64,
+) -> Option<SpriteBlit<'a>> {
+    let GraphicsParameters {
+        palette_index,
+        sprite_sheet_index,
+        sprite_index,
+        flip_x,
+        flip_y,
+        ..
+    } = graphics_parameters.into();
+
+    Some(SpriteBlit {
+        sheet: rom.graphics.sprite_sheet(sprite_sheet_index)?,
+        sprite_index,
+        palette: rom.graphics.palette(palette_index)?,
+        palette_remap,
+        transparency_mask,
+        flip_x,
+        flip_y,
+    })
+}
+
 impl DrawApi for DrawContext {
     fn sprite(&mut self, graphics_parameters: i32, transparency_mask: i64, x: i32, y: i32) {
+        let (x, y) = self.camera_offset(x, y);
+
+        if let Some(blit) = sprite_blit(
+            &self.rom,
+            &self.palette_remap,
+            graphics_parameters,
+            transparency_mask,
+        ) {
+            self.frame_buffer.draw_sprite(&blit, (x, y));
+        }
+    }
+
+    fn sprite_rotated(
+        &mut self,
+        graphics_parameters: i32,
+        transparency_mask: i64,
+        x: i32,
+        y: i32,
+        angle: f32,
+    ) {
+        let (x, y) = self.camera_offset(x, y);
+
+        if let Some(blit) = sprite_blit(
+            &self.rom,
+            &self.palette_remap,
+            graphics_parameters,
+            transparency_mask,
+        ) {
+            self.frame_buffer.draw_sprite_rotated(&blit, (x, y), angle);
+        }
+    }
+
+    fn sprite_scaled(
+        &mut self,
+        graphics_parameters: i32,
+        transparency_mask: i64,
+        x: i32,
+        y: i32,
+        scale_x: f32,
+        scale_y: f32,
+    ) {
+        let (x, y) = self.camera_offset(x, y);
+
+        if let Some(blit) = sprite_blit(
+            &self.rom,
+            &self.palette_remap,
+            graphics_parameters,
+            transparency_mask,
+        ) {
+            self.frame_buffer
+                .draw_sprite_scaled(&blit, (x, y), (scale_x, scale_y));
+        }
+    }
+
+    fn draw_text(&mut self, text: &str, graphics_parameters: i32, x: i32, y: i32, spacing: i32) {
+        let (x, y) = self.camera_offset(x, y);
+
         let GraphicsParameters {
+            color_index,
             palette_index,
-            sprite_sheet_index,
-            sprite_index,
-            flip_x,
-            flip_y,
             ..
         } = graphics_parameters.into();
 
-        let palette = match self.rom.graphics.palette(palette_index) {
-            Some(palette) => palette,
+        let color = match self.rom.graphics.palette(palette_index) {
+            Some(palette) => palette[color_index],
             None => return,
         };
-        let sheet = match self.rom.graphics.sprite_sheet(sprite_sheet_index) {
-            Some(sheet) => sheet,
-            None => return,
+
+        let advance_x = (FONT_GLYPH_WIDTH as i32).saturating_add(spacing);
+        let advance_y = (FONT_GLYPH_HEIGHT as i32).saturating_add(spacing);
+
+        text.lines().enumerate().for_each(|(line, text)| {
+            let glyph_y = y.saturating_add((line as i32).saturating_mul(advance_y));
+
+            text.chars().enumerate().for_each(|(column, character)| {
+                let glyph_x = x.saturating_add((column as i32).saturating_mul(advance_x));
+
+                (0..FONT_GLYPH_HEIGHT).for_each(|pixel_y| {
+                    (0..FONT_GLYPH_WIDTH).for_each(|pixel_x| {
+                        if font_glyph_pixel(character, pixel_x, pixel_y) {
+                            let x = self.try_get_xcord(glyph_x.saturating_add(pixel_x as i32));
+                            let y = self.try_get_ycord(glyph_y.saturating_add(pixel_y as i32));
+                            self.try_set_pixel_safe(x, y, color);
+                        }
+                    })
+                })
+            })
+        });
+    }
+
+    fn sprite_absolute(
+        &mut self,
+        graphics_parameters: i32,
+        transparency_mask: i64,
+        x: i32,
+        y: i32,
+    ) {
+        if let Some(blit) = sprite_blit(
+            &self.rom,
+            &self.palette_remap,
+            graphics_parameters,
+            transparency_mask,
+        ) {
+            self.frame_buffer.draw_sprite(&blit, (x, y));
+        }
+    }
+
+    fn draw_tilemap(
+        &mut self,
+        graphics_parameters: i32,
+        transparency_mask: i64,
+        tilemap_index: i32,
+        camera_x: i32,
+        camera_y: i32,
+    ) {
+        let tilemap = match self.rom.graphics.validate_tilemap_index(tilemap_index) {
+            Ok(tilemap_index) => &self.rom.graphics.tilemaps[tilemap_index.0 as usize],
+            Err(_) => return,
         };
 
-        self.frame_buffer.draw_sprite(
-            sheet,
-            sprite_index,
-            palette,
-            (x, y),
+        // The tilemap uses its own sprite sheet, instead of the one in the graphics parameters
+        let graphics_parameters = GraphicsParameters::from(graphics_parameters)
+            .sprite_sheet_index(tilemap.sprite_sheet.0);
+
+        if let Some(blit) = sprite_blit(
+            &self.rom,
+            &self.palette_remap,
+            graphics_parameters.into(),
             transparency_mask,
-            (flip_x, flip_y),
-        );
+        ) {
+            self.frame_buffer
+                .draw_tilemap(&blit, tilemap, (camera_x, camera_y));
+        }
+    }
+
+    fn set_camera(&mut self, x: i32, y: i32) {
+        self.camera = (x, y);
+    }
+
+    fn set_clip_rect(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        self.frame_buffer.set_clip(x, y, width, height);
+    }
+
+    fn clear_clip(&mut self) {
+        self.frame_buffer.clear_clip();
+    }
+
+    fn set_palette_remap(&mut self, from_color_index: i32, to_color_index: i32) {
+        let valid = 0..PALETTE_COLORS as i32;
+
+        if valid.contains(&from_color_index) && valid.contains(&to_color_index) {
+            self.palette_remap[from_color_index as usize] = ColorIndex(to_color_index as u8);
+        }
+    }
+
+    fn clear_palette_remap(&mut self) {
+        self.palette_remap = no_palette_remap();
     }
 
     fn clear_screen(&mut self, graphics_parameters: i32) {
@@ -71,6 +255,8 @@ impl DrawApi for DrawContext {
     }
 
     fn set_pixel(&mut self, graphics_parameters: i32, x: i32, y: i32) {
+        let (x, y) = self.camera_offset(x, y);
+
         let GraphicsParameters {
             color_index,
             palette_index,
@@ -86,6 +272,9 @@ impl DrawApi for DrawContext {
     }
 
     fn line(&mut self, graphics_parameters: i32, x0: i32, y0: i32, x1: i32, y1: i32) {
+        let (x0, y0) = self.camera_offset(x0, y0);
+        let (x1, y1) = self.camera_offset(x1, y1);
+
         let GraphicsParameters {
             color_index,
             palette_index,
@@ -122,6 +311,8 @@ impl DrawApi for DrawContext {
     }
 
     fn rect(&mut self, graphics_parameters: i32, x: i32, y: i32, width: i32, height: i32) {
+        let (x, y) = self.camera_offset(x, y);
+
         let GraphicsParameters {
             color_index,
             palette_index,
@@ -133,8 +324,12 @@ impl DrawApi for DrawContext {
             None => return,
         };
 
-        let x1 = x + width;
-        let y1 = y + height;
+        if width <= 0 || height <= 0 {
+            return;
+        }
+
+        let x1 = x.saturating_add(width - 1);
+        let y1 = y.saturating_add(height - 1);
 
         // Top
         self.draw_line_horizontal(x, x1, y, color);
@@ -150,6 +345,8 @@ impl DrawApi for DrawContext {
     }
 
     fn rect_filled(&mut self, graphics_parameters: i32, x: i32, y: i32, width: i32, height: i32) {
+        let (x, y) = self.camera_offset(x, y);
+
         let GraphicsParameters {
             color_index,
             palette_index,
@@ -161,15 +358,24 @@ impl DrawApi for DrawContext {
             None => return,
         };
 
-        let x1 = x + width;
-        let y1 = y + height;
+        if width <= 0 || height <= 0 {
+            return;
+        }
 
-        (y..y1).for_each(|y| {
+        let clip = self.frame_buffer.clip();
+        let x1 = x.saturating_add(width - 1);
+        let y0 = y.max(clip.top);
+        let y1 = y.saturating_add(height - 1).min(clip.bottom - 1);
+
+        // Only visible rows are filled
+        (y0..=y1).for_each(|y| {
             self.draw_line_horizontal(x, x1, y, color);
         })
     }
 
     fn circle(&mut self, graphics_parameters: i32, x: i32, y: i32, radius: i32) {
+        let (x, y) = self.camera_offset(x, y);
+
         let GraphicsParameters {
             color_index,
             palette_index,
@@ -206,6 +412,8 @@ impl DrawApi for DrawContext {
     }
 
     fn circle_filled(&mut self, graphics_parameters: i32, x: i32, y: i32, radius: i32) {
+        let (x, y) = self.camera_offset(x, y);
+
         let GraphicsParameters {
             color_index,
             palette_index,
@@ -243,6 +451,64 @@ impl DrawApi for DrawContext {
             self.draw_line_horizontal(x0 - y, x0 + y, y0 - x, color);
         }
     }
+
+    fn ellipse(&mut self, graphics_parameters: i32, x: i32, y: i32, radius_x: i32, radius_y: i32) {
+        let (x, y) = self.camera_offset(x, y);
+
+        let GraphicsParameters {
+            color_index,
+            palette_index,
+            ..
+        } = graphics_parameters.into();
+
+        let color = match self.rom.graphics.palette(palette_index) {
+            Some(palette) => palette[color_index],
+            None => return,
+        };
+
+        if radius_x < 0 || radius_y < 0 {
+            return;
+        } else if radius_y == 0 {
+            self.draw_line_horizontal(x - radius_x, x + radius_x, y, color);
+            return;
+        }
+
+        // Midpoint ellipse, with the decision values multiplied
+        // by 4 to keep everything in whole numbers
+        let rx2 = radius_x as i64 * radius_x as i64;
+        let ry2 = radius_y as i64 * radius_y as i64;
+
+        let x0 = x;
+        let y0 = y;
+        let mut x = 0_i64;
+        let mut y = radius_y as i64;
+
+        // Region where the slope is shallow, stepping along x
+        let mut d = 4 * ry2 - 4 * rx2 * y + rx2;
+        while ry2 * x < rx2 * y {
+            self.draw_ellipse_points(x0, y0, x as i32, y as i32, color);
+            x += 1;
+            if d < 0 {
+                d += 4 * ry2 * (2 * x + 1);
+            } else {
+                y -= 1;
+                d += 4 * ry2 * (2 * x + 1) - 8 * rx2 * y;
+            }
+        }
+
+        // Region where the slope is steep, stepping along y
+        let mut d = ry2 * (2 * x + 1) * (2 * x + 1) + 4 * rx2 * (y - 1) * (y - 1) - 4 * rx2 * ry2;
+        while y >= 0 {
+            self.draw_ellipse_points(x0, y0, x as i32, y as i32, color);
+            y -= 1;
+            if d > 0 {
+                d += 4 * rx2 * (1 - 2 * y);
+            } else {
+                x += 1;
+                d += 8 * ry2 * x + 4 * rx2 * (1 - 2 * y);
+            }
+        }
+    }
 }
 
 impl DrawContext {
@@ -250,11 +516,12 @@ impl DrawContext {
         self.rom.width()
     }
 
-    fn height(&self) -> i32 {
-        self.rom.height()
-    }
-
     fn set_pixel_safe(&mut self, x: XCord, y: YCord, color: Color) {
+        let clip = self.frame_buffer.clip();
+        if !clip.contains(x.raw_value() as i32, y.raw_value() as i32) {
+            return;
+        }
+
         let pixel_index = self.x_y_cord_to_pixel_buffer_index(x, y);
         let color = color.into_pixel_data();
         if let Some(index_bound) = pixel_index.checked_add(BYTES_PER_PIXEL) {
@@ -283,8 +550,9 @@ impl DrawContext {
         let mut dy = y1 - y0;
 
         let rem_limit = (dx + 1) / 2;
-        let mut x = x0.max(0);
-        let xe = x1.min(self.width() - 1);
+        let clip = self.frame_buffer.clip();
+        let mut x = x0.max(clip.left);
+        let xe = x1.min(clip.right - 1);
 
         let mut rem = ((x - x0) * dy % dx) - rem_limit;
         let mut y = y0 + (x - x0) * dy / dx;
@@ -314,8 +582,9 @@ impl DrawContext {
         let dy = y1 - y0;
 
         let rem_limit = (dy + 1) / 2;
-        let mut y = y0.max(0);
-        let ye = y1.min(self.height() - 1);
+        let clip = self.frame_buffer.clip();
+        let mut y = y0.max(clip.top);
+        let ye = y1.min(clip.bottom - 1);
 
         let mut rem = ((y - y0) * dx % dy) - rem_limit;
         let mut x = x0 + (y - y0) * dx / dy;
@@ -343,18 +612,20 @@ impl DrawContext {
     // TODO: Can optimize this further with direct access into
     // the pixel buffers?
     fn draw_line_vertical(&mut self, x: i32, y0: i32, y1: i32, color: Color) {
-        if x < 0 || x > self.width() - 1 {
+        let clip = self.frame_buffer.clip();
+
+        if x < clip.left || x > clip.right - 1 {
             return;
         }
 
         let (start, end) = if y0 < y1 { (y0, y1) } else { (y1, y0) };
 
-        if start > self.height() || end < 0 {
+        if start > clip.bottom - 1 || end < clip.top {
             return;
         }
 
-        let start = start.max(0) as usize;
-        let end = end.min(self.height() as i32) as usize;
+        let start = start.max(clip.top) as usize;
+        let end = end.min(clip.bottom - 1) as usize;
 
         let width = self.width() as usize;
         let start_index = (start * width) + x as usize;
@@ -372,18 +643,20 @@ impl DrawContext {
 
     /// Efficiently draws a horizontal line with direct array access
     fn draw_line_horizontal(&mut self, x0: i32, x1: i32, y: i32, color: Color) {
-        if y < 0 || y > self.height() {
+        let clip = self.frame_buffer.clip();
+
+        if y < clip.top || y > clip.bottom - 1 {
             return;
         }
 
         let (start, end) = if x0 < x1 { (x0, x1) } else { (x1, x0) };
 
-        if start > self.width() - 1 || end < 0 {
+        if start > clip.right - 1 || end < clip.left {
             return;
         }
 
-        let start = start.max(0) as usize;
-        let end = end.min(self.width() as i32 - 1) as usize;
+        let start = start.max(clip.left) as usize;
+        let end = end.min(clip.right - 1) as usize;
         let y = y as usize;
 
         let start_index = (y * self.width() as usize) + start;
@@ -398,6 +671,19 @@ impl DrawContext {
             .for_each(|pixel| pixel.copy_from_slice(&color));
     }
 
+    /// Draws the 4 ellipse points
+    fn draw_ellipse_points(&mut self, x0: i32, y0: i32, x: i32, y: i32, color: Color) {
+        let up = self.try_get_ycord(y0.sub(y));
+        let down = self.try_get_ycord(y0.add(y));
+        let left = self.try_get_xcord(x0.sub(x));
+        let right = self.try_get_xcord(x0.add(x));
+
+        self.try_set_pixel_safe(left, up, color);
+        self.try_set_pixel_safe(right, up, color);
+        self.try_set_pixel_safe(left, down, color);
+        self.try_set_pixel_safe(right, down, color);
+    }
+
     /// Draws the 8 circle points
     fn draw_circle_points(&mut self, x0: i32, y0: i32, x: i32, y: i32, color: Color) {
         let up_x = self.try_get_ycord(y0.add(x));
@@ -419,3 +705,196 @@ impl DrawContext {
         self.try_set_pixel_safe(left_x, down_y, color);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    const COLOR: u8 = 1;
+
+    /// Returns every pixel which was drawn with COLOR.
+    fn drawn_pixels(context: &DrawContext) -> HashSet<(i32, i32)> {
+        let color = context.rom.graphics.palettes[0].colors[COLOR as usize].into_pixel_data();
+
+        context
+            .frame_buffer
+            .pixel_buffer
+            .chunks_exact(BYTES_PER_PIXEL)
+            .enumerate()
+            .filter(|(_, pixel)| **pixel == color)
+            .map(|(index, _)| {
+                let index = index as i32;
+                (index % context.width(), index / context.width())
+            })
+            .collect()
+    }
+
+    fn draw(shape: impl FnOnce(&mut DrawContext, i32)) -> HashSet<(i32, i32)> {
+        let mut context = DrawContext::new(Arc::new(Rom::default()));
+        let graphics_parameters = GraphicsParameters::default().color_index(COLOR);
+
+        shape(&mut context, graphics_parameters.into());
+        drawn_pixels(&context)
+    }
+
+    fn draw_line(x0: i32, y0: i32, x1: i32, y1: i32) -> HashSet<(i32, i32)> {
+        draw(|context, graphics_parameters| context.line(graphics_parameters, x0, y0, x1, y1))
+    }
+
+    #[test]
+    fn test_lines() {
+        let horizontal = draw_line(8, 4, 2, 4);
+        assert_eq!(horizontal, (2..=8).map(|x| (x, 4)).collect());
+
+        let diagonal = draw_line(3, 5, 9, 11);
+        assert_eq!(diagonal, (0..=6).map(|i| (3 + i, 5 + i)).collect());
+
+        let reversed = draw_line(9, 11, 3, 5);
+        assert_eq!(reversed, diagonal);
+    }
+
+    #[test]
+    fn test_lines_clip_to_screen() {
+        let rom = Rom::default();
+        let (width, height) = (rom.width(), rom.height());
+
+        let clipped = draw_line(-4, -4, 4, 4);
+        assert_eq!(clipped, (0..=4).map(|i| (i, i)).collect());
+
+        let bottom = draw_line(-10, height - 1, width + 10, height - 1);
+        assert_eq!(bottom, (0..width).map(|x| (x, height - 1)).collect());
+
+        assert!(draw_line(0, height, width, height).is_empty());
+        assert!(draw_line(-1, -5, -1, 5).is_empty());
+    }
+
+    #[test]
+    fn test_ellipse() {
+        let ellipse =
+            draw(|context, graphics_parameters| context.ellipse(graphics_parameters, 20, 10, 8, 3));
+
+        [(12, 10), (28, 10), (20, 7), (20, 13)]
+            .iter()
+            .for_each(|point| assert!(ellipse.contains(point)));
+        ellipse.iter().for_each(|(x, y)| {
+            assert!((12..=28).contains(x) && (7..=13).contains(y));
+            assert!(ellipse.contains(&(40 - x, *y)) && ellipse.contains(&(*x, 20 - y)));
+        });
+
+        let circle =
+            draw(|context, graphics_parameters| context.circle(graphics_parameters, 20, 10, 5));
+        let round =
+            draw(|context, graphics_parameters| context.ellipse(graphics_parameters, 20, 10, 5, 5));
+        assert_eq!(round, circle);
+
+        let flat =
+            draw(|context, graphics_parameters| context.ellipse(graphics_parameters, 20, 10, 4, 0));
+        assert_eq!(flat, (16..=24).map(|x| (x, 10)).collect());
+
+        let clipped =
+            draw(|context, graphics_parameters| context.ellipse(graphics_parameters, 0, 0, 30, 12));
+        assert!(clipped.contains(&(30, 0)) && clipped.contains(&(0, 12)));
+    }
+
+    #[test]
+    fn test_rects() {
+        let outline =
+            draw(|context, graphics_parameters| context.rect(graphics_parameters, 2, 3, 4, 3));
+        let expected = [(2, 3), (3, 3), (4, 3), (5, 3), (2, 4), (5, 4)]
+            .into_iter()
+            .chain((2..=5).map(|x| (x, 5)))
+            .collect();
+        assert_eq!(outline, expected);
+
+        let filled = draw(|context, graphics_parameters| {
+            context.rect_filled(graphics_parameters, -2, -1, 4, 3)
+        });
+        assert_eq!(
+            filled,
+            [(0, 0), (1, 0), (0, 1), (1, 1)].into_iter().collect()
+        );
+
+        let empty = draw(|context, graphics_parameters| {
+            context.rect(graphics_parameters, 2, 3, 0, 3);
+            context.rect_filled(graphics_parameters, 2, 3, 4, -3);
+        });
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_text() {
+        let text = draw(|context, graphics_parameters| {
+            context.draw_text("I\n-", graphics_parameters, 10, 20, 1)
+        });
+
+        let letter = (11..=13)
+            .flat_map(|x| [(x, 20), (x, 26)])
+            .chain((21..=25).map(|y| (12, y)));
+        let dash = (10..=14).map(|x| (x, 31));
+        assert_eq!(text, letter.chain(dash).collect());
+
+        let missing = draw(|context, graphics_parameters| {
+            context.draw_text("\u{2603}", graphics_parameters, 0, 0, 0)
+        });
+        let question = draw(|context, graphics_parameters| {
+            context.draw_text("?", graphics_parameters, 0, 0, 0)
+        });
+        assert_eq!(missing, question);
+    }
+
+    #[test]
+    fn test_text_extreme_spacing() {
+        let first_glyph = draw(|context, graphics_parameters| {
+            context.draw_text("A", graphics_parameters, 0, 0, 0)
+        });
+
+        for spacing in [i32::MAX, i32::MIN] {
+            let text = draw(|context, graphics_parameters| {
+                context.draw_text("AB\nCD", graphics_parameters, 0, 0, spacing)
+            });
+            assert_eq!(text, first_glyph);
+        }
+    }
+
+    #[test]
+    fn test_camera() {
+        let moved = draw(|context, graphics_parameters| {
+            context.set_camera(-3, 2);
+            context.rect_filled(graphics_parameters, 0, 4, 2, 1);
+            context.line(graphics_parameters, 0, 2, 1, 2);
+        });
+        assert_eq!(
+            moved,
+            [(3, 2), (4, 2), (3, 0), (4, 0)].into_iter().collect()
+        );
+
+        let reset = draw(|context, graphics_parameters| {
+            context.set_camera(-3, 2);
+            context.set_camera(0, 0);
+            context.set_pixel(graphics_parameters, 1, 1);
+        });
+        assert_eq!(reset, [(1, 1)].into_iter().collect());
+    }
+
+    #[test]
+    fn test_clip_rect() {
+        let clipped = draw(|context, graphics_parameters| {
+            context.set_clip_rect(2, 2, 3, 2);
+            context.rect_filled(graphics_parameters, 0, 0, 10, 10);
+            context.line(graphics_parameters, 0, 0, 9, 9);
+            context.line(graphics_parameters, 0, 1, 9, 4);
+            context.circle(graphics_parameters, 3, 3, 2);
+            context.draw_text("Clip", graphics_parameters, 0, 0, 0);
+        });
+        assert_eq!(clipped, (2..5).flat_map(|x| [(x, 2), (x, 3)]).collect());
+
+        let cleared = draw(|context, graphics_parameters| {
+            context.set_clip_rect(2, 2, 3, 2);
+            context.clear_clip();
+            context.set_pixel(graphics_parameters, 0, 0);
+        });
+        assert_eq!(cleared, [(0, 0)].into_iter().collect());
+    }
+}
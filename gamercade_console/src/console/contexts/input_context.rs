@@ -18,6 +18,14 @@ impl InputContext {
                 .collect(),
         }
     }
+
+    /// Returns the input state from the history. Frames before the history
+    /// began are neutral. Returns None if the player or frame are invalid.
+    fn history_state(&self, player_id: i32, frames_ago: i32) -> Option<InputState> {
+        let player_input = self.input_entries.get(usize::try_from(player_id).ok()?)?;
+        let frames_ago = usize::try_from(frames_ago).ok()?;
+        Some(player_input.history.get(frames_ago))
+    }
 }
 
 /// This file automatically derives the various "get input" or "check input"
@@ -97,8 +105,76 @@ macro_rules! derive_generate_input_api {
                         InputState::INVALID_STATE
                     };
 
-                    unsafe { std::mem::transmute(state) }
+                    state.as_raw_state()
+                }
+
+                fn mouse_x(&self, player_id: i32) -> i32 {
+                    if let Some(player_input) = self.input_entries.get(player_id as usize) {
+                        player_input.current.mouse.get_x()
+                    } else {
+                        -1
+                    }
+                }
+
+                fn mouse_y(&self, player_id: i32) -> i32 {
+                    if let Some(player_input) = self.input_entries.get(player_id as usize) {
+                        player_input.current.mouse.get_y()
+                    } else {
+                        -1
+                    }
+                }
+
+                fn mouse_buttons(&self, player_id: i32) -> i32 {
+                    if let Some(player_input) = self.input_entries.get(player_id as usize) {
+                        player_input.current.mouse.bits() as i32
+                    } else {
+                        -1
+                    }
+                }
+
+                fn input_history_len(&self, player_id: i32) -> i32 {
+                    if let Some(player_input) = self.input_entries.get(player_id as usize) {
+                        player_input.history.len() as i32
+                    } else {
+                        -1
+                    }
                 }
+
+                fn input_history_buttons(&self, player_id: i32, frames_ago: i32) -> i32 {
+                    if let Some(state) = self.history_state(player_id, frames_ago) {
+                        state.buttons.bits() as i32
+                    } else {
+                        -1
+                    }
+                }
+
+                $(
+                    fn [<input_history_analog_ $anlg_name _x>](&self, player_id: i32, frames_ago: i32) -> f32 {
+                        if let Some(state) = self.history_state(player_id, frames_ago) {
+                            state.[<$anlg_name _stick>].get_x_axis()
+                        } else {
+                            f32::NAN
+                        }
+                    }
+
+                    fn [<input_history_analog_ $anlg_name _y>](&self, player_id: i32, frames_ago: i32) -> f32 {
+                        if let Some(state) = self.history_state(player_id, frames_ago) {
+                            state.[<$anlg_name _stick>].get_y_axis()
+                        } else {
+                            f32::NAN
+                        }
+                    }
+                )*
+
+                $(
+                    fn [<input_history_trigger_ $trg_name>](&self, player_id: i32, frames_ago: i32) -> f32 {
+                        if let Some(state) = self.history_state(player_id, frames_ago) {
+                            state.[<$trg_name _trigger>].get_value()
+                        } else {
+                            f32::NAN
+                        }
+                    }
+                )*
             }
         }
     }
@@ -132,3 +208,208 @@ derive_generate_input_api! {
         right,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use gamercade_core::{Buttons, MouseButtonCode};
+    use ggrs::{Config, GGRSRequest, SessionBuilder};
+
+    use super::*;
+    use crate::console::INPUT_HISTORY_LENGTH;
+
+    const PLAYERS: usize = 2;
+    const FRAMES: i32 = 60;
+
+    /// How many frames back a motion can start.
+    const MOTION_WINDOW: i32 = 15;
+
+    #[derive(Clone)]
+    struct MotionGame {
+        input_context: InputContext,
+        frame: i32,
+        detected: Vec<(i32, i32)>,
+    }
+
+    struct MotionConfig;
+
+    impl Config for MotionConfig {
+        type Input = InputState;
+        type State = MotionGame;
+        type Address = SocketAddr;
+    }
+
+    fn held(context: &InputContext, player_id: i32, frames_ago: i32, codes: &[ButtonCode]) -> bool {
+        let mut mask = Buttons::default();
+        codes.iter().for_each(|code| mask.enable_button(*code));
+        let bits = context.input_history_buttons(player_id, frames_ago);
+        bits & mask.bits() as i32 == mask.bits() as i32
+    }
+
+    /// Detects a quarter circle forward, followed by A: Down, Down + Right, Right, A.
+    fn quarter_circle_forward(context: &InputContext, player_id: i32) -> bool {
+        if context.button_a_pressed(player_id) != 1 {
+            return false;
+        }
+
+        let steps = [
+            &[ButtonCode::Right][..],
+            &[ButtonCode::Down, ButtonCode::Right][..],
+            &[ButtonCode::Down][..],
+        ];
+
+        let mut step = 0;
+        for frames_ago in 1..=MOTION_WINDOW {
+            if step < steps.len() && held(context, player_id, frames_ago, steps[step]) {
+                step += 1;
+            }
+        }
+        step == steps.len()
+    }
+
+    impl MotionGame {
+        fn new() -> Self {
+            Self {
+                input_context: InputContext::new(PLAYERS),
+                frame: 0,
+                detected: Vec::new(),
+            }
+        }
+
+        /// Mirrors how the console advances a frame.
+        fn advance(&mut self, inputs: &[InputState]) {
+            self.input_context
+                .input_entries
+                .iter_mut()
+                .zip(inputs.iter())
+                .for_each(|(entry, input)| entry.push_input_state(*input));
+
+            (0..PLAYERS as i32).for_each(|player_id| {
+                if quarter_circle_forward(&self.input_context, player_id) {
+                    self.detected.push((player_id, self.frame));
+                }
+            });
+
+            self.input_context
+                .input_entries
+                .iter_mut()
+                .for_each(|entry| entry.previous = entry.current.buttons);
+            self.frame += 1;
+        }
+
+        fn checksum(&self) -> u128 {
+            let history = (0..PLAYERS as i32).fold(0u128, |sum, player_id| {
+                (0..INPUT_HISTORY_LENGTH as i32).fold(sum, |sum, frames_ago| {
+                    let bits = self
+                        .input_context
+                        .input_history_buttons(player_id, frames_ago);
+                    sum.wrapping_mul(31)
+                        .wrapping_add(bits as u128 + frames_ago as u128)
+                })
+            });
+            let detected = self.detected.iter().fold(0u128, |sum, (player_id, frame)| {
+                sum.wrapping_mul(31)
+                    .wrapping_add(*player_id as u128 * 1000 + *frame as u128)
+            });
+            history ^ (detected << 64) ^ self.frame as u128
+        }
+    }
+
+    /// Scripted inputs. Player 0 performs the motion ending on frame 15, player 1 on frame 35.
+    fn scripted_input(player_id: usize, frame: i32) -> InputState {
+        let start = 10 + player_id as i32 * 20;
+        let codes = match frame - start {
+            0 | 1 => &[ButtonCode::Down][..],
+            2 | 3 => &[ButtonCode::Down, ButtonCode::Right][..],
+            4 => &[ButtonCode::Right][..],
+            5 => &[ButtonCode::A][..],
+            _ => &[][..],
+        };
+
+        let mut state = InputState::default();
+        codes
+            .iter()
+            .for_each(|code| state.buttons.enable_button(*code));
+        state
+    }
+
+    #[test]
+    fn test_mouse_state() {
+        let mut context = InputContext::new(1);
+
+        let mut state = InputState::default();
+        state.mouse.set_position(12, 34);
+        state.mouse.enable_button(MouseButtonCode::Right);
+        context.input_entries[0].push_input_state(state);
+
+        assert_eq!(context.mouse_x(0), 12);
+        assert_eq!(context.mouse_y(0), 34);
+        assert_eq!(context.mouse_buttons(0), 0b10);
+        assert_eq!(context.mouse_x(1), -1);
+        assert_eq!(context.mouse_buttons(1), -1);
+
+        // The raw state only packs the gamepad
+        assert_eq!(
+            InputState::from_raw_state(state.as_raw_state()).mouse,
+            Default::default()
+        );
+    }
+
+    /// Runs a peer in a synctest session, which rolls back and resimulates
+    /// every frame, failing if the checksums don't match.
+    fn run_peer(check_distance: usize) -> Vec<(i32, i32)> {
+        let mut session = SessionBuilder::<MotionConfig>::new()
+            .with_num_players(PLAYERS)
+            .with_check_distance(check_distance)
+            .start_synctest_session()
+            .unwrap();
+        let mut game = MotionGame::new();
+
+        for frame in 0..FRAMES {
+            (0..PLAYERS).for_each(|player_id| {
+                session
+                    .add_local_input(player_id, scripted_input(player_id, frame))
+                    .unwrap()
+            });
+
+            for request in session.advance_frame().unwrap() {
+                match request {
+                    GGRSRequest::SaveGameState { cell, frame } => {
+                        cell.save(frame, Some(game.clone()), Some(game.checksum()))
+                    }
+                    GGRSRequest::LoadGameState { cell, .. } => game = cell.load().unwrap(),
+                    GGRSRequest::AdvanceFrame { inputs } => {
+                        let inputs = inputs.iter().map(|(input, _)| *input).collect::<Vec<_>>();
+                        game.advance(&inputs)
+                    }
+                }
+            }
+        }
+
+        game.detected
+    }
+
+    #[test]
+    fn test_input_history_neutral_before_start() {
+        let mut context = InputContext::new(1);
+        assert_eq!(context.input_history_len(0), 0);
+        assert_eq!(context.input_history_buttons(0, 0), 0);
+        assert_eq!(context.input_history_len(1), -1);
+
+        context.input_entries[0].push_input_state(scripted_input(0, 10));
+        assert_eq!(context.input_history_len(0), 1);
+        assert!(held(&context, 0, 0, &[ButtonCode::Down]));
+        assert_eq!(context.input_history_buttons(0, 1), 0);
+        assert_eq!(context.input_history_analog_left_x(0, 1), 0.0);
+        assert!(context.input_history_analog_left_x(0, -1).is_nan());
+    }
+
+    #[test]
+    fn test_motion_detected_on_same_frame_by_both_peers() {
+        let expected = vec![(0, 15), (1, 35)];
+
+        assert_eq!(run_peer(2), expected);
+        assert_eq!(run_peer(7), expected);
+    }
+}
@@ -5,6 +5,8 @@ mod graphics_parameter_context;
 mod input_context;
 mod multiplayer_context;
 mod random_context;
+mod rumble_context;
+mod save_context;
 mod text_context;
 
 use std::sync::Arc;
@@ -18,6 +20,8 @@ use graphics_parameter_context::GraphicsParameterContext;
 use input_context::InputContext;
 use multiplayer_context::MultiplayerContext;
 use random_context::RandomContext;
+pub(crate) use rumble_context::{Rumble, RumbleContext};
+pub(crate) use save_context::{load_save_data, try_write_save_data, SaveContext};
 use text_context::TextContext;
 
 use super::SessionDescriptor;
@@ -30,6 +34,8 @@ pub struct Contexts {
     pub(crate) text_context: TextContext,
     pub(crate) multiplayer_context: MultiplayerContext,
     pub(crate) audio_context: AudioContext,
+    pub(crate) save_context: SaveContext,
+    pub(crate) rumble_context: RumbleContext,
 }
 
 impl Contexts {
@@ -37,18 +43,21 @@ impl Contexts {
         rom: &Arc<Rom>,
         seed: u64,
         session: SessionDescriptor,
+        save_data: Vec<u8>,
         sound_rom: &Arc<SoundRomInstance>,
         output_sample_rate: usize,
     ) -> Self {
         Self {
             draw_context: DrawContext::new(rom.clone()),
             input_context: InputContext::new(session.num_players),
+            rumble_context: RumbleContext::new(session.num_players),
             random_context: RandomContext::new(seed),
             data_context: DataContext::new(rom.clone()),
             graphics_parameter_context: GraphicsParameterContext::default(),
             text_context: TextContext::default(),
             multiplayer_context: MultiplayerContext::new(session),
-            audio_context: AudioContext::new(sound_rom, output_sample_rate),
+            audio_context: AudioContext::new(sound_rom, rom.frame_rate, output_sample_rate),
+            save_context: SaveContext::new(save_data),
         }
     }
 }
@@ -11,6 +11,10 @@ impl MultiplayerContext {
     pub fn new(session: SessionDescriptor) -> Self {
         Self { session }
     }
+
+    pub(crate) fn session(&self) -> &SessionDescriptor {
+        &self.session
+    }
 }
 
 impl MultiplayerApi for MultiplayerContext {
@@ -13,6 +13,16 @@ impl RandomContext {
             shared_rng: Rng::with_seed(shared_seed),
         }
     }
+
+    /// The current state of the generator, which is saved and restored
+    /// along with the rest of the game during rollback.
+    pub(crate) fn state(&self) -> u64 {
+        self.shared_rng.get_seed()
+    }
+
+    pub(crate) fn set_state(&self, state: u64) {
+        self.shared_rng.seed(state);
+    }
 }
 
 impl RandomApi for RandomContext {
@@ -21,7 +31,11 @@ impl RandomApi for RandomContext {
     }
 
     fn random_int_range(&self, min: i32, max: i32) -> i32 {
-        self.shared_rng.i32(min..max)
+        if min < max {
+            self.shared_rng.i32(min..max)
+        } else {
+            min
+        }
     }
 
     fn random_float(&self) -> f32 {
@@ -30,7 +44,39 @@ impl RandomApi for RandomContext {
 
     fn random_float_range(&self, min: f32, max: f32) -> f32 {
         let range = max - min;
-        let scale = self.shared_rng.f32() * max;
-        (scale * range) + min
+        (self.shared_rng.f32() * range) + min
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn take(context: &RandomContext) -> Vec<i32> {
+        (0..8).map(|_| context.random_int_range(0, 1000)).collect()
+    }
+
+    #[test]
+    fn test_restoring_state_repeats_values() {
+        let first = RandomContext::new(7);
+        let second = RandomContext::new(7);
+        assert_eq!(take(&first), take(&second));
+
+        let state = first.state();
+        let expected = take(&first);
+        first.set_state(state);
+        assert_eq!(take(&first), expected);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let context = RandomContext::new(0);
+        assert_eq!(context.random_int_range(5, 5), 5);
+        assert_eq!(context.random_int_range(5, -5), 5);
+
+        (0..100).for_each(|_| {
+            let value = context.random_float_range(2.0, 3.0);
+            assert!((2.0..3.0).contains(&value));
+        });
     }
 }
@@ -0,0 +1,132 @@
+use ggrs::{Frame, NULL_FRAME};
+
+use crate::api::RumbleApi;
+
+/// A request to rumble a player's gamepad.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rumble {
+    /// From 0.0 to 1.0, where 0.0 stops any rumble.
+    pub strength: f32,
+
+    /// How long to rumble for, in frames.
+    pub frames: u32,
+}
+
+/// Rumble requests from the game, waiting to be sent to the gamepads.
+///
+/// Rumble is a side effect on the local machine, so it isn't part of the rolled back
+/// game state. A rollback reruns frames which already happened, and if those frames
+/// rumbled again, each rollback would restart the rumble. Instead, a rumble only fires
+/// the first time its frame is simulated, and requests made while resimulating are dropped.
+/// Requests are held here until they're taken once the session has advanced, outside of
+/// the rollback loop.
+///
+/// This means rumble follows the predicted inputs. A rumble from a mispredicted frame
+/// can't be taken back, and one which only happens with the corrected inputs is missed.
+#[derive(Clone)]
+pub struct RumbleContext {
+    /// The latest request of each player which hasn't been taken yet.
+    requests: Box<[Option<Rumble>]>,
+
+    /// The newest frame simulated so far.
+    newest_frame: Frame,
+
+    /// Set while rerunning frames after a rollback.
+    resimulating: bool,
+}
+
+impl RumbleContext {
+    pub fn new(num_players: usize) -> Self {
+        Self {
+            requests: vec![None; num_players].into_boxed_slice(),
+            newest_frame: NULL_FRAME,
+            resimulating: false,
+        }
+    }
+
+    /// Called before each frame of a session runs, to tell if it's being resimulated.
+    pub(crate) fn begin_frame(&mut self, frame: Frame) {
+        self.resimulating = frame <= self.newest_frame;
+        self.newest_frame = self.newest_frame.max(frame);
+    }
+
+    /// Takes the requests made since last time, along with the player they're for.
+    pub(crate) fn take(&mut self) -> Vec<(usize, Rumble)> {
+        self.requests
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(player, request)| Some((player, request.take()?)))
+            .collect()
+    }
+}
+
+impl RumbleApi for RumbleContext {
+    fn set_rumble(&mut self, player_id: i32, strength: f32, frames: i32) {
+        if self.resimulating || !strength.is_finite() {
+            return;
+        }
+
+        if let Some(request) = self.requests.get_mut(player_id as u32 as usize) {
+            *request = Some(Rumble {
+                strength: strength.clamp(0.0, 1.0),
+                frames: frames.max(0) as u32,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resimulated_frames_dont_rumble() {
+        let mut context = RumbleContext::new(2);
+
+        context.begin_frame(0);
+        context.set_rumble(1, 0.5, 10);
+        context.begin_frame(1);
+        context.set_rumble(0, 2.0, 5);
+
+        // A rollback reruns both frames, which request the same rumble again
+        context.begin_frame(0);
+        context.set_rumble(1, 0.5, 10);
+        context.begin_frame(1);
+        context.set_rumble(0, 2.0, 5);
+
+        let rumble = |strength, frames| Rumble { strength, frames };
+        assert_eq!(
+            context.take(),
+            vec![(0, rumble(1.0, 5)), (1, rumble(0.5, 10))]
+        );
+
+        // Only new frames rumble again
+        context.begin_frame(1);
+        context.set_rumble(0, 1.0, 1);
+        context.begin_frame(2);
+        context.set_rumble(0, 0.25, 1);
+        assert_eq!(context.take(), vec![(0, rumble(0.25, 1))]);
+        assert_eq!(context.take(), vec![]);
+    }
+
+    #[test]
+    fn test_invalid_requests_are_ignored() {
+        let mut context = RumbleContext::new(1);
+        context.set_rumble(1, 1.0, 10);
+        context.set_rumble(-1, 1.0, 10);
+        context.set_rumble(0, f32::NAN, 10);
+        assert_eq!(context.take(), vec![]);
+
+        context.set_rumble(0, 1.0, -10);
+        assert_eq!(
+            context.take(),
+            vec![(
+                0,
+                Rumble {
+                    strength: 1.0,
+                    frames: 0
+                }
+            )]
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use std::{fs, path::PathBuf};
+
+use ggrs::{Frame, NULL_FRAME};
+
+use crate::api::{SaveApi, SAVE_DATA_MAX_SIZE};
+use gamercade_fs::{fnv1a, Rom};
+
+const SAVES_DIRECTORY: &str = "saves";
+
+/// Save data which persists between sessions, stored on the local machine.
+///
+/// Saving is a side effect outside of the rolled back game state, so writes are
+/// tagged with the frame they happened on and held until that frame is confirmed.
+/// Rolling back drops any writes from the frames being resimulated, as those frames
+/// will write again if they still need to. Only confirmed writes are flushed to disk,
+/// so a write from a mispredicted frame is never saved.
+#[derive(Clone)]
+pub struct SaveContext {
+    /// The most recent data flushed to disk, or loaded from it.
+    saved: Vec<u8>,
+
+    /// Writes which haven't been confirmed yet, oldest first.
+    pending: Vec<(Frame, Vec<u8>)>,
+
+    /// The frame currently being simulated. Writes during init use NULL_FRAME.
+    pub(crate) frame: Frame,
+}
+
+impl SaveContext {
+    pub fn new(saved: Vec<u8>) -> Self {
+        Self {
+            saved,
+            pending: Vec::new(),
+            frame: NULL_FRAME,
+        }
+    }
+
+    /// The most recent data flushed to disk, or loaded from it.
+    pub(crate) fn saved(&self) -> &[u8] {
+        &self.saved
+    }
+
+    /// The data the game currently sees, including writes which aren't confirmed yet.
+    fn data(&self) -> &[u8] {
+        match self.pending.last() {
+            Some((_, data)) => data,
+            None => &self.saved,
+        }
+    }
+
+    /// Drops the writes made on or after the frame being loaded.
+    pub(crate) fn rollback(&mut self, frame: Frame) {
+        self.pending.retain(|(written, _)| *written < frame);
+        self.frame = frame;
+    }
+
+    /// Confirms the writes made up to and including the frame. Returns the
+    /// latest confirmed data if it changed, which should then be written to disk.
+    pub(crate) fn confirm(&mut self, confirmed_frame: Frame) -> Option<Vec<u8>> {
+        let confirmed = self
+            .pending
+            .iter()
+            .take_while(|(written, _)| *written <= confirmed_frame)
+            .count();
+
+        let (_, data) = self.pending.drain(..confirmed).next_back()?;
+        self.saved = data.clone();
+        Some(data)
+    }
+}
+
+impl SaveApi for SaveContext {
+    fn save_data_len(&self) -> i32 {
+        self.data().len() as i32
+    }
+
+    fn read_save_data(&self, buffer: &mut [u8]) -> i32 {
+        let data = self.data();
+        let len = data.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&data[..len]);
+        len as i32
+    }
+
+    fn write_save_data(&mut self, data: &[u8]) -> i32 {
+        if data.len() > SAVE_DATA_MAX_SIZE {
+            return -1;
+        }
+
+        // Only the last write of each frame matters
+        match self.pending.last_mut() {
+            Some((written, pending)) if *written == self.frame => *pending = data.to_vec(),
+            _ => self.pending.push((self.frame, data.to_vec())),
+        }
+
+        data.len() as i32
+    }
+}
+
+/// Saves are keyed on a hash of the game's code, so each game gets its own file.
+pub(crate) fn save_data_path(rom: &Rom) -> PathBuf {
+    let hash = fnv1a(&rom.code);
+
+    PathBuf::from(SAVES_DIRECTORY).join(format!("{:016x}.sav", hash))
+}
+
+/// Loads the save data of the game, or returns empty data if there isn't any yet.
+pub(crate) fn load_save_data(rom: &Rom) -> Vec<u8> {
+    let mut data = fs::read(save_data_path(rom)).unwrap_or_default();
+    data.truncate(SAVE_DATA_MAX_SIZE);
+    data
+}
+
+pub(crate) fn try_write_save_data(rom: &Rom, data: &[u8]) -> Result<(), String> {
+    fs::create_dir_all(SAVES_DIRECTORY).map_err(|e| e.to_string())?;
+    fs::write(save_data_path(rom), data).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(context: &SaveContext) -> Vec<u8> {
+        let mut buffer = [0; 8];
+        let len = context.read_save_data(&mut buffer);
+        buffer[..len as usize].to_vec()
+    }
+
+    #[test]
+    fn test_only_confirmed_writes_are_flushed() {
+        let mut context = SaveContext::new(vec![1]);
+
+        context.frame = 3;
+        context.write_save_data(&[2]);
+        context.frame = 5;
+        context.write_save_data(&[3, 3]);
+        assert_eq!(read(&context), vec![3, 3]);
+        assert_eq!(context.save_data_len(), 2);
+
+        // Frame 5 was mispredicted, and doesn't write after resimulating
+        context.rollback(4);
+        assert_eq!(read(&context), vec![2]);
+
+        assert_eq!(context.confirm(2), None);
+        assert_eq!(context.confirm(6), Some(vec![2]));
+        assert_eq!(context.confirm(7), None);
+        assert_eq!(read(&context), vec![2]);
+    }
+
+    #[test]
+    fn test_writes_are_limited() {
+        let mut context = SaveContext::new(Vec::new());
+        assert_eq!(context.write_save_data(&[0; SAVE_DATA_MAX_SIZE + 1]), -1);
+        assert_eq!(context.save_data_len(), 0);
+
+        assert_eq!(context.write_save_data(&[4; 4]), 4);
+        assert_eq!(context.write_save_data(&[5; 2]), 2);
+        assert_eq!(context.confirm(NULL_FRAME), Some(vec![5; 2]));
+
+        let mut small = [0; 1];
+        assert_eq!(context.read_save_data(&mut small), 1);
+    }
+}
@@ -0,0 +1,71 @@
+use gamercade_core::InputState;
+use gamercade_fs::{bundle, GameAssetProvider, GameCodeProvider, Rom};
+use ggrs::PlayerType;
+
+use super::{Console, LoadedGame, SessionDescriptor, WasmConsole};
+
+/// How many audio sync messages can be queued up before the audio thread reads them.
+const AUDIO_MESSAGE_BUFFER_SIZE: usize = 8;
+
+/// Runs a game locally for a single player, without networking or a window.
+/// Used to embed the console in other tools, such as the editor.
+pub struct EmbeddedRunner {
+    console: WasmConsole,
+}
+
+impl EmbeddedRunner {
+    /// Bundles the code and assets into a rom, then starts the game.
+    pub fn new(
+        code: &impl GameCodeProvider,
+        assets: &impl GameAssetProvider,
+        seed: u64,
+    ) -> Result<Self, String> {
+        let game = LoadedGame::from_rom(bundle(code, assets)).map_err(|e| e.to_string())?;
+
+        let session = SessionDescriptor {
+            num_players: 1,
+            player_types: vec![PlayerType::Local].into_boxed_slice(),
+            spectators: Box::default(),
+            port: 0,
+            input_delay: 0,
+        };
+
+        let (console, _) = WasmConsole::new(game, seed, session, AUDIO_MESSAGE_BUFFER_SIZE)
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self { console })
+    }
+
+    pub fn rom(&self) -> &Rom {
+        self.console.rom()
+    }
+
+    pub fn width(&self) -> usize {
+        self.rom().width() as usize
+    }
+
+    pub fn height(&self) -> usize {
+        self.rom().height() as usize
+    }
+
+    /// Runs and draws a single frame of the game, returning the rgba frame buffer.
+    /// Any error, such as the game trapping, should be treated as the game having stopped.
+    pub fn tick(&mut self, inputs: &[InputState]) -> Result<&[u8], String> {
+        self.console.advance_frame(inputs.iter().copied())?;
+        self.console.sync_audio();
+        self.console.call_draw()?;
+
+        Ok(self.console.frame_buffer())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_code_is_an_error() {
+        let result = EmbeddedRunner::new(&[0, 1, 2, 3], &Rom::default(), 0);
+        assert!(result.is_err());
+    }
+}
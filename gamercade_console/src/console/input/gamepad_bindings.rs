@@ -1,10 +1,111 @@
-use gamercade_core::ButtonCode;
-use gilrs::Button;
+use gamercade_core::{ButtonCode, InputState};
+use gilrs::{Axis, Button};
 use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+use super::key_types::{AnalogAxis, AnalogSide};
+
+/// All of the physical buttons which can be bound.
+pub(crate) const BINDABLE_BUTTONS: [Button; 19] = [
+    Button::South,
+    Button::East,
+    Button::North,
+    Button::West,
+    Button::C,
+    Button::Z,
+    Button::LeftTrigger,
+    Button::LeftTrigger2,
+    Button::RightTrigger,
+    Button::RightTrigger2,
+    Button::Select,
+    Button::Start,
+    Button::Mode,
+    Button::LeftThumb,
+    Button::RightThumb,
+    Button::DPadUp,
+    Button::DPadDown,
+    Button::DPadLeft,
+    Button::DPadRight,
+];
+
+/// All of the physical axes which can be bound. Some devices report
+/// their triggers or sticks on the Z axes.
+pub(crate) const BINDABLE_AXES: [Axis; 6] = [
+    Axis::LeftStickX,
+    Axis::LeftStickY,
+    Axis::RightStickX,
+    Axis::RightStickY,
+    Axis::LeftZ,
+    Axis::RightZ,
+];
+
+/// Where a physical axis goes on the console's sticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct AxisBinding {
+    pub side: AnalogSide,
+    pub axis: AnalogAxis,
+
+    /// Set if the physical axis goes the opposite way to the console's.
+    pub inverted: bool,
+}
+
+impl AxisBinding {
+    pub(crate) const fn new(side: AnalogSide, axis: AnalogAxis) -> Self {
+        Self {
+            side,
+            axis,
+            inverted: false,
+        }
+    }
+
+    pub(crate) fn adjust_input_state(self, value: f32, input_state: &mut InputState) {
+        let value = if self.inverted { -value } else { value };
+
+        let stick = match self.side {
+            AnalogSide::Left => &mut input_state.left_stick,
+            AnalogSide::Right => &mut input_state.right_stick,
+        };
+
+        match self.axis {
+            AnalogAxis::X => stick.set_x_axis(value),
+            AnalogAxis::Y => stick.set_y_axis(value),
+        };
+    }
+}
+
+/// The built in layouts used when a device doesn't have a saved profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GamepadLayout {
+    Nintendo,
+    Xbox,
+}
+
+impl GamepadLayout {
+    /// Guesses the layout of a device from its name.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+
+        if ["nintendo", "switch", "pro controller", "joy-con"]
+            .iter()
+            .any(|pattern| name.contains(pattern))
+        {
+            Some(Self::Nintendo)
+        } else if ["xbox", "xinput", "x-box"]
+            .iter()
+            .any(|pattern| name.contains(pattern))
+        {
+            Some(Self::Xbox)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "SavedBindings", into = "SavedBindings")]
 pub(crate) struct GamepadBindings {
     pub buttons: HashMap<Button, ButtonCode>,
+    pub axes: HashMap<Axis, AxisBinding>,
 }
 
 impl Default for GamepadBindings {
@@ -22,12 +123,217 @@ impl Default for GamepadBindings {
             (Button::Select, ButtonCode::Select),
             (Button::LeftTrigger, ButtonCode::LeftShoulder),
             (Button::RightTrigger, ButtonCode::RightShoulder),
+            (Button::LeftTrigger2, ButtonCode::LeftTrigger),
+            (Button::RightTrigger2, ButtonCode::RightTrigger),
             (Button::LeftThumb, ButtonCode::LeftStick),
             (Button::RightThumb, ButtonCode::RightStick),
         ]
         .into_iter()
         .collect();
 
-        Self { buttons }
+        Self {
+            buttons,
+            axes: default_axes(),
+        }
+    }
+}
+
+fn default_axes() -> HashMap<Axis, AxisBinding> {
+    [
+        (
+            Axis::LeftStickX,
+            AxisBinding::new(AnalogSide::Left, AnalogAxis::X),
+        ),
+        (
+            Axis::LeftStickY,
+            AxisBinding::new(AnalogSide::Left, AnalogAxis::Y),
+        ),
+        (
+            Axis::RightStickX,
+            AxisBinding::new(AnalogSide::Right, AnalogAxis::X),
+        ),
+        (
+            Axis::RightStickY,
+            AxisBinding::new(AnalogSide::Right, AnalogAxis::Y),
+        ),
+    ]
+    .into_iter()
+    .collect()
+}
+
+impl GamepadBindings {
+    /// Returns the built in bindings for the given layout.
+    pub(crate) fn from_layout(layout: GamepadLayout) -> Self {
+        let mut out = Self::default();
+
+        if layout == GamepadLayout::Xbox {
+            // Xbox controllers have A on the bottom and B on the right.
+            out.buttons.insert(Button::South, ButtonCode::A);
+            out.buttons.insert(Button::East, ButtonCode::B);
+            out.buttons.insert(Button::North, ButtonCode::C);
+            out.buttons.insert(Button::West, ButtonCode::D);
+        }
+
+        out
+    }
+
+    /// Returns the built in bindings which best match the device name.
+    pub(crate) fn from_device_name(name: &str) -> Self {
+        match GamepadLayout::from_name(name) {
+            Some(layout) => Self::from_layout(layout),
+            None => Self::default(),
+        }
+    }
+
+    /// Returns an empty set of bindings.
+    pub(crate) fn empty() -> Self {
+        Self {
+            buttons: HashMap::new(),
+            axes: HashMap::new(),
+        }
+    }
+}
+
+/// How bindings are saved. Physical buttons and axes are saved by name.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum SavedBindings {
+    Full {
+        buttons: Vec<(String, ButtonCode)>,
+        axes: Vec<(String, AxisBinding)>,
+    },
+
+    /// Profiles saved before axes could be remapped, which use the default axes.
+    Buttons(Vec<(String, ButtonCode)>),
+}
+
+fn find_by_name<T: std::fmt::Debug + Copy>(all: &[T], name: &str) -> Option<T> {
+    all.iter()
+        .find(|physical| format!("{:?}", physical) == name)
+        .copied()
+}
+
+fn from_names<T: std::fmt::Debug + Copy + Eq + std::hash::Hash, V>(
+    all: &[T],
+    entries: Vec<(String, V)>,
+) -> HashMap<T, V> {
+    entries
+        .into_iter()
+        .filter_map(|(name, value)| Some((find_by_name(all, &name)?, value)))
+        .collect()
+}
+
+fn into_names<T: std::fmt::Debug, V>(bindings: HashMap<T, V>) -> Vec<(String, V)> {
+    bindings
+        .into_iter()
+        .map(|(physical, value)| (format!("{:?}", physical), value))
+        .collect()
+}
+
+impl From<SavedBindings> for GamepadBindings {
+    fn from(saved: SavedBindings) -> Self {
+        match saved {
+            SavedBindings::Full { buttons, axes } => Self {
+                buttons: from_names(&BINDABLE_BUTTONS, buttons),
+                axes: from_names(&BINDABLE_AXES, axes),
+            },
+            SavedBindings::Buttons(buttons) => Self {
+                buttons: from_names(&BINDABLE_BUTTONS, buttons),
+                axes: default_axes(),
+            },
+        }
+    }
+}
+
+impl From<GamepadBindings> for SavedBindings {
+    fn from(bindings: GamepadBindings) -> Self {
+        Self::Full {
+            buttons: into_names(bindings.buttons),
+            axes: into_names(bindings.axes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bindings_round_trip() {
+        let mut bindings = GamepadBindings::from_layout(GamepadLayout::Xbox);
+        bindings.axes.insert(
+            Axis::RightZ,
+            AxisBinding {
+                side: AnalogSide::Right,
+                axis: AnalogAxis::Y,
+                inverted: true,
+            },
+        );
+
+        let json = serde_json::to_string(&bindings).unwrap();
+        let loaded: GamepadBindings = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.buttons, bindings.buttons);
+        assert_eq!(loaded.axes, bindings.axes);
+    }
+
+    #[test]
+    fn test_axes_go_to_the_bound_stick() {
+        let mut state = InputState::default();
+
+        AxisBinding::new(AnalogSide::Left, AnalogAxis::X).adjust_input_state(1.0, &mut state);
+        AxisBinding::new(AnalogSide::Right, AnalogAxis::Y).adjust_input_state(-1.0, &mut state);
+
+        assert_eq!(state.left_stick.get_x_axis(), 1.0);
+        assert_eq!(state.left_stick.get_y_axis(), 0.0);
+        assert_eq!(state.right_stick.get_x_axis(), 0.0);
+        assert_eq!(state.right_stick.get_y_axis(), -1.0);
+    }
+
+    #[test]
+    fn test_inverted_axes_flip_the_value() {
+        let binding = AxisBinding {
+            side: AnalogSide::Left,
+            axis: AnalogAxis::Y,
+            inverted: true,
+        };
+
+        [(-1.0, 1.0), (1.0, -1.0), (0.0, 0.0)]
+            .into_iter()
+            .for_each(|(value, expected)| {
+                let mut state = InputState::default();
+                binding.adjust_input_state(value, &mut state);
+                assert_eq!(state.left_stick.get_y_axis(), expected);
+            });
+    }
+
+    #[test]
+    fn test_layouts_from_device_names() {
+        assert_eq!(
+            GamepadLayout::from_name("Nintendo Switch Pro Controller"),
+            Some(GamepadLayout::Nintendo)
+        );
+        assert_eq!(
+            GamepadLayout::from_name("Xbox 360 Controller"),
+            Some(GamepadLayout::Xbox)
+        );
+        assert_eq!(GamepadLayout::from_name("Generic USB Joystick"), None);
+
+        let xbox = GamepadBindings::from_device_name("XInput Controller");
+        assert_eq!(xbox.buttons[&Button::South], ButtonCode::A);
+        assert_eq!(xbox.buttons[&Button::East], ButtonCode::B);
+
+        let unknown = GamepadBindings::from_device_name("Generic USB Joystick");
+        assert_eq!(unknown.buttons[&Button::East], ButtonCode::A);
+        assert_eq!(unknown.axes, default_axes());
+    }
+
+    #[test]
+    fn test_button_only_profiles_use_the_default_axes() {
+        let loaded: GamepadBindings =
+            serde_json::from_str(r#"[["South", "A"], ["East", "B"]]"#).unwrap();
+
+        assert_eq!(loaded.buttons.len(), 2);
+        assert_eq!(loaded.buttons[&Button::South], ButtonCode::A);
+        assert_eq!(loaded.axes, default_axes());
     }
 }
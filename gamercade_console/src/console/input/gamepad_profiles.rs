@@ -0,0 +1,95 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use gilrs::Gamepad;
+use serde::{Deserialize, Serialize};
+
+use super::gamepad_bindings::GamepadBindings;
+
+const PROFILES_PATH: &str = "gamepad_profiles.json";
+
+/// A saved set of bindings for a single device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct GamepadProfile {
+    pub name: String,
+    pub bindings: GamepadBindings,
+}
+
+/// Per device button mappings, keyed by the device's uuid.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct GamepadProfiles {
+    pub profiles: HashMap<String, GamepadProfile>,
+
+    #[serde(skip)]
+    fallbacks: HashMap<String, GamepadBindings>,
+}
+
+impl GamepadProfiles {
+    /// Loads the profiles from the settings file, or returns an empty set
+    /// if they don't exist.
+    pub(crate) fn load() -> Self {
+        match Self::try_load(&PathBuf::from(PROFILES_PATH)) {
+            Ok(profiles) => profiles,
+            Err(e) => {
+                println!("Using default gamepad profiles: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    fn try_load(path: &PathBuf) -> Result<Self, String> {
+        let file = fs::read(path).map_err(|e| e.to_string())?;
+        serde_json::from_slice(&file).map_err(|e| e.to_string())
+    }
+
+    pub(crate) fn try_save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(PROFILES_PATH, json).map_err(|e| e.to_string())
+    }
+
+    /// Returns the bindings for the device, falling back to the
+    /// built in layouts if the device doesn't have a saved profile.
+    pub(crate) fn bindings(&mut self, gamepad: &Gamepad) -> &GamepadBindings {
+        let key = device_key(gamepad);
+
+        if let Some(profile) = self.profiles.get(&key) {
+            &profile.bindings
+        } else {
+            self.fallbacks
+                .entry(key)
+                .or_insert_with(|| GamepadBindings::from_device_name(gamepad.name()))
+        }
+    }
+
+    /// Saves the bindings as the profile for this device.
+    pub(crate) fn set_profile(&mut self, gamepad: &Gamepad, bindings: GamepadBindings) {
+        self.profiles.insert(
+            device_key(gamepad),
+            GamepadProfile {
+                name: gamepad.name().to_string(),
+                bindings,
+            },
+        );
+    }
+
+    /// Copies an existing profile onto the target device.
+    pub(crate) fn copy_profile(&mut self, source_key: &str, target: &Gamepad) {
+        if let Some(source) = self.profiles.get(source_key) {
+            let bindings = source.bindings.clone();
+            self.set_profile(target, bindings)
+        }
+    }
+
+    /// Removes the saved profile, so the device uses the built in layout again.
+    pub(crate) fn reset_profile(&mut self, gamepad: &Gamepad) {
+        self.profiles.remove(&device_key(gamepad));
+    }
+}
+
+/// The key used to identify a device between sessions.
+pub(crate) fn device_key(gamepad: &Gamepad) -> String {
+    gamepad
+        .uuid()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
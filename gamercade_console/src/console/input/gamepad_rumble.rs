@@ -0,0 +1,65 @@
+use std::{collections::HashMap, fmt, time::Duration};
+
+use gilrs::{
+    ff::{BaseEffect, BaseEffectType, Effect, EffectBuilder, Repeat, Replay, Ticks},
+    GamepadId, Gilrs,
+};
+
+/// The rumble playing on each gamepad. An effect stops once it's dropped,
+/// so each is kept until it's replaced.
+#[derive(Default)]
+pub(crate) struct GamepadRumble {
+    effects: HashMap<GamepadId, Effect>,
+}
+
+impl GamepadRumble {
+    /// Rumbles the gamepad, replacing what it was doing before. A strength of 0.0 stops it.
+    /// Gamepads which can't rumble are ignored.
+    pub(crate) fn set(
+        &mut self,
+        id: GamepadId,
+        strength: f32,
+        duration: Duration,
+        gilrs: &mut Gilrs,
+    ) {
+        self.effects.remove(&id);
+
+        let supported = gilrs
+            .connected_gamepad(id)
+            .is_some_and(|gamepad| gamepad.is_ff_supported());
+        if !supported || strength <= 0.0 || duration.is_zero() {
+            return;
+        }
+
+        let ticks = Ticks::from_ms(duration.as_millis().try_into().unwrap_or(u32::MAX));
+        let effect = EffectBuilder::new()
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Strong {
+                    magnitude: (strength * u16::MAX as f32) as u16,
+                },
+                scheduling: Replay {
+                    play_for: ticks,
+                    ..Default::default()
+                },
+                envelope: Default::default(),
+            })
+            .repeat(Repeat::For(ticks))
+            .gamepads(&[id])
+            .finish(gilrs);
+
+        match effect.and_then(|effect| effect.play().map(|_| effect)) {
+            Ok(effect) => {
+                self.effects.insert(id, effect);
+            }
+            Err(e) => println!("Failed to rumble gamepad: {}", e),
+        }
+    }
+}
+
+impl fmt::Debug for GamepadRumble {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GamepadRumble")
+            .field("gamepads", &self.effects.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
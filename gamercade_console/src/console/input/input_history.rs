@@ -0,0 +1,47 @@
+use gamercade_core::InputState;
+
+/// How many frames of input are kept for each player.
+pub const INPUT_HISTORY_LENGTH: usize = 60;
+
+/// A bounded history of a player's inputs, newest first.
+/// Stored as part of the rollback state, so resimulated frames
+/// rebuild it identically.
+#[derive(Debug, Clone)]
+pub struct InputHistory {
+    states: [InputState; INPUT_HISTORY_LENGTH],
+    head: usize,
+    len: usize,
+}
+
+impl Default for InputHistory {
+    fn default() -> Self {
+        Self {
+            states: [InputState::default(); INPUT_HISTORY_LENGTH],
+            head: 0,
+            len: 0,
+        }
+    }
+}
+
+impl InputHistory {
+    pub(crate) fn push(&mut self, state: InputState) {
+        self.head = (self.head + 1) % INPUT_HISTORY_LENGTH;
+        self.states[self.head] = state;
+        self.len = (self.len + 1).min(INPUT_HISTORY_LENGTH);
+    }
+
+    /// The number of frames stored. Less than the max during the first frames.
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the input from the given number of frames ago, where 0 is the
+    /// current frame. Returns a neutral input if the history doesn't reach back that far.
+    pub(crate) fn get(&self, frames_ago: usize) -> InputState {
+        if frames_ago < self.len {
+            self.states[(self.head + INPUT_HISTORY_LENGTH - frames_ago) % INPUT_HISTORY_LENGTH]
+        } else {
+            InputState::default()
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
 use gamercade_core::{ButtonCode, InputState};
 
-use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
 use winit::event::VirtualKeyCode;
 
 use super::key_types::{Analog, AnalogAxis, AnalogDirection, AnalogSide, KeyType};
@@ -24,11 +26,122 @@ impl Analog {
     }
 }
 
-#[derive(Debug)]
+const KEY_BINDINGS_PATH: &str = "key_bindings.json";
+
+/// Keys which the console uses itself as hotkeys, so can't be bound.
+pub(crate) const RESERVED_KEYS: [VirtualKeyCode; 4] = [
+    VirtualKeyCode::Escape,
+    VirtualKeyCode::Space,
+    VirtualKeyCode::F5,
+    VirtualKeyCode::F6,
+];
+
+const fn analog(side: AnalogSide, axis: AnalogAxis, direction: AnalogDirection) -> KeyType {
+    KeyType::Analog(Analog {
+        side,
+        axis,
+        direction,
+    })
+}
+
+/// The order inputs are listed in when remapping the keyboard.
+pub(crate) const KEY_REMAP_ORDER: [KeyType; 24] = [
+    KeyType::ButtonCode(ButtonCode::Up),
+    KeyType::ButtonCode(ButtonCode::Down),
+    KeyType::ButtonCode(ButtonCode::Left),
+    KeyType::ButtonCode(ButtonCode::Right),
+    KeyType::ButtonCode(ButtonCode::A),
+    KeyType::ButtonCode(ButtonCode::B),
+    KeyType::ButtonCode(ButtonCode::C),
+    KeyType::ButtonCode(ButtonCode::D),
+    KeyType::ButtonCode(ButtonCode::Start),
+    KeyType::ButtonCode(ButtonCode::Select),
+    KeyType::ButtonCode(ButtonCode::LeftShoulder),
+    KeyType::ButtonCode(ButtonCode::RightShoulder),
+    KeyType::Trigger(AnalogSide::Left),
+    KeyType::Trigger(AnalogSide::Right),
+    KeyType::ButtonCode(ButtonCode::LeftStick),
+    KeyType::ButtonCode(ButtonCode::RightStick),
+    analog(AnalogSide::Left, AnalogAxis::Y, AnalogDirection::Positive),
+    analog(AnalogSide::Left, AnalogAxis::Y, AnalogDirection::Negative),
+    analog(AnalogSide::Left, AnalogAxis::X, AnalogDirection::Negative),
+    analog(AnalogSide::Left, AnalogAxis::X, AnalogDirection::Positive),
+    analog(AnalogSide::Right, AnalogAxis::Y, AnalogDirection::Positive),
+    analog(AnalogSide::Right, AnalogAxis::Y, AnalogDirection::Negative),
+    analog(AnalogSide::Right, AnalogAxis::X, AnalogDirection::Negative),
+    analog(AnalogSide::Right, AnalogAxis::X, AnalogDirection::Positive),
+];
+
+#[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct KeyBindings {
     pub buttons: HashMap<VirtualKeyCode, KeyType>,
 }
 
+impl KeyBindings {
+    /// Loads the bindings from the settings file, or returns the defaults
+    /// if they don't exist.
+    pub(crate) fn load() -> Self {
+        match Self::try_load(&PathBuf::from(KEY_BINDINGS_PATH)) {
+            Ok(bindings) => bindings,
+            Err(e) => {
+                println!("Using default key bindings: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    fn try_load(path: &PathBuf) -> Result<Self, String> {
+        let file = fs::read(path).map_err(|e| e.to_string())?;
+        serde_json::from_slice(&file).map_err(|e| e.to_string())
+    }
+
+    pub(crate) fn try_save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(KEY_BINDINGS_PATH, json).map_err(|e| e.to_string())
+    }
+
+    /// The keys bound to the input, in no particular order.
+    pub(crate) fn keys_for(&self, key_type: KeyType) -> impl Iterator<Item = VirtualKeyCode> + '_ {
+        self.buttons
+            .iter()
+            .filter(move |(_, bound)| **bound == key_type)
+            .map(|(key, _)| *key)
+    }
+
+    /// The inputs of the keys which are held.
+    pub(crate) fn input_state(&self, key_held: impl Fn(VirtualKeyCode) -> bool) -> InputState {
+        let mut output = InputState::default();
+
+        self.buttons.iter().for_each(|(code, input)| {
+            if key_held(*code) {
+                match input {
+                    KeyType::ButtonCode(code) => output.buttons.enable_button(*code),
+                    KeyType::Analog(emulated) => emulated.adjust_input_state(&mut output),
+                    KeyType::Trigger(side) => match side {
+                        AnalogSide::Left => {
+                            output.buttons.enable_button(ButtonCode::LeftTrigger);
+                            output.left_trigger.set_value(1.0);
+                        }
+                        AnalogSide::Right => {
+                            output.buttons.enable_button(ButtonCode::RightTrigger);
+                            output.right_trigger.set_value(1.0)
+                        }
+                    },
+                }
+            }
+        });
+
+        output
+    }
+
+    /// Binds the key to the input, replacing the keys it was bound to before.
+    /// The key stops doing whatever it did before.
+    pub(crate) fn bind(&mut self, key: VirtualKeyCode, key_type: KeyType) {
+        self.buttons.retain(|_, bound| *bound != key_type);
+        self.buttons.insert(key, key_type);
+    }
+}
+
 impl Default for KeyBindings {
     fn default() -> Self {
         let buttons = [
@@ -143,3 +256,34 @@ impl Default for KeyBindings {
         Self { buttons }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebinding_replaces_the_old_key() {
+        let mut bindings = KeyBindings::default();
+        let a = KeyType::ButtonCode(ButtonCode::A);
+        let b = KeyType::ButtonCode(ButtonCode::B);
+
+        // I was bound to B, and U to A
+        bindings.bind(VirtualKeyCode::I, a);
+
+        assert_eq!(
+            bindings.keys_for(a).collect::<Vec<_>>(),
+            [VirtualKeyCode::I]
+        );
+        assert_eq!(bindings.keys_for(b).count(), 0);
+        assert!(!bindings.buttons.contains_key(&VirtualKeyCode::U));
+    }
+
+    #[test]
+    fn test_every_default_binding_can_be_remapped() {
+        let bindings = KeyBindings::default();
+        assert!(bindings
+            .buttons
+            .values()
+            .all(|key_type| KEY_REMAP_ORDER.contains(key_type)));
+    }
+}
@@ -1,31 +1,51 @@
 use gamercade_core::ButtonCode;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum KeyType {
     ButtonCode(ButtonCode),
     Analog(Analog),
     Trigger(AnalogSide),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct Analog {
     pub(crate) side: AnalogSide,
     pub(crate) axis: AnalogAxis,
     pub(crate) direction: AnalogDirection,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum AnalogSide {
     Left,
     Right,
 }
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum AnalogAxis {
     X,
     Y,
 }
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum AnalogDirection {
     Positive,
     Negative,
 }
+
+impl KeyType {
+    /// The name shown when remapping.
+    pub(crate) fn name(&self) -> String {
+        match self {
+            KeyType::ButtonCode(code) => format!("{:?}", code),
+            KeyType::Trigger(side) => format!("{:?} Trigger", side),
+            KeyType::Analog(analog) => {
+                let direction = match (analog.axis, analog.direction) {
+                    (AnalogAxis::X, AnalogDirection::Negative) => "Left",
+                    (AnalogAxis::X, AnalogDirection::Positive) => "Right",
+                    (AnalogAxis::Y, AnalogDirection::Negative) => "Down",
+                    (AnalogAxis::Y, AnalogDirection::Positive) => "Up",
+                };
+                format!("{:?} Stick {}", analog.side, direction)
+            }
+        }
+    }
+}
@@ -1,47 +1,167 @@
-use gamercade_core::{ButtonCode, InputState};
-use gilrs::{Axis, Button, Gamepad, GamepadId, Gilrs};
+use std::{collections::VecDeque, time::Duration};
+
+use gamercade_core::{ButtonCode, InputState, MouseButtonCode, MouseState, MAX_PLAYERS};
+use gilrs::{Event, EventType, Gamepad, GamepadId, Gilrs};
+use pixels::Pixels;
 
 use super::{
-    gamepad_bindings::GamepadBindings,
-    key_types::{AnalogSide, KeyType},
-    InputMode, KeyBindings,
+    gamepad_bindings::GamepadBindings, gamepad_profiles::GamepadProfiles,
+    gamepad_rumble::GamepadRumble, InputMode, KeyBindings, VirtualKeyCode,
 };
 
+/// The most players which can share one machine.
+pub const MAX_LOCAL_PLAYERS: usize = MAX_PLAYERS;
+
+/// How many of the latest gamepad events are kept for the remapping window.
+const RECENT_EVENTS_MAX: usize = 32;
+
 #[derive(Debug)]
 pub struct LocalInputManager {
-    keybinds: KeyBindings,
-    gamepad_binds: GamepadBindings,
+    pub(crate) keybinds: KeyBindings,
+    pub(crate) gamepad_profiles: GamepadProfiles,
     pub(crate) input_mode: InputMode,
+
+    /// The gamepads of the other players sharing this machine, in order.
+    /// The first local player uses the input mode instead.
+    pub(crate) local_gamepads: Vec<Option<GamepadId>>,
+
+    rumble: GamepadRumble,
+
+    /// The latest gamepad events, so the remapping window can look at
+    /// them without taking them from gilrs before they're handled here.
+    recent_events: VecDeque<Event>,
 }
 
 impl LocalInputManager {
     pub fn new(input_mode: InputMode) -> Self {
         Self {
-            keybinds: KeyBindings::default(),
-            gamepad_binds: GamepadBindings::default(),
+            keybinds: KeyBindings::load(),
+            gamepad_profiles: GamepadProfiles::load(),
             input_mode,
+            local_gamepads: Vec::new(),
+            rumble: GamepadRumble::default(),
+            recent_events: VecDeque::new(),
+        }
+    }
+
+    /// Sets how many players share this machine, keeping any gamepads already assigned.
+    pub fn set_local_player_count(&mut self, count: usize) {
+        self.local_gamepads.resize(count.saturating_sub(1), None);
+    }
+
+    /// The inputs of one of the players on this machine. The first uses the input mode
+    /// and the mouse, and the others each use their own gamepad.
+    pub fn generate_local_input_state(
+        &mut self,
+        local_player: usize,
+        helper: &winit_input_helper::WinitInputHelper,
+        gilrs: &Gilrs,
+        pixels: &Pixels,
+    ) -> InputState {
+        let index = match local_player.checked_sub(1) {
+            Some(index) => index,
+            None => return self.generate_input_state(helper, gilrs, pixels),
+        };
+
+        match self.local_gamepads.get(index).copied().flatten() {
+            Some(id) => self.new_gamepad_state(id, gilrs),
+            None => InputState::default(),
+        }
+    }
+
+    /// The gamepad of one of the players on this machine, if they're using one.
+    fn local_gamepad(&self, local_player: usize) -> Option<GamepadId> {
+        match local_player.checked_sub(1) {
+            Some(index) => self.local_gamepads.get(index).copied().flatten(),
+            None => match self.input_mode {
+                InputMode::Gamepad(id) => Some(id),
+                InputMode::Emulated => None,
+            },
+        }
+    }
+
+    /// Rumbles the gamepad of one of the players on this machine.
+    /// Players without a gamepad are ignored.
+    pub fn rumble(
+        &mut self,
+        local_player: usize,
+        strength: f32,
+        duration: Duration,
+        gilrs: &mut Gilrs,
+    ) {
+        if let Some(id) = self.local_gamepad(local_player) {
+            self.rumble.set(id, strength, duration, gilrs);
+        }
+    }
+
+    /// Handles the pending gamepad events. A disconnected gamepad keeps its player,
+    /// and its inputs are left neutral until it comes back. A newly connected gamepad
+    /// is given to the first player without a connected one.
+    pub fn process_gamepad_events(&mut self, gilrs: &mut Gilrs) {
+        while let Some(event) = gilrs.next_event() {
+            if event.event == EventType::Connected {
+                self.assign_gamepad(event.id, gilrs);
+            }
+
+            if self.recent_events.len() == RECENT_EVENTS_MAX {
+                self.recent_events.pop_front();
+            }
+            self.recent_events.push_back(event);
         }
     }
 
+    /// Takes the gamepad events handled since this was last called, oldest first.
+    pub(crate) fn take_recent_events(&mut self) -> VecDeque<Event> {
+        std::mem::take(&mut self.recent_events)
+    }
+
+    fn assign_gamepad(&mut self, id: GamepadId, gilrs: &Gilrs) {
+        if self.input_mode == InputMode::Gamepad(id) || self.local_gamepads.contains(&Some(id)) {
+            return;
+        }
+
+        let free_slot = self.local_gamepads.iter_mut().find(|slot| match slot {
+            Some(assigned) => gilrs.connected_gamepad(*assigned).is_none(),
+            None => true,
+        });
+
+        if let Some(slot) = free_slot {
+            *slot = Some(id);
+        }
+    }
+
+    /// The mouse is used in either input mode.
     pub fn generate_input_state(
-        &self,
+        &mut self,
         helper: &winit_input_helper::WinitInputHelper,
         gilrs: &Gilrs,
+        pixels: &Pixels,
     ) -> InputState {
-        match self.input_mode {
+        let mut output = match self.input_mode {
             InputMode::Emulated => self.new_emulated_state(helper),
             InputMode::Gamepad(id) => self.new_gamepad_state(id, gilrs),
-        }
+        };
+
+        output.mouse = generate_mouse_state(helper, pixels);
+        output
     }
 
     fn new_emulated_state(&self, helper: &winit_input_helper::WinitInputHelper) -> InputState {
-        generate_emulated_state(&self.keybinds, helper)
+        self.new_keyboard_state(|code| helper.key_held(code))
+    }
+
+    /// The inputs of the held keys, using the same key bindings as the console.
+    /// Useful for embedders which get their keyboard input from somewhere else.
+    pub fn new_keyboard_state(&self, key_held: impl Fn(VirtualKeyCode) -> bool) -> InputState {
+        self.keybinds.input_state(key_held)
     }
 
-    //TODO: This
-    fn new_gamepad_state(&self, id: GamepadId, gilrs: &Gilrs) -> InputState {
+    /// Bindings are looked up per device, so profiles are applied
+    /// as soon as a device is connected.
+    pub fn new_gamepad_state(&mut self, id: GamepadId, gilrs: &Gilrs) -> InputState {
         if let Some(gamepad) = gilrs.connected_gamepad(id) {
-            generate_gamepad_state(&self.gamepad_binds, &gamepad)
+            let binds = self.gamepad_profiles.bindings(&gamepad);
+            generate_gamepad_state(binds, &gamepad)
         } else {
             InputState::default()
         }
@@ -57,52 +177,56 @@ fn generate_gamepad_state(binds: &GamepadBindings, gamepad: &Gamepad) -> InputSt
         }
     });
 
-    if let Some(axis) = gamepad.axis_data(Axis::LeftStickX) {
-        output.left_stick.set_x_axis(axis.value())
-    }
-    if let Some(axis) = gamepad.axis_data(Axis::LeftStickY) {
-        output.left_stick.set_y_axis(axis.value())
-    }
-
-    if let Some(axis) = gamepad.axis_data(Axis::RightStickX) {
-        output.right_stick.set_x_axis(axis.value())
-    }
-    if let Some(axis) = gamepad.axis_data(Axis::RightStickY) {
-        output.right_stick.set_y_axis(axis.value())
-    }
+    binds.axes.iter().for_each(|(axis, binding)| {
+        if let Some(data) = gamepad.axis_data(*axis) {
+            binding.adjust_input_state(data.value(), &mut output)
+        }
+    });
 
-    if let Some(trigger) = gamepad.button_data(Button::LeftTrigger2) {
-        output.left_trigger.set_value(trigger.value())
+    if let Some(value) = trigger_value(binds, gamepad, ButtonCode::LeftTrigger) {
+        output.left_trigger.set_value(value)
     }
-    if let Some(trigger) = gamepad.button_data(Button::RightTrigger2) {
-        output.right_trigger.set_value(trigger.value())
+    if let Some(value) = trigger_value(binds, gamepad, ButtonCode::RightTrigger) {
+        output.right_trigger.set_value(value)
     }
 
     output
 }
 
-fn generate_emulated_state(
-    binds: &KeyBindings,
+/// How far the buttons bound to the trigger are pressed, if any are bound.
+fn trigger_value(binds: &GamepadBindings, gamepad: &Gamepad, trigger: ButtonCode) -> Option<f32> {
+    binds
+        .buttons
+        .iter()
+        .filter(|(_, code)| **code == trigger)
+        .filter_map(|(button, _)| gamepad.button_data(*button))
+        .map(|data| data.value())
+        .reduce(f32::max)
+}
+
+/// Positions outside of the screen are clamped to its edges.
+fn generate_mouse_state(
     input_helper: &winit_input_helper::WinitInputHelper,
-) -> InputState {
-    let mut output = InputState::default();
+    pixels: &Pixels,
+) -> MouseState {
+    let mut output = MouseState::default();
 
-    binds.buttons.iter().for_each(|(code, input)| {
-        if input_helper.key_held(*code) {
-            match input {
-                KeyType::ButtonCode(code) => output.buttons.enable_button(*code),
-                KeyType::Analog(emulated) => emulated.adjust_input_state(&mut output),
-                KeyType::Trigger(side) => match side {
-                    AnalogSide::Left => {
-                        output.buttons.enable_button(ButtonCode::LeftTrigger);
-                        output.left_trigger.set_value(1.0);
-                    }
-                    AnalogSide::Right => {
-                        output.buttons.enable_button(ButtonCode::RightTrigger);
-                        output.right_trigger.set_value(1.0)
-                    }
-                },
-            }
+    if let Some(position) = input_helper.mouse() {
+        let (x, y) = pixels
+            .window_pos_to_pixel(position)
+            .unwrap_or_else(|position| pixels.clamp_pixel_pos(position));
+        output.set_position(x, y);
+    }
+
+    [
+        (0, MouseButtonCode::Left),
+        (1, MouseButtonCode::Right),
+        (2, MouseButtonCode::Middle),
+    ]
+    .into_iter()
+    .for_each(|(button, code)| {
+        if input_helper.mouse_held(button) {
+            output.enable_button(code)
         }
     });
 
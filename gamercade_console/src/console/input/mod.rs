@@ -1,13 +1,21 @@
 mod gamepad_bindings;
+mod gamepad_profiles;
+mod gamepad_rumble;
+mod input_history;
 mod key_bindings;
 mod key_types;
 mod local_input_manager;
 mod player_input_entry;
 
+pub(crate) use gamepad_bindings::{AxisBinding, GamepadBindings, BINDABLE_AXES, BINDABLE_BUTTONS};
+pub(crate) use gamepad_profiles::{device_key, GamepadProfiles};
 use gilrs::GamepadId;
-use key_bindings::*;
+pub use input_history::*;
+pub(crate) use key_bindings::{KeyBindings, KEY_REMAP_ORDER, RESERVED_KEYS};
+pub(crate) use key_types::{AnalogAxis, AnalogSide};
 pub use local_input_manager::*;
 pub use player_input_entry::*;
+pub use winit::event::VirtualKeyCode;
 
 #[derive(Debug, PartialEq, Eq)]
 #[allow(dead_code)]
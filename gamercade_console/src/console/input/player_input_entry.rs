@@ -1,8 +1,19 @@
 use gamercade_core::{Buttons, InputState};
 
+use super::InputHistory;
+
 // TOOD: Move this to SoA instead of AoS for perf?
 #[derive(Debug, Default, Clone)]
 pub struct PlayerInputEntry {
     pub(crate) previous: Buttons,
     pub(crate) current: InputState,
+    pub(crate) history: InputHistory,
+}
+
+impl PlayerInputEntry {
+    /// Sets the input for this frame, and records it in the history.
+    pub(crate) fn push_input_state(&mut self, state: InputState) {
+        self.current = state;
+        self.history.push(state);
+    }
 }
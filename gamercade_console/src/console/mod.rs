@@ -1,24 +1,34 @@
 mod bindings;
 mod contexts;
+mod embedded_runner;
 mod input;
 mod network;
+mod replay_playback;
 mod wasm_console;
 
 pub use contexts::Contexts;
+pub use embedded_runner::EmbeddedRunner;
 use gamercade_fs::Rom;
 use ggrs::{Config, GGRSRequest};
 pub use input::*;
-pub use network::{SessionDescriptor, WasmConsoleState};
-pub use wasm_console::WasmConsole;
+pub use network::{
+    RemotePlayerStats, SessionDescriptor, SessionStats, WasmConsoleState, DEFAULT_CHECK_DISTANCE,
+    DEFAULT_INPUT_DELAY, MAX_CHECK_DISTANCE, MAX_INPUT_DELAY, MAX_SPECTATORS,
+};
+pub use replay_playback::{ReplayPlayback, PLAYBACK_AUDIO_BUFFER_SIZE};
+pub use wasm_console::{LoadedGame, WasmConsole, WasmConsoleError, DEFAULT_FUEL_BUDGET};
 
 pub trait Console: Sized + Config {
-    fn call_init(&mut self);
-    fn call_update(&mut self);
-    fn call_draw(&mut self);
+    /// Calls one of the game's functions. Returns an error if the game traps,
+    /// including the reason and a backtrace when one is available.
+    fn call_init(&mut self) -> Result<(), String>;
+    fn call_update(&mut self) -> Result<(), String>;
+    fn call_draw(&mut self) -> Result<(), String>;
 
     fn rom(&self) -> &Rom;
 
     fn blit(&self, buffer: &mut [u8]);
 
-    fn handle_requests(&mut self, requests: Vec<GGRSRequest<Self>>);
+    /// Returns an error if the game traps, after which it shouldn't be run any further.
+    fn handle_requests(&mut self, requests: Vec<GGRSRequest<Self>>) -> Result<(), String>;
 }
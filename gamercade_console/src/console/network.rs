@@ -1,23 +1,96 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 
 use gamercade_core::{Buttons, InputState};
 use gamercade_sound_engine::SoundEngineData;
-use ggrs::{Config, PlayerType};
-use wasmtime::Global;
+use ggrs::{Config, Frame, P2PSession, PlayerHandle, PlayerType};
+use hashbrown::{hash_map::Entry, HashMap};
+use wasmtime::Val;
 
-use super::WasmConsole;
+use super::{wasm_console::state_checksum, InputHistory, WasmConsole};
 
 #[derive(Clone)]
 pub struct WasmConsoleState {
     pub(crate) previous_buttons: Box<[Buttons]>,
-    pub(crate) memories: Vec<Vec<u8>>,
-    pub(crate) mutable_globals: Vec<Global>,
+    pub(crate) input_histories: Box<[InputHistory]>,
+    pub(crate) memories: Vec<MemorySnapshot>,
+    pub(crate) mutable_globals: Vec<Val>,
     pub(crate) sound_engine_data: SoundEngineData,
+    pub(crate) rng_state: u64,
 }
 
 pub struct SaveStateDefinition {
     pub(crate) memories: Vec<String>,
     pub(crate) mutable_globals: Vec<String>,
+
+    /// The memories as of the last capture or restore, to share unchanged chunks with.
+    pub(crate) previous: Vec<MemorySnapshot>,
+}
+
+/// How finely memory is split up when it's saved. Smaller chunks share more between
+/// saves, at the cost of more bookkeeping per chunk.
+const SNAPSHOT_CHUNK_SIZE: usize = 4096;
+
+/// A copy of a memory, split into chunks. Most frames only change a little memory,
+/// so chunks which match the previous snapshot share its copy instead of making a new one.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MemorySnapshot {
+    chunks: Vec<Arc<[u8]>>,
+
+    /// The hash of each chunk, which is shared along with the chunk so
+    /// checksums only need to hash the chunks which changed.
+    hashes: Vec<u64>,
+}
+
+/// The starting value of a hash.
+pub(crate) const HASH_SEED: u64 = 0xcbf29ce484222325;
+
+/// Mixes the words into the hash. This is much faster than hashing byte by byte,
+/// and is only used to detect desyncs, so doesn't need to resist tampering.
+pub(crate) fn hash_words(hash: u64, words: impl IntoIterator<Item = u64>) -> u64 {
+    const MULTIPLIER: u64 = 0x517cc1b727220a95;
+
+    words.into_iter().fold(hash, |hash, word| {
+        (hash.rotate_left(5) ^ word).wrapping_mul(MULTIPLIER)
+    })
+}
+
+fn hash_chunk(chunk: &[u8]) -> u64 {
+    let words = chunk.chunks(8).map(|bytes| {
+        let mut word = [0; 8];
+        word[..bytes.len()].copy_from_slice(bytes);
+        u64::from_le_bytes(word)
+    });
+
+    hash_words(HASH_SEED, words.chain(Some(chunk.len() as u64)))
+}
+
+impl MemorySnapshot {
+    /// Copies the memory, sharing any chunks which are the same in the previous snapshot.
+    pub(crate) fn capture(data: &[u8], previous: &Self) -> Self {
+        let (chunks, hashes) = data
+            .chunks(SNAPSHOT_CHUNK_SIZE)
+            .enumerate()
+            .map(|(index, chunk)| match previous.chunks.get(index) {
+                Some(shared) if **shared == *chunk => (shared.clone(), previous.hashes[index]),
+                _ => (Arc::from(chunk), hash_chunk(chunk)),
+            })
+            .unzip();
+
+        Self { chunks, hashes }
+    }
+
+    /// Writes the whole snapshot back to the start of the memory.
+    pub(crate) fn restore(&self, destination: &mut [u8]) {
+        destination
+            .chunks_mut(SNAPSHOT_CHUNK_SIZE)
+            .zip(&self.chunks)
+            .for_each(|(destination, chunk)| destination.copy_from_slice(chunk));
+    }
+
+    /// The hash of each chunk, in order.
+    pub(crate) fn chunk_hashes(&self) -> &[u64] {
+        &self.hashes
+    }
 }
 
 impl Config for WasmConsole {
@@ -26,9 +99,324 @@ impl Config for WasmConsole {
     type Address = SocketAddr;
 }
 
+/// The most spectators a session can have. Every spectator is sent each
+/// confirmed input, so the host's upload grows with each one.
+pub const MAX_SPECTATORS: usize = 4;
+
+/// The default input delay for networked games, in frames. On a LAN, 0 or 1 frames
+/// is usually enough. Over the internet, 2 or 3 frames hides most rollbacks
+/// without feeling sluggish.
+pub const DEFAULT_INPUT_DELAY: usize = 2;
+
+/// The most input delay which can be set. Past this, it is better to roll back.
+pub const MAX_INPUT_DELAY: usize = 6;
+
+/// The connection to a remote player, as reported by GGRS.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RemotePlayerStats {
+    pub handle: PlayerHandle,
+
+    /// The round trip time, in milliseconds.
+    pub ping: u128,
+
+    /// Packets which haven't been acknowledged yet. This grows with latency and packet loss.
+    pub send_queue_len: usize,
+    pub kbps_sent: usize,
+
+    /// How many frames the remote player is ahead of the local one.
+    pub local_frames_behind: i32,
+}
+
+/// Statistics about the running session, updated every frame for a netplay HUD.
+#[derive(Clone, Debug, Default)]
+pub struct SessionStats {
+    /// Remote players who have synchronized, by handle.
+    pub remote_players: Vec<RemotePlayerStats>,
+
+    /// How many frames the local player is ahead, which is their frame advantage.
+    pub frames_ahead: i32,
+
+    /// How many frames were resimulated by the last rollback, and in total.
+    pub last_rollback_frames: usize,
+    pub total_rollback_frames: usize,
+}
+
+impl SessionStats {
+    /// Collects the latest statistics of each remote player.
+    pub(crate) fn update(&mut self, session: &P2PSession<WasmConsole>) {
+        self.frames_ahead = session.frames_ahead();
+        self.remote_players = session
+            .remote_player_handles()
+            .into_iter()
+            .filter_map(|handle| {
+                let stats = session.network_stats(handle).ok()?;
+                Some(RemotePlayerStats {
+                    handle,
+                    ping: stats.ping,
+                    send_queue_len: stats.send_queue_len,
+                    kbps_sent: stats.kbps_sent,
+                    local_frames_behind: stats.local_frames_behind,
+                })
+            })
+            .collect();
+    }
+
+    /// Records a rollback from the current frame to an earlier one.
+    pub(crate) fn record_rollback(&mut self, current_frame: Frame, loaded_frame: Frame) {
+        let frames = (current_frame - loaded_frame).max(0) as usize;
+        self.last_rollback_frames = frames;
+        self.total_rollback_frames += frames;
+    }
+}
+
+/// The default number of frames a sync test rolls back and resimulates each frame.
+/// Checksums can only be compared with a distance of at least 2.
+pub const DEFAULT_CHECK_DISTANCE: usize = 2;
+
+/// The largest check distance, which must be less than the prediction window.
+pub const MAX_CHECK_DISTANCE: usize = 7;
+
+/// Checksums of each part of the state, so a mismatch can say which part changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct StateChecksums {
+    memories: u128,
+    mutable_globals: u128,
+    rng_state: u64,
+}
+
+impl StateChecksums {
+    fn new(frame: &SavedFrame) -> Self {
+        Self {
+            memories: state_checksum(&frame.memories, &[], 0),
+            mutable_globals: state_checksum(&[], &frame.mutable_globals, 0),
+            rng_state: frame.rng_state,
+        }
+    }
+}
+
+/// The parts of a saved frame's state which are checked for determinism.
+/// The memories share their chunks with the saved state, so are cheap to keep.
+pub(crate) struct SavedFrame {
+    pub(crate) memories: Vec<MemorySnapshot>,
+    pub(crate) mutable_globals: Vec<Val>,
+    pub(crate) rng_state: u64,
+}
+
+/// Remembers the checksums of each frame when it was first saved. When a sync test
+/// saves a resimulated frame, any part which doesn't match is nondeterministic.
+#[derive(Default)]
+pub(crate) struct ChecksumHistory {
+    frames: HashMap<Frame, (u128, SavedFrame)>,
+    mismatch: Option<String>,
+}
+
+impl ChecksumHistory {
+    /// How many frames are kept, which must cover the check distance.
+    const LENGTH: Frame = 16;
+
+    /// Compares the checksum against the first one saved for the frame. The checksums
+    /// of each part are only worked out when they don't match, to say which part changed.
+    pub(crate) fn check(&mut self, frame: Frame, checksum: u128, saved: SavedFrame) {
+        self.frames.retain(|saved, _| saved + Self::LENGTH > frame);
+
+        let (first_checksum, first) = match self.frames.entry(frame) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                entry.insert((checksum, saved));
+                return;
+            }
+        };
+
+        if self.mismatch.is_some() || *first_checksum == checksum {
+            return;
+        }
+
+        let checksums = StateChecksums::new(&saved);
+        let first = StateChecksums::new(first);
+
+        let mut parts = Vec::new();
+        if first.memories != checksums.memories {
+            parts.push("memory");
+        }
+        if first.mutable_globals != checksums.mutable_globals {
+            parts.push("mutable globals");
+        }
+        if first.rng_state != checksums.rng_state {
+            parts.push("random state");
+        }
+
+        self.mismatch = Some(format!(
+            "Resimulating frame {} changed the {}.",
+            frame,
+            parts.join(" and ")
+        ));
+    }
+
+    /// Describes the first mismatch found, if any.
+    pub(crate) fn mismatch(&self) -> Option<&str> {
+        self.mismatch.as_deref()
+    }
+}
+
 #[derive(Clone)]
 pub struct SessionDescriptor {
     pub num_players: usize,
     pub player_types: Box<[PlayerType<SocketAddr>]>,
+
+    /// Addresses which are sent the confirmed inputs, so they can watch without
+    /// affecting the game. They join after the players, in this order.
+    pub spectators: Box<[SocketAddr]>,
     pub port: u16,
+
+    /// How many frames local inputs are delayed, trading latency for fewer rollbacks.
+    /// This only applies to the local players. Each player sets their own delay.
+    pub input_delay: usize,
+}
+
+impl SessionDescriptor {
+    /// True if every player is on this machine, and no one is watching.
+    pub fn is_offline(&self) -> bool {
+        self.spectators.is_empty()
+            && self
+                .player_types
+                .iter()
+                .all(|player| *player == PlayerType::Local)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    /// How many chunks the snapshot has its own copy of, rather than sharing with the previous one.
+    fn new_chunks(snapshot: &MemorySnapshot, previous: &MemorySnapshot) -> usize {
+        snapshot
+            .chunks
+            .iter()
+            .zip(&previous.chunks)
+            .filter(|(chunk, shared)| !Arc::ptr_eq(chunk, shared))
+            .count()
+    }
+
+    #[test]
+    fn test_memory_snapshot_shares_unchanged_chunks() {
+        let mut memory = vec![0; SNAPSHOT_CHUNK_SIZE * 16];
+        let first = MemorySnapshot::capture(&memory, &MemorySnapshot::default());
+
+        memory[SNAPSHOT_CHUNK_SIZE * 3 + 7] = 1;
+        let second = MemorySnapshot::capture(&memory, &first);
+        assert_eq!(new_chunks(&second, &first), 1);
+
+        let mut restored = vec![0xff; memory.len()];
+        second.restore(&mut restored);
+        assert_eq!(restored, memory);
+
+        first.restore(&mut restored);
+        assert!(restored.iter().all(|byte| *byte == 0));
+    }
+
+    /// Compares saving a 1 MiB memory in full each frame against sharing unchanged chunks,
+    /// when each frame changes a few chunks. Run with `--ignored --nocapture` to see the results.
+    #[test]
+    #[ignore]
+    fn bench_memory_snapshots() {
+        const FRAMES: usize = 600;
+        const MEMORY_SIZE: usize = 1024 * 1024;
+        let mut memory = vec![0u8; MEMORY_SIZE];
+
+        let start = Instant::now();
+        let full = (0..FRAMES)
+            .map(|frame| {
+                memory[(frame * 7919) % MEMORY_SIZE] ^= 1;
+                memory.to_vec()
+            })
+            .collect::<Vec<_>>();
+        let full_time = start.elapsed();
+        let full_bytes = full.len() * memory.len();
+
+        let start = Instant::now();
+        let mut previous = MemorySnapshot::default();
+        let mut chunked_bytes = 0;
+        for frame in 0..FRAMES {
+            memory[(frame * 7919) % MEMORY_SIZE] ^= 1;
+            let snapshot = MemorySnapshot::capture(&memory, &previous);
+            chunked_bytes += if previous.chunks.is_empty() {
+                memory.len()
+            } else {
+                new_chunks(&snapshot, &previous) * SNAPSHOT_CHUNK_SIZE
+            };
+            previous = snapshot;
+        }
+        let chunked_time = start.elapsed();
+
+        println!(
+            "full copies: {} KiB in {:?}, shared chunks: {} KiB in {:?}",
+            full_bytes / 1024,
+            full_time,
+            chunked_bytes / 1024,
+            chunked_time
+        );
+        assert!(chunked_bytes * 10 < full_bytes);
+    }
+
+    fn saved_frame(memory: u8, rng_state: u64) -> (u128, SavedFrame) {
+        let memories = vec![MemorySnapshot::capture(
+            &[memory; 16],
+            &MemorySnapshot::default(),
+        )];
+        let checksum = state_checksum(&memories, &[], rng_state);
+        let saved = SavedFrame {
+            memories,
+            mutable_globals: Vec::new(),
+            rng_state,
+        };
+        (checksum, saved)
+    }
+
+    fn check(history: &mut ChecksumHistory, frame: Frame, memory: u8, rng_state: u64) {
+        let (checksum, saved) = saved_frame(memory, rng_state);
+        history.check(frame, checksum, saved);
+    }
+
+    #[test]
+    fn test_checksum_history_names_the_changed_part() {
+        let mut history = ChecksumHistory::default();
+
+        check(&mut history, 0, 1, 1);
+        check(&mut history, 1, 2, 1);
+        check(&mut history, 0, 1, 1);
+        assert_eq!(history.mismatch(), None);
+
+        check(&mut history, 1, 3, 1);
+        assert_eq!(
+            history.mismatch(),
+            Some("Resimulating frame 1 changed the memory.")
+        );
+
+        // Only the first mismatch is kept
+        check(&mut history, 0, 1, 2);
+        assert_eq!(
+            history.mismatch(),
+            Some("Resimulating frame 1 changed the memory.")
+        );
+    }
+
+    #[test]
+    fn test_shared_chunks_keep_their_hash() {
+        let mut memory = vec![0; SNAPSHOT_CHUNK_SIZE * 4];
+        let first = MemorySnapshot::capture(&memory, &MemorySnapshot::default());
+
+        memory[SNAPSHOT_CHUNK_SIZE * 2] = 1;
+        let second = MemorySnapshot::capture(&memory, &first);
+        let fresh = MemorySnapshot::capture(&memory, &MemorySnapshot::default());
+
+        assert_eq!(second.chunk_hashes(), fresh.chunk_hashes());
+        assert_ne!(second.chunk_hashes()[2], first.chunk_hashes()[2]);
+        assert_eq!(
+            state_checksum(&[second], &[], 0),
+            state_checksum(&[fresh], &[], 0)
+        );
+    }
 }
@@ -0,0 +1,129 @@
+use gamercade_fs::Replay;
+use ggrs::Frame;
+
+use super::{WasmConsole, WasmConsoleState};
+
+/// How many audio sync messages can be queued up before the audio thread reads them.
+/// Playback never rolls back, so this doesn't need to cover a prediction window.
+pub const PLAYBACK_AUDIO_BUFFER_SIZE: usize = 8;
+
+/// How many frames apart the snapshots are. Seeking runs forward from the closest
+/// snapshot before the target, so this trades memory for seek time.
+const SNAPSHOT_INTERVAL: usize = 120;
+
+/// Runs the console from the inputs of a replay, in place of a session.
+/// Playback can be paused, stepped a frame at a time, and seeked to any frame.
+pub struct ReplayPlayback {
+    replay: Replay,
+    frame: usize,
+    paused: bool,
+
+    /// States saved while playing, oldest first.
+    snapshots: Vec<(usize, WasmConsoleState)>,
+}
+
+impl ReplayPlayback {
+    /// Plays the replay on a console which has just been started. Fails if the
+    /// replay was recorded with a different game or number of players.
+    pub fn new(replay: Replay, console: &WasmConsole) -> Result<Self, String> {
+        if replay.rom_checksum != console.rom.checksum() {
+            return Err(String::from(
+                "The replay was recorded with a different game.",
+            ));
+        }
+
+        let num_players = console.store.data().input_context.input_entries.len();
+        if replay.num_players != num_players {
+            return Err(format!(
+                "The replay has {} players, but the game was started with {}.",
+                replay.num_players, num_players
+            ));
+        }
+
+        Ok(Self {
+            replay,
+            frame: 0,
+            paused: false,
+            snapshots: Vec::new(),
+        })
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// The next frame to be played.
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.replay.len()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Runs the next frame, unless paused or finished.
+    pub fn tick(&mut self, console: &mut WasmConsole) -> Result<(), String> {
+        if self.paused {
+            return Ok(());
+        }
+
+        self.step(console)
+    }
+
+    /// Runs the next frame, even while paused. Does nothing once finished.
+    pub fn step(&mut self, console: &mut WasmConsole) -> Result<(), String> {
+        let inputs = match self.replay.frame(self.frame) {
+            Some(inputs) => inputs,
+            None => return Ok(()),
+        };
+
+        let is_new_frame = self
+            .snapshots
+            .last()
+            .is_none_or(|(snapshot_frame, _)| *snapshot_frame < self.frame);
+
+        if self.frame.is_multiple_of(SNAPSHOT_INTERVAL) && is_new_frame {
+            let state = console.save_frame(self.frame as Frame);
+            self.snapshots.push((self.frame, state));
+        }
+
+        console.advance_session_frame(inputs)?;
+        self.frame += 1;
+
+        Ok(())
+    }
+
+    /// Jumps to the frame, by loading the closest snapshot before it and running
+    /// forward from there. Frames past the end of the replay jump to the end.
+    pub fn seek(&mut self, console: &mut WasmConsole, frame: usize) -> Result<(), String> {
+        let target = frame.min(self.replay.len());
+
+        let snapshot = self
+            .snapshots
+            .iter()
+            .rev()
+            .find(|(snapshot_frame, _)| *snapshot_frame <= target);
+
+        // Going forward only needs the snapshot if it skips some frames
+        if let Some((snapshot_frame, state)) = snapshot {
+            if target < self.frame || *snapshot_frame > self.frame {
+                console.load_frame(state.clone(), *snapshot_frame as Frame);
+                self.frame = *snapshot_frame;
+            }
+        }
+
+        while self.frame < target {
+            self.step(console)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,18 +1,25 @@
-use std::sync::Arc;
+use std::{fmt, path::PathBuf, sync::Arc};
 
+use gamercade_core::InputState;
 use gamercade_sound_engine::{SoundEngine, SoundEngineData, SoundRomInstance};
-use ggrs::GGRSRequest;
-use wasmtime::{Engine, ExternType, Instance, Linker, Module, Mutability, Store, TypedFunc};
+use ggrs::{Frame, GGRSRequest, P2PSession};
+use wasmtime::{
+    Config, Engine, ExternType, Instance, Linker, Module, Mutability, Store, TypedFunc, Val,
+};
 
 type GameFunc = TypedFunc<(), ()>;
 
 use super::{
     bindings,
-    network::{SaveStateDefinition, WasmConsoleState},
+    contexts::{load_save_data, try_write_save_data, Rumble},
+    network::{
+        hash_words, ChecksumHistory, MemorySnapshot, SaveStateDefinition, SavedFrame, SessionStats,
+        WasmConsoleState, HASH_SEED,
+    },
     Contexts, SessionDescriptor,
 };
 use crate::Console;
-use gamercade_fs::Rom;
+use gamercade_fs::{LoadPhase, LoadProgress, Replay, Rom};
 
 pub struct WasmConsole {
     pub(crate) rom: Arc<Rom>,
@@ -22,8 +29,54 @@ pub struct WasmConsole {
     pub(crate) state_definition: SaveStateDefinition,
     pub(crate) sound_engine: SoundEngine,
     pub(crate) audio_out: SoundEngineData,
+    pub(crate) fuel_budget: u64,
+    pub(crate) seed: u64,
+
+    /// The inputs of each frame so far, if recording.
+    pub(crate) recording: Option<Replay>,
+    pub(crate) session_stats: SessionStats,
+
+    /// Set when running a sync test, to find what made a frame nondeterministic.
+    pub(crate) checksum_history: Option<ChecksumHistory>,
+}
+
+/// Why a game couldn't be started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WasmConsoleError {
+    /// The code isn't a valid module, or couldn't be instantiated, such as when
+    /// it imports something the console doesn't provide.
+    InvalidModule(String),
+
+    /// The game exports none of the init, update or draw functions.
+    MissingExport,
+
+    /// A game function doesn't take and return nothing.
+    BadSignature { name: String, reason: String },
+
+    /// The init function trapped.
+    InitFailed(String),
 }
 
+impl fmt::Display for WasmConsoleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidModule(reason) => write!(f, "The rom's code is invalid: {}", reason),
+            Self::MissingExport => write!(
+                f,
+                "Loaded rom doesn't export an init, update or draw function."
+            ),
+            Self::BadSignature { name, reason } => write!(
+                f,
+                "{} function has the wrong signature, it must take and return nothing: {}",
+                name, reason
+            ),
+            Self::InitFailed(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for WasmConsoleError {}
+
 #[derive(Clone)]
 pub(crate) struct Functions {
     init_fn: Option<GameFunc>,
@@ -32,74 +85,52 @@ pub(crate) struct Functions {
 }
 
 impl Functions {
-    pub(crate) fn find_functions<T>(store: &mut Store<T>, instance: &Instance) -> Self {
-        let init_fn = match instance.get_typed_func(&mut *store, "init") {
-            Ok(init_fn) => Some(init_fn),
-            Err(e) => {
-                println!("init function not found: {}", e);
-                None
-            }
-        };
-        let update_fn = match instance.get_typed_func(&mut *store, "update") {
-            Ok(update_fn) => Some(update_fn),
-            Err(e) => {
-                println!("update function not found: {}", e);
-                None
-            }
-        };
-        let draw_fn = match instance.get_typed_func(&mut *store, "draw") {
-            Ok(draw_fn) => Some(draw_fn),
-            Err(e) => {
-                println!("draw function not found: {}", e);
-                None
-            }
-        };
+    /// Finds the init, update and draw functions. Each is optional, but at least
+    /// one must exist, and any which do exist must take and return nothing.
+    pub(crate) fn find_functions<T>(
+        store: &mut Store<T>,
+        instance: &Instance,
+    ) -> Result<Self, WasmConsoleError> {
+        let init_fn = find_function(store, instance, "init")?;
+        let update_fn = find_function(store, instance, "update")?;
+        let draw_fn = find_function(store, instance, "draw")?;
 
         if init_fn.is_some() || update_fn.is_some() || draw_fn.is_some() {
-            Self {
+            Ok(Self {
                 init_fn,
                 update_fn,
                 draw_fn,
-            }
+            })
         } else {
-            panic!("Loaded rom doesn't contain any valid functions.")
+            Err(WasmConsoleError::MissingExport)
         }
     }
 }
 
-impl WasmConsole {
-    pub fn new(
-        rom: Rom,
-        seed: u64,
-        session: SessionDescriptor,
-        max_prediction: usize,
-    ) -> (Self, WasmConsoleState) {
-        // Initialize sound output
-
-        let rom = Arc::new(rom);
-        let sound_rom = Arc::new(SoundRomInstance::new(&rom.sounds));
-
-        let sound_engine = SoundEngine::new(
-            rom.frame_rate.frames_per_second(),
-            &sound_rom,
-            max_prediction,
-        );
-        let output_sample_rate = sound_engine.output_sample_rate();
-
-        // Initialize the contexts
-        let contexts = Contexts::new(&rom, seed, session, &sound_rom, output_sample_rate);
-        let engine = Engine::default();
-        let module = Module::new(&engine, &rom.code).unwrap();
-        let mut linker = Linker::new(&engine);
+/// Returns None if the function isn't exported, or an error if it has the wrong signature.
+fn find_function<T>(
+    store: &mut Store<T>,
+    instance: &Instance,
+    name: &str,
+) -> Result<Option<GameFunc>, WasmConsoleError> {
+    match instance.get_func(&mut *store, name) {
+        Some(func) => func
+            .typed(&*store)
+            .map(Some)
+            .map_err(|e| WasmConsoleError::BadSignature {
+                name: name.to_string(),
+                reason: e.to_string(),
+            }),
+        None => Ok(None),
+    }
+}
 
-        // TODO: Make this static? Is there a way we can not have to call this
-        // each time?
-        bindings::bind_all_apis(&mut linker);
-
-        let mut store = Store::new(&engine, contexts);
-        let instance = linker.instantiate(&mut store, &module).unwrap();
-        let functions = Functions::find_functions(&mut store, &instance);
+/// The default fuel budget for each call into the game.
+pub const DEFAULT_FUEL_BUDGET: u64 = 1_000_000_000;
 
+impl SaveStateDefinition {
+    /// Finds the exported memories and mutable globals, which together make up the game's state.
+    pub(crate) fn new(module: &Module) -> Self {
         let mut memories = Vec::new();
         let mut mutable_globals = Vec::new();
 
@@ -117,10 +148,215 @@ impl WasmConsole {
             }
         });
 
-        let state_definition = SaveStateDefinition {
+        Self {
             memories,
             mutable_globals,
-        };
+            previous: Vec::new(),
+        }
+    }
+
+    /// Copies the contents of the memories, and the values of the globals.
+    /// Memory which hasn't changed since the last capture or restore is shared, not copied.
+    pub(crate) fn capture<T>(
+        &mut self,
+        store: &mut Store<T>,
+        instance: &Instance,
+    ) -> (Vec<MemorySnapshot>, Vec<Val>) {
+        let no_previous = MemorySnapshot::default();
+        let memories = self
+            .memories
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let memory = instance.get_memory(&mut *store, name).unwrap();
+                let previous = self.previous.get(index).unwrap_or(&no_previous);
+                MemorySnapshot::capture(memory.data(&*store), previous)
+            })
+            .collect::<Vec<_>>();
+
+        self.previous = memories.clone();
+
+        let mutable_globals = self
+            .mutable_globals
+            .iter()
+            .map(|name| {
+                instance
+                    .get_global(&mut *store, name)
+                    .unwrap()
+                    .get(&mut *store)
+            })
+            .collect();
+
+        (memories, mutable_globals)
+    }
+
+    /// Writes back a state from capture. Memory can't shrink, so any pages
+    /// grown since then are kept.
+    pub(crate) fn restore<T>(
+        &mut self,
+        store: &mut Store<T>,
+        instance: &Instance,
+        memories: &[MemorySnapshot],
+        mutable_globals: &[Val],
+    ) {
+        self.memories
+            .iter()
+            .zip(memories)
+            .for_each(|(name, source)| {
+                let destination = instance.get_memory(&mut *store, name).unwrap();
+                source.restore(destination.data_mut(&mut *store))
+            });
+
+        self.previous = memories.to_vec();
+
+        self.mutable_globals
+            .iter()
+            .zip(mutable_globals)
+            .for_each(|(name, source)| {
+                instance
+                    .get_global(&mut *store, name)
+                    .unwrap()
+                    .set(&mut *store, source.clone())
+                    .unwrap()
+            });
+    }
+}
+
+/// Hashes the game's state, so peers can compare frames and detect a desync. Memory is
+/// hashed from the chunk hashes of its snapshots, so unchanged chunks aren't hashed again.
+/// The audio state is left out, since it's only driven by the game.
+pub(crate) fn state_checksum(
+    memories: &[MemorySnapshot],
+    mutable_globals: &[Val],
+    rng_state: u64,
+) -> u128 {
+    let globals = mutable_globals.iter().map(|global| match global {
+        Val::I32(value) => *value as u64,
+        Val::I64(value) => *value as u64,
+        Val::F32(bits) => *bits as u64,
+        Val::F64(bits) => *bits,
+        _ => 0,
+    });
+
+    let chunks = memories
+        .iter()
+        .flat_map(|memory| memory.chunk_hashes().iter().copied());
+
+    hash_words(HASH_SEED, chunks.chain(globals).chain(Some(rng_state))) as u128
+}
+
+/// A rom which has been loaded and had its code compiled, ready to be run.
+pub struct LoadedGame {
+    pub rom: Rom,
+    pub engine: Engine,
+    pub module: Module,
+
+    /// How much fuel each call to init, update or draw gets. Most wasm instructions
+    /// use one unit of fuel, and a call which runs out traps instead of hanging the console.
+    pub fuel_budget: u64,
+
+    /// The save data to start with instead of the one on disk, such as when playing a replay.
+    pub save_data: Option<Vec<u8>>,
+}
+
+impl LoadedGame {
+    /// Loads the rom and compiles its code, reporting progress as it goes.
+    /// This can take a while, so should be called from a background thread.
+    pub fn try_load(path: &PathBuf, progress: &LoadProgress) -> Result<Self, String> {
+        let rom = Rom::try_load_with_progress(path, progress)?;
+        rom.validate()?;
+
+        progress.set_phase(LoadPhase::Compiling);
+        let game = Self::from_rom(rom).map_err(|e| e.to_string())?;
+
+        if progress.is_cancelled() {
+            return Err(String::from("Loading cancelled"));
+        }
+
+        Ok(game)
+    }
+
+    /// Compiles the code of an already loaded rom.
+    pub fn from_rom(rom: Rom) -> Result<Self, WasmConsoleError> {
+        let engine = Engine::new(Config::new().consume_fuel(true))
+            .map_err(|e| WasmConsoleError::InvalidModule(e.to_string()))?;
+        let module = Module::new(&engine, &rom.code)
+            .map_err(|e| WasmConsoleError::InvalidModule(e.to_string()))?;
+
+        Ok(Self {
+            rom,
+            engine,
+            module,
+            fuel_budget: DEFAULT_FUEL_BUDGET,
+            save_data: None,
+        })
+    }
+
+    /// Sets how much fuel each call into the game gets.
+    pub fn with_fuel_budget(self, fuel_budget: u64) -> Self {
+        Self {
+            fuel_budget,
+            ..self
+        }
+    }
+
+    /// Starts the game with this save data, rather than loading it from disk.
+    pub fn with_save_data(self, save_data: Vec<u8>) -> Self {
+        Self {
+            save_data: Some(save_data),
+            ..self
+        }
+    }
+}
+
+impl WasmConsole {
+    /// Instantiates the game and calls its init function. Fails if the game
+    /// can't be instantiated, has no functions to call, or traps during init.
+    pub fn new(
+        game: LoadedGame,
+        seed: u64,
+        session: SessionDescriptor,
+        max_prediction: usize,
+    ) -> Result<(Self, WasmConsoleState), WasmConsoleError> {
+        let LoadedGame {
+            rom,
+            engine,
+            module,
+            fuel_budget,
+            save_data,
+        } = game;
+
+        // Initialize sound output
+
+        let rom = Arc::new(rom);
+        let sound_rom = Arc::new(SoundRomInstance::new(&rom.sounds));
+
+        let sound_engine = SoundEngine::new(rom.frame_rate, &sound_rom, max_prediction);
+        let output_sample_rate = sound_engine.output_sample_rate();
+
+        // Initialize the contexts
+        let save_data = save_data.unwrap_or_else(|| load_save_data(&rom));
+        let contexts = Contexts::new(
+            &rom,
+            seed,
+            session,
+            save_data,
+            &sound_rom,
+            output_sample_rate,
+        );
+        let mut linker = Linker::new(&engine);
+
+        // TODO: Make this static? Is there a way we can not have to call this
+        // each time?
+        bindings::bind_all_apis(&mut linker);
+
+        let mut store = Store::new(&engine, contexts);
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .map_err(|e| WasmConsoleError::InvalidModule(e.to_string()))?;
+        let functions = Functions::find_functions(&mut store, &instance)?;
+
+        let state_definition = SaveStateDefinition::new(&module);
 
         let audio_out = store.data().audio_context.sound_engine_data.clone();
 
@@ -132,13 +368,18 @@ impl WasmConsole {
             store,
             sound_engine,
             audio_out,
+            fuel_budget,
+            seed,
+            recording: None,
+            session_stats: SessionStats::default(),
+            checksum_history: None,
         };
 
-        out.call_init();
+        out.call_init().map_err(WasmConsoleError::InitFailed)?;
 
         let initial_state = out.generate_save_state();
 
-        (out, initial_state)
+        Ok((out, initial_state))
     }
 
     fn generate_save_state(&mut self) -> WasmConsoleState {
@@ -152,44 +393,45 @@ impl WasmConsole {
             .collect::<Vec<_>>()
             .into_boxed_slice();
 
-        let memories = self
-            .state_definition
-            .memories
+        let input_histories = self
+            .store
+            .data()
+            .input_context
+            .input_entries
             .iter()
-            .map(|name| {
-                self.instance
-                    .get_memory(&mut self.store, name)
-                    .unwrap()
-                    .data(&self.store)
-                    .to_vec()
-            })
-            .collect();
+            .map(|input| input.history.clone())
+            .collect::<Vec<_>>()
+            .into_boxed_slice();
 
-        let mutable_globals = self
+        let (memories, mutable_globals) = self
             .state_definition
-            .mutable_globals
-            .iter()
-            .map(|name| self.instance.get_global(&mut self.store, name).unwrap())
-            .collect();
+            .capture(&mut self.store, &self.instance);
 
         let sound_engine_data = self.store.data().audio_context.sound_engine_data.clone();
+        let rng_state = self.store.data().random_context.state();
 
         WasmConsoleState {
             previous_buttons,
+            input_histories,
             memories,
             mutable_globals,
             sound_engine_data,
+            rng_state,
         }
     }
 
     pub fn load_save_state(&mut self, state: WasmConsoleState) {
         let WasmConsoleState {
             previous_buttons,
+            input_histories,
             memories,
             mutable_globals,
             sound_engine_data,
+            rng_state,
         } = state;
 
+        self.store.data().random_context.set_state(rng_state);
+
         let audio_context = &mut self.store.data_mut().audio_context;
         audio_context.sound_engine_data = sound_engine_data;
         audio_context.changed = true;
@@ -201,57 +443,226 @@ impl WasmConsole {
                 self.store.data_mut().input_context.input_entries[index].previous = *prev;
             });
 
-        self.state_definition
-            .memories
-            .iter()
+        input_histories
+            .into_vec()
+            .into_iter()
             .enumerate()
-            .for_each(|(index, name)| {
-                let source = &memories[index];
-                let destination = self.instance.get_memory(&mut self.store, name).unwrap();
-                let destination = &mut destination.data_mut(&mut self.store)[..source.len()];
-                destination.copy_from_slice(source)
+            .for_each(|(index, history)| {
+                self.store.data_mut().input_context.input_entries[index].history = history;
             });
 
         self.state_definition
-            .mutable_globals
-            .iter()
-            .enumerate()
-            .for_each(|(index, name)| {
-                let source = mutable_globals[index];
-                let val = source.get(&mut self.store);
-                self.instance
-                    .get_global(&mut self.store, name)
-                    .unwrap()
-                    .set(&mut self.store, val)
-                    .unwrap()
+            .restore(&mut self.store, &self.instance, &memories, &mutable_globals);
+    }
+
+    /// Saves the state before the frame is run, for a session to load later.
+    pub(crate) fn save_frame(&mut self, frame: Frame) -> WasmConsoleState {
+        self.store.data_mut().save_context.frame = frame;
+        self.generate_save_state()
+    }
+
+    /// Loads a state from save_frame, dropping any save data written since.
+    pub(crate) fn load_frame(&mut self, state: WasmConsoleState, frame: Frame) {
+        self.load_save_state(state);
+        self.store.data_mut().save_context.rollback(frame);
+    }
+
+    /// Runs the next frame of a session, recording its inputs if recording.
+    pub(crate) fn advance_session_frame(&mut self, inputs: &[InputState]) -> Result<(), String> {
+        let frame = self.store.data().save_context.frame;
+        self.store.data_mut().rumble_context.begin_frame(frame);
+
+        if let Some(recording) = &mut self.recording {
+            recording.record(frame as usize, inputs);
+        }
+
+        self.advance_frame(inputs.iter().copied())
+            .map_err(|e| format!("Stopped on frame {}. {}", frame, e))?;
+        self.store.data_mut().save_context.frame += 1;
+
+        Ok(())
+    }
+
+    /// Runs a single frame of the game with the passed in inputs, one per player.
+    pub fn advance_frame(
+        &mut self,
+        inputs: impl IntoIterator<Item = InputState>,
+    ) -> Result<(), String> {
+        // Copy new inputs into the state
+        self.store
+            .data_mut()
+            .input_context
+            .input_entries
+            .iter_mut()
+            .zip(inputs)
+            .for_each(|(current, new)| {
+                current.push_input_state(new);
+            });
+
+        // Call update
+        let result = self.call_update();
+
+        // Store the "output audio" for when we need to render later
+        self.audio_out = self.store.data().audio_context.sound_engine_data.clone();
+
+        // Advance the audio data locally
+        self.sound_engine
+            .fast_forward(&mut self.store.data_mut().audio_context.sound_engine_data);
+
+        // Advance the input data
+        self.store
+            .data_mut()
+            .input_context
+            .input_entries
+            .iter_mut()
+            .for_each(|inputs| {
+                inputs.previous = inputs.current.buttons;
             });
+
+        result
+    }
+
+    /// The frame buffer of the game, as drawn by the last call to draw.
+    pub fn frame_buffer(&self) -> &[u8] {
+        &self.store.data().draw_context.frame_buffer.pixel_buffer
     }
 
-    pub(crate) fn sync_audio(&mut self) {
+    pub fn sync_audio(&mut self) {
         if self.store.data_mut().audio_context.changed {
             self.sound_engine.sync_audio_thread(&self.audio_out);
             self.store.data_mut().audio_context.changed = false;
         }
     }
+
+    /// Starts recording the inputs of each frame. Should be called before the
+    /// first frame, so the recording can be played back from the start.
+    pub fn start_recording(&mut self) {
+        let contexts = self.store.data();
+        self.recording = Some(Replay::new(
+            &self.rom,
+            self.seed,
+            contexts.input_context.input_entries.len(),
+            contexts.save_context.saved().to_vec(),
+        ));
+    }
+
+    /// The recording so far, if recording.
+    pub fn recording(&self) -> Option<&Replay> {
+        self.recording.as_ref()
+    }
+
+    /// Stops recording, and returns what was recorded.
+    pub fn stop_recording(&mut self) -> Option<Replay> {
+        self.recording.take()
+    }
+
+    /// Tracks the checksums of each saved frame, so a sync test can report what
+    /// part of the state didn't match. Should be called before the first frame.
+    pub fn start_sync_test(&mut self) {
+        self.checksum_history = Some(ChecksumHistory::default());
+    }
+
+    /// Describes the first nondeterministic frame a sync test found, if any.
+    pub fn sync_test_mismatch(&self) -> Option<&str> {
+        self.checksum_history.as_ref()?.mismatch()
+    }
+
+    /// The players and spectators the game was started with.
+    pub(crate) fn session_descriptor(&self) -> &SessionDescriptor {
+        self.store.data().multiplayer_context.session()
+    }
+
+    /// The statistics of the session, as of the last update.
+    pub fn session_stats(&self) -> &SessionStats {
+        &self.session_stats
+    }
+
+    /// Collects the network statistics of the session. Should be called each frame.
+    pub fn update_session_stats(&mut self, session: &P2PSession<Self>) {
+        self.session_stats.update(session);
+    }
+
+    /// Takes the rumble requested by the game since last time, with the player
+    /// each is for. Taken after the session advances, so rollbacks can't repeat them.
+    pub fn take_rumbles(&mut self) -> Vec<(usize, Rumble)> {
+        self.store.data_mut().rumble_context.take()
+    }
+
+    /// Writes the game's save data to disk, if it changed on or before the confirmed frame.
+    pub fn flush_save_data(&mut self, confirmed_frame: Frame) {
+        let data = match self.store.data_mut().save_context.confirm(confirmed_frame) {
+            Some(data) => data,
+            None => return,
+        };
+
+        if let Err(e) = try_write_save_data(&self.rom, &data) {
+            println!("Failed to write save data: {}", e);
+        }
+    }
 }
 
-fn call<T>(func: &Option<GameFunc>, store: &mut Store<T>) {
-    if let Some(func) = func {
-        func.call(store, ()).unwrap()
+/// Calls the function with a full tank of fuel, so each call gets the same budget.
+fn try_call<T>(
+    name: &str,
+    func: &Option<GameFunc>,
+    store: &mut Store<T>,
+    fuel_budget: u64,
+) -> Result<(), String> {
+    let func = match func {
+        Some(func) => func,
+        None => return Ok(()),
+    };
+
+    // Consuming nothing returns what's left, or an error once it has all been used
+    let remaining = store.consume_fuel(0).unwrap_or_default();
+    if remaining > fuel_budget {
+        store
+            .consume_fuel(remaining - fuel_budget)
+            .map_err(|e| e.to_string())?;
+    } else {
+        store
+            .add_fuel(fuel_budget - remaining)
+            .map_err(|e| e.to_string())?;
     }
+
+    func.call(&mut *store, ()).map_err(|e| {
+        if store.consume_fuel(0).is_err() {
+            format!(
+                "{} ran out of fuel after {} instructions, it may be stuck in a loop",
+                name, fuel_budget
+            )
+        } else {
+            format!("{} trapped: {}", name, e)
+        }
+    })
 }
 
 impl Console for WasmConsole {
-    fn call_init(&mut self) {
-        call(&self.functions.init_fn, &mut self.store);
+    fn call_init(&mut self) -> Result<(), String> {
+        try_call(
+            "init",
+            &self.functions.init_fn,
+            &mut self.store,
+            self.fuel_budget,
+        )
     }
 
-    fn call_update(&mut self) {
-        call(&self.functions.update_fn, &mut self.store);
+    fn call_update(&mut self) -> Result<(), String> {
+        try_call(
+            "update",
+            &self.functions.update_fn,
+            &mut self.store,
+            self.fuel_budget,
+        )
     }
 
-    fn call_draw(&mut self) {
-        call(&self.functions.draw_fn, &mut self.store);
+    fn call_draw(&mut self) -> Result<(), String> {
+        try_call(
+            "draw",
+            &self.functions.draw_fn,
+            &mut self.store,
+            self.fuel_budget,
+        )
     }
 
     fn rom(&self) -> &Rom {
@@ -259,53 +670,159 @@ impl Console for WasmConsole {
     }
 
     fn blit(&self, buffer: &mut [u8]) {
-        buffer.copy_from_slice(&self.store.data().draw_context.frame_buffer.pixel_buffer);
+        buffer.copy_from_slice(self.frame_buffer());
     }
 
-    fn handle_requests(&mut self, requests: Vec<GGRSRequest<Self>>) {
+    fn handle_requests(&mut self, requests: Vec<GGRSRequest<Self>>) -> Result<(), String> {
         for request in requests {
             match request {
                 GGRSRequest::SaveGameState { cell, frame } => {
-                    let state = self.generate_save_state();
-                    cell.save(frame, Some(state), None);
+                    let state = self.save_frame(frame);
+                    let checksum =
+                        state_checksum(&state.memories, &state.mutable_globals, state.rng_state);
+
+                    if let Some(history) = &mut self.checksum_history {
+                        let saved = SavedFrame {
+                            memories: state.memories.clone(),
+                            mutable_globals: state.mutable_globals.clone(),
+                            rng_state: state.rng_state,
+                        };
+                        history.check(frame, checksum, saved);
+                    }
+
+                    cell.save(frame, Some(state), Some(checksum));
                 }
-                GGRSRequest::LoadGameState { cell, .. } => {
+                GGRSRequest::LoadGameState { cell, frame } => {
                     let state = cell.load().expect("Failed to load game state");
-                    self.load_save_state(state);
+                    let current_frame = self.store.data().save_context.frame;
+                    self.session_stats.record_rollback(current_frame, frame);
+                    self.load_frame(state, frame);
                 }
                 GGRSRequest::AdvanceFrame { inputs } => {
-                    // Copy new inputs into the state
-                    self.store
-                        .data_mut()
-                        .input_context
-                        .input_entries
-                        .iter_mut()
-                        .zip(inputs.iter())
-                        .for_each(|(current, new)| {
-                            current.current = new.0;
-                        });
-
-                    // Call update
-                    self.call_update();
-
-                    // Store the "output audio" for when we need to render later
-                    self.audio_out = self.store.data().audio_context.sound_engine_data.clone();
-
-                    // Advance the audio data locally
-                    self.sound_engine
-                        .fast_forward(&mut self.store.data_mut().audio_context.sound_engine_data);
-
-                    // Advance the input data
-                    self.store
-                        .data_mut()
-                        .input_context
-                        .input_entries
-                        .iter_mut()
-                        .for_each(|inputs| {
-                            inputs.previous = inputs.current.buttons;
-                        });
+                    let inputs = inputs.iter().map(|(input, _)| *input).collect::<Vec<_>>();
+                    self.advance_session_frame(&inputs)?;
                 }
             }
         }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find_functions(wat: &str) -> Result<Functions, WasmConsoleError> {
+        let engine = Engine::default();
+        let module = Module::new(&engine, wat).unwrap();
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[]).unwrap();
+        Functions::find_functions(&mut store, &instance)
+    }
+
+    #[test]
+    fn test_find_functions() {
+        let functions = find_functions(r#"(module (func (export "draw")))"#).unwrap();
+        assert!(functions.init_fn.is_none());
+        assert!(functions.update_fn.is_none());
+        assert!(functions.draw_fn.is_some());
+
+        assert_eq!(
+            find_functions("(module)").err(),
+            Some(WasmConsoleError::MissingExport)
+        );
+        assert_eq!(
+            find_functions(r#"(module (memory (export "draw") 1))"#).err(),
+            Some(WasmConsoleError::MissingExport)
+        );
+
+        let bad_signature = find_functions(
+            r#"(module (func (export "draw")) (func (export "update") (param i32)))"#,
+        );
+        assert!(matches!(
+            bad_signature,
+            Err(WasmConsoleError::BadSignature { name, .. }) if name == "update"
+        ));
+    }
+
+    #[test]
+    fn test_calls_are_limited_by_fuel() {
+        let engine = Engine::new(Config::new().consume_fuel(true)).unwrap();
+        let wat = r#"(module
+            (func (export "update") (loop br 0))
+            (func (export "draw") (local i32)
+                (loop
+                    (local.set 0 (i32.add (local.get 0) (i32.const 1)))
+                    (br_if 0 (i32.lt_u (local.get 0) (i32.const 100))))))"#;
+        let module = Module::new(&engine, wat).unwrap();
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[]).unwrap();
+        let functions = Functions::find_functions(&mut store, &instance).unwrap();
+
+        let error = try_call("update", &functions.update_fn, &mut store, 10_000).unwrap_err();
+        assert!(error.contains("ran out of fuel"));
+
+        // Each call gets the full budget again, no matter how much the last one used
+        (0..3).for_each(|_| {
+            try_call("draw", &functions.draw_fn, &mut store, 10_000).unwrap();
+        });
+        assert!(try_call("draw", &functions.draw_fn, &mut store, 100).is_err());
+    }
+
+    #[test]
+    fn test_loading_a_state_restores_memory_and_globals() {
+        let engine = Engine::default();
+        let wat = r#"(module
+            (memory (export "memory") 1)
+            (global (export "counter") (mut i32) (i32.const 0))
+            (func (export "update")
+                (global.set 0 (i32.add (global.get 0) (i32.const 1)))
+                (i32.store (i32.const 64) (global.get 0))))"#;
+        let module = Module::new(&engine, wat).unwrap();
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[]).unwrap();
+        let update = instance
+            .get_typed_func::<(), (), _>(&mut store, "update")
+            .unwrap();
+
+        let mut definition = SaveStateDefinition::new(&module);
+        assert_eq!(definition.memories, vec!["memory"]);
+        assert_eq!(definition.mutable_globals, vec!["counter"]);
+
+        let read = |store: &mut Store<()>| {
+            let memory = instance.get_memory(&mut *store, "memory").unwrap();
+            let counter = instance.get_global(&mut *store, "counter").unwrap();
+            (
+                memory.data(&*store)[64],
+                counter.get(&mut *store).unwrap_i32(),
+            )
+        };
+
+        update.call(&mut store, ()).unwrap();
+        let (memories, globals) = definition.capture(&mut store, &instance);
+
+        update.call(&mut store, ()).unwrap();
+        update.call(&mut store, ()).unwrap();
+        assert_eq!(read(&mut store), (3, 3));
+
+        definition.restore(&mut store, &instance, &memories, &globals);
+        assert_eq!(read(&mut store), (1, 1));
+    }
+
+    #[test]
+    fn test_state_checksum() {
+        let snapshot = |data: &[u8]| MemorySnapshot::capture(data, &MemorySnapshot::default());
+        let memories = vec![snapshot(&[0, 1, 2, 3])];
+        let globals = vec![Val::I32(7)];
+        let checksum = state_checksum(&memories, &globals, 42);
+
+        assert_eq!(checksum, state_checksum(&memories, &globals, 42));
+        assert_ne!(
+            checksum,
+            state_checksum(&[snapshot(&[0, 1, 2, 4])], &globals, 42)
+        );
+        assert_ne!(checksum, state_checksum(&memories, &[Val::I32(8)], 42));
+        assert_ne!(checksum, state_checksum(&memories, &globals, 43));
     }
 }
@@ -0,0 +1,141 @@
+use gamercade_core::BYTES_PER_PIXEL;
+
+/// How much a pixel's luminance must change between frames to count as a large swing.
+const LARGE_SWING: f32 = 0.2;
+
+/// How much of the screen must swing at once before the frame is treated as a flash.
+const FLASH_SCREEN_FRACTION: f32 = 0.5;
+
+/// The largest average change in luminance allowed between two presented frames.
+pub const MAX_LUMINANCE_STEP: f32 = 0.1;
+
+/// Reduces flashing by blending consecutive presented frames when most of
+/// the screen changes brightness at once. This only affects the frame being
+/// presented, the game's own frame buffer is never modified.
+#[derive(Default)]
+pub struct FlashReducer {
+    pub enabled: bool,
+    previous: Vec<u8>,
+}
+
+/// Relative luminance of an rgba pixel, from 0.0 to 1.0.
+fn luminance(pixel: &[u8]) -> f32 {
+    (0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32) / 255.0
+}
+
+impl FlashReducer {
+    /// Processes the frame about to be presented.
+    pub fn apply(&mut self, frame: &mut [u8]) {
+        if !self.enabled || self.previous.len() != frame.len() {
+            self.previous.clear();
+            self.previous.extend_from_slice(frame);
+            return;
+        }
+
+        let pixels = frame.len() / BYTES_PER_PIXEL;
+        if pixels == 0 {
+            return;
+        }
+
+        let (swings, total_change) = frame
+            .chunks_exact(BYTES_PER_PIXEL)
+            .zip(self.previous.chunks_exact(BYTES_PER_PIXEL))
+            .fold((0, 0.0), |(swings, total), (next, previous)| {
+                let change = (luminance(next) - luminance(previous)).abs();
+                (swings + usize::from(change > LARGE_SWING), total + change)
+            });
+
+        let mean_change = total_change / pixels as f32;
+
+        if swings as f32 > pixels as f32 * FLASH_SCREEN_FRACTION && mean_change > MAX_LUMINANCE_STEP
+        {
+            let blend = MAX_LUMINANCE_STEP / mean_change;
+
+            frame
+                .iter_mut()
+                .zip(self.previous.iter())
+                .for_each(|(next, previous)| {
+                    let blended = *previous as f32 + (*next as f32 - *previous as f32) * blend;
+                    *next = blended.round() as u8;
+                });
+        }
+
+        self.previous.copy_from_slice(frame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIXELS: usize = 64;
+
+    fn solid(value: u8) -> Vec<u8> {
+        (0..PIXELS)
+            .flat_map(|_| [value, value, value, 255])
+            .collect()
+    }
+
+    fn mean_luminance(frame: &[u8]) -> f32 {
+        frame
+            .chunks_exact(BYTES_PER_PIXEL)
+            .map(luminance)
+            .sum::<f32>()
+            / PIXELS as f32
+    }
+
+    #[test]
+    fn test_flashing_is_clamped() {
+        let mut reducer = FlashReducer {
+            enabled: true,
+            ..Default::default()
+        };
+
+        let mut last = None;
+        for index in 0..30 {
+            let mut frame = solid(if index % 2 == 0 { 0 } else { 255 });
+            reducer.apply(&mut frame);
+
+            let presented = mean_luminance(&frame);
+            if let Some(last) = last {
+                let change: f32 = presented - last;
+                // Allow for rounding back to u8.
+                assert!(change.abs() <= MAX_LUMINANCE_STEP + 1.0 / 255.0);
+            }
+            last = Some(presented);
+        }
+    }
+
+    #[test]
+    fn test_small_changes_pass_through() {
+        let mut reducer = FlashReducer {
+            enabled: true,
+            ..Default::default()
+        };
+
+        let mut first = solid(100);
+        reducer.apply(&mut first);
+
+        // A large swing on a small part of the screen isn't a flash.
+        let mut sprite = solid(100);
+        sprite[..BYTES_PER_PIXEL * 8].fill(255);
+        let expected = sprite.clone();
+        reducer.apply(&mut sprite);
+        assert_eq!(sprite, expected);
+
+        // Neither is a gentle fade across the whole screen.
+        let mut fade = solid(110);
+        reducer.apply(&mut fade);
+        assert_eq!(fade, solid(110));
+    }
+
+    #[test]
+    fn test_disabled_does_nothing() {
+        let mut reducer = FlashReducer::default();
+        reducer.apply(&mut solid(0));
+
+        let mut frame = solid(255);
+        reducer.apply(&mut frame);
+        assert_eq!(frame, solid(255));
+    }
+}
@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+/// Pauses the game's updates for debugging, and steps through them one frame at a time.
+/// The game is still drawn while paused.
+#[derive(Debug, Default)]
+pub struct FrameStep {
+    paused: bool,
+    step: bool,
+}
+
+impl FrameStep {
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.step = false;
+    }
+
+    /// Pauses the game, if it wasn't already, and runs its next frame.
+    pub fn step(&mut self) {
+        self.paused = true;
+        self.step = true;
+    }
+
+    /// How much of the accumulated time the game can run for. None of it while paused,
+    /// except for enough to run a single frame when stepping.
+    pub fn limit(&mut self, accumulated: Duration, frame_time: Duration) -> Duration {
+        if !self.paused {
+            accumulated
+        } else if std::mem::take(&mut self.step) {
+            // The frame loop only runs while there's more than a frame of time left
+            frame_time + frame_time / 2
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_runs_a_single_frame() {
+        let frame_time = Duration::from_millis(16);
+        let mut frame_step = FrameStep::default();
+        let limited = |frame_step: &mut FrameStep| {
+            frame_step.limit(frame_time * 10, frame_time).as_nanos() / frame_time.as_nanos()
+        };
+
+        assert_eq!(limited(&mut frame_step), 10);
+
+        frame_step.toggle_pause();
+        assert_eq!(limited(&mut frame_step), 0);
+
+        frame_step.step();
+        assert_eq!(limited(&mut frame_step), 1);
+        assert_eq!(limited(&mut frame_step), 0);
+
+        frame_step.toggle_pause();
+        assert_eq!(limited(&mut frame_step), 10);
+
+        // Stepping while running pauses the game after the frame
+        frame_step.step();
+        assert_eq!(limited(&mut frame_step), 1);
+        assert!(frame_step.is_paused());
+    }
+}
@@ -10,7 +10,7 @@ use crate::console::{LocalInputManager, WasmConsole};
 use super::Gui;
 
 /// Manages all state required for rendering egui over `Pixels`.
-pub(crate) struct Framework {
+pub struct Framework {
     // State for egui.
     egui_ctx: Context,
     egui_state: egui_winit::State,
@@ -25,7 +25,7 @@ pub(crate) struct Framework {
 
 impl Framework {
     /// Create egui.
-    pub(crate) fn new(
+    pub fn new(
         width: u32,
         height: u32,
         scale_factor: f32,
@@ -56,12 +56,25 @@ impl Framework {
     }
 
     /// Handle input events from the window manager.
-    pub(crate) fn handle_event(&mut self, event: &winit::event::WindowEvent) {
+    pub fn handle_event(&mut self, event: &winit::event::WindowEvent) {
+        if let winit::event::WindowEvent::KeyboardInput {
+            input:
+                winit::event::KeyboardInput {
+                    state: winit::event::ElementState::Pressed,
+                    virtual_keycode: Some(key),
+                    ..
+                },
+            ..
+        } = event
+        {
+            self.gui.key_remap.key_pressed(*key);
+        }
+
         self.egui_state.on_event(&self.egui_ctx, event);
     }
 
     /// Resize egui.
-    pub(crate) fn resize(&mut self, width: u32, height: u32) {
+    pub fn resize(&mut self, width: u32, height: u32) {
         if width > 0 && height > 0 {
             self.screen_descriptor.physical_width = width;
             self.screen_descriptor.physical_height = height;
@@ -69,12 +82,12 @@ impl Framework {
     }
 
     /// Update scaling factor.
-    pub(crate) fn scale_factor(&mut self, scale_factor: f64) {
+    pub fn scale_factor(&mut self, scale_factor: f64) {
         self.screen_descriptor.scale_factor = scale_factor as f32;
     }
 
     /// Prepare egui.
-    pub(crate) fn prepare(
+    pub fn prepare(
         &mut self,
         pixels: &mut Pixels,
         session: &mut Option<P2PSession<WasmConsole>>,
@@ -96,7 +109,7 @@ impl Framework {
     }
 
     /// Render egui.
-    pub(crate) fn render(
+    pub fn render(
         &mut self,
         encoder: &mut wgpu::CommandEncoder,
         render_target: &wgpu::TextureView,
@@ -0,0 +1,78 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver},
+    time::{Duration, Instant},
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// How long the rom has to go without changing before it's reloaded,
+/// so a rom which is still being written isn't loaded half finished.
+const SETTLE_TIME: Duration = Duration::from_millis(250);
+
+/// Watches the running game's rom, so it can be reloaded when it changes on disk.
+pub(crate) struct GameWatcher {
+    /// The path the game was launched from, as opposed to the watched path.
+    path: PathBuf,
+    watched: PathBuf,
+    events: Receiver<notify::Result<Event>>,
+    last_change: Option<Instant>,
+
+    // Stops watching once dropped
+    _watcher: RecommendedWatcher,
+}
+
+impl GameWatcher {
+    pub(crate) fn new(path: &Path) -> Result<Self, String> {
+        let watched = path.canonicalize().map_err(|e| e.to_string())?;
+        let directory = watched
+            .parent()
+            .ok_or_else(|| String::from("The rom isn't in a directory"))?;
+
+        let (sender, events) = channel();
+        let mut watcher = notify::recommended_watcher(sender).map_err(|e| e.to_string())?;
+
+        // Builds often replace the file rather than writing to it,
+        // which only the directory sees
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            watched,
+            events,
+            last_change: None,
+            _watcher: watcher,
+        })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns true once the rom has changed and finished changing.
+    pub(crate) fn poll(&mut self) -> bool {
+        for event in self.events.try_iter() {
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    println!("Error watching the game: {}", e);
+                    continue;
+                }
+            };
+
+            if !matches!(event.kind, EventKind::Access(_)) && event.paths.contains(&self.watched) {
+                self.last_change = Some(Instant::now());
+            }
+        }
+
+        match self.last_change {
+            Some(last_change) if last_change.elapsed() >= SETTLE_TIME => {
+                self.last_change = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
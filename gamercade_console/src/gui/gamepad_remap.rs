@@ -0,0 +1,313 @@
+use egui::{ComboBox, Context, Ui};
+use gamercade_core::{ButtonCode, InputState};
+use gilrs::{Axis, Button, EventType, Gamepad, GamepadId, Gilrs};
+
+use crate::console::{
+    device_key, AnalogAxis, AnalogSide, AxisBinding, GamepadBindings, GamepadProfiles, InputMode,
+    LocalInputManager, BINDABLE_AXES, BINDABLE_BUTTONS,
+};
+
+/// The order buttons are asked for when remapping a device.
+const REMAP_ORDER: [ButtonCode; 16] = [
+    ButtonCode::Up,
+    ButtonCode::Down,
+    ButtonCode::Left,
+    ButtonCode::Right,
+    ButtonCode::A,
+    ButtonCode::B,
+    ButtonCode::C,
+    ButtonCode::D,
+    ButtonCode::Start,
+    ButtonCode::Select,
+    ButtonCode::LeftShoulder,
+    ButtonCode::RightShoulder,
+    ButtonCode::LeftTrigger,
+    ButtonCode::RightTrigger,
+    ButtonCode::LeftStick,
+    ButtonCode::RightStick,
+];
+
+/// The order stick axes are asked for, after the buttons.
+const AXIS_REMAP_ORDER: [(AnalogSide, AnalogAxis, &str); 4] = [
+    (AnalogSide::Left, AnalogAxis::X, "Push the left stick right"),
+    (AnalogSide::Left, AnalogAxis::Y, "Push the left stick up"),
+    (
+        AnalogSide::Right,
+        AnalogAxis::X,
+        "Push the right stick right",
+    ),
+    (AnalogSide::Right, AnalogAxis::Y, "Push the right stick up"),
+];
+
+const REMAP_STEPS: usize = REMAP_ORDER.len() + AXIS_REMAP_ORDER.len();
+
+/// How far an axis has to move from where it rests to be bound.
+const AXIS_THRESHOLD: f32 = 0.5;
+
+#[derive(Default)]
+pub struct GamepadRemapWindow {
+    pub open: bool,
+    remapping: Option<RemapState>,
+}
+
+struct RemapState {
+    target: GamepadId,
+    step: usize,
+    bindings: GamepadBindings,
+    waiting_for_release: bool,
+
+    /// Where each axis was when remapping started. Some axes, like
+    /// triggers, don't rest in the middle.
+    rest: Vec<(Axis, f32)>,
+}
+
+impl RemapState {
+    fn new(gamepad: &Gamepad) -> Self {
+        Self {
+            target: gamepad.id(),
+            step: 0,
+            bindings: GamepadBindings::empty(),
+            waiting_for_release: true,
+            rest: BINDABLE_AXES
+                .iter()
+                .map(|axis| (*axis, gamepad.value(*axis)))
+                .collect(),
+        }
+    }
+
+    /// How far the axis has moved from where it rests.
+    fn moved(&self, axis: Axis, value: f32) -> f32 {
+        let rest = self
+            .rest
+            .iter()
+            .find(|(rested, _)| *rested == axis)
+            .map_or(0.0, |(_, rest)| *rest);
+        value - rest
+    }
+
+    fn is_held(&self, gamepad: &Gamepad) -> bool {
+        BINDABLE_BUTTONS
+            .iter()
+            .any(|button| gamepad.is_pressed(*button))
+            || BINDABLE_AXES
+                .iter()
+                .any(|axis| self.moved(*axis, gamepad.value(*axis)).abs() > AXIS_THRESHOLD)
+    }
+
+    /// Binds the first button pressed or axis moved for the current step,
+    /// returning true if one was bound.
+    fn bind(&mut self, pressed: &[Button], moved: &[(Axis, f32)]) -> bool {
+        if let Some(code) = REMAP_ORDER.get(self.step) {
+            return match pressed.first() {
+                Some(button) => {
+                    self.bindings.buttons.insert(*button, *code);
+                    true
+                }
+                None => false,
+            };
+        }
+
+        let (side, axis, _) = AXIS_REMAP_ORDER[self.step - REMAP_ORDER.len()];
+        let moved = moved
+            .iter()
+            .map(|(physical, value)| (*physical, self.moved(*physical, *value)))
+            .find(|(_, moved)| moved.abs() > AXIS_THRESHOLD);
+
+        match moved {
+            Some((physical, moved)) => {
+                let binding = AxisBinding {
+                    side,
+                    axis,
+                    inverted: moved < 0.0,
+                };
+                self.bindings.axes.insert(physical, binding);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl GamepadRemapWindow {
+    pub(crate) fn draw(&mut self, ctx: &Context, input: &mut LocalInputManager, gilrs: &mut Gilrs) {
+        let mut open = self.open;
+
+        egui::Window::new("Gamepad Remapping")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                let id = match input.input_mode {
+                    InputMode::Gamepad(id) => id,
+                    InputMode::Emulated => {
+                        ui.label("Select a gamepad in the Main Menu first.");
+                        return;
+                    }
+                };
+
+                // The events are looked at after they've been handled, so
+                // hot-plugging keeps working while the window is open.
+                let events = input.take_recent_events();
+                let logical = input.new_gamepad_state(id, gilrs);
+
+                let gamepad = match gilrs.connected_gamepad(id) {
+                    Some(gamepad) => gamepad,
+                    None => {
+                        ui.label("Gamepad is disconnected.");
+                        self.remapping = None;
+                        return;
+                    }
+                };
+
+                let key = device_key(&gamepad);
+                let profiles = &mut input.gamepad_profiles;
+
+                ui.label(format!("Device: {}", gamepad.name()));
+                ui.label(format!("Id: {}", key));
+                if profiles.profiles.contains_key(&key) {
+                    ui.label("Using saved profile.");
+                } else {
+                    ui.label("Using built in layout.");
+                }
+
+                ui.separator();
+                draw_logical_inputs(ui, &logical);
+                ui.separator();
+
+                if let Some(state) = &mut self.remapping {
+                    if state.target != id {
+                        self.remapping = None;
+                        return;
+                    }
+
+                    let prompt = match REMAP_ORDER.get(state.step) {
+                        Some(code) => format!("Press the button for {:?}", code),
+                        None => AXIS_REMAP_ORDER[state.step - REMAP_ORDER.len()]
+                            .2
+                            .to_string(),
+                    };
+                    ui.label(format!("{} ({}/{})", prompt, state.step + 1, REMAP_STEPS));
+
+                    let events = events.iter().filter(|event| event.id == id);
+                    let pressed = events
+                        .clone()
+                        .filter_map(|event| match event.event {
+                            EventType::ButtonPressed(button, _)
+                                if BINDABLE_BUTTONS.contains(&button) =>
+                            {
+                                Some(button)
+                            }
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>();
+                    let moved = events
+                        .filter_map(|event| match event.event {
+                            EventType::AxisChanged(axis, value, _)
+                                if BINDABLE_AXES.contains(&axis) =>
+                            {
+                                Some((axis, value))
+                            }
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>();
+
+                    let mut advance = false;
+                    if state.waiting_for_release {
+                        state.waiting_for_release = state.is_held(&gamepad);
+                    } else {
+                        advance = state.bind(&pressed, &moved);
+                    }
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Skip").clicked() {
+                            advance = true;
+                        }
+
+                        if ui.button("Cancel").clicked() {
+                            self.remapping = None;
+                        }
+                    });
+
+                    if let (true, Some(state)) = (advance, &mut self.remapping) {
+                        state.step += 1;
+                        state.waiting_for_release = true;
+
+                        if state.step == REMAP_STEPS {
+                            let bindings =
+                                std::mem::replace(&mut state.bindings, GamepadBindings::empty());
+                            profiles.set_profile(&gamepad, bindings);
+                            save(profiles);
+                            self.remapping = None;
+                        }
+                    }
+                } else {
+                    ui.horizontal(|ui| {
+                        if ui.button("Start Remapping").clicked() {
+                            self.remapping = Some(RemapState::new(&gamepad));
+                        }
+
+                        if ui.button("Reset to Default").clicked() {
+                            profiles.reset_profile(&gamepad);
+                            save(profiles);
+                        }
+                    });
+
+                    let mut copy_from = None;
+                    ComboBox::from_label("Copy Profile From")
+                        .selected_text("Select a profile")
+                        .show_ui(ui, |ui| {
+                            profiles
+                                .profiles
+                                .iter()
+                                .filter(|(source, _)| **source != key)
+                                .for_each(|(source, profile)| {
+                                    if ui.selectable_label(false, profile.name.as_str()).clicked() {
+                                        copy_from = Some(source.clone());
+                                    }
+                                });
+                        });
+
+                    if let Some(source) = copy_from {
+                        profiles.copy_profile(&source, &gamepad);
+                        save(profiles);
+                    }
+                }
+            });
+
+        self.open = open;
+    }
+}
+
+fn save(profiles: &GamepadProfiles) {
+    if let Err(e) = profiles.try_save() {
+        println!("Failed to save gamepad profiles: {}", e);
+    }
+}
+
+/// Shows the inputs the game sees, after the device's bindings are applied.
+fn draw_logical_inputs(ui: &mut Ui, logical: &InputState) {
+    let pressed = REMAP_ORDER
+        .iter()
+        .filter(|code| logical.buttons.get_button_state(**code))
+        .map(|code| format!("{:?}", code))
+        .collect::<Vec<_>>();
+
+    ui.label("Inputs seen by the game:");
+    if pressed.is_empty() {
+        ui.label("Buttons: None");
+    } else {
+        ui.label(format!("Buttons: {}", pressed.join(", ")));
+    }
+
+    ui.label(format!(
+        "Left Stick: ({:.2}, {:.2})  Right Stick: ({:.2}, {:.2})",
+        logical.left_stick.get_x_axis(),
+        logical.left_stick.get_y_axis(),
+        logical.right_stick.get_x_axis(),
+        logical.right_stick.get_y_axis(),
+    ));
+    ui.label(format!(
+        "Triggers: {:.2}, {:.2}",
+        logical.left_trigger.get_value(),
+        logical.right_trigger.get_value(),
+    ));
+}
@@ -0,0 +1,96 @@
+use egui::{Context, Grid};
+use winit::event::VirtualKeyCode;
+
+use crate::console::{KeyBindings, LocalInputManager, KEY_REMAP_ORDER, RESERVED_KEYS};
+
+#[derive(Default)]
+pub struct KeyRemapWindow {
+    pub open: bool,
+
+    /// The index into the remap order of the input waiting for a key.
+    listening: Option<usize>,
+    pressed: Option<VirtualKeyCode>,
+}
+
+impl KeyRemapWindow {
+    /// True while waiting for a key, so the console's own hotkeys should be ignored.
+    pub fn is_listening(&self) -> bool {
+        self.listening.is_some()
+    }
+
+    /// Called with each key pressed in the window, even when not listening.
+    pub fn key_pressed(&mut self, key: VirtualKeyCode) {
+        if self.listening.is_some() {
+            self.pressed = Some(key);
+        }
+    }
+
+    pub(crate) fn draw(&mut self, ctx: &Context, input: &mut LocalInputManager) {
+        if !self.open {
+            self.listening = None;
+            return;
+        }
+
+        let mut open = self.open;
+        let bindings = &mut input.keybinds;
+
+        if let (Some(index), Some(key)) = (self.listening, self.pressed.take()) {
+            if !RESERVED_KEYS.contains(&key) {
+                bindings.bind(key, KEY_REMAP_ORDER[index]);
+                save(bindings);
+                self.listening = None;
+            }
+        }
+
+        egui::Window::new("Keyboard Remapping")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                Grid::new("key_remap_grid").striped(true).show(ui, |ui| {
+                    for (index, key_type) in KEY_REMAP_ORDER.iter().enumerate() {
+                        ui.label(key_type.name());
+
+                        if self.listening == Some(index) {
+                            ui.label("Press a key...");
+                            if ui.button("Cancel").clicked() {
+                                self.listening = None;
+                            }
+                        } else {
+                            let keys = bindings
+                                .keys_for(*key_type)
+                                .map(|key| format!("{:?}", key))
+                                .collect::<Vec<_>>();
+
+                            if keys.is_empty() {
+                                ui.label("Unbound");
+                            } else {
+                                ui.label(keys.join(", "));
+                            }
+
+                            if ui.button("Rebind").clicked() {
+                                self.listening = Some(index);
+                                self.pressed = None;
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+                ui.label("Escape, Space, F5 and F6 are used by the console, so can't be bound.");
+                if ui.button("Reset to Default").clicked() {
+                    *bindings = KeyBindings::default();
+                    save(bindings);
+                    self.listening = None;
+                }
+            });
+
+        self.open = open;
+    }
+}
+
+fn save(bindings: &KeyBindings) {
+    if let Err(e) = bindings.try_save() {
+        println!("Failed to save key bindings: {}", e);
+    }
+}
@@ -1,32 +1,85 @@
 use std::{net::SocketAddr, path::PathBuf};
 
-use egui::{Button, ComboBox, Context, Slider};
+use egui::{Align2, Button, ComboBox, Context, ScrollArea, Slider};
 
-use gamercade_fs::Rom;
-use ggrs::{P2PSession, PlayerType, SessionBuilder, SessionState, UdpNonBlockingSocket};
+use gamercade_fs::{Replay, Rom, RomMeta};
+use ggrs::{
+    P2PSession, PlayerType, SessionBuilder, SessionState, SyncTestSession, UdpNonBlockingSocket,
+};
 use gilrs::Gilrs;
 use pixels::Pixels;
 use rfd::FileDialog;
 use winit::{dpi::PhysicalSize, window::Window};
 
 use crate::{
-    console::{InputMode, LocalInputManager, SessionDescriptor, WasmConsole, WasmConsoleState},
+    console::{
+        InputMode, LoadedGame, LocalInputManager, ReplayPlayback, SessionDescriptor, WasmConsole,
+        WasmConsoleState, DEFAULT_CHECK_DISTANCE, DEFAULT_INPUT_DELAY, MAX_CHECK_DISTANCE,
+        MAX_INPUT_DELAY, MAX_LOCAL_PLAYERS, MAX_SPECTATORS, PLAYBACK_AUDIO_BUFFER_SIZE,
+    },
+    flash_reduction::FlashReducer,
     DEFAULT_WINDOW_RESOLUTION,
 };
 
+mod frame_step;
 pub mod framework;
+mod game_watcher;
+mod gamepad_remap;
+mod key_remap;
+mod rom_loader;
+
+pub use frame_step::FrameStep;
+use game_watcher::GameWatcher;
+use gamepad_remap::GamepadRemapWindow;
+use key_remap::KeyRemapWindow;
+use rom_loader::{FinishedRomLoad, RomLoadTask};
 
 pub struct Gui {
     pub window_open: bool,
     pub game_file: Option<PathBuf>,
+    pub game_meta: Option<RomMeta>,
     pub play_mode: PlayMode,
     pub remote_addr: String,
+    pub spectator_addrs: String,
     pub player_num: usize,
+    pub local_players: usize,
+    pub input_delay: usize,
+    pub check_distance: usize,
     pub port: String,
     pub seed: String,
+    pub record_replay: bool,
+    pub show_network_stats: bool,
+
+    /// Reloads the running game when its rom changes on disk, for quicker iteration.
+    pub live_reload: bool,
 
     pub wasm_console: Option<WasmConsole>,
     pub initial_state: Option<WasmConsoleState>,
+
+    /// Set when playing a replay, which runs the game without a session.
+    pub replay_playback: Option<ReplayPlayback>,
+
+    /// Set when running a sync test instead of a networked session.
+    pub sync_test: Option<SyncTestSession<WasmConsole>>,
+
+    pub gamepad_remap: GamepadRemapWindow,
+    pub key_remap: KeyRemapWindow,
+
+    pub audio_limiter: bool,
+    pub flash_reducer: FlashReducer,
+
+    /// Pauses and steps through an offline game, for debugging.
+    pub frame_step: FrameStep,
+
+    rom_load: Option<RomLoadTask>,
+    game_watcher: Option<GameWatcher>,
+
+    /// The changed rom of the running game, loading in the background.
+    reload: Option<RomLoadTask>,
+
+    pending_replay: Option<Replay>,
+    pending_check_distance: Option<usize>,
+    error: Option<String>,
 }
 
 const DEFAULT_SEED: &str = "a12cade";
@@ -37,12 +90,33 @@ impl Default for Gui {
             seed: DEFAULT_SEED.to_string(),
             window_open: true,
             game_file: None,
+            game_meta: None,
             play_mode: PlayMode::SinglePlayer,
             remote_addr: String::new(),
+            spectator_addrs: String::new(),
             player_num: 1,
+            local_players: 2,
+            input_delay: DEFAULT_INPUT_DELAY,
+            check_distance: DEFAULT_CHECK_DISTANCE,
             port: String::new(),
+            record_replay: false,
+            show_network_stats: false,
+            live_reload: false,
             wasm_console: None,
             initial_state: None,
+            replay_playback: None,
+            sync_test: None,
+            gamepad_remap: GamepadRemapWindow::default(),
+            key_remap: KeyRemapWindow::default(),
+            audio_limiter: false,
+            flash_reducer: FlashReducer::default(),
+            frame_step: FrameStep::default(),
+            rom_load: None,
+            game_watcher: None,
+            reload: None,
+            pending_replay: None,
+            pending_check_distance: None,
+            error: None,
         }
     }
 }
@@ -51,6 +125,12 @@ impl Default for Gui {
 pub enum PlayMode {
     SinglePlayer,
     Networked,
+
+    /// Rolls back and resimulates every frame, to check the game is deterministic.
+    SyncTest,
+
+    /// Several players on this machine, each with their own gamepad.
+    LocalMultiplayer,
 }
 
 impl Gui {
@@ -74,6 +154,10 @@ impl Gui {
                             self.game_file = FileDialog::new()
                                 .add_filter("gcrom (.gcrom)", &["gcrom"])
                                 .pick_file();
+                            self.game_meta = self
+                                .game_file
+                                .as_ref()
+                                .and_then(|file| Rom::try_load_meta(file).ok());
                         };
 
                         if let Some(file) = &self.game_file {
@@ -86,6 +170,29 @@ impl Gui {
                         }
                     });
 
+                    if let Some(meta) = self
+                        .game_meta
+                        .as_ref()
+                        .filter(|meta| !meta.title.is_empty())
+                    {
+                        ui.horizontal(|ui| {
+                            ui.strong(&meta.title);
+                            if !meta.version.is_empty() {
+                                ui.label(&meta.version);
+                            }
+                            if !meta.authors.is_empty() {
+                                ui.label(format!("by {}", meta.authors_line()));
+                            }
+                        });
+
+                        if !meta.description.is_empty() {
+                            ui.label(&meta.description);
+                        }
+                    }
+
+                    ui.checkbox(&mut self.live_reload, "Reload When the Game Changes")
+                        .on_hover_text("Restarts the game whenever its .gcrom is rebuilt.");
+
                     ui.horizontal(|ui| {
                         ui.label("Random Seed:");
                         ui.text_edit_singleline(&mut self.seed);
@@ -118,6 +225,29 @@ impl Gui {
                                 );
                             });
                         });
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Remap Keyboard").clicked() {
+                            self.key_remap.open = !self.key_remap.open;
+                        }
+
+                        if ui.button("Remap Gamepad").clicked() {
+                            self.gamepad_remap.open = !self.gamepad_remap.open;
+                        }
+                    });
+                });
+
+                ui.group(|ui| {
+                    ui.label("Accessibility:");
+                    if ui
+                        .checkbox(&mut self.audio_limiter, "Limit sudden loud sounds")
+                        .changed()
+                    {
+                        if let Some(console) = &mut self.wasm_console {
+                            console.sound_engine.set_limiter_enabled(self.audio_limiter);
+                        }
+                    }
+                    ui.checkbox(&mut self.flash_reducer.enabled, "Reduce flashing");
                 });
 
                 ui.group(|ui| {
@@ -128,9 +258,55 @@ impl Gui {
                             PlayMode::SinglePlayer,
                             "Single Player",
                         );
+                        ui.selectable_value(
+                            &mut self.play_mode,
+                            PlayMode::LocalMultiplayer,
+                            "Local Co-op",
+                        );
                         ui.selectable_value(&mut self.play_mode, PlayMode::Networked, "Networked");
+                        ui.selectable_value(&mut self.play_mode, PlayMode::SyncTest, "Sync Test");
                     });
 
+                    if self.play_mode == PlayMode::LocalMultiplayer {
+                        ui.add(
+                            Slider::new(&mut self.local_players, 2..=MAX_LOCAL_PLAYERS)
+                                .text("Players"),
+                        );
+                        input.set_local_player_count(self.local_players);
+
+                        ui.label("Player 1 uses the selected controller.");
+                        input
+                            .local_gamepads
+                            .iter_mut()
+                            .enumerate()
+                            .for_each(|(index, slot)| {
+                                let selected_text = match slot {
+                                    Some(id) => format!("Gamepad: {}", id),
+                                    None => String::from("None"),
+                                };
+
+                                ComboBox::from_label(format!("Player {}", index + 2))
+                                    .selected_text(selected_text)
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(slot, None, "None");
+                                        gilrs.gamepads().for_each(|(id, gamepad)| {
+                                            ui.selectable_value(slot, Some(id), gamepad.name());
+                                        });
+                                    });
+                            });
+                    }
+
+                    if self.play_mode == PlayMode::SyncTest {
+                        ui.add(
+                            Slider::new(
+                                &mut self.check_distance,
+                                DEFAULT_CHECK_DISTANCE..=MAX_CHECK_DISTANCE,
+                            )
+                            .text("Check Distance"),
+                        )
+                        .on_hover_text("How many frames are resimulated each frame.");
+                    }
+
                     let enabled = self.play_mode == PlayMode::Networked;
 
                     if enabled {
@@ -139,13 +315,32 @@ impl Gui {
                             ui.text_edit_singleline(&mut self.remote_addr);
                         });
 
+                        ui.horizontal(|ui| {
+                            ui.label("Spectator Addresses:");
+                            ui.text_edit_singleline(&mut self.spectator_addrs)
+                                .on_hover_text(format!(
+                                    "Comma separated, up to {}. Only the host needs these.",
+                                    MAX_SPECTATORS
+                                ));
+                        });
+
                         ui.add(Slider::new(&mut self.player_num, 1..=2).text("Player Number"));
 
+                        ui.add(
+                            Slider::new(&mut self.input_delay, 0..=MAX_INPUT_DELAY)
+                                .text("Input Delay"),
+                        )
+                        .on_hover_text("Frames of delay. Use 0-1 on a LAN, 2-3 over the internet.");
+
                         ui.horizontal(|ui| {
                             ui.label("Local Port: ");
                             ui.text_edit_singleline(&mut self.port);
                         });
+
+                        ui.checkbox(&mut self.show_network_stats, "Show Network Stats");
                     }
+
+                    ui.checkbox(&mut self.record_replay, "Record Replay");
                 });
 
                 let launch_game_text = if let Some(session) = session {
@@ -163,20 +358,37 @@ impl Gui {
                 ui.horizontal(|ui| {
                     let launch_game = egui::Button::new(launch_game_text);
                     if ui
-                        .add_enabled(self.game_file.is_some() && session.is_none(), launch_game)
+                        .add_enabled(
+                            self.game_file.is_some()
+                                && self.wasm_console.is_none()
+                                && self.rom_load.is_none(),
+                            launch_game,
+                        )
                         .clicked()
                     {
-                        self.try_launch_game(pixels, window, session)
+                        self.try_launch_game()
                     }
 
-                    let buttons_enabled = self.game_file.is_some() && session.is_some();
+                    let buttons_enabled = self.wasm_console.is_some();
 
                     if ui
                         .add_enabled(buttons_enabled, Button::new("Reset Game"))
                         .clicked()
                     {
                         let console = self.wasm_console.as_mut().unwrap();
-                        console.load_save_state(self.initial_state.as_ref().unwrap().clone());
+
+                        if let Some(playback) = &mut self.replay_playback {
+                            if let Err(e) = playback.seek(console, 0) {
+                                self.stop_game(e);
+                            }
+                        } else {
+                            console.load_save_state(self.initial_state.as_ref().unwrap().clone());
+
+                            // The recording can't be played back past the reset
+                            if console.stop_recording().is_some() {
+                                println!("Resetting the game stopped the recording");
+                            }
+                        }
                     }
 
                     if ui
@@ -184,83 +396,454 @@ impl Gui {
                         .clicked()
                     {
                         self.wasm_console = None;
+                        self.replay_playback = None;
+                        self.sync_test = None;
                         *session = None;
                     }
+
+                    let recording = self
+                        .wasm_console
+                        .as_ref()
+                        .and_then(|console| console.recording());
+
+                    if ui
+                        .add_enabled(recording.is_some(), Button::new("Save Replay"))
+                        .clicked()
+                    {
+                        if let Some(path) = FileDialog::new()
+                            .add_filter("gcreplay (.gcreplay)", &["gcreplay"])
+                            .save_file()
+                        {
+                            if let Err(e) = recording.unwrap().try_save(&path) {
+                                self.error = Some(format!("Failed to save replay: {}", e));
+                            }
+                        }
+                    }
                 });
             });
+
+        self.gamepad_remap.draw(ctx, input, gilrs);
+        self.key_remap.draw(ctx, input);
+
+        if let Some(FinishedRomLoad {
+            result,
+            seed,
+            session_descriptor,
+        }) = RomLoadTask::draw(&mut self.rom_load, ctx)
+        {
+            match result {
+                Ok(game) => {
+                    self.init_with_console(seed, game, pixels, window, session_descriptor, session)
+                }
+                Err(e) => self.error = Some(e),
+            }
+        }
+
+        self.update_live_reload(pixels, window, session);
+        self.draw_replay_controls(ctx);
+        self.draw_error(ctx);
+        self.draw_safety_indicators(ctx);
+        self.draw_network_stats(ctx);
+        self.draw_pause_indicator(ctx);
     }
 
-    /// Quickly launch a single player session, usually from the command line
-    pub(crate) fn fast_launch_game(
+    /// Pauses or resumes the game. Replays have their own pause, and networked
+    /// games can't be paused, as the other players would keep going.
+    pub fn toggle_pause(&mut self) {
+        if let Some(playback) = &mut self.replay_playback {
+            playback.set_paused(!playback.is_paused());
+        } else if self.can_frame_step() {
+            self.frame_step.toggle_pause();
+        }
+    }
+
+    /// Pauses the game and runs its next frame.
+    pub fn step_frame(&mut self) {
+        if let (Some(playback), Some(console)) = (&mut self.replay_playback, &mut self.wasm_console)
+        {
+            playback.set_paused(true);
+            if let Err(e) = playback.step(console) {
+                self.stop_game(e);
+            }
+        } else if self.can_frame_step() {
+            self.frame_step.step();
+        }
+    }
+
+    fn can_frame_step(&self) -> bool {
+        self.wasm_console
+            .as_ref()
+            .is_some_and(|console| console.session_descriptor().is_offline())
+    }
+
+    fn draw_pause_indicator(&self, ctx: &Context) {
+        if !self.frame_step.is_paused() || self.wasm_console.is_none() {
+            return;
+        }
+
+        egui::Area::new("pause_indicator")
+            .anchor(Align2::CENTER_TOP, [0.0, 4.0])
+            .interactable(false)
+            .show(ctx, |ui| {
+                ui.label("Paused: F5 to resume, F6 to step");
+            });
+    }
+
+    /// Shows a small icon in the corner for each active safety option.
+    fn draw_safety_indicators(&self, ctx: &Context) {
+        if !self.audio_limiter && !self.flash_reducer.enabled {
+            return;
+        }
+
+        egui::Area::new("safety_indicators")
+            .anchor(Align2::RIGHT_TOP, [-4.0, 4.0])
+            .interactable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if self.audio_limiter {
+                        ui.label("🔉").on_hover_text("Limiting sudden loud sounds");
+                    }
+                    if self.flash_reducer.enabled {
+                        ui.label("☀").on_hover_text("Reducing flashing");
+                    }
+                });
+            });
+    }
+
+    /// Shows the ping and rollbacks of a networked game in the corner.
+    fn draw_network_stats(&self, ctx: &Context) {
+        if !self.show_network_stats || self.play_mode != PlayMode::Networked {
+            return;
+        }
+
+        let stats = match &self.wasm_console {
+            Some(console) => console.session_stats(),
+            None => return,
+        };
+
+        egui::Area::new("network_stats")
+            .anchor(Align2::LEFT_TOP, [4.0, 4.0])
+            .interactable(false)
+            .show(ctx, |ui| {
+                for player in &stats.remote_players {
+                    ui.label(format!(
+                        "P{}: {}ms, {} kbps, {} unacked",
+                        player.handle + 1,
+                        player.ping,
+                        player.kbps_sent,
+                        player.send_queue_len
+                    ));
+                }
+                ui.label(format!("Frames ahead: {}", stats.frames_ahead));
+                ui.label(format!(
+                    "Rollback: {} frames ({} total)",
+                    stats.last_rollback_frames, stats.total_rollback_frames
+                ));
+            });
+    }
+
+    /// Watches the running game's rom, and restarts the game once it changes. Networked
+    /// games and replays aren't reloaded, as the new code would desync them.
+    fn update_live_reload(
         &mut self,
-        game_path: PathBuf,
-        seed: u64,
         pixels: &mut Pixels,
         window: &Window,
-        session: &mut Option<P2PSession<WasmConsole>>,
+        session: &Option<P2PSession<WasmConsole>>,
     ) {
-        let rom = match Rom::try_load(&game_path) {
-            Err(e) => {
-                println!("{}", e);
+        let reloadable = self.live_reload
+            && self.replay_playback.is_none()
+            && self
+                .wasm_console
+                .as_ref()
+                .is_some_and(|console| console.session_descriptor().is_offline());
+
+        let path = match (&self.game_file, reloadable) {
+            (Some(path), true) => path,
+            _ => {
+                self.game_watcher = None;
+                self.reload = None;
                 return;
             }
-            Ok(rom) => rom,
         };
 
-        self.game_file = Some(game_path);
+        if self.game_watcher.as_ref().map(GameWatcher::path) != Some(path.as_path()) {
+            self.game_watcher = match GameWatcher::new(path) {
+                Ok(watcher) => Some(watcher),
+                Err(e) => {
+                    println!("Failed to watch the game for changes: {}", e);
+                    self.live_reload = false;
+                    None
+                }
+            };
+        }
 
+        let changed = self.game_watcher.as_mut().is_some_and(GameWatcher::poll);
+        if changed && self.reload.is_none() {
+            let console = self.wasm_console.as_ref().unwrap();
+            self.reload = Some(RomLoadTask::spawn(
+                path.clone(),
+                console.seed,
+                console.session_descriptor().clone(),
+            ));
+        }
+
+        if let Some(finished) = RomLoadTask::poll(&mut self.reload) {
+            let result = finished.result.and_then(|game| {
+                let descriptor = finished.session_descriptor;
+                self.reload_console(game, finished.seed, descriptor, pixels, window, session)
+            });
+
+            // The old game keeps running if the new one is broken
+            if let Err(e) = result {
+                self.error = Some(format!("Failed to reload the game: {}", e));
+            }
+        }
+    }
+
+    /// Replaces the running game with its reloaded code, starting it over from init.
+    fn reload_console(
+        &mut self,
+        game: LoadedGame,
+        seed: u64,
+        session_descriptor: SessionDescriptor,
+        pixels: &mut Pixels,
+        window: &Window,
+        session: &Option<P2PSession<WasmConsole>>,
+    ) -> Result<(), String> {
+        // The session only has local players, so it never rolls back, and can keep going
+        let sync_test = self
+            .sync_test
+            .is_some()
+            .then(|| init_sync_test_session(&session_descriptor, self.check_distance));
+        let max_prediction = match (&sync_test, session) {
+            (Some(sync_test), _) => sync_test.max_prediction(),
+            (None, Some(session)) => session.max_prediction(),
+            (None, None) => return Err(String::from("The game isn't running")),
+        };
+
+        let (mut console, reset) = WasmConsole::new(game, seed, session_descriptor, max_prediction)
+            .map_err(|e| e.to_string())?;
+        console.sound_engine.set_limiter_enabled(self.audio_limiter);
+
+        if sync_test.is_some() {
+            console.start_sync_test();
+        }
+
+        // The old recording can't be played back with the new code
+        if self.record_replay {
+            console.start_recording();
+        }
+
+        resize_to_rom(&console, pixels, window);
+
+        println!("Reloaded the game");
+        self.wasm_console = Some(console);
+        self.initial_state = Some(reset);
+        if sync_test.is_some() {
+            self.sync_test = sync_test;
+        }
+
+        Ok(())
+    }
+
+    /// Stops the running game, and shows the error which stopped it.
+    pub fn stop_game(&mut self, error: String) {
+        self.wasm_console = None;
+        self.replay_playback = None;
+        self.sync_test = None;
+        self.error = Some(error);
+    }
+
+    /// Shows the controls for the replay being played, if there is one.
+    fn draw_replay_controls(&mut self, ctx: &Context) {
+        let (playback, console) = match (&mut self.replay_playback, &mut self.wasm_console) {
+            (Some(playback), Some(console)) => (playback, console),
+            _ => return,
+        };
+
+        let mut result = Ok(());
+
+        egui::Window::new("Replay")
+            .anchor(Align2::CENTER_BOTTOM, [0.0, -4.0])
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let pause_text = if playback.is_paused() {
+                        "Play"
+                    } else {
+                        "Pause"
+                    };
+                    if ui.button(pause_text).clicked() {
+                        playback.set_paused(!playback.is_paused());
+                    }
+
+                    let can_step = playback.is_paused() && !playback.is_finished();
+                    if ui.add_enabled(can_step, Button::new("Step")).clicked() {
+                        result = playback.step(console);
+                    }
+                });
+
+                let mut frame = playback.frame();
+                let last_frame = playback.replay().len();
+                if ui
+                    .add(Slider::new(&mut frame, 0..=last_frame).text("Frame"))
+                    .changed()
+                {
+                    result = playback.seek(console, frame);
+                }
+            });
+
+        if let Err(e) = result {
+            self.stop_game(e);
+        }
+    }
+
+    fn draw_error(&mut self, ctx: &Context) {
+        let mut dismissed = false;
+
+        if let Some(error) = &self.error {
+            egui::Window::new("Error")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    // Traps can include a long backtrace
+                    ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                        ui.label(error.as_str());
+                    });
+                    if ui.button("Ok").clicked() {
+                        dismissed = true;
+                    }
+                });
+        }
+
+        if dismissed {
+            self.error = None;
+        }
+    }
+
+    /// Quickly launch a single player session, usually from the command line
+    pub fn fast_launch_game(&mut self, game_path: PathBuf, seed: u64) {
         let session_descriptor = SessionDescriptor {
             num_players: 1,
             player_types: vec![PlayerType::Local].into_boxed_slice(),
+            spectators: Box::default(),
             port: 8000,
+            input_delay: 0,
         };
 
-        self.init_with_console(seed, rom, pixels, window, session_descriptor, session);
+        self.rom_load = Some(RomLoadTask::spawn(
+            game_path.clone(),
+            seed,
+            session_descriptor,
+        ));
+        self.game_file = Some(game_path);
+    }
+
+    /// Launches a game to play back the replay, without a session.
+    pub fn launch_replay(&mut self, game_path: PathBuf, replay: Replay) {
+        let session_descriptor = SessionDescriptor {
+            num_players: replay.num_players,
+            player_types: vec![PlayerType::Local; replay.num_players].into_boxed_slice(),
+            spectators: Box::default(),
+            port: 0,
+            input_delay: 0,
+        };
+
+        self.rom_load = Some(RomLoadTask::spawn(
+            game_path.clone(),
+            replay.seed,
+            session_descriptor,
+        ));
+        self.game_file = Some(game_path);
+        self.pending_replay = Some(replay);
     }
 
     fn init_with_console(
         &mut self,
         seed: u64,
-        rom: Rom,
+        game: LoadedGame,
         pixels: &mut Pixels,
         window: &Window,
         session_descriptor: SessionDescriptor,
         session: &mut Option<P2PSession<WasmConsole>>,
     ) {
-        pixels.resize_buffer(rom.width() as u32, rom.height() as u32);
-        window.set_inner_size(PhysicalSize::new(
-            rom.width().max(DEFAULT_WINDOW_RESOLUTION.width()),
-            rom.height().max(DEFAULT_WINDOW_RESOLUTION.height()),
-        ));
+        let replay = self.pending_replay.take();
+        let check_distance = self.pending_check_distance.take();
+
+        let (game, max_prediction, new_session, sync_test) = match (&replay, check_distance) {
+            // The inputs are already known, so there's no need for a session
+            (Some(replay), _) => (
+                game.with_save_data(replay.save_data.clone()),
+                PLAYBACK_AUDIO_BUFFER_SIZE,
+                None,
+                None,
+            ),
+            (None, Some(check_distance)) => {
+                let sync_test = init_sync_test_session(&session_descriptor, check_distance);
+                (game, sync_test.max_prediction(), None, Some(sync_test))
+            }
+            (None, None) => {
+                let new_session = init_session(&game.rom, &session_descriptor);
+                (game, new_session.max_prediction(), Some(new_session), None)
+            }
+        };
 
-        let (max_prediction, new_session) = {
-            let new_session = init_session(
-                &rom,
-                session_descriptor.port,
-                &session_descriptor.player_types,
-            );
-            (new_session.max_prediction(), new_session)
+        let (mut console, reset) =
+            match WasmConsole::new(game, seed, session_descriptor, max_prediction) {
+                Ok(console) => console,
+                Err(e) => {
+                    self.error = Some(e.to_string());
+                    return;
+                }
+            };
+        console.sound_engine.set_limiter_enabled(self.audio_limiter);
+
+        if sync_test.is_some() {
+            console.start_sync_test();
+        }
+
+        let playback = match replay {
+            Some(replay) => match ReplayPlayback::new(replay, &console) {
+                Ok(playback) => Some(playback),
+                Err(e) => {
+                    self.error = Some(e);
+                    return;
+                }
+            },
+            None => {
+                if self.record_replay {
+                    console.start_recording();
+                }
+                None
+            }
         };
 
-        *session = Some(new_session);
+        resize_to_rom(&console, pixels, window);
 
-        self.window_open = false;
+        *session = new_session;
 
-        let (console, reset) = WasmConsole::new(rom, seed, session_descriptor, max_prediction);
+        self.window_open = false;
+        self.frame_step = FrameStep::default();
 
         self.wasm_console = Some(console);
         self.initial_state = Some(reset);
+        self.replay_playback = playback;
+        self.sync_test = sync_test;
     }
 
-    pub(crate) fn try_launch_game(
-        &mut self,
-        pixels: &mut Pixels,
-        window: &Window,
-        session: &mut Option<P2PSession<WasmConsole>>,
-    ) {
-        let path = self.game_file.as_ref().unwrap();
-        let (players, port) = match self.play_mode {
-            PlayMode::SinglePlayer => (vec![PlayerType::Local], 8000),
+    pub fn try_launch_game(&mut self) {
+        let path = self.game_file.clone().unwrap();
+        let (players, spectators, port, input_delay) = match self.play_mode {
+            // Nothing to wait for, so there's no need for delay
+            PlayMode::SinglePlayer | PlayMode::SyncTest => {
+                (vec![PlayerType::Local], Vec::new(), 8000, 0)
+            }
+            PlayMode::LocalMultiplayer => (
+                vec![PlayerType::Local; self.local_players],
+                Vec::new(),
+                8000,
+                0,
+            ),
             PlayMode::Networked => {
                 let remote_addr = self.remote_addr.parse::<SocketAddr>();
                 let port = self.port.parse::<u16>();
@@ -286,52 +869,91 @@ impl Gui {
                     return;
                 };
 
-                (players, port)
+                let spectators = self
+                    .spectator_addrs
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|addr| !addr.is_empty())
+                    .map(str::parse::<SocketAddr>)
+                    .collect::<Result<Vec<_>, _>>();
+
+                let spectators = match spectators {
+                    Ok(spectators) if spectators.len() <= MAX_SPECTATORS => spectators,
+                    Ok(_) => {
+                        println!("There can be at most {} spectators", MAX_SPECTATORS);
+                        return;
+                    }
+                    Err(_) => {
+                        println!("Spectator Addr is invalid");
+                        return;
+                    }
+                };
+
+                (players, spectators, port, self.input_delay)
             }
         };
 
         let players = players.into_boxed_slice();
 
-        let rom = match Rom::try_load(path) {
-            Err(e) => {
-                println!("{}", e);
-                return;
-            }
-            Ok(rom) => rom,
-        };
-
-        let num_players = if self.play_mode == PlayMode::SinglePlayer {
-            1
-        } else {
-            2
-        };
+        let num_players = players.len();
 
         let session_descriptor = SessionDescriptor {
             num_players,
             player_types: players,
+            spectators: spectators.into_boxed_slice(),
             port,
+            input_delay,
         };
 
         let seed = u64::from_str_radix(&self.seed, 16).unwrap();
 
-        self.init_with_console(seed, rom, pixels, window, session_descriptor, session);
+        self.pending_check_distance =
+            (self.play_mode == PlayMode::SyncTest).then_some(self.check_distance);
+
+        self.rom_load = Some(RomLoadTask::spawn(path, seed, session_descriptor));
     }
 }
 
-fn init_session(
-    rom: &Rom,
-    port: u16,
-    players: &[PlayerType<SocketAddr>],
-) -> P2PSession<WasmConsole> {
+/// Fits the pixel buffer and window to the resolution of the game.
+fn resize_to_rom(console: &WasmConsole, pixels: &mut Pixels, window: &Window) {
+    let rom = &console.rom;
+    pixels.resize_buffer(rom.width() as u32, rom.height() as u32);
+    window.set_inner_size(PhysicalSize::new(
+        rom.width().max(DEFAULT_WINDOW_RESOLUTION.width()),
+        rom.height().max(DEFAULT_WINDOW_RESOLUTION.height()),
+    ));
+}
+
+fn init_session(rom: &Rom, session: &SessionDescriptor) -> P2PSession<WasmConsole> {
+    let players = &session.player_types;
     let mut sess_builder = SessionBuilder::new()
         .with_num_players(players.len())
         .with_fps(rom.frame_rate.frames_per_second())
-        .unwrap();
+        .unwrap()
+        .with_input_delay(session.input_delay);
 
     for (id, address) in players.iter().enumerate() {
         sess_builder = sess_builder.add_player(*address, id).unwrap();
     }
 
-    let socket = UdpNonBlockingSocket::bind_to_port(port).unwrap();
+    // Spectator handles come after the players
+    for (id, address) in session.spectators.iter().enumerate() {
+        sess_builder = sess_builder
+            .add_player(PlayerType::Spectator(*address), players.len() + id)
+            .unwrap();
+    }
+
+    let socket = UdpNonBlockingSocket::bind_to_port(session.port).unwrap();
     sess_builder.start_p2p_session(socket).unwrap()
 }
+
+fn init_sync_test_session(
+    session: &SessionDescriptor,
+    check_distance: usize,
+) -> SyncTestSession<WasmConsole> {
+    SessionBuilder::new()
+        .with_num_players(session.num_players)
+        .with_check_distance(check_distance)
+        .start_synctest_session()
+        .unwrap()
+}
@@ -0,0 +1,118 @@
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    thread::{self, JoinHandle},
+};
+
+use egui::{Context, ProgressBar};
+use gamercade_fs::{LoadPhase, LoadProgress};
+
+use crate::console::{LoadedGame, SessionDescriptor};
+
+/// A rom being loaded on a background thread, so the window stays responsive.
+pub(crate) struct RomLoadTask {
+    progress: Arc<LoadProgress>,
+    handle: JoinHandle<Result<LoadedGame, String>>,
+    seed: u64,
+    session_descriptor: SessionDescriptor,
+}
+
+/// The outcome of a finished load, along with the settings to launch it with.
+pub(crate) struct FinishedRomLoad {
+    pub(crate) result: Result<LoadedGame, String>,
+    pub(crate) seed: u64,
+    pub(crate) session_descriptor: SessionDescriptor,
+}
+
+impl RomLoadTask {
+    pub(crate) fn spawn(path: PathBuf, seed: u64, session_descriptor: SessionDescriptor) -> Self {
+        let progress = Arc::new(LoadProgress::default());
+        let thread_progress = progress.clone();
+
+        let handle = thread::spawn(move || LoadedGame::try_load(&path, &thread_progress));
+
+        Self {
+            progress,
+            handle,
+            seed,
+            session_descriptor,
+        }
+    }
+
+    /// Returns the finished event once the background work has completed.
+    fn try_finish(self) -> Result<FinishedRomLoad, Self> {
+        if self.handle.is_finished() {
+            let result = self
+                .handle
+                .join()
+                .unwrap_or_else(|_| Err(String::from("Loading thread panicked")));
+
+            Ok(FinishedRomLoad {
+                result,
+                seed: self.seed,
+                session_descriptor: self.session_descriptor,
+            })
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Takes the task out of the option once it has finished, without drawing anything.
+    pub(crate) fn poll(task: &mut Option<Self>) -> Option<FinishedRomLoad> {
+        match task.take()?.try_finish() {
+            Ok(finished) => Some(finished),
+            Err(current) => {
+                *task = Some(current);
+                None
+            }
+        }
+    }
+
+    /// Draws the progress window. Takes the task out of the option when it has
+    /// finished or been cancelled, returning the result if it finished.
+    pub(crate) fn draw(task: &mut Option<Self>, ctx: &Context) -> Option<FinishedRomLoad> {
+        let current = task.take()?;
+
+        let current = match current.try_finish() {
+            Ok(finished) => return Some(finished),
+            Err(current) => current,
+        };
+
+        let mut cancelled = false;
+        egui::Window::new("Loading Game")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let progress = &current.progress;
+                match progress.phase() {
+                    LoadPhase::Decompressing => {
+                        ui.label(format!(
+                            "Decompressing... {} / {} KiB",
+                            progress.bytes_read() / 1024,
+                            progress.total_bytes() / 1024
+                        ));
+                        ui.add(ProgressBar::new(progress.fraction()).show_percentage());
+                    }
+                    LoadPhase::Deserializing => {
+                        ui.label("Reading rom...");
+                        ui.add(ProgressBar::new(1.0));
+                    }
+                    LoadPhase::Compiling => {
+                        ui.label("Compiling game code...");
+                        ui.add(ProgressBar::new(1.0));
+                    }
+                }
+
+                if ui.button("Cancel").clicked() {
+                    progress.cancel();
+                    cancelled = true;
+                }
+            });
+
+        if !cancelled {
+            *task = Some(current);
+        }
+
+        None
+    }
+}
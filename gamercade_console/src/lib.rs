@@ -0,0 +1,11 @@
+mod api;
+pub mod console;
+mod flash_reduction;
+pub mod gui;
+mod pixel_buffer;
+
+use gamercade_core::Resolution;
+
+pub use console::Console;
+
+pub const DEFAULT_WINDOW_RESOLUTION: Resolution = Resolution::High;
@@ -1,16 +1,10 @@
-mod api;
-mod console;
-mod gui;
-mod pixel_buffer;
-
 use std::{
     path::PathBuf,
     time::{Duration, Instant},
 };
 
 use clap::Parser;
-use gamercade_core::Resolution;
-use ggrs::{GGRSError, P2PSession, SessionState};
+use ggrs::{Frame, GGRSError, P2PSession, SessionState};
 use gilrs::Gilrs;
 use pixels::{Pixels, SurfaceTexture};
 use winit::{
@@ -21,17 +15,27 @@ use winit::{
 };
 use winit_input_helper::WinitInputHelper;
 
-use crate::{
-    console::LocalInputManager,
+use gamercade_fs::{has_embedded_rom, Replay};
+
+use gamercade_console::{
+    console::{Console, InputMode, LocalInputManager, WasmConsole},
     gui::{framework::Framework, Gui},
+    DEFAULT_WINDOW_RESOLUTION,
 };
-use console::{Console, InputMode, WasmConsole};
 
 #[derive(Parser, Debug)]
 struct Cli {
-    /// Path to .gcrom to load.
+    /// Path to .gcrom to load. Exported games launch their own rom when this isn't set.
     #[clap(short, long, value_parser)]
     game: Option<PathBuf>,
+
+    /// Path to a .gcreplay to play back. Must be recorded from the same game.
+    #[clap(short, long, value_parser, requires = "game")]
+    replay: Option<PathBuf>,
+
+    /// Restart the game whenever its .gcrom changes on disk.
+    #[clap(short, long, value_parser)]
+    live_reload: bool,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -60,12 +64,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         &pixels,
         Gui::default(),
     );
+    framework.gui.live_reload = cli.live_reload;
 
     if let Some(game_path) = &cli.game {
+        match &cli.replay {
+            Some(replay_path) => match Replay::try_load(replay_path) {
+                Ok(replay) => framework.gui.launch_replay(game_path.clone(), replay),
+                Err(e) => println!("Failed to load replay: {}", e),
+            },
+            None => {
+                let seed = fastrand::u64(0..u64::MAX);
+                framework.gui.fast_launch_game(game_path.clone(), seed);
+            }
+        }
+    } else if let Some(exe) = std::env::current_exe().ok().filter(has_embedded_rom) {
+        // This binary was exported with a rom appended, which loads like any other
         let seed = fastrand::u64(0..u64::MAX);
-        framework
-            .gui
-            .fast_launch_game(game_path.clone(), seed, &mut pixels, &window, &mut session);
+        framework.gui.fast_launch_game(exe, seed);
     }
 
     event_loop.run(move |event, _, control_flow| {
@@ -73,6 +88,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             framework.handle_event(event);
         }
 
+        // Handled once per frame, so the gamepads stay up to date for
+        // the remapping window while a game isn't running.
+        if let Event::MainEventsCleared = event {
+            input_manager.process_gamepad_events(&mut gilrs);
+        }
+
         framework.prepare(
             &mut pixels,
             &mut session,
@@ -83,16 +104,28 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         // Handle input events
         if input.update(&event) {
+            // The console's hotkeys are ignored while a key is being bound
+            let hotkeys = !framework.gui.key_remap.is_listening();
+
             // Close events
-            if input.key_pressed(VirtualKeyCode::Escape) || input.quit() {
+            if (hotkeys && input.key_pressed(VirtualKeyCode::Escape)) || input.quit() {
                 *control_flow = ControlFlow::Exit;
                 return;
             }
 
-            if input.key_pressed(VirtualKeyCode::Space) {
+            if hotkeys && input.key_pressed(VirtualKeyCode::Space) {
                 framework.gui.window_open = !framework.gui.window_open;
             }
 
+            // Debugging controls, which only work offline
+            if hotkeys && input.key_pressed(VirtualKeyCode::F5) {
+                framework.gui.toggle_pause();
+            }
+
+            if hotkeys && input.key_pressed(VirtualKeyCode::F6) {
+                framework.gui.step_frame();
+            }
+
             // Update the scale factor
             if let Some(scale_factor) = input.scale_factor() {
                 framework.scale_factor(scale_factor);
@@ -104,22 +137,46 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 framework.resize(size.width, size.height);
             }
 
+            // Set if the game traps, which stops it
+            let mut game_error = None;
+
             if let Some(console) = &mut framework.gui.wasm_console {
-                // Handle GGRS packets
-                let session = session.as_mut().unwrap();
-                session.poll_remote_clients();
-
-                if session.current_state() == SessionState::Running {
-                    // this is to keep ticks between clients synchronized.
-                    // if a client is ahead, it will run frames slightly slower to allow catching up
-                    let mut fps_delta = 1. / console.rom.frame_rate.frames_per_second() as f64;
-                    if session.frames_ahead() > 0 {
-                        fps_delta *= 1.1;
+                // Set once the game is running, so it should be drawn
+                let mut running = false;
+
+                if let Some(playback) = &mut framework.gui.replay_playback {
+                    running = true;
+
+                    // Replays don't need a session, so just play at the game's frame rate
+                    let fps_delta = 1. / console.rom().frame_rate.frames_per_second() as f64;
+
+                    let delta = Instant::now().duration_since(last_update);
+                    accumulator = accumulator.saturating_add(delta);
+                    last_update = Instant::now();
+
+                    while accumulator.as_secs_f64() > fps_delta {
+                        accumulator =
+                            accumulator.saturating_sub(Duration::from_secs_f64(fps_delta));
+
+                        if let Err(e) = playback.tick(console) {
+                            game_error = Some(e);
+                            break;
+                        }
                     }
 
-                    // get delta time from last iteration and accumulate it
+                    // Replays are watched, not played, so they don't rumble
+                    console.take_rumbles();
+                } else if let Some(sync_test) = &mut framework.gui.sync_test {
+                    running = true;
+
+                    let fps_delta = 1. / console.rom().frame_rate.frames_per_second() as f64;
+
                     let delta = Instant::now().duration_since(last_update);
                     accumulator = accumulator.saturating_add(delta);
+                    accumulator = framework
+                        .gui
+                        .frame_step
+                        .limit(accumulator, Duration::from_secs_f64(fps_delta));
                     last_update = Instant::now();
 
                     while accumulator.as_secs_f64() > fps_delta {
@@ -127,38 +184,122 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                             accumulator.saturating_sub(Duration::from_secs_f64(fps_delta));
 
                         // Process all the gamepad events
-                        while gilrs.next_event().is_some() {}
-
-                        // Generate all local inputs
-                        // TODO: Refactor this to handle multiple local players correctly
-                        for handle in session.local_player_handles() {
-                            session
-                                .add_local_input(
-                                    handle,
-                                    input_manager.generate_input_state(&input, &gilrs),
-                                )
-                                .unwrap();
+                        input_manager.process_gamepad_events(&mut gilrs);
+
+                        // Every player in a sync test is local
+                        for handle in 0..sync_test.num_players() {
+                            let state = input_manager
+                                .generate_local_input_state(handle, &input, &gilrs, &pixels);
+                            sync_test.add_local_input(handle, state).unwrap();
                         }
 
-                        // Update internal state
-                        match session.advance_frame() {
+                        match sync_test.advance_frame() {
                             Ok(requests) => {
-                                console.handle_requests(requests);
+                                if let Err(e) = console.handle_requests(requests) {
+                                    game_error = Some(e);
+                                    break;
+                                }
+                            }
+                            Err(GGRSError::MismatchedChecksum { frame }) => {
+                                game_error = Some(desync_error(console, frame));
+                                break;
                             }
-                            Err(GGRSError::PredictionThreshold) => (),
                             Err(e) => panic!("{}", e),
                         }
                     }
 
+                    let local_handles: Vec<usize> = (0..sync_test.num_players()).collect();
+                    apply_rumbles(console, &mut input_manager, &mut gilrs, &local_handles);
+                } else if let Some(session) = session.as_mut() {
+                    // Handle GGRS packets
+                    session.poll_remote_clients();
+
+                    if session.current_state() == SessionState::Running {
+                        running = true;
+
+                        // this is to keep ticks between clients synchronized.
+                        // if a client is ahead, it will run frames slightly slower to allow catching up
+                        let mut fps_delta =
+                            1. / console.rom().frame_rate.frames_per_second() as f64;
+                        if session.frames_ahead() > 0 {
+                            fps_delta *= 1.1;
+                        }
+
+                        // get delta time from last iteration and accumulate it
+                        let delta = Instant::now().duration_since(last_update);
+                        accumulator = accumulator.saturating_add(delta);
+                        accumulator = framework
+                            .gui
+                            .frame_step
+                            .limit(accumulator, Duration::from_secs_f64(fps_delta));
+                        last_update = Instant::now();
+
+                        while accumulator.as_secs_f64() > fps_delta {
+                            accumulator =
+                                accumulator.saturating_sub(Duration::from_secs_f64(fps_delta));
+
+                            // Process all the gamepad events
+                            input_manager.process_gamepad_events(&mut gilrs);
+
+                            // Generate all local inputs, in the order of their handles
+                            for (local_player, handle) in
+                                session.local_player_handles().into_iter().enumerate()
+                            {
+                                let state = input_manager.generate_local_input_state(
+                                    local_player,
+                                    &input,
+                                    &gilrs,
+                                    &pixels,
+                                );
+                                session.add_local_input(handle, state).unwrap();
+                            }
+
+                            // Update internal state
+                            match session.advance_frame() {
+                                Ok(requests) => {
+                                    if let Err(e) = console.handle_requests(requests) {
+                                        game_error = Some(e);
+                                        break;
+                                    }
+                                }
+                                Err(GGRSError::PredictionThreshold) => (),
+                                Err(GGRSError::MismatchedChecksum { frame }) => {
+                                    game_error = Some(desync_error(console, frame));
+                                    break;
+                                }
+                                Err(e) => panic!("{}", e),
+                            }
+                        }
+
+                        let local_handles = session.local_player_handles();
+                        apply_rumbles(console, &mut input_manager, &mut gilrs, &local_handles);
+                        console.flush_save_data(session.confirmed_frame());
+                        console.update_session_stats(session);
+                    }
+                }
+
+                if running {
                     // If sound changed, update the output
                     console.sync_audio();
 
                     // Render the game
-                    console.call_draw();
-                    console.blit(pixels.get_frame());
-                };
+                    if game_error.is_none() {
+                        match console.call_draw() {
+                            Ok(()) => {
+                                console.blit(pixels.get_frame());
+                                framework.gui.flash_reducer.apply(pixels.get_frame());
+                            }
+                            Err(e) => game_error = Some(e),
+                        }
+                    }
+                }
             };
 
+            if let Some(error) = game_error {
+                framework.gui.stop_game(error);
+                session = None;
+            }
+
             let render_result = pixels.render_with(|encoder, render_target, context| {
                 //TODO: Handle this correctly
                 context.scaling_renderer.render(encoder, render_target);
@@ -177,7 +318,31 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     });
 }
 
-const DEFAULT_WINDOW_RESOLUTION: Resolution = Resolution::High;
+/// Sends the rumble requested by the game to the gamepads on this machine. This happens
+/// after the session advances, outside of the rollback loop, so each rumble fires once.
+fn apply_rumbles(
+    console: &mut WasmConsole,
+    input_manager: &mut LocalInputManager,
+    gilrs: &mut Gilrs,
+    local_handles: &[usize],
+) {
+    let frames_per_second = console.rom().frame_rate.frames_per_second() as f64;
+
+    for (player, rumble) in console.take_rumbles() {
+        if let Some(local_player) = local_handles.iter().position(|handle| *handle == player) {
+            let duration = Duration::from_secs_f64(rumble.frames as f64 / frames_per_second);
+            input_manager.rumble(local_player, rumble.strength, duration, gilrs);
+        }
+    }
+}
+
+/// Describes a desync, including which part of the state changed if a sync test found it.
+fn desync_error(console: &WasmConsole, frame: Frame) -> String {
+    match console.sync_test_mismatch() {
+        Some(mismatch) => format!("Desync detected on frame {}. {}", frame, mismatch),
+        None => format!("Desync detected on frame {}.", frame),
+    }
+}
 
 fn init_window(event_loop: &EventLoop<()>) -> Window {
     let size = LogicalSize::new(
@@ -2,7 +2,7 @@ use std::ops::{Index, IndexMut, Range};
 
 use gamercade_core::{ColorIndex, PaletteIndex};
 
-use gamercade_core::{Palette, SpriteIndex, SpriteSheet, BYTES_PER_PIXEL};
+use gamercade_core::{Palette, SpriteIndex, SpriteSheet, Tilemap, BYTES_PER_PIXEL, PALETTE_COLORS};
 use gamercade_fs::Rom;
 
 #[derive(Clone)]
@@ -10,6 +10,22 @@ pub struct PixelBuffer {
     pub pixel_buffer: Box<[u8]>,
     pub buffer_width: usize,
     pub buffer_height: usize,
+    clip: ClipRect,
+}
+
+/// The region of the buffer which can be drawn to. Right and bottom are exclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClipRect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl ClipRect {
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        (self.left..self.right).contains(&x) && (self.top..self.bottom).contains(&y)
+    }
 }
 
 impl PixelBuffer {
@@ -19,13 +35,57 @@ impl PixelBuffer {
             .collect::<Vec<u8>>()
             .into_boxed_slice();
 
+        Self::new(
+            pixel_buffer,
+            rom.resolution.width() as usize,
+            rom.resolution.height() as usize,
+        )
+    }
+
+    fn new(pixel_buffer: Box<[u8]>, buffer_width: usize, buffer_height: usize) -> Self {
         Self {
             pixel_buffer,
-            buffer_width: rom.resolution.width() as usize,
-            buffer_height: rom.resolution.height() as usize,
+            buffer_width,
+            buffer_height,
+            clip: ClipRect {
+                left: 0,
+                top: 0,
+                right: buffer_width as i32,
+                bottom: buffer_height as i32,
+            },
         }
     }
 
+    pub fn clip(&self) -> ClipRect {
+        self.clip
+    }
+
+    /// Restricts drawing to the rectangle, clamped to the buffer.
+    /// An empty rectangle prevents anything from being drawn.
+    pub fn set_clip(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        let clamp_x = |x: i32| x.clamp(0, self.buffer_width as i32);
+        let clamp_y = |y: i32| y.clamp(0, self.buffer_height as i32);
+
+        let left = clamp_x(x);
+        let top = clamp_y(y);
+
+        self.clip = ClipRect {
+            left,
+            top,
+            right: clamp_x(x.saturating_add(width.max(0))).max(left),
+            bottom: clamp_y(y.saturating_add(height.max(0))).max(top),
+        };
+    }
+
+    pub fn clear_clip(&mut self) {
+        self.clip = ClipRect {
+            left: 0,
+            top: 0,
+            right: self.buffer_width as i32,
+            bottom: self.buffer_height as i32,
+        };
+    }
+
     pub fn clear_buffer(&mut self, color: ColorIndex, palette: PaletteIndex, rom: &Rom) {
         let color = if let Some(Some(color)) = rom
             .graphics
@@ -36,60 +96,252 @@ impl PixelBuffer {
         } else {
             return;
         };
+
+        let clip = self.clip;
+        let row_length = self.buffer_width * BYTES_PER_PIXEL;
+
         self.pixel_buffer
-            .chunks_exact_mut(BYTES_PER_PIXEL)
-            .for_each(|pixel| pixel.copy_from_slice(&color));
+            .chunks_exact_mut(row_length)
+            .skip(clip.top as usize)
+            .take((clip.bottom - clip.top) as usize)
+            .for_each(|row| {
+                row[clip.left as usize * BYTES_PER_PIXEL..clip.right as usize * BYTES_PER_PIXEL]
+                    .chunks_exact_mut(BYTES_PER_PIXEL)
+                    .for_each(|pixel| pixel.copy_from_slice(&color))
+            });
     }
 
-    pub fn draw_sprite(
-        &mut self,
-        sheet: &SpriteSheet,
-        sprite_index: SpriteIndex,
-        palette: &Palette,
-        (x, y): (i32, i32),
-        transparency_mask: i64,
-        (flip_x, flip_y): (bool, bool),
-    ) {
-        let palette = palette.as_pixel_colors();
-        let sprite_width = sheet.width;
-        let sprite_height = sheet.height;
-        let sprite = &sheet[sprite_index];
+    pub fn draw_sprite(&mut self, blit: &SpriteBlit, (x, y): (i32, i32)) {
+        let palette = blit.palette.as_pixel_colors();
+        let sprite_width = blit.sheet.width;
+        let sprite_height = blit.sheet.height;
 
         let start = (y * self.buffer_width as i32) + x;
 
-        let sprite_start_x = x.min(0).unsigned_abs() as usize;
-        let sprite_start_y = y.min(0).unsigned_abs() as usize;
-        let sprite_bounds_width = (self.buffer_width as i32 - x)
-            .min(sprite_width as i32)
-            .max(0) as usize;
-        let sprite_bounds_height = (self.buffer_height as i32 - y)
-            .min(sprite_height as i32)
-            .max(0) as usize;
+        let sprite_start_x = (self.clip.left - x).max(0) as usize;
+        let sprite_start_y = (self.clip.top - y).max(0) as usize;
+        let sprite_bounds_width = (self.clip.right - x).min(sprite_width as i32).max(0) as usize;
+        let sprite_bounds_height = (self.clip.bottom - y).min(sprite_height as i32).max(0) as usize;
 
         (sprite_start_y..sprite_bounds_height).for_each(|y| {
             (sprite_start_x..sprite_bounds_width).for_each(|x| {
                 let target_pixel = start + x as i32 + (y as i32 * self.buffer_width as i32);
                 let target_pixel = target_pixel as usize * BYTES_PER_PIXEL;
 
-                let sprite_x = if flip_x { sprite_width - x - 1 } else { x };
+                if let Some(color) = blit.color(&palette, x, y) {
+                    self.pixel_buffer[target_pixel..target_pixel + BYTES_PER_PIXEL]
+                        .copy_from_slice(&color);
+                }
+            });
+        });
+    }
+
+    /// Draws a sprite rotated clockwise by the angle, in radians, about its center.
+    /// Each screen pixel is rotated back into the sprite and uses the nearest texel,
+    /// so no new colors are introduced. An angle of 0 is the same as draw_sprite.
+    pub fn draw_sprite_rotated(&mut self, blit: &SpriteBlit, (x, y): (i32, i32), angle: f32) {
+        if angle == 0.0 {
+            return self.draw_sprite(blit, (x, y));
+        } else if !angle.is_finite() {
+            return;
+        }
+
+        let palette = blit.palette.as_pixel_colors();
+        let sprite_width = blit.sheet.width as f32;
+        let sprite_height = blit.sheet.height as f32;
+
+        let (sin, cos) = angle.sin_cos();
+        let center_x = x as f32 + sprite_width / 2.0;
+        let center_y = y as f32 + sprite_height / 2.0;
+
+        // The bounding box of the rotated sprite, clipped to the screen
+        let half_width = (sprite_width * cos.abs() + sprite_height * sin.abs()) / 2.0;
+        let half_height = (sprite_width * sin.abs() + sprite_height * cos.abs()) / 2.0;
+        let start_x = ((center_x - half_width).floor() as i32).max(self.clip.left);
+        let start_y = ((center_y - half_height).floor() as i32).max(self.clip.top);
+        let end_x = ((center_x + half_width).ceil() as i32).min(self.clip.right);
+        let end_y = ((center_y + half_height).ceil() as i32).min(self.clip.bottom);
 
-                let sprite_y = if flip_y { sprite_height - y - 1 } else { y };
+        (start_y..end_y).for_each(|screen_y| {
+            (start_x..end_x).for_each(|screen_x| {
+                let dx = screen_x as f32 + 0.5 - center_x;
+                let dy = screen_y as f32 + 0.5 - center_y;
 
-                let color_index = sprite[sprite_x + (sprite_y * sprite_width)];
-                let color = palette[color_index.0 as usize];
+                let sprite_x = (cos * dx + sin * dy + sprite_width / 2.0).floor();
+                let sprite_y = (cos * dy - sin * dx + sprite_height / 2.0).floor();
 
-                // We skip this color, due to transparency
-                if (1 << color_index.0) & transparency_mask != 0 || color[3] == 0 {
+                if sprite_x < 0.0
+                    || sprite_y < 0.0
+                    || sprite_x >= sprite_width
+                    || sprite_y >= sprite_height
+                {
                     return;
                 }
 
-                self.pixel_buffer[target_pixel..target_pixel + BYTES_PER_PIXEL]
-                    .copy_from_slice(&color);
+                if let Some(color) = blit.color(&palette, sprite_x as usize, sprite_y as usize) {
+                    let target_pixel = screen_x as usize + screen_y as usize * self.buffer_width;
+                    let target_pixel = target_pixel * BYTES_PER_PIXEL;
+
+                    self.pixel_buffer[target_pixel..target_pixel + BYTES_PER_PIXEL]
+                        .copy_from_slice(&color);
+                }
+            });
+        });
+    }
+
+    /// Draws a sprite stretched by whole number scales, with the top left point (x, y).
+    /// Scales are rounded to the nearest whole number, and negative scales flip the sprite,
+    /// on top of any flipping already set. A scale of 0 draws nothing.
+    pub fn draw_sprite_scaled(
+        &mut self,
+        blit: &SpriteBlit,
+        (x, y): (i32, i32),
+        (scale_x, scale_y): (f32, f32),
+    ) {
+        if !scale_x.is_finite() || !scale_y.is_finite() {
+            return;
+        }
+
+        // Any larger and a single sprite pixel would cover the whole screen anyway
+        let max_scale = self.buffer_width.max(self.buffer_height) as f32;
+        let scale_x = scale_x.round().clamp(-max_scale, max_scale) as i32;
+        let scale_y = scale_y.round().clamp(-max_scale, max_scale) as i32;
+
+        if scale_x == 0 || scale_y == 0 {
+            return;
+        }
+
+        let blit = SpriteBlit {
+            flip_x: blit.flip_x ^ (scale_x < 0),
+            flip_y: blit.flip_y ^ (scale_y < 0),
+            ..*blit
+        };
+        let scale_x = scale_x.unsigned_abs() as usize;
+        let scale_y = scale_y.unsigned_abs() as usize;
+
+        let palette = blit.palette.as_pixel_colors();
+        let scaled_width = (blit.sheet.width * scale_x) as i32;
+        let scaled_height = (blit.sheet.height * scale_y) as i32;
+
+        let start_x = x.max(self.clip.left);
+        let start_y = y.max(self.clip.top);
+        let end_x = x.saturating_add(scaled_width).min(self.clip.right);
+        let end_y = y.saturating_add(scaled_height).min(self.clip.bottom);
+
+        (start_y..end_y).for_each(|screen_y| {
+            let sprite_y = (screen_y - y) as usize / scale_y;
+
+            (start_x..end_x).for_each(|screen_x| {
+                let sprite_x = (screen_x - x) as usize / scale_x;
+
+                if let Some(color) = blit.color(&palette, sprite_x, sprite_y) {
+                    let target_pixel = screen_x as usize + screen_y as usize * self.buffer_width;
+                    let target_pixel = target_pixel * BYTES_PER_PIXEL;
+
+                    self.pixel_buffer[target_pixel..target_pixel + BYTES_PER_PIXEL]
+                        .copy_from_slice(&color);
+                }
+            });
+        });
+    }
+
+    /// Draws the tiles of the tilemap which are visible, with the camera at the top left of
+    /// the screen. Each tile uses the blit, with its sprite index replaced by the tile's.
+    /// Empty tiles, and tiles with sprites missing from the sheet, are skipped.
+    pub fn draw_tilemap(
+        &mut self,
+        blit: &SpriteBlit,
+        tilemap: &Tilemap,
+        (camera_x, camera_y): (i32, i32),
+    ) {
+        let tile_width = blit.sheet.width as i32;
+        let tile_height = blit.sheet.height as i32;
+
+        if tile_width == 0 || tile_height == 0 {
+            return;
+        }
+
+        // The range of tiles which overlap the clip rect along one axis
+        let visible = |camera: i32, start: i32, end: i32, size: i32, count: usize| {
+            let first = camera.saturating_add(start).div_euclid(size).max(0);
+            let last = camera
+                .saturating_add(end - 1)
+                .div_euclid(size)
+                .min(count as i32 - 1);
+            first..=last
+        };
+
+        let clip = self.clip;
+        let columns = visible(camera_x, clip.left, clip.right, tile_width, tilemap.width);
+        let rows = visible(camera_y, clip.top, clip.bottom, tile_height, tilemap.height);
+
+        rows.for_each(|tile_y| {
+            columns.clone().for_each(|tile_x| {
+                let sprite_index = match tilemap.tile(tile_x as usize, tile_y as usize) {
+                    Some(sprite_index) if sprite_index.0 < blit.sheet.count => sprite_index,
+                    _ => return,
+                };
+
+                self.draw_sprite(
+                    &SpriteBlit {
+                        sprite_index,
+                        ..*blit
+                    },
+                    (
+                        tile_x * tile_width - camera_x,
+                        tile_y * tile_height - camera_y,
+                    ),
+                );
             });
         });
     }
 }
 
+#[derive(Clone, Copy)]
+/// A sprite, along with the settings used to color it in.
+pub struct SpriteBlit<'a> {
+    pub sheet: &'a SpriteSheet,
+    pub sprite_index: SpriteIndex,
+    pub palette: &'a Palette,
+    pub palette_remap: &'a [ColorIndex; PALETTE_COLORS],
+    pub transparency_mask: i64,
+    pub flip_x: bool,
+    pub flip_y: bool,
+}
+
+impl<'a> SpriteBlit<'a> {
+    /// Returns the color of the sprite pixel at (x, y) after flipping and
+    /// remapping, or None if it should be skipped. The transparency mask
+    /// applies to the sprite's own color indices.
+    fn color(
+        &self,
+        palette: &[[u8; BYTES_PER_PIXEL]; PALETTE_COLORS],
+        x: usize,
+        y: usize,
+    ) -> Option<[u8; BYTES_PER_PIXEL]> {
+        let sprite_width = self.sheet.width;
+        let sprite_height = self.sheet.height;
+
+        let sprite_x = if self.flip_x { sprite_width - x - 1 } else { x };
+
+        let sprite_y = if self.flip_y {
+            sprite_height - y - 1
+        } else {
+            y
+        };
+
+        let color_index = self.sheet[self.sprite_index][sprite_x + (sprite_y * sprite_width)];
+        let color = palette[self.palette_remap[color_index.0 as usize].0 as usize];
+
+        // We skip this color, due to transparency
+        if (1 << color_index.0) & self.transparency_mask != 0 || color[3] == 0 {
+            None
+        } else {
+            Some(color)
+        }
+    }
+}
+
 impl Index<usize> for PixelBuffer {
     type Output = u8;
 
@@ -117,3 +369,212 @@ impl IndexMut<Range<usize>> for PixelBuffer {
         &mut self.pixel_buffer[index.start..index.end]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gamercade_core::{Color, SpriteSheetIndex};
+
+    const SPRITE_SIZE: usize = 4;
+    const BUFFER_SIZE: usize = 8;
+
+    fn buffer() -> PixelBuffer {
+        PixelBuffer::new(
+            vec![0; BUFFER_SIZE * BUFFER_SIZE * BYTES_PER_PIXEL].into_boxed_slice(),
+            BUFFER_SIZE,
+            BUFFER_SIZE,
+        )
+    }
+
+    fn sheet() -> SpriteSheet {
+        SpriteSheet {
+            width: SPRITE_SIZE,
+            height: SPRITE_SIZE,
+            sprites: (0..SPRITE_SIZE * SPRITE_SIZE)
+                .map(|index| ColorIndex(index as u8))
+                .collect(),
+            count: 1,
+        }
+    }
+
+    fn palette() -> Palette {
+        let mut palette = Palette::default();
+        palette
+            .colors
+            .iter_mut()
+            .enumerate()
+            .for_each(|(index, color)| *color = Color::new(index as u8, 0, 0, 255));
+        palette
+    }
+
+    const NO_REMAP: [ColorIndex; PALETTE_COLORS] = {
+        let mut remap = [ColorIndex(0); PALETTE_COLORS];
+        let mut index = 0;
+        while index < PALETTE_COLORS {
+            remap[index] = ColorIndex(index as u8);
+            index += 1;
+        }
+        remap
+    };
+
+    fn blit<'a>(sheet: &'a SpriteSheet, palette: &'a Palette, flip: bool) -> SpriteBlit<'a> {
+        SpriteBlit {
+            sheet,
+            sprite_index: SpriteIndex(0),
+            palette,
+            palette_remap: &NO_REMAP,
+            transparency_mask: 1,
+            flip_x: flip,
+            flip_y: flip,
+        }
+    }
+
+    #[test]
+    fn test_rotation_matches_blit() {
+        let (sheet, palette) = (sheet(), palette());
+
+        [(2, 2), (-1, 3), (6, -2)].into_iter().for_each(|position| {
+            let mut expected = buffer();
+            expected.draw_sprite(&blit(&sheet, &palette, false), position);
+
+            let mut unrotated = buffer();
+            unrotated.draw_sprite_rotated(&blit(&sheet, &palette, false), position, 0.0);
+            assert_eq!(unrotated.pixel_buffer, expected.pixel_buffer);
+
+            let mut flipped = buffer();
+            flipped.draw_sprite(&blit(&sheet, &palette, true), position);
+
+            let mut half_turn = buffer();
+            half_turn.draw_sprite_rotated(
+                &blit(&sheet, &palette, false),
+                position,
+                std::f32::consts::PI,
+            );
+            assert_eq!(half_turn.pixel_buffer, flipped.pixel_buffer);
+        });
+    }
+
+    #[test]
+    fn test_scaling() {
+        let (sheet, palette) = (sheet(), palette());
+
+        let mut expected = buffer();
+        expected.draw_sprite(&blit(&sheet, &palette, false), (2, 3));
+
+        let mut unscaled = buffer();
+        unscaled.draw_sprite_scaled(&blit(&sheet, &palette, false), (2, 3), (1.2, 0.8));
+        assert_eq!(unscaled.pixel_buffer, expected.pixel_buffer);
+
+        let mut flipped = buffer();
+        flipped.draw_sprite(&blit(&sheet, &palette, true), (2, 3));
+
+        let mut mirrored = buffer();
+        mirrored.draw_sprite_scaled(&blit(&sheet, &palette, false), (2, 3), (-1.0, -1.0));
+        assert_eq!(mirrored.pixel_buffer, flipped.pixel_buffer);
+
+        let mut doubled = buffer();
+        doubled.draw_sprite_scaled(&blit(&sheet, &palette, false), (-1, 0), (2.0, 3.0));
+        let red = |x: usize, y: usize| doubled[(x + y * BUFFER_SIZE) * BYTES_PER_PIXEL];
+        assert_eq!(red(0, 0), 0);
+        assert_eq!(red(1, 2), 1);
+        assert_eq!(red(2, 3), 5);
+        assert_eq!(red(6, 7), 11);
+        assert_eq!(red(7, 7), 0);
+    }
+
+    #[test]
+    fn test_palette_remap() {
+        let (sheet, palette) = (sheet(), palette());
+        let mut remap = NO_REMAP;
+        remap[0] = ColorIndex(9);
+        remap[5] = ColorIndex(20);
+
+        let mut buffer = buffer();
+        buffer.draw_sprite(
+            &SpriteBlit {
+                palette_remap: &remap,
+                ..blit(&sheet, &palette, false)
+            },
+            (0, 0),
+        );
+
+        let red = |x: usize, y: usize| buffer[(x + y * BUFFER_SIZE) * BYTES_PER_PIXEL];
+        assert_eq!(red(0, 0), 0);
+        assert_eq!(red(1, 1), 20);
+        assert_eq!(red(2, 1), 6);
+    }
+
+    #[test]
+    fn test_clip_rect() {
+        let (sheet, palette) = (sheet(), palette());
+        let mut clipped = buffer();
+        clipped.set_clip(2, 3, 2, 10);
+        assert_eq!(
+            clipped.clip(),
+            ClipRect {
+                left: 2,
+                top: 3,
+                right: 4,
+                bottom: BUFFER_SIZE as i32,
+            }
+        );
+
+        let blit = blit(&sheet, &palette, false);
+        clipped.draw_sprite(&blit, (1, 1));
+        clipped.draw_sprite_rotated(&blit, (1, 1), 1.0);
+        clipped.draw_sprite_scaled(&blit, (1, 1), (3.0, 3.0));
+
+        (0..BUFFER_SIZE as i32).for_each(|y| {
+            (0..BUFFER_SIZE as i32).for_each(|x| {
+                let index = (x + y * BUFFER_SIZE as i32) as usize * BYTES_PER_PIXEL;
+                if !clipped.clip().contains(x, y) {
+                    assert_eq!(
+                        clipped[index..index + BYTES_PER_PIXEL],
+                        [0; BYTES_PER_PIXEL]
+                    );
+                }
+            })
+        });
+
+        clipped.clear_clip();
+        clipped.set_clip(5, 5, -1, 2);
+        assert_eq!(clipped.clip().right, 5);
+        clipped.clear_clip();
+        assert_eq!(buffer().clip(), clipped.clip());
+    }
+
+    #[test]
+    fn test_tilemap() {
+        let (sheet, palette) = (sheet(), palette());
+        let blit = blit(&sheet, &palette, false);
+
+        let mut tilemap = Tilemap::new(SpriteSheetIndex(0), 3, 2);
+        tilemap.set_tile(0, 0, Some(SpriteIndex(0)));
+        tilemap.set_tile(1, 0, Some(SpriteIndex(5)));
+        tilemap.set_tile(2, 1, Some(SpriteIndex(0)));
+
+        let mut expected = buffer();
+        expected.draw_sprite(&blit, (-2, -1));
+        expected.draw_sprite(&blit, (6, 3));
+
+        let mut drawn = buffer();
+        drawn.draw_tilemap(&blit, &tilemap, (2, 1));
+        assert_eq!(drawn.pixel_buffer, expected.pixel_buffer);
+
+        let mut far_away = buffer();
+        far_away.draw_tilemap(&blit, &tilemap, (i32::MIN, i32::MAX));
+        far_away.draw_tilemap(&blit, &tilemap, (i32::MAX, i32::MIN));
+        assert_eq!(far_away.pixel_buffer, buffer().pixel_buffer);
+    }
+
+    #[test]
+    fn test_rotation_clips_to_screen() {
+        let (sheet, palette) = (sheet(), palette());
+        let mut buffer = buffer();
+
+        buffer.draw_sprite_rotated(&blit(&sheet, &palette, false), (-3, 6), 0.7);
+        buffer.draw_sprite_rotated(&blit(&sheet, &palette, false), (100, 100), 1.0);
+        buffer.draw_sprite_rotated(&blit(&sheet, &palette, false), (2, 2), f32::NAN);
+        buffer.draw_sprite_scaled(&blit(&sheet, &palette, false), (-5, 6), (1e9, -1e9));
+    }
+}
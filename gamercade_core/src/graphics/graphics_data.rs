@@ -2,12 +2,15 @@ use serde::{Deserialize, Serialize};
 
 use crate::{Color, ColorIndex, SpriteIndex};
 
-use super::{Palette, PaletteIndex, SpriteSheet, SpriteSheetIndex};
+use super::{Palette, PaletteIndex, SpriteSheet, SpriteSheetIndex, Tilemap, TilemapIndex};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GraphicsData {
     pub sprite_sheets: Box<[SpriteSheet]>,
     pub palettes: Box<[Palette]>,
+
+    #[serde(default)]
+    pub tilemaps: Box<[Tilemap]>,
 }
 
 impl GraphicsData {
@@ -52,6 +55,14 @@ impl GraphicsData {
         }
     }
 
+    pub fn validate_tilemap_index(&self, index: i32) -> Result<TilemapIndex, &'static str> {
+        // Tilemap indices past 255 can't be represented, rather than wrapping around
+        match u8::try_from(index) {
+            Ok(tilemap) if (tilemap as usize) < self.tilemaps.len() => Ok(TilemapIndex(tilemap)),
+            _ => Err("invalid tilemap index"),
+        }
+    }
+
     pub fn palette(&self, palette_index: PaletteIndex) -> Option<&Palette> {
         self.palettes.get(palette_index.0 as usize)
     }
@@ -60,6 +71,10 @@ impl GraphicsData {
         self.sprite_sheets.get(sprite_sheet_index.0 as usize)
     }
 
+    pub fn tilemap(&self, tilemap_index: TilemapIndex) -> Option<&Tilemap> {
+        self.tilemaps.get(tilemap_index.0 as usize)
+    }
+
     pub fn color(&self, palette_index: PaletteIndex, color: ColorIndex) -> Option<&Color> {
         if let Some(palette) = self.palettes.get(palette_index.0 as usize) {
             palette.colors.get(color.0 as usize)
@@ -78,6 +93,25 @@ impl Default for GraphicsData {
                 .map(|x| x.0)
                 .collect::<Vec<_>>()
                 .into_boxed_slice(),
+            tilemaps: Box::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tilemap_index_doesnt_wrap() {
+        let graphics = GraphicsData {
+            tilemaps: vec![Tilemap::new(SpriteSheetIndex::default(), 1, 1); 300].into_boxed_slice(),
+            ..GraphicsData::default()
+        };
+
+        assert_eq!(graphics.validate_tilemap_index(255), Ok(TilemapIndex(255)));
+        assert!(graphics.validate_tilemap_index(256).is_err());
+        assert!(graphics.validate_tilemap_index(257).is_err());
+        assert!(graphics.validate_tilemap_index(-1).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 mod color;
+mod font;
 mod frame_rate;
 mod graphics_data;
 mod graphics_parameters;
@@ -6,8 +7,10 @@ mod palette;
 mod resolution;
 mod sprite_iter;
 mod sprites;
+mod tilemap;
 
 pub use color::*;
+pub use font::*;
 pub use frame_rate::*;
 pub use graphics_data::*;
 pub use graphics_parameters::*;
@@ -15,8 +18,11 @@ pub use palette::*;
 pub use resolution::*;
 pub use sprite_iter::*;
 pub use sprites::*;
+pub use tilemap::*;
 
 pub const PALETTE_COLORS: usize = 64;
 pub const PALETTE_MAX_COUNT: usize = 256;
 pub const SPRITE_SHEET_MAX_COUNT: usize = 256;
 pub const SPRITE_SHEET_MAX_SPRITES: usize = 256;
+pub const TILEMAP_MAX_COUNT: usize = 256;
+pub const TILEMAP_MAX_SIZE: usize = 256;
@@ -26,7 +26,7 @@ impl Display for PaletteIndex {
         self.0.fmt(f)
     }
 }
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct Palette {
     #[serde(with = "BigArray")]
@@ -11,6 +11,7 @@ pub enum Resolution {
     High,      // 640 x 360
     VeryHigh,  // 1280 x 720
     UltraHigh, // 1920 x 1080
+    Custom { width: i32, height: i32 },
 }
 
 impl Resolution {
@@ -23,6 +24,26 @@ impl Resolution {
     pub const VERYHIGH: (i32, i32) = (1280, 720);
     pub const ULTRAHIGH: (i32, i32) = (1920, 1080);
 
+    /// The largest size allowed for custom resolutions. Anything larger
+    /// is clamped, so a bad rom can't allocate a huge frame buffer.
+    pub const CUSTOM_MAX: (i32, i32) = (1920, 1920);
+
+    /// Creates a custom resolution, as long as it's between 1 and CUSTOM_MAX.
+    pub fn custom(width: i32, height: i32) -> Result<Self, String> {
+        if !(1..=Self::CUSTOM_MAX.0).contains(&width) || !(1..=Self::CUSTOM_MAX.1).contains(&height)
+        {
+            return Err(format!(
+                "Resolution must be between 1 x 1 and {} x {}, but was {} x {}",
+                Self::CUSTOM_MAX.0,
+                Self::CUSTOM_MAX.1,
+                width,
+                height
+            ));
+        }
+
+        Ok(Self::Custom { width, height })
+    }
+
     pub const fn width(&self) -> i32 {
         match self {
             Self::UltraLow => Self::ULTRALOW.0,
@@ -32,6 +53,7 @@ impl Resolution {
             Self::High => Self::HIGH.0,
             Self::VeryHigh => Self::VERYHIGH.0,
             Self::UltraHigh => Self::ULTRAHIGH.0,
+            Self::Custom { width, .. } => clamp_dimension(*width, Self::CUSTOM_MAX.0),
         }
     }
 
@@ -44,6 +66,7 @@ impl Resolution {
             Self::High => Self::HIGH.1,
             Self::VeryHigh => Self::VERYHIGH.1,
             Self::UltraHigh => Self::ULTRAHIGH.1,
+            Self::Custom { height, .. } => clamp_dimension(*height, Self::CUSTOM_MAX.1),
         }
     }
 
@@ -72,6 +95,16 @@ impl Resolution {
     }
 }
 
+const fn clamp_dimension(value: i32, max: i32) -> i32 {
+    if value < 1 {
+        1
+    } else if value > max {
+        max
+    } else {
+        value
+    }
+}
+
 impl Default for Resolution {
     fn default() -> Self {
         Resolution::Low
@@ -105,3 +138,55 @@ impl YCord {
         self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_rejects_out_of_range_dimensions() {
+        let (max_width, max_height) = Resolution::CUSTOM_MAX;
+
+        assert!(Resolution::custom(1, 1).is_ok());
+        assert!(Resolution::custom(max_width, max_height).is_ok());
+
+        assert!(Resolution::custom(0, 100).is_err());
+        assert!(Resolution::custom(100, 0).is_err());
+        assert!(Resolution::custom(-5, 100).is_err());
+        assert!(Resolution::custom(100, -5).is_err());
+        assert!(Resolution::custom(max_width + 1, 100).is_err());
+        assert!(Resolution::custom(100, max_height + 1).is_err());
+    }
+
+    #[test]
+    fn test_clamp_dimension() {
+        assert_eq!(clamp_dimension(0, 100), 1);
+        assert_eq!(clamp_dimension(-20, 100), 1);
+        assert_eq!(clamp_dimension(i32::MIN, 100), 1);
+        assert_eq!(clamp_dimension(50, 100), 50);
+        assert_eq!(clamp_dimension(101, 100), 100);
+        assert_eq!(clamp_dimension(i32::MAX, 100), 100);
+    }
+
+    #[test]
+    fn test_custom_dimensions_are_clamped() {
+        // Roms can hold custom resolutions which never went through Resolution::custom
+        let (max_width, max_height) = Resolution::CUSTOM_MAX;
+
+        let resolution = Resolution::Custom {
+            width: 0,
+            height: -10,
+        };
+        assert_eq!((resolution.width(), resolution.height()), (1, 1));
+
+        let resolution = Resolution::Custom {
+            width: max_width * 4,
+            height: i32::MAX,
+        };
+        assert_eq!(
+            (resolution.width(), resolution.height()),
+            (max_width, max_height)
+        );
+        assert_eq!(resolution.total_pixels(), max_width * max_height);
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{SpriteIndex, SpriteSheetIndex, TILEMAP_MAX_SIZE};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct TilemapIndex(pub u8);
+
+/// A grid of sprites from a single sprite sheet. Each tile is the
+/// size of one sprite in the sheet.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Tilemap {
+    pub sprite_sheet: SpriteSheetIndex,
+
+    /// The number of tiles in each row.
+    pub width: usize,
+
+    /// The number of rows of tiles.
+    pub height: usize,
+
+    /// The sprite of each tile, row by row. Empty tiles aren't drawn.
+    pub tiles: Box<[Option<SpriteIndex>]>,
+}
+
+impl Default for Tilemap {
+    fn default() -> Self {
+        Self::new(SpriteSheetIndex::default(), 16, 16)
+    }
+}
+
+impl Tilemap {
+    /// Creates an empty tilemap. The size is clamped to TILEMAP_MAX_SIZE tiles.
+    pub fn new(sprite_sheet: SpriteSheetIndex, width: usize, height: usize) -> Self {
+        let width = width.min(TILEMAP_MAX_SIZE);
+        let height = height.min(TILEMAP_MAX_SIZE);
+
+        Self {
+            sprite_sheet,
+            width,
+            height,
+            tiles: vec![None; width * height].into_boxed_slice(),
+        }
+    }
+
+    /// Returns the tile at the position, or None if it is empty or out of bounds.
+    pub fn tile(&self, x: usize, y: usize) -> Option<SpriteIndex> {
+        if x < self.width && y < self.height {
+            // Tilemaps loaded from roms might not have width * height tiles
+            self.tiles.get(x + (y * self.width)).copied().flatten()
+        } else {
+            None
+        }
+    }
+
+    /// Sets the tile at the position. Positions out of bounds are ignored.
+    pub fn set_tile(&mut self, x: usize, y: usize, tile: Option<SpriteIndex>) {
+        if x < self.width && y < self.height {
+            if let Some(existing) = self.tiles.get_mut(x + (y * self.width)) {
+                *existing = tile;
+            }
+        }
+    }
+
+    /// Resizes the tilemap, keeping the tiles in the top left and
+    /// leaving any new ones empty.
+    pub fn resize(&mut self, new_width: usize, new_height: usize) {
+        let mut resized = Self::new(self.sprite_sheet, new_width, new_height);
+
+        (0..resized.height).for_each(|y| {
+            (0..resized.width).for_each(|x| resized.set_tile(x, y, self.tile(x, y)));
+        });
+
+        *self = resized;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mismatched_tile_count_doesnt_panic() {
+        let mut tilemap = Tilemap {
+            sprite_sheet: SpriteSheetIndex::default(),
+            width: 4,
+            height: 4,
+            tiles: vec![Some(SpriteIndex(1)); 6].into_boxed_slice(),
+        };
+
+        assert_eq!(tilemap.tile(1, 1), Some(SpriteIndex(1)));
+        assert_eq!(tilemap.tile(3, 3), None);
+
+        tilemap.set_tile(3, 3, Some(SpriteIndex(2)));
+        assert_eq!(tilemap.tile(3, 3), None);
+
+        tilemap.resize(2, 2);
+        assert_eq!(tilemap.tiles.len(), 4);
+    }
+}
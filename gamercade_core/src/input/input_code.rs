@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
 use super::AsApiCode;
 
-#[derive(Debug, Copy, Clone, EnumIter)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
 pub enum ButtonCode {
     // DPad
     Up,
@@ -114,6 +115,23 @@ impl ToBitMask<u16> for ButtonCode {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
+pub enum MouseButtonCode {
+    Left,
+    Right,
+    Middle,
+}
+
+impl ToBitMask<u16> for MouseButtonCode {
+    fn to_bit_mask(&self) -> u16 {
+        match self {
+            Self::Left => 0b1,
+            Self::Right => 0b10,
+            Self::Middle => 0b100,
+        }
+    }
+}
+
 pub trait ToBitMask<T> {
     fn to_bit_mask(&self) -> T;
 }
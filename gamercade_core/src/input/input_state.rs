@@ -1,15 +1,20 @@
 use super::input_code::*;
-use bytemuck::{cast, Pod, Zeroable};
+use bytemuck::{bytes_of, bytes_of_mut, Pod, Zeroable};
+use serde::{Deserialize, Serialize};
 
 #[repr(C)]
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
-// 60-64 bits aka 8 bytes when compressed
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+// 60-64 bits aka 8 bytes when compressed, plus 48 bits for the mouse
 pub struct InputState {
     pub left_trigger: AnalogTrigger,
     pub right_trigger: AnalogTrigger,
     pub left_stick: AnalogStick,
     pub right_stick: AnalogStick,
     pub buttons: Buttons,
+
+    /// Part of the state so it is sent over the network and rolled back
+    /// like any other input.
+    pub mouse: MouseState,
 }
 
 impl InputState {
@@ -25,14 +30,24 @@ impl InputState {
             y_axis: 0,
         },
         buttons: Buttons { state: 0 },
+        mouse: MouseState {
+            x: 0,
+            y: 0,
+            buttons: 0,
+        },
     };
 
+    /// Packs the gamepad part of the state. The mouse state isn't included.
     pub fn as_raw_state(self) -> i64 {
-        cast(self)
+        let mut raw = [0; 8];
+        raw.copy_from_slice(&bytes_of(&self)[..8]);
+        i64::from_ne_bytes(raw)
     }
 
     pub fn from_raw_state(raw: i64) -> Self {
-        cast(raw)
+        let mut state = Self::default();
+        bytes_of_mut(&mut state)[..8].copy_from_slice(&raw.to_ne_bytes());
+        state
     }
 
     pub fn is_valid(self) -> bool {
@@ -41,7 +56,7 @@ impl InputState {
 }
 
 #[repr(C)]
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
 // 16 bits
 pub struct AnalogStick {
     x_axis: i8,
@@ -71,7 +86,7 @@ impl AnalogStick {
 }
 
 #[repr(transparent)]
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
 // 7 bits
 // Sign bit will be dropped/unused
 pub struct AnalogTrigger {
@@ -93,7 +108,7 @@ impl AnalogTrigger {
 }
 
 #[repr(transparent)]
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
 // 14 bits for with Analog Triggers
 // 16 bits for binary triggers
 pub struct Buttons {
@@ -108,14 +123,59 @@ impl Buttons {
     pub fn get_button_state(&self, code: ButtonCode) -> bool {
         self.state & code.to_bit_mask() != 0
     }
+
+    /// Returns the state of all buttons as a bit mask.
+    pub fn bits(&self) -> u16 {
+        self.state
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+// 48 bits
+pub struct MouseState {
+    x: i16,
+    y: i16,
+    buttons: u16,
+}
+
+impl MouseState {
+    /// Sets the position of the cursor, in screen pixels.
+    pub fn set_position(&mut self, x: usize, y: usize) {
+        self.x = x.min(i16::MAX as usize) as i16;
+        self.y = y.min(i16::MAX as usize) as i16;
+    }
+
+    pub fn get_x(&self) -> i32 {
+        self.x as i32
+    }
+
+    pub fn get_y(&self) -> i32 {
+        self.y as i32
+    }
+
+    pub fn enable_button(&mut self, code: MouseButtonCode) {
+        self.buttons |= code.to_bit_mask();
+    }
+
+    pub fn get_button_state(&self, code: MouseButtonCode) -> bool {
+        self.buttons & code.to_bit_mask() != 0
+    }
+
+    /// Returns the state of all mouse buttons as a bit mask.
+    pub fn bits(&self) -> u16 {
+        self.buttons
+    }
 }
 
 unsafe impl Pod for Buttons {}
 unsafe impl Pod for AnalogTrigger {}
 unsafe impl Pod for AnalogStick {}
+unsafe impl Pod for MouseState {}
 unsafe impl Pod for InputState {}
 
 unsafe impl Zeroable for Buttons {}
 unsafe impl Zeroable for AnalogTrigger {}
 unsafe impl Zeroable for AnalogStick {}
+unsafe impl Zeroable for MouseState {}
 unsafe impl Zeroable for InputState {}
@@ -4,6 +4,9 @@ mod input_state;
 pub use input_code::*;
 pub use input_state::*;
 
+/// The most players a game can have, whether local or over the network.
+pub const MAX_PLAYERS: usize = 4;
+
 pub trait AsApiCode: Sized {
     fn to_api_code(&self) -> u8;
     fn from_api_code(code: u8) -> Option<Self>;
@@ -1,16 +1,20 @@
-use std::{iter::Cycle, ops::Range, sync::Arc};
+use std::sync::Arc;
 
 use eframe::egui::Ui;
-use gamercade_audio::{ChainId, Sfx, SFX_CHANNELS};
+use gamercade_audio::{ChainId, PhraseVolumeType, Sfx};
+use gamercade_core::FrameRate;
 use gamercade_sound_engine::{
-    SoundEngine, SoundEngineChannelType, SoundEngineData, SoundRomInstance,
+    MixerChannel, MixerStrip, SoundEngine, SoundEngineChannelType, SoundEngineData,
+    SoundRomInstance, VoiceStealPolicy,
 };
 
-use gamercade_fs::EditorSoundData;
+use gamercade_fs::{EditorAudioDataEntry, EditorSoundData};
+
+use crate::ui::Undoable;
 
 use super::{
-    AudioEditorHelp, ChainEditor, InstrumentEditor, Oscilloscope, OscilloscopeMode, PhraseEditor,
-    SfxEditor, SongEditor,
+    AudioEditorHelp, AudioSettings, ChainEditor, InstrumentEditor, MixerPanel, Oscilloscope,
+    OscilloscopeMode, PhraseEditor, SfxEditor, SongEditor,
 };
 
 pub struct AudioEditor {
@@ -25,7 +29,9 @@ pub struct AudioEditor {
     pub(crate) audio_sync_helper: AudioSyncHelper,
 
     audio_editor_help: AudioEditorHelp,
+    audio_settings: AudioSettings,
     oscilloscope: Oscilloscope,
+    mixer_panel: MixerPanel,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -40,12 +46,17 @@ pub enum AudioEditorMode {
 impl AudioEditor {
     pub(crate) fn new(data: &EditorSoundData) -> Self {
         let sound_rom_instance = Arc::new(SoundRomInstance::from(data));
-        let mut sound_engine = SoundEngine::new(60, &sound_rom_instance, 64);
+        // The editor plays sounds live, rather than advancing them each frame
+        let mut sound_engine = SoundEngine::new(FrameRate::default(), &sound_rom_instance, 64);
 
-        let sound_engine_data =
-            SoundEngineData::new(sound_engine.output_sample_rate(), &sound_rom_instance);
+        let sound_engine_data = SoundEngineData::new(
+            sound_engine.output_sample_rate(),
+            sound_engine.frame_rate(),
+            &sound_rom_instance,
+        );
 
         let (producer, consumer) = rtrb::RingBuffer::new(sound_engine.output_sample_rate());
+        let oscilloscope = Oscilloscope::new(consumer, sound_engine.output_sample_rate());
 
         sound_engine.send(SoundEngineChannelType::UpdateOutputProducer(Some(producer)));
 
@@ -60,11 +71,13 @@ impl AudioEditor {
             audio_sync_helper: AudioSyncHelper {
                 sync_rom: false,
                 sound_engine_data,
-                channel_ticker: (0..SFX_CHANNELS).cycle(),
+                next_voice: 0,
                 command_queue: Vec::new(),
             },
-            oscilloscope: Oscilloscope::new(consumer),
+            oscilloscope,
             audio_editor_help: AudioEditorHelp::default(),
+            audio_settings: AudioSettings::default(),
+            mixer_panel: MixerPanel::default(),
         }
     }
 }
@@ -73,14 +86,16 @@ pub(crate) enum AudioSyncCommand {
     PressedKey {
         note_index: usize,
         instrument_index: usize,
-        channel: usize,
+        voice: usize,
+        velocity: PhraseVolumeType,
     },
     ReleasedKey {
-        channel: usize,
+        voice: usize,
     },
     TriggerNote {
         note_index: usize,
         instrument_index: usize,
+        velocity: PhraseVolumeType,
     },
     PlayPhrase {
         phrase_index: usize,
@@ -90,12 +105,25 @@ pub(crate) enum AudioSyncCommand {
     StopSfx,
     PlayBgm(usize),
     StopBgm,
+    SetBgmLoop(Option<(usize, usize)>),
+    SetPan {
+        channel: usize,
+        pan: f32,
+    },
+    SetMasterVolume(f32),
+    SetVoiceStealPolicy(VoiceStealPolicy),
+    SetMixerStrip {
+        channel: MixerChannel,
+        strip: MixerStrip,
+    },
+    SetTranspose(i32),
+    SetTuningReference(f32),
 }
 
 pub(crate) struct AudioSyncHelper {
     sync_rom: bool,
     pub(crate) sound_engine_data: SoundEngineData,
-    channel_ticker: Cycle<Range<usize>>,
+    next_voice: usize,
     command_queue: Vec<AudioSyncCommand>,
 }
 
@@ -104,14 +132,23 @@ impl AudioSyncHelper {
         self.sync_rom = true;
     }
 
-    pub(crate) fn play_note(&mut self, note_index: usize, instrument_index: usize) -> usize {
-        let channel = self.channel_ticker.next().unwrap();
+    /// Plays a note until released, returning the voice to pass into stop_note.
+    /// The sound engine picks which channel the voice is played on.
+    pub(crate) fn play_note(
+        &mut self,
+        note_index: usize,
+        instrument_index: usize,
+        velocity: PhraseVolumeType,
+    ) -> usize {
+        let voice = self.next_voice;
+        self.next_voice = self.next_voice.wrapping_add(1);
         self.command_queue.push(AudioSyncCommand::PressedKey {
             note_index,
             instrument_index,
-            channel,
+            voice,
+            velocity,
         });
-        channel
+        voice
     }
 
     pub(crate) fn play_phrase(&mut self, phrase_index: usize, target_bpm: f32) {
@@ -121,23 +158,40 @@ impl AudioSyncHelper {
         });
     }
 
-    pub(crate) fn stop_note(&mut self, channel: usize) {
+    pub(crate) fn stop_note(&mut self, voice: usize) {
         self.command_queue
-            .push(AudioSyncCommand::ReleasedKey { channel })
+            .push(AudioSyncCommand::ReleasedKey { voice })
     }
 
-    pub(crate) fn trigger_note(&mut self, note_index: usize, instrument_index: usize) {
+    pub(crate) fn trigger_note(
+        &mut self,
+        note_index: usize,
+        instrument_index: usize,
+        velocity: PhraseVolumeType,
+    ) {
         self.command_queue.push(AudioSyncCommand::TriggerNote {
             note_index,
             instrument_index,
+            velocity,
         })
     }
 
-    pub(crate) fn play_chain(&mut self, chain_id: usize, bpm: f32) {
-        self.command_queue.push(AudioSyncCommand::PlaySfx(Sfx {
-            bpm,
-            chain: ChainId(chain_id),
-        }))
+    /// Previews a chain at the gain of the first sfx which plays it,
+    /// so it sounds as loud as it does in game.
+    pub(crate) fn play_chain(
+        &mut self,
+        chain_id: usize,
+        bpm: f32,
+        sfx: &[EditorAudioDataEntry<Sfx>],
+    ) {
+        let chain = ChainId(chain_id);
+        let gain = sfx
+            .iter()
+            .find(|sfx| sfx.data.chain == chain)
+            .map_or(1.0, |sfx| sfx.data.gain);
+
+        self.command_queue
+            .push(AudioSyncCommand::PlaySfx(Sfx { bpm, chain, gain }))
     }
 
     pub(crate) fn play_sfx(&mut self, sfx: Sfx) {
@@ -156,6 +210,42 @@ impl AudioSyncHelper {
         self.command_queue.push(AudioSyncCommand::StopBgm)
     }
 
+    /// Sets the rows of the bgm to repeat, or None to play the whole song.
+    pub(crate) fn set_bgm_loop(&mut self, region: Option<(usize, usize)>) {
+        self.command_queue
+            .push(AudioSyncCommand::SetBgmLoop(region))
+    }
+
+    pub(crate) fn set_pan(&mut self, channel: usize, pan: f32) {
+        self.command_queue
+            .push(AudioSyncCommand::SetPan { channel, pan })
+    }
+
+    pub(crate) fn set_master_volume(&mut self, volume: f32) {
+        self.command_queue
+            .push(AudioSyncCommand::SetMasterVolume(volume))
+    }
+
+    pub(crate) fn set_voice_steal_policy(&mut self, policy: VoiceStealPolicy) {
+        self.command_queue
+            .push(AudioSyncCommand::SetVoiceStealPolicy(policy))
+    }
+
+    pub(crate) fn set_mixer_strip(&mut self, channel: MixerChannel, strip: MixerStrip) {
+        self.command_queue
+            .push(AudioSyncCommand::SetMixerStrip { channel, strip })
+    }
+
+    pub(crate) fn set_transpose(&mut self, semitones: i32) {
+        self.command_queue
+            .push(AudioSyncCommand::SetTranspose(semitones))
+    }
+
+    pub(crate) fn set_tuning_reference(&mut self, frequency: f32) {
+        self.command_queue
+            .push(AudioSyncCommand::SetTuningReference(frequency))
+    }
+
     fn push_commands(&mut self, engine: &mut SoundEngine, data: &EditorSoundData) {
         if self.sync_rom {
             self.sync_rom = false;
@@ -172,22 +262,25 @@ impl AudioSyncHelper {
                 AudioSyncCommand::PressedKey {
                     note_index,
                     instrument_index,
-                    channel,
+                    voice,
+                    velocity,
                 } => engine.send(SoundEngineChannelType::PianoKeyPressed {
                     note_index,
                     instrument_index,
-                    channel,
+                    voice,
+                    velocity,
                 }),
-                AudioSyncCommand::ReleasedKey { channel } => {
-                    engine.send(SoundEngineChannelType::PianoKeyReleased { channel })
+                AudioSyncCommand::ReleasedKey { voice } => {
+                    engine.send(SoundEngineChannelType::PianoKeyReleased { voice })
                 }
                 AudioSyncCommand::TriggerNote {
                     note_index,
                     instrument_index,
+                    velocity,
                 } => engine.send(SoundEngineChannelType::TriggerNote {
                     note_index,
                     instrument_index,
-                    channel: self.channel_ticker.next().unwrap(),
+                    velocity,
                 }),
                 AudioSyncCommand::PlayPhrase {
                     phrase_index,
@@ -202,6 +295,27 @@ impl AudioSyncHelper {
                     engine.send(SoundEngineChannelType::PlayBgm(song))
                 }
                 AudioSyncCommand::StopBgm => engine.send(SoundEngineChannelType::StopBgm),
+                AudioSyncCommand::SetBgmLoop(region) => {
+                    engine.send(SoundEngineChannelType::SetBgmLoop(region))
+                }
+                AudioSyncCommand::SetPan { channel, pan } => {
+                    engine.send(SoundEngineChannelType::SetPan { channel, pan })
+                }
+                AudioSyncCommand::SetMasterVolume(volume) => {
+                    engine.send(SoundEngineChannelType::SetMasterVolume(volume))
+                }
+                AudioSyncCommand::SetVoiceStealPolicy(policy) => {
+                    engine.send(SoundEngineChannelType::SetVoiceStealPolicy(policy))
+                }
+                AudioSyncCommand::SetMixerStrip { channel, strip } => {
+                    engine.send(SoundEngineChannelType::SetMixerStrip { channel, strip })
+                }
+                AudioSyncCommand::SetTranspose(semitones) => {
+                    engine.send(SoundEngineChannelType::SetTranspose(semitones))
+                }
+                AudioSyncCommand::SetTuningReference(frequency) => {
+                    engine.send(SoundEngineChannelType::SetTuningReference(frequency))
+                }
             });
     }
 }
@@ -219,6 +333,20 @@ impl AudioEditor {
         let editor_help_open = self.audio_editor_help.open;
         ui.selectable_value(&mut self.audio_editor_help.open, !editor_help_open, "Help!");
 
+        let audio_settings_open = self.audio_settings.open;
+        ui.selectable_value(
+            &mut self.audio_settings.open,
+            !audio_settings_open,
+            "Bus Gains",
+        );
+
+        let vu_meter_open = self.oscilloscope.vu_meter.open;
+        ui.selectable_value(
+            &mut self.oscilloscope.vu_meter.open,
+            !vu_meter_open,
+            "VU Meter",
+        );
+
         ui.separator();
 
         ui.label("Oscilloscope:");
@@ -248,6 +376,16 @@ impl AudioEditor {
         {
             self.oscilloscope.open = true
         };
+        if ui
+            .selectable_value(
+                &mut self.oscilloscope.mode,
+                OscilloscopeMode::Spectrum,
+                "Spectrum",
+            )
+            .clicked()
+        {
+            self.oscilloscope.open = true
+        };
 
         self.audio_editor_help.draw(ui);
         self.oscilloscope.draw(ui);
@@ -271,12 +409,54 @@ impl AudioEditor {
             }
         };
 
+        self.audio_settings
+            .draw(ui, data, &mut self.audio_sync_helper);
+
         self.audio_sync_helper
             .push_commands(&mut self.sound_engine, data);
     }
 
     pub fn draw_bottom_panel(&mut self, ui: &mut Ui) {
-        //TODO: Write this
-        ui.label("TODO!");
+        self.mixer_panel.draw(ui, &mut self.audio_sync_helper);
+    }
+}
+
+impl Undoable<EditorSoundData> for AudioEditor {
+    fn undo(&mut self, data: &mut EditorSoundData) -> bool {
+        let changed = match self.mode {
+            AudioEditorMode::Instrument => self.instrument_editor.undo(data),
+            AudioEditorMode::Sfx => self.sfx_editor.undo(data),
+            AudioEditorMode::Songs => self.song_editor.undo(data),
+            AudioEditorMode::Chains => self.chain_editor.undo(data),
+            AudioEditorMode::Phrases => self.phrase_editor.undo(data),
+        };
+
+        if changed {
+            self.audio_sync_helper.notify_rom_changed();
+        }
+        changed
+    }
+
+    fn redo(&mut self, data: &mut EditorSoundData) -> bool {
+        let changed = match self.mode {
+            AudioEditorMode::Instrument => self.instrument_editor.redo(data),
+            AudioEditorMode::Sfx => self.sfx_editor.redo(data),
+            AudioEditorMode::Songs => self.song_editor.redo(data),
+            AudioEditorMode::Chains => self.chain_editor.redo(data),
+            AudioEditorMode::Phrases => self.phrase_editor.redo(data),
+        };
+
+        if changed {
+            self.audio_sync_helper.notify_rom_changed();
+        }
+        changed
+    }
+
+    fn clear_history(&mut self) {
+        self.instrument_editor.clear_history();
+        self.sfx_editor.clear_history();
+        self.song_editor.clear_history();
+        self.chain_editor.clear_history();
+        self.phrase_editor.clear_history();
     }
 }
@@ -44,6 +44,19 @@ impl AudioEditorHelp {
             ui.label("Hold [Shift] and [Z] to create or delete entries.");
             ui.label("Hold [Shift] and press [Up] or [Down] arrows to increase or decrease a value.");
             ui.label("Hold [Shift] and press [Right] or [Left] arrows to increase or decrease a value by 16.");
+
+            ui.label(RichText::new("Phrase effects: ").strong());
+            ui.label("Each phrase row has three effect columns (E0 - E2). Effects keep going until replaced.");
+            ui.label("Vxy: Vibrato - x is the depth and y is the speed. V00 stops the vibrato.");
+            ui.label("Gxx: Glide - slides into each new note over xx hundredths of a second. G00 stops the glide.");
+            ui.label("Axy: Arpeggio - cycles the note up by x then y semitones, A47 plays a major chord. A00 stops the arpeggio.");
+            ui.label("Hold [Shift] and press [X] to switch an effect to the next kind.");
+            ui.label("Decreasing an effect with a value of 00 removes it from the row.");
+
+            ui.label(RichText::new("Phrase selections: ").strong());
+            ui.label("Hold [Shift] and click a cell to select everything between it and the current cell. [Escape] clears the selection.");
+            ui.label("[Ctrl] and [C], [X] or [V] copies, cuts, or pastes the selection. Pasting starts from the current row.");
+            ui.label("[+] or [-] transposes the selected notes by a semitone, [*] or [/] by an octave.");
         });
     }
 }
@@ -39,6 +39,13 @@ pub(crate) trait AudioList<T> {
         });
     }
 
+    /// Keeps the selection in range, such as after undoing a new entry.
+    fn clamp_selection(&mut self, data: &mut EditorSoundData) {
+        let last = Self::target_data_mut(data).len().saturating_sub(1);
+        let selected = self.selected_index();
+        *selected = (*selected).min(last);
+    }
+
     fn draw(&mut self, ui: &mut Ui, data: &mut EditorSoundData, sync: &mut AudioSyncHelper) {
         SidePanel::left("Audio List")
             .resizable(false)
@@ -0,0 +1,243 @@
+use eframe::egui::{Checkbox, ComboBox, Slider, TextEdit, Ui, Window};
+use gamercade_audio::{
+    Tuning, DEFAULT_TUNING_REFERENCE, DELAY_MAX_FEEDBACK, DELAY_MAX_SECONDS, SFX_CHANNELS_MAX,
+    TRANSPOSE_MAX_SEMITONES, TUNING_MAX_DIVISIONS, TUNING_MIN_DIVISIONS, TUNING_REFERENCE_MAX,
+    TUNING_REFERENCE_MIN,
+};
+
+/// The scale shown when first picking a custom scale, a just intonation major scale.
+const DEFAULT_SCALE: &str = "9/8 5/4 4/3 3/2 5/3 15/8 2/1";
+use gamercade_fs::EditorSoundData;
+use gamercade_sound_engine::VoiceStealPolicy;
+
+use super::AudioSyncHelper;
+
+#[derive(Debug)]
+pub(crate) struct AudioSettings {
+    pub(crate) open: bool,
+    master_volume: f32,
+    voice_steal_policy: VoiceStealPolicy,
+    transpose: i32,
+    tuning_reference: f32,
+    scale_text: String,
+    scale_error: Option<String>,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            open: false,
+            master_volume: 1.0,
+            voice_steal_policy: VoiceStealPolicy::default(),
+            transpose: 0,
+            tuning_reference: DEFAULT_TUNING_REFERENCE,
+            scale_text: String::from(DEFAULT_SCALE),
+            scale_error: None,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub(crate) fn draw(
+        &mut self,
+        ui: &mut Ui,
+        data: &mut EditorSoundData,
+        sync: &mut AudioSyncHelper,
+    ) {
+        let ctx = ui.ctx();
+        Window::new("Audio Settings")
+            .open(&mut self.open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Bus gains are applied on top of each song and sfx gain.");
+
+                if ui
+                    .add(Slider::new(&mut data.bus_gains.music, 0.0..=2.0).text("Music"))
+                    .changed()
+                {
+                    sync.notify_rom_changed();
+                }
+
+                if ui
+                    .add(Slider::new(&mut data.bus_gains.sfx, 0.0..=2.0).text("Sfx"))
+                    .changed()
+                {
+                    sync.notify_rom_changed();
+                }
+
+                ui.separator();
+                ui.label("How many sfx channels the game can play at once.");
+
+                if ui
+                    .add(
+                        Slider::new(&mut data.sfx_channels, 1..=SFX_CHANNELS_MAX)
+                            .text("Sfx Channels"),
+                    )
+                    .changed()
+                {
+                    sync.notify_rom_changed();
+                }
+
+                ui.label("Which voice is cut off when playing notes with every channel busy.");
+
+                let mut policy = self.voice_steal_policy;
+                ComboBox::from_label("Voice Stealing")
+                    .selected_text(format!("{:?}", policy))
+                    .show_ui(ui, |ui| {
+                        VoiceStealPolicy::ALL.into_iter().for_each(|option| {
+                            ui.selectable_value(&mut policy, option, format!("{:?}", option));
+                        });
+                    });
+
+                if policy != self.voice_steal_policy {
+                    self.voice_steal_policy = policy;
+                    sync.set_voice_steal_policy(policy);
+                }
+
+                ui.separator();
+                ui.label("The delay echoes any audio sent into it from the buses.");
+
+                let delay = &mut data.delay;
+                let mut changed = ui
+                    .add(Checkbox::new(&mut delay.enabled, "Delay Enabled"))
+                    .changed();
+                changed |= ui
+                    .add(
+                        Slider::new(&mut delay.time, 0.0..=DELAY_MAX_SECONDS)
+                            .text("Time")
+                            .suffix("s"),
+                    )
+                    .changed();
+                changed |= ui
+                    .add(
+                        Slider::new(&mut delay.feedback, 0.0..=DELAY_MAX_FEEDBACK).text("Feedback"),
+                    )
+                    .changed();
+                changed |= ui
+                    .add(Slider::new(&mut delay.wet, 0.0..=1.0).text("Wet"))
+                    .changed();
+                changed |= ui
+                    .add(Slider::new(&mut delay.music_send, 0.0..=1.0).text("Music Send"))
+                    .changed();
+                changed |= ui
+                    .add(Slider::new(&mut delay.sfx_send, 0.0..=1.0).text("Sfx Send"))
+                    .changed();
+
+                if changed {
+                    sync.notify_rom_changed();
+                }
+
+                ui.separator();
+                ui.label("How notes are tuned, which is saved with the rom.");
+
+                let selected_text = match &data.tuning {
+                    Tuning::EqualTemperament => String::from("12-TET"),
+                    Tuning::EqualDivision(divisions) => format!("{}-EDO", divisions),
+                    Tuning::Ratios(_) => String::from("Custom Scale"),
+                };
+
+                let mut tuning = data.tuning.clone();
+                ComboBox::from_label("Tuning")
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        if ui
+                            .selectable_label(tuning == Tuning::EqualTemperament, "12-TET")
+                            .clicked()
+                        {
+                            tuning = Tuning::EqualTemperament;
+                        }
+
+                        let is_division = matches!(tuning, Tuning::EqualDivision(_));
+                        if ui.selectable_label(is_division, "Equal Division").clicked()
+                            && !is_division
+                        {
+                            tuning = Tuning::EqualDivision(19);
+                        }
+
+                        let is_scale = matches!(tuning, Tuning::Ratios(_));
+                        if ui.selectable_label(is_scale, "Custom Scale").clicked() && !is_scale {
+                            if let Ok(ratios) = Tuning::parse_ratios(&self.scale_text) {
+                                tuning = Tuning::Ratios(ratios);
+                            }
+                        }
+                    });
+
+                match &mut tuning {
+                    Tuning::EqualTemperament => (),
+                    Tuning::EqualDivision(divisions) => {
+                        ui.add(
+                            Slider::new(divisions, TUNING_MIN_DIVISIONS..=TUNING_MAX_DIVISIONS)
+                                .text("Steps per Octave"),
+                        );
+                    }
+                    Tuning::Ratios(ratios) => {
+                        ui.label(
+                            "Ratios from the root, as fractions or cents, ending with the octave:",
+                        );
+                        ui.add(TextEdit::multiline(&mut self.scale_text).desired_rows(2));
+
+                        if ui.button("Apply Scale").clicked() {
+                            match Tuning::parse_ratios(&self.scale_text) {
+                                Ok(new_ratios) => {
+                                    *ratios = new_ratios;
+                                    self.scale_error = None;
+                                }
+                                Err(e) => self.scale_error = Some(e),
+                            }
+                        }
+
+                        if let Some(error) = &self.scale_error {
+                            ui.label(error.as_str());
+                        }
+                    }
+                }
+
+                if tuning != data.tuning {
+                    data.tuning = tuning;
+                    sync.notify_rom_changed();
+                }
+
+                ui.separator();
+                ui.label("Retunes every note played in the editor.");
+
+                if ui
+                    .add(
+                        Slider::new(
+                            &mut self.transpose,
+                            -TRANSPOSE_MAX_SEMITONES..=TRANSPOSE_MAX_SEMITONES,
+                        )
+                        .text("Transpose")
+                        .suffix(" semitones"),
+                    )
+                    .changed()
+                {
+                    sync.set_transpose(self.transpose);
+                }
+
+                if ui
+                    .add(
+                        Slider::new(
+                            &mut self.tuning_reference,
+                            TUNING_REFERENCE_MIN..=TUNING_REFERENCE_MAX,
+                        )
+                        .text("A4 Tuning")
+                        .suffix(" Hz"),
+                    )
+                    .changed()
+                {
+                    sync.set_tuning_reference(self.tuning_reference);
+                }
+
+                ui.separator();
+                ui.label("The master volume only affects playback in the editor.");
+
+                if ui
+                    .add(Slider::new(&mut self.master_volume, 0.0..=1.0).text("Master"))
+                    .changed()
+                {
+                    sync.set_master_volume(self.master_volume);
+                }
+            });
+    }
+}
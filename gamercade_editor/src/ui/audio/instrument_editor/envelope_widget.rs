@@ -1,5 +1,7 @@
 use eframe::egui::{Slider, Ui};
-use gamercade_audio::{EnvelopeDefinition, EnvelopeValueType};
+use gamercade_audio::{
+    EnvelopeCurve, EnvelopeDefinition, EnvelopeValueType, ENVELOPE_DEFAULT_CURVE_COEFFICIENT,
+};
 
 use crate::ui::AudioSyncHelper;
 
@@ -60,6 +62,44 @@ impl EnvelopeWidget {
                     sync.notify_rom_changed();
                 }
             });
+
+            ui.horizontal(|ui| {
+                let mut changed = false;
+                let exponential = EnvelopeCurve::Exponential(ENVELOPE_DEFAULT_CURVE_COEFFICIENT);
+
+                ui.label("Curve:");
+                changed |= ui
+                    .radio_value(&mut envelope.curve, EnvelopeCurve::Linear, "Linear")
+                    .changed();
+                if ui
+                    .radio(
+                        matches!(envelope.curve, EnvelopeCurve::Exponential(_)),
+                        "Exponential",
+                    )
+                    .clicked()
+                    && !matches!(envelope.curve, EnvelopeCurve::Exponential(_))
+                {
+                    envelope.curve = exponential;
+                    changed = true;
+                }
+                changed |= ui
+                    .radio_value(
+                        &mut envelope.curve,
+                        EnvelopeCurve::Logarithmic,
+                        "Logarithmic",
+                    )
+                    .changed();
+
+                if let EnvelopeCurve::Exponential(coefficient) = &mut envelope.curve {
+                    changed |= ui
+                        .add(Slider::new(coefficient, 0.0..=16.0).text("Coefficient"))
+                        .changed();
+                }
+
+                if changed {
+                    sync.notify_rom_changed();
+                }
+            });
         });
     }
 }
@@ -0,0 +1,44 @@
+use eframe::egui::{Slider, Ui};
+use gamercade_audio::{FilterDefinition, FILTER_MAX_CUTOFF, FILTER_MIN_CUTOFF};
+
+use crate::ui::AudioSyncHelper;
+
+pub(crate) struct FilterWidget {}
+
+impl FilterWidget {
+    pub(crate) fn draw(
+        ui: &mut Ui,
+        filter: &mut Option<FilterDefinition>,
+        sync: &mut AudioSyncHelper,
+    ) {
+        ui.group(|ui| {
+            let mut enabled = filter.is_some();
+            if ui.checkbox(&mut enabled, "Low-pass Filter").changed() {
+                *filter = enabled.then(FilterDefinition::default);
+                sync.notify_rom_changed();
+            }
+
+            if let Some(filter) = filter {
+                let responses = [
+                    ui.add(
+                        Slider::new(&mut filter.cutoff, FILTER_MIN_CUTOFF..=FILTER_MAX_CUTOFF)
+                            .logarithmic(true)
+                            .text("Cutoff"),
+                    )
+                    .changed(),
+                    ui.add(Slider::new(&mut filter.resonance, 0.0..=1.0).text("Resonance"))
+                        .changed(),
+                    ui.add(
+                        Slider::new(&mut filter.envelope_amount, -1.0..=1.0)
+                            .text("Envelope Amount"),
+                    )
+                    .changed(),
+                ];
+
+                if responses.contains(&true) {
+                    sync.notify_rom_changed();
+                }
+            }
+        });
+    }
+}
@@ -3,8 +3,8 @@ use eframe::{
     epaint::{ColorImage, TextureHandle, Vec2},
 };
 use gamercade_audio::{
-    Algorithm, Detune, FMWaveform, FeedbackLevel, FrequencyMultiplier, OperatorDefinition,
-    PatchDefinition, OPERATOR_COUNT,
+    Algorithm, Detune, FMWaveform, FeedbackLevel, FrequencyMode, FrequencyMultiplier,
+    OperatorDefinition, PatchDefinition, OPERATOR_COUNT,
 };
 
 use crate::ui::AudioSyncHelper;
@@ -57,6 +57,11 @@ impl FMHelp {
 
                 ui.label("Algorithm Chart:");
                 ui.add(Image::new(texture_id, self.diagram_size.unwrap()));
+
+                ui.separator();
+                ui.label("Ring Modulation Algorithms (* multiplies the outputs):");
+                ui.label("12: [A * B] + [C * D]");
+                ui.label("13: [A > B > C] * D");
             });
     }
 }
@@ -205,39 +210,74 @@ impl OperatorWidget {
 
             ui.group(|ui| {
                 ui.label("Frequency Adjustment");
-                ui.label(format!(
-                    "Frequency Multiplier: {:.3}",
-                    operator.frequency_multiplier.top as f32
-                        / operator.frequency_multiplier.bottom as f32
-                ));
-                ui.label("Top");
-                if ui
-                    .add(Slider::new(
-                        &mut operator.frequency_multiplier.top,
-                        FrequencyMultiplier::min_value()..=FrequencyMultiplier::max_value(),
-                    ))
-                    .changed()
-                {
-                    should_notify = true;
-                }
 
-                ui.label("Bottom");
-                if ui
-                    .add(Slider::new(
-                        &mut operator.frequency_multiplier.bottom,
-                        FrequencyMultiplier::min_value()..=FrequencyMultiplier::max_value(),
-                    ))
-                    .changed()
-                {
-                    should_notify = true;
+                ui.horizontal(|ui| {
+                    let is_fixed = matches!(operator.frequency_mode, FrequencyMode::Fixed(_));
+
+                    if ui.radio(!is_fixed, "Ratio").clicked() && is_fixed {
+                        operator.frequency_mode = FrequencyMode::Ratio;
+                        should_notify = true;
+                    }
+
+                    if ui.radio(is_fixed, "Fixed").clicked() && !is_fixed {
+                        operator.frequency_mode = FrequencyMode::Fixed(440.0);
+                        should_notify = true;
+                    }
+                });
+
+                match &mut operator.frequency_mode {
+                    FrequencyMode::Ratio => {
+                        ui.label(format!(
+                            "Frequency Multiplier: {:.3}",
+                            operator.frequency_multiplier.top as f32
+                                / operator.frequency_multiplier.bottom as f32
+                        ));
+                        ui.label("Top");
+                        if ui
+                            .add(Slider::new(
+                                &mut operator.frequency_multiplier.top,
+                                FrequencyMultiplier::min_value()..=FrequencyMultiplier::max_value(),
+                            ))
+                            .changed()
+                        {
+                            should_notify = true;
+                        }
+
+                        ui.label("Bottom");
+                        if ui
+                            .add(Slider::new(
+                                &mut operator.frequency_multiplier.bottom,
+                                FrequencyMultiplier::min_value()..=FrequencyMultiplier::max_value(),
+                            ))
+                            .changed()
+                        {
+                            should_notify = true;
+                        }
+                    }
+                    FrequencyMode::Fixed(frequency) => {
+                        ui.label("Fixed Frequency");
+                        if ui
+                            .add(
+                                Slider::new(
+                                    frequency,
+                                    FrequencyMode::min_fixed()..=FrequencyMode::max_fixed(),
+                                )
+                                .logarithmic(true)
+                                .suffix(" Hz"),
+                            )
+                            .changed()
+                        {
+                            should_notify = true;
+                        }
+                    }
                 }
 
                 ui.label("Detune");
                 if ui
-                    .add(Slider::new(
-                        &mut operator.detune.0,
-                        Detune::min()..=Detune::max(),
-                    ))
+                    .add(
+                        Slider::new(&mut operator.detune.0, Detune::min()..=Detune::max())
+                            .suffix(" cents"),
+                    )
                     .changed()
                 {
                     should_notify = true;
@@ -1,6 +1,7 @@
 use eframe::egui::Ui;
 use gamercade_audio::{InstrumentDataDefinition, INSTRUMENTS_MAX_COUNT};
-use gamercade_fs::{EditorAudioDataEntry, EditorSoundData};
+use gamercade_fs::{EditorAudioDataEntry, EditorSoundData, InstrumentPreset};
+use rfd::{FileDialog, MessageDialog, MessageLevel};
 
 use crate::ui::{AudioList, AudioSyncHelper};
 
@@ -37,6 +38,25 @@ impl AudioList<Option<InstrumentDataDefinition>> for InstrumentList {
                 println!("TODO: Clean Up Instruments")
             }
         });
+
+        ui.horizontal(|ui| {
+            if ui.button("Export Instrument...").clicked() {
+                if let Err(e) = try_export_instrument(data, self.selected_instrument) {
+                    show_error("Export Instrument", &e);
+                }
+            }
+
+            if ui.button("Import Instrument...").clicked() {
+                match try_import_instrument(data) {
+                    Ok(Some(index)) => {
+                        self.selected_instrument = index;
+                        sync.notify_rom_changed();
+                    }
+                    Ok(None) => (),
+                    Err(e) => show_error("Import Instrument", &e),
+                }
+            }
+        });
     }
 
     fn target_data_mut(
@@ -60,3 +80,48 @@ impl AudioList<Option<InstrumentDataDefinition>> for InstrumentList {
         unreachable!()
     }
 }
+
+const INSTRUMENT_PRESET_EXTENSION: &str = "gcinst";
+
+fn try_export_instrument(data: &EditorSoundData, instrument_index: usize) -> Result<(), String> {
+    let preset = InstrumentPreset::export(data, instrument_index)?;
+
+    if let Some(path) = FileDialog::new()
+        .add_filter(
+            "instrument preset (.gcinst)",
+            &[INSTRUMENT_PRESET_EXTENSION],
+        )
+        .set_title("Export Instrument")
+        .set_file_name(&format!("{}.{}", preset.name, INSTRUMENT_PRESET_EXTENSION))
+        .save_file()
+    {
+        preset.try_save(&path)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the index of the imported instrument, or None if cancelled.
+fn try_import_instrument(data: &mut EditorSoundData) -> Result<Option<usize>, String> {
+    let path = match FileDialog::new()
+        .add_filter(
+            "instrument preset (.gcinst)",
+            &[INSTRUMENT_PRESET_EXTENSION],
+        )
+        .set_title("Import Instrument")
+        .pick_file()
+    {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+
+    InstrumentPreset::try_load(&path)?.import(data).map(Some)
+}
+
+fn show_error(title: &str, description: &str) {
+    MessageDialog::new()
+        .set_level(MessageLevel::Error)
+        .set_title(title)
+        .set_description(description)
+        .show();
+}
@@ -0,0 +1,70 @@
+use eframe::egui::{ComboBox, Slider, Ui};
+use gamercade_audio::{Lfo, LfoTarget, LfoWaveform, LFO_MAX_DELAY, LFO_MAX_RATE};
+
+use crate::ui::AudioSyncHelper;
+
+pub(crate) struct LfoWidget {}
+
+impl LfoWidget {
+    pub(crate) fn draw(ui: &mut Ui, lfo: &mut Option<Lfo>, sync: &mut AudioSyncHelper) {
+        ui.group(|ui| {
+            let mut enabled = lfo.is_some();
+            if ui.checkbox(&mut enabled, "Lfo").changed() {
+                *lfo = enabled.then(Lfo::default);
+                sync.notify_rom_changed();
+            }
+
+            if let Some(lfo) = lfo {
+                let mut changed = false;
+
+                ui.horizontal(|ui| {
+                    ui.label("Target: ");
+                    changed |= ui
+                        .radio_value(&mut lfo.target, LfoTarget::Pitch, "Pitch")
+                        .changed();
+                    changed |= ui
+                        .radio_value(&mut lfo.target, LfoTarget::Amplitude, "Amplitude")
+                        .changed();
+                    changed |= ui
+                        .radio_value(&mut lfo.target, LfoTarget::Morph, "Morph")
+                        .changed();
+                });
+
+                ComboBox::from_label("Waveform")
+                    .selected_text(format!("{:?}", lfo.waveform))
+                    .show_ui(ui, |ui| {
+                        [
+                            LfoWaveform::Sine,
+                            LfoWaveform::Triangle,
+                            LfoWaveform::Square,
+                            LfoWaveform::Saw,
+                        ]
+                        .into_iter()
+                        .for_each(|waveform| {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut lfo.waveform,
+                                    waveform,
+                                    format!("{:?}", waveform),
+                                )
+                                .changed();
+                        });
+                    });
+
+                changed |= ui
+                    .add(Slider::new(&mut lfo.rate, 0.0..=LFO_MAX_RATE).text("Rate"))
+                    .changed();
+                changed |= ui
+                    .add(Slider::new(&mut lfo.depth, 0.0..=1.0).text("Depth"))
+                    .changed();
+                changed |= ui
+                    .add(Slider::new(&mut lfo.delay, 0.0..=LFO_MAX_DELAY).text("Delay"))
+                    .changed();
+
+                if changed {
+                    sync.notify_rom_changed();
+                }
+            }
+        });
+    }
+}
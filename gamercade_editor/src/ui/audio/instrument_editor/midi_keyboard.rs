@@ -0,0 +1,222 @@
+use std::sync::mpsc::{channel, Receiver};
+
+use eframe::egui::{ComboBox, Context, Ui};
+use gamercade_audio::{midi_note_to_index, PhraseVolumeType, TOTAL_NOTES_COUNT};
+use midir::{MidiInput, MidiInputConnection, MidiInputPort};
+
+use crate::ui::AudioSyncHelper;
+
+const MIDI_CLIENT_NAME: &str = "Gamercade Editor";
+
+const MIDI_NOTE_ON: u8 = 0x90;
+const MIDI_NOTE_OFF: u8 = 0x80;
+const MIDI_MAX_VELOCITY: u8 = 0x7F;
+
+enum MidiNoteEvent {
+    Pressed {
+        note: usize,
+        velocity: PhraseVolumeType,
+    },
+    Released(usize),
+}
+
+/// Scales a midi velocity up to the range of a phrase volume.
+fn velocity_to_volume(velocity: u8) -> PhraseVolumeType {
+    let velocity = velocity.min(MIDI_MAX_VELOCITY) as usize;
+    (velocity * PhraseVolumeType::MAX as usize / MIDI_MAX_VELOCITY as usize) as PhraseVolumeType
+}
+
+/// Parses a raw midi message into a note event. Note on with
+/// a velocity of zero is treated as a note off.
+fn parse_midi_message(message: &[u8]) -> Option<MidiNoteEvent> {
+    let (status, note, velocity) = match message {
+        [status, note, velocity, ..] => (status & 0xF0, *note, *velocity),
+        _ => return None,
+    };
+
+    let note = midi_note_to_index(note)?.0;
+
+    match status {
+        MIDI_NOTE_ON if velocity > 0 => Some(MidiNoteEvent::Pressed {
+            note,
+            velocity: velocity_to_volume(velocity),
+        }),
+        MIDI_NOTE_ON | MIDI_NOTE_OFF => Some(MidiNoteEvent::Released(note)),
+        _ => None,
+    }
+}
+
+struct MidiConnection {
+    // Kept alive so the port stays connected.
+    _connection: MidiInputConnection<()>,
+    receiver: Receiver<MidiNoteEvent>,
+}
+
+/// Lets an attached midi keyboard play the selected instrument.
+pub(crate) struct MidiKeyboard {
+    ports: Vec<(String, MidiInputPort)>,
+    selected_port: Option<String>,
+    connection: Option<MidiConnection>,
+    note_voices: [Option<usize>; TOTAL_NOTES_COUNT],
+    error: Option<String>,
+    scanned: bool,
+}
+
+impl Default for MidiKeyboard {
+    fn default() -> Self {
+        Self {
+            ports: Vec::new(),
+            selected_port: None,
+            connection: None,
+            note_voices: [None; TOTAL_NOTES_COUNT],
+            error: None,
+            scanned: false,
+        }
+    }
+}
+
+impl MidiKeyboard {
+    pub(crate) fn draw(
+        &mut self,
+        ui: &mut Ui,
+        sync: &mut AudioSyncHelper,
+        selected_instrument: usize,
+    ) {
+        if !self.scanned {
+            self.refresh_ports();
+        }
+
+        self.update_notes(sync, selected_instrument);
+
+        ui.horizontal(|ui| {
+            let mut next_port = self.selected_port.clone();
+
+            ComboBox::from_label("MIDI Input")
+                .selected_text(next_port.as_deref().unwrap_or("None"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut next_port, None, "None");
+                    self.ports.iter().for_each(|(name, _)| {
+                        ui.selectable_value(&mut next_port, Some(name.clone()), name);
+                    });
+                });
+
+            if next_port != self.selected_port {
+                self.connect(next_port, ui.ctx(), sync);
+            }
+
+            if ui.button("Refresh").clicked() {
+                self.refresh_ports();
+            }
+
+            if let Some(error) = &self.error {
+                ui.label(error.as_str());
+            }
+        });
+    }
+
+    fn refresh_ports(&mut self) {
+        self.scanned = true;
+
+        match MidiInput::new(MIDI_CLIENT_NAME) {
+            Ok(input) => {
+                self.ports = input
+                    .ports()
+                    .into_iter()
+                    .filter_map(|port| Some((input.port_name(&port).ok()?, port)))
+                    .collect();
+                self.error = None;
+            }
+            Err(e) => {
+                self.ports.clear();
+                self.error = Some(format!("MIDI unavailable: {}", e));
+            }
+        }
+    }
+
+    /// Connects to the named port, or disconnects if None.
+    /// Any held notes are released first.
+    fn connect(&mut self, port_name: Option<String>, ctx: &Context, sync: &mut AudioSyncHelper) {
+        self.release_all(sync);
+        self.connection = None;
+        self.selected_port = None;
+
+        let port_name = match port_name {
+            Some(port_name) => port_name,
+            None => return,
+        };
+
+        let port = match self.ports.iter().find(|(name, _)| *name == port_name) {
+            Some((_, port)) => port.clone(),
+            None => return,
+        };
+
+        let result = MidiInput::new(MIDI_CLIENT_NAME)
+            .map_err(|e| e.to_string())
+            .and_then(|input| {
+                let (sender, receiver) = channel();
+                let ctx = ctx.clone();
+
+                input
+                    .connect(
+                        &port,
+                        MIDI_CLIENT_NAME,
+                        move |_, message, _| {
+                            if let Some(event) = parse_midi_message(message) {
+                                // The editor doesn't redraw without input, so wake it up.
+                                if sender.send(event).is_ok() {
+                                    ctx.request_repaint();
+                                }
+                            }
+                        },
+                        (),
+                    )
+                    .map(|connection| MidiConnection {
+                        _connection: connection,
+                        receiver,
+                    })
+                    .map_err(|e| e.to_string())
+            });
+
+        match result {
+            Ok(connection) => {
+                self.connection = Some(connection);
+                self.selected_port = Some(port_name);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Failed to connect: {}", e)),
+        }
+    }
+
+    /// Plays and releases notes received since the last update.
+    fn update_notes(&mut self, sync: &mut AudioSyncHelper, selected_instrument: usize) {
+        let connection = match &self.connection {
+            Some(connection) => connection,
+            None => return,
+        };
+
+        while let Ok(event) = connection.receiver.try_recv() {
+            match event {
+                MidiNoteEvent::Pressed { note, velocity } => {
+                    if let Some(voice) = self.note_voices[note].take() {
+                        sync.stop_note(voice);
+                    }
+                    let voice = sync.play_note(note, selected_instrument, velocity);
+                    self.note_voices[note] = Some(voice);
+                }
+                MidiNoteEvent::Released(note) => {
+                    if let Some(voice) = self.note_voices[note].take() {
+                        sync.stop_note(voice);
+                    }
+                }
+            }
+        }
+    }
+
+    fn release_all(&mut self, sync: &mut AudioSyncHelper) {
+        self.note_voices.iter_mut().for_each(|voice| {
+            if let Some(voice) = voice.take() {
+                sync.stop_note(voice);
+            }
+        });
+    }
+}
@@ -1,15 +1,19 @@
 use eframe::egui::Ui;
 use gamercade_audio::InstrumentDataDefinition;
 
-use gamercade_fs::EditorSoundData;
+use gamercade_fs::{EditorAudioDataEntry, EditorSoundData};
 
 use super::{AudioList, AudioSyncHelper};
+use crate::ui::{SnapshotHistory, SnapshotTarget, Undoable};
 
 mod envelope_widget;
+mod filter_widget;
 mod fm_editor;
 mod instrument_list;
 mod instrument_top_panel;
 mod interpolator_widget;
+mod lfo_widget;
+mod midi_keyboard;
 mod piano_roll;
 mod sampler_editor;
 mod wavetable_editor;
@@ -17,6 +21,7 @@ mod wavetable_editor;
 use fm_editor::*;
 use instrument_list::*;
 use instrument_top_panel::*;
+use midi_keyboard::*;
 use piano_roll::*;
 use sampler_editor::*;
 use wavetable_editor::*;
@@ -38,7 +43,11 @@ pub struct InstrumentEditor {
     instrument_list: InstrumentList,
     instrument_top_panel: InstrumentTopPanel,
     piano_roll: PianoRoll,
+    midi_keyboard: MidiKeyboard,
     keyboard_mode: KeyboardMode,
+
+    /// Each edit is to a single instrument, as samples can be large.
+    history: SnapshotHistory<EditorAudioDataEntry<Option<InstrumentDataDefinition>>>,
 }
 
 impl InstrumentEditor {
@@ -48,6 +57,9 @@ impl InstrumentEditor {
         data: &mut EditorSoundData,
         sync: &mut AudioSyncHelper,
     ) {
+        self.history
+            .begin(data, self.instrument_list.selected_instrument);
+
         self.instrument_list.draw(ui, data, sync);
 
         let index = self.instrument_list.selected_instrument;
@@ -59,7 +71,7 @@ impl InstrumentEditor {
             // Now we need to determine which instrument kind we are currenty editing
             ui.group(|ui| match &mut instrument.data {
                 Some(InstrumentDataDefinition::Wavetable(wv)) => {
-                    self.wavetable_editor.draw(ui, wv, sync)
+                    self.wavetable_editor.draw(ui, wv, sync, index)
                 }
                 Some(InstrumentDataDefinition::FMSynth(fm)) => self.fm_editor.draw(ui, fm, sync),
                 Some(InstrumentDataDefinition::Sampler(sm)) => {
@@ -79,5 +91,31 @@ impl InstrumentEditor {
             self.instrument_list.selected_instrument,
             &self.keyboard_mode,
         );
+
+        self.midi_keyboard
+            .draw(ui, sync, self.instrument_list.selected_instrument);
+
+        self.history
+            .end(ui, data, self.instrument_list.selected_instrument);
+    }
+}
+
+impl SnapshotTarget<EditorSoundData> for EditorAudioDataEntry<Option<InstrumentDataDefinition>> {
+    fn locate(data: &mut EditorSoundData, index: usize) -> Option<&mut Self> {
+        data.instruments.get_mut(index)
+    }
+}
+
+impl Undoable<EditorSoundData> for InstrumentEditor {
+    fn undo(&mut self, data: &mut EditorSoundData) -> bool {
+        self.history.undo(data)
+    }
+
+    fn redo(&mut self, data: &mut EditorSoundData) -> bool {
+        self.history.redo(data)
+    }
+
+    fn clear_history(&mut self) {
+        self.history.clear();
     }
 }
@@ -2,7 +2,7 @@ use eframe::{
     egui::{ImageButton, Key, TextureFilter, Ui},
     epaint::{Color32, ColorImage, TextureHandle, Vec2},
 };
-use gamercade_audio::{NoteColor, NoteName, NotesIter, TOTAL_NOTES_COUNT};
+use gamercade_audio::{NoteColor, NoteName, NotesIter, PhraseVolumeType, TOTAL_NOTES_COUNT};
 
 use crate::ui::AudioSyncHelper;
 
@@ -16,7 +16,7 @@ pub struct PianoRoll {
 
     bottom_note_index: usize,
     key_states: [bool; KEYBOARD_KEY_COUNT],
-    key_channels: [Option<usize>; KEYBOARD_KEY_COUNT],
+    key_voices: [Option<usize>; KEYBOARD_KEY_COUNT],
 }
 
 impl Default for PianoRoll {
@@ -25,7 +25,7 @@ impl Default for PianoRoll {
             default_piano_texture: Default::default(),
             bottom_note_index: BOTTOM_NOTE_INDEX_START,
             key_states: Default::default(),
-            key_channels: Default::default(),
+            key_voices: Default::default(),
         }
     }
 }
@@ -87,11 +87,14 @@ impl PianoRoll {
             .for_each(|(index, (prev, next))| {
                 if prev != next {
                     if *next {
-                        let assigned_channel =
-                            sync.play_note(index + self.bottom_note_index, selected_instrument);
-                        self.key_channels[index] = Some(assigned_channel);
-                    } else if let Some(assigned_channel) = self.key_channels[index] {
-                        sync.stop_note(assigned_channel);
+                        let assigned_voice = sync.play_note(
+                            index + self.bottom_note_index,
+                            selected_instrument,
+                            PhraseVolumeType::MAX,
+                        );
+                        self.key_voices[index] = Some(assigned_voice);
+                    } else if let Some(assigned_voice) = self.key_voices[index] {
+                        sync.stop_note(assigned_voice);
                     } else {
                         println!("Err: Released key for an unknown note!")
                     }
@@ -173,7 +176,7 @@ impl PianoRoll {
 
                     let button_top = ImageButton::new(texture_id, TOP_KEY_SIZE).tint(color);
                     if ui.add(button_top).clicked() {
-                        sync.trigger_note(index, selected_instrument);
+                        sync.trigger_note(index, selected_instrument, PhraseVolumeType::MAX);
                     };
                 });
             });
@@ -193,7 +196,7 @@ impl PianoRoll {
                             ImageButton::new(texture_id, BOTTOM_KEY_SIZE).tint(tint);
 
                         if ui.add(button_bottom).clicked() {
-                            sync.trigger_note(index, selected_instrument);
+                            sync.trigger_note(index, selected_instrument, PhraseVolumeType::MAX);
                         };
                     }
                 }
@@ -1,18 +1,20 @@
 use eframe::{
     egui::{
         plot::{HLine, Line, Plot, PlotPoint, PlotPoints, VLine},
-        Slider, Ui,
+        ComboBox, Slider, Ui,
     },
     epaint::{Color32, Vec2},
 };
 use hound::WavReader;
 use rfd::FileDialog;
 
-use gamercade_audio::{LoopMode, SampleBitDepth, SampleDefinition};
+use gamercade_audio::{
+    get_note, LoopMode, NoteId, SampleBitDepth, SampleDefinition, TOTAL_NOTES_COUNT,
+};
 
 use crate::ui::AudioSyncHelper;
 
-use super::envelope_widget::EnvelopeWidget;
+use super::{envelope_widget::EnvelopeWidget, filter_widget::FilterWidget};
 
 #[derive(Default)]
 pub struct SamplerEditor {
@@ -38,6 +40,21 @@ impl SamplerEditor {
                     sync.notify_rom_changed();
                 }
 
+                ComboBox::from_label("Root Note")
+                    .selected_text(format!("{:.2}hz", frequency))
+                    .show_ui(ui, |ui| {
+                        (0..TOTAL_NOTES_COUNT).for_each(|index| {
+                            let note = get_note(NoteId(index));
+                            if ui
+                                .selectable_label(*frequency == note.frequency, note.name.as_str())
+                                .clicked()
+                            {
+                                *frequency = note.frequency;
+                                sync.notify_rom_changed();
+                            }
+                        })
+                    });
+
                 if ui.button("Disable Sample Frequency").clicked() {
                     instrument.sample_frequency = None;
                     sync.notify_rom_changed();
@@ -45,7 +62,8 @@ impl SamplerEditor {
             }
             None => {
                 if ui.button("Enable Sample Frequency").clicked() {
-                    instrument.sample_frequency = Some(0.0);
+                    instrument.sample_frequency = Some(get_note(NoteId::default()).frequency);
+                    sync.notify_rom_changed();
                 }
             }
         }
@@ -160,6 +178,7 @@ impl SamplerEditor {
         });
 
         EnvelopeWidget::draw(ui, &mut instrument.envelope_definition, sync);
+        FilterWidget::draw(ui, &mut instrument.filter, sync);
     }
 
     fn generate_sample_plot(&mut self, data: &[SampleBitDepth]) {
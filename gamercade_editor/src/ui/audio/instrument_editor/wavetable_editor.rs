@@ -6,17 +6,28 @@ use eframe::{
     epaint::{Color32, Vec2},
 };
 use gamercade_audio::{
-    WavetableBitDepth, WavetableDefinition, WavetableGenerator, WavetableWaveform,
+    normalize_wavetable, resample_wavetable, NoteId, PhraseVolumeType, WavetableBitDepth,
+    WavetableDefinition, WavetableGenerator, WavetableResampleSource, WavetableWaveform,
     WAVETABLE_MAX_LENGTH,
 };
+use hound::{SampleFormat, WavReader};
+use rfd::FileDialog;
 
 use crate::ui::AudioSyncHelper;
 
-use super::{envelope_widget::EnvelopeWidget, interpolator_widget::InterpolatorWidget};
+use super::{
+    envelope_widget::EnvelopeWidget, filter_widget::FilterWidget,
+    interpolator_widget::InterpolatorWidget, lfo_widget::LfoWidget,
+};
 
 #[derive(Clone, Debug, Default)]
 pub struct WavetableEditor {
     generator: WavetableGeneratorWidget,
+    wav_import: WavImportWidget,
+
+    /// The last point drawn while dragging, so gaps between
+    /// frames can be filled in.
+    last_draw_point: Option<(usize, WavetableBitDepth)>,
 }
 
 impl WavetableEditor {
@@ -25,12 +36,34 @@ impl WavetableEditor {
         ui: &mut Ui,
         instrument: &mut WavetableDefinition,
         sync: &mut AudioSyncHelper,
+        instrument_index: usize,
     ) {
         self.generator.draw(ui, instrument, sync);
+        self.wav_import.draw(ui, instrument, sync, instrument_index);
 
         let ptr = &(&instrument.interpolator as *const _ as usize);
         InterpolatorWidget::draw(ui, &mut instrument.interpolator, sync, ptr);
 
+        ui.horizontal(|ui| {
+            ui.label("Wavetable Length:");
+            ComboBox::from_id_source("Wavetable Length")
+                .selected_text(instrument.data.len().to_string())
+                .show_ui(ui, |ui| {
+                    WAVETABLE_SIZES.iter().for_each(|size| {
+                        if ui
+                            .selectable_label(*size == instrument.data.len(), size.to_string())
+                            .clicked()
+                            && *size != instrument.data.len()
+                        {
+                            *instrument = instrument.resample(*size);
+                            sync.notify_rom_changed();
+                        }
+                    });
+                })
+                .response
+                .on_hover_text("Resamples the table, keeping its shape.");
+        });
+
         let len = instrument.data.len();
 
         if len == 0 {
@@ -54,9 +87,18 @@ impl WavetableEditor {
         ];
         let line_segment = Line::new(lerp_segment).color(Color32::DARK_GREEN);
 
+        // Draw the morph table stretched over the same length:
+        let morph_scale = len as f64 / instrument.morph_data.len().max(1) as f64;
+        let morph_points: PlotPoints = instrument
+            .morph_data
+            .iter()
+            .enumerate()
+            .map(|(index, val)| [index as f64 * morph_scale, *val as f64])
+            .collect();
+        let morph_line = Line::new(morph_points).color(Color32::YELLOW);
+
         let primary_pointer_down = ui.input().pointer.primary_down();
 
-        ui.label(&format!("Wavetable Length: {}", instrument.data.len()));
         Plot::new("Wavetable Plot")
             .width(1000.0)
             .height(200.0)
@@ -74,6 +116,7 @@ impl WavetableEditor {
                 format!("Idx:{}\nVal:{}", x, y)
             })
             .show(ui, |plot_ui| {
+                plot_ui.line(morph_line);
                 plot_ui.line(line);
                 plot_ui.line(line_segment);
 
@@ -84,13 +127,17 @@ impl WavetableEditor {
 
                 if plot_ui.plot_hovered() && primary_pointer_down {
                     let point = plot_ui.pointer_coordinate().unwrap();
-                    let (x, y) = plot_point_to_x_y(&point, last_index);
+                    let point = plot_point_to_x_y(&point, last_index);
+                    let from = self.last_draw_point.unwrap_or(point);
 
                     // Only update if we changed the value!
-                    if instrument.data[x] != y {
+                    if draw_line(&mut instrument.data, from, point) {
                         sync.notify_rom_changed();
-                        instrument.data[x] = y;
                     }
+
+                    self.last_draw_point = Some(point);
+                } else {
+                    self.last_draw_point = None;
                 }
             });
 
@@ -109,12 +156,46 @@ impl WavetableEditor {
             sync.notify_rom_changed();
         }
 
+        if ui.button("Normalize").clicked() {
+            normalize_wavetable(&mut instrument.data);
+            sync.notify_rom_changed();
+        }
+
         // TODO: Add wavetable generator helper UI
         if ui.button("Waveform Generator").clicked() {
             self.generator.open = !self.generator.open;
         }
 
-        EnvelopeWidget::draw(ui, &mut instrument.envelope, sync)
+        if ui.button("Import .wav").clicked() {
+            self.wav_import.open = !self.wav_import.open;
+        }
+
+        let mut morph_enabled = !instrument.morph_data.is_empty();
+        if ui.checkbox(&mut morph_enabled, "Morph").changed() {
+            instrument.morph_data = if morph_enabled {
+                instrument.data.clone()
+            } else {
+                Box::default()
+            };
+            sync.notify_rom_changed();
+        }
+
+        if morph_enabled {
+            ui.label(&format!(
+                "Morph Table Length: {}",
+                instrument.morph_data.len()
+            ));
+            if ui
+                .add(Slider::new(&mut instrument.morph, 0.0..=1.0).text("Morph Amount"))
+                .changed()
+            {
+                sync.notify_rom_changed();
+            }
+        }
+
+        EnvelopeWidget::draw(ui, &mut instrument.envelope, sync);
+        FilterWidget::draw(ui, &mut instrument.filter, sync);
+        LfoWidget::draw(ui, &mut instrument.lfo, sync);
     }
 }
 
@@ -129,11 +210,59 @@ fn plot_point_to_x_y(point: &PlotPoint, last_index: usize) -> (usize, WavetableB
     (x, y)
 }
 
-#[derive(Clone, Debug, Default)]
+/// The number of harmonic sliders shown for additive waveforms.
+const ADDITIVE_HARMONICS: usize = 16;
+
+/// Writes a straight line between the two points into the table.
+/// Returns true if any values changed.
+fn draw_line(
+    data: &mut [WavetableBitDepth],
+    from: (usize, WavetableBitDepth),
+    to: (usize, WavetableBitDepth),
+) -> bool {
+    let ((start_x, start_y), (end_x, end_y)) = if from.0 <= to.0 {
+        (from, to)
+    } else {
+        (to, from)
+    };
+
+    let width = (end_x - start_x).max(1) as f32;
+    let mut changed = false;
+
+    (start_x..=end_x).for_each(|x| {
+        let fraction = (x - start_x) as f32 / width;
+        let y = start_y as f32 + (end_y as f32 - start_y as f32) * fraction;
+        let y = y.round() as WavetableBitDepth;
+
+        if data[x] != y {
+            data[x] = y;
+            changed = true;
+        }
+    });
+
+    changed
+}
+
+#[derive(Clone, Debug)]
 struct WavetableGeneratorWidget {
     open: bool,
     generator: WavetableGenerator,
     duty_cycle: f32,
+    harmonics: Vec<f32>,
+}
+
+impl Default for WavetableGeneratorWidget {
+    fn default() -> Self {
+        let mut harmonics = vec![0.0; ADDITIVE_HARMONICS];
+        harmonics[0] = 1.0;
+
+        Self {
+            open: false,
+            generator: WavetableGenerator::default(),
+            duty_cycle: 0.0,
+            harmonics,
+        }
+    }
 }
 
 impl WavetableGeneratorWidget {
@@ -147,8 +276,13 @@ impl WavetableGeneratorWidget {
             .open(&mut self.open)
             .collapsible(false)
             .show(ui.ctx(), |ui| {
+                let selected_text = match &self.generator.waveform {
+                    WavetableWaveform::Additive(_) => String::from("Additive"),
+                    waveform => format!("{:?}", waveform),
+                };
+
                 ComboBox::from_label("Waveform")
-                    .selected_text(format!("{:?}", &self.generator.waveform))
+                    .selected_text(selected_text)
                     .show_ui(ui, |ui| {
                         ui.selectable_value(
                             &mut self.generator.waveform,
@@ -230,6 +364,11 @@ impl WavetableGeneratorWidget {
                             WavetableWaveform::Noise,
                             "Noise",
                         );
+                        ui.selectable_value(
+                            &mut self.generator.waveform,
+                            WavetableWaveform::Additive(self.harmonics.clone()),
+                            "Additive",
+                        );
                     });
 
                 if let WavetableWaveform::Pulse(duty_cycle) = &mut self.generator.waveform {
@@ -237,15 +376,162 @@ impl WavetableGeneratorWidget {
                     self.duty_cycle = *duty_cycle;
                 };
 
+                if let WavetableWaveform::Additive(harmonics) = &mut self.generator.waveform {
+                    ui.label("Harmonics:");
+                    ui.horizontal(|ui| {
+                        harmonics
+                            .iter_mut()
+                            .enumerate()
+                            .for_each(|(index, amplitude)| {
+                                ui.vertical(|ui| {
+                                    ui.add(Slider::new(amplitude, 0.0..=1.0).vertical());
+                                    ui.label((index + 1).to_string());
+                                });
+                            });
+                    });
+                    self.harmonics.clone_from(harmonics);
+                };
+
                 ui.add(Slider::new(
                     &mut self.generator.size,
                     1..=WAVETABLE_MAX_LENGTH,
                 ));
 
-                if ui.button("Generate").clicked() {
-                    instrument.data = self.generator.generate();
-                    sync.notify_rom_changed()
+                ui.horizontal(|ui| {
+                    if ui.button("Generate").clicked() {
+                        instrument.data = self.generator.generate();
+                        sync.notify_rom_changed()
+                    }
+
+                    if ui.button("Generate Morph Table").clicked() {
+                        instrument.morph_data = self.generator.generate();
+                        sync.notify_rom_changed()
+                    }
+                });
+            });
+    }
+}
+
+/// Table sizes offered when resampling a table, or importing a .wav file.
+const WAVETABLE_SIZES: [usize; 6] = [32, 64, 128, 256, 512, 1024];
+
+#[derive(Clone, Debug)]
+struct WavImportWidget {
+    open: bool,
+    source: WavetableResampleSource,
+    size: usize,
+    error: Option<String>,
+}
+
+impl Default for WavImportWidget {
+    fn default() -> Self {
+        Self {
+            open: false,
+            source: WavetableResampleSource::default(),
+            size: 64,
+            error: None,
+        }
+    }
+}
+
+impl WavImportWidget {
+    fn draw(
+        &mut self,
+        ui: &mut Ui,
+        instrument: &mut WavetableDefinition,
+        sync: &mut AudioSyncHelper,
+        instrument_index: usize,
+    ) {
+        Window::new("Import .wav")
+            .open(&mut self.open)
+            .collapsible(false)
+            .show(ui.ctx(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Source:");
+                    ui.selectable_value(
+                        &mut self.source,
+                        WavetableResampleSource::SingleCycle,
+                        "Single Cycle",
+                    );
+                    ui.selectable_value(
+                        &mut self.source,
+                        WavetableResampleSource::WholeClip,
+                        "Whole Clip",
+                    );
+                });
+
+                ComboBox::from_label("Table Size")
+                    .selected_text(self.size.to_string())
+                    .show_ui(ui, |ui| {
+                        WAVETABLE_SIZES.iter().for_each(|size| {
+                            ui.selectable_value(&mut self.size, *size, size.to_string());
+                        })
+                    });
+
+                if ui.button("Choose File...").clicked() {
+                    if let Some(path) = FileDialog::new()
+                        .add_filter("wave (.wav)", &["wav"])
+                        .set_title("Import .wav")
+                        .pick_file()
+                    {
+                        match try_load_wav_mono(&path) {
+                            Ok(samples) => {
+                                instrument.data =
+                                    resample_wavetable(&samples, self.source, self.size);
+                                sync.notify_rom_changed();
+                                sync.trigger_note(
+                                    NoteId::default().0,
+                                    instrument_index,
+                                    PhraseVolumeType::MAX,
+                                );
+                            }
+                            Err(e) => self.error = Some(e),
+                        }
+                    }
                 }
             });
+
+        let mut error_open = self.error.is_some();
+        if let Some(error) = &self.error {
+            Window::new("Import Failed")
+                .open(&mut error_open)
+                .collapsible(false)
+                .show(ui.ctx(), |ui| {
+                    ui.label(error.as_str());
+                });
+        }
+
+        if !error_open {
+            self.error = None;
+        }
+    }
+}
+
+/// Decodes a .wav file, downmixing all channels into
+/// mono samples from -1.0 to 1.0.
+fn try_load_wav_mono(path: &std::path::Path) -> Result<Vec<f32>, String> {
+    let reader = WavReader::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let spec = reader.spec();
+    let channels = spec.channels as usize;
+
+    let samples = match spec.sample_format {
+        SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<Vec<_>, _>>(),
+        SampleFormat::Int => {
+            let max = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .into_samples::<i32>()
+                .map(|sample| sample.map(|sample| sample as f32 / max))
+                .collect::<Result<Vec<_>, _>>()
+        }
     }
+    .map_err(|e| format!("Failed to decode file: {}", e))?;
+
+    if channels == 0 || samples.len() < channels {
+        return Err(String::from("File doesn't contain any audio."));
+    }
+
+    Ok(samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect())
 }
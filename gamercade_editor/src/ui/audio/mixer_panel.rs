@@ -0,0 +1,81 @@
+use eframe::egui::{ScrollArea, Slider, Ui};
+use gamercade_audio::{SFX_CHANNELS_MAX, SONG_TRACK_CHANNELS};
+use gamercade_sound_engine::{MixerChannel, MixerStrip};
+
+use super::AudioSyncHelper;
+
+/// Mutes, solos and sets the volume of each channel during playback.
+/// None of these are saved into the rom.
+#[derive(Debug, Default)]
+pub(crate) struct MixerPanel {
+    sfx: [MixerStrip; SFX_CHANNELS_MAX],
+    bgm: [MixerStrip; SONG_TRACK_CHANNELS],
+}
+
+impl MixerPanel {
+    pub(crate) fn draw(&mut self, ui: &mut Ui, sync: &mut AudioSyncHelper) {
+        let sfx_channels = sync.sound_engine_data.channel_count();
+
+        ScrollArea::horizontal().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                self.bgm.iter_mut().enumerate().for_each(|(index, strip)| {
+                    draw_strip(
+                        ui,
+                        &format!("Bgm {}", index),
+                        strip,
+                        MixerChannel::Bgm(index),
+                        sync,
+                    )
+                });
+
+                ui.separator();
+
+                self.sfx
+                    .iter_mut()
+                    .take(sfx_channels)
+                    .enumerate()
+                    .for_each(|(index, strip)| {
+                        draw_strip(
+                            ui,
+                            &format!("Sfx {}", index),
+                            strip,
+                            MixerChannel::Sfx(index),
+                            sync,
+                        )
+                    });
+            });
+        });
+    }
+}
+
+fn draw_strip(
+    ui: &mut Ui,
+    label: &str,
+    strip: &mut MixerStrip,
+    channel: MixerChannel,
+    sync: &mut AudioSyncHelper,
+) {
+    ui.vertical(|ui| {
+        ui.label(label);
+
+        let mut changed = ui
+            .add(Slider::new(&mut strip.volume, 0.0..=1.0).vertical())
+            .changed();
+
+        ui.horizontal(|ui| {
+            if ui.selectable_label(strip.muted, "M").clicked() {
+                strip.muted = !strip.muted;
+                changed = true;
+            }
+
+            if ui.selectable_label(strip.soloed, "S").clicked() {
+                strip.soloed = !strip.soloed;
+                changed = true;
+            }
+        });
+
+        if changed {
+            sync.set_mixer_strip(channel, *strip);
+        }
+    });
+}
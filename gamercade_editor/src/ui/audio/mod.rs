@@ -1,13 +1,21 @@
 mod audio_editor;
 mod audio_editor_help;
 mod audio_list;
+mod audio_settings;
 mod instrument_editor;
+mod mixer_panel;
 mod oscilloscope;
 mod sequences;
+mod spectrum_analyzer;
+mod vu_meter;
 
 pub use audio_editor::*;
 pub(crate) use audio_editor_help::*;
 pub(crate) use audio_list::*;
+pub(crate) use audio_settings::*;
 pub(crate) use instrument_editor::*;
+pub(crate) use mixer_panel::*;
 pub(crate) use oscilloscope::*;
 use sequences::*;
+pub(crate) use spectrum_analyzer::*;
+pub(crate) use vu_meter::*;
@@ -3,24 +3,34 @@ use std::collections::VecDeque;
 use eframe::{
     egui::{
         plot::{Line, Plot, PlotPoints},
-        Ui, Window,
+        Checkbox, Grid, Ui, Window,
     },
     epaint::{Color32, Vec2},
 };
-use gamercade_audio::SFX_CHANNELS;
+use gamercade_audio::{SFX_CHANNELS, SFX_CHANNELS_MAX, SONG_TRACK_CHANNELS};
 use gamercade_sound_engine::SoundOutputChannels;
 use rtrb::Consumer;
 
+use super::{SpectrumAnalyzer, VuMeter, SPECTRUM_DB_FLOOR, SPECTRUM_MIN_FREQUENCY};
+
 // TODO: Make this configurable?
 const OSCILLOSCOPE_FRAMES: usize = 1024;
 const BUFFER_LENGTH: usize = OSCILLOSCOPE_FRAMES * 4;
 
+/// Every sfx channel followed by every bgm channel.
+const TOTAL_CHANNELS: usize = SFX_CHANNELS_MAX + SONG_TRACK_CHANNELS;
+
+/// How many channel plots are drawn in each row of the grid.
+const CHANNEL_GRID_COLUMNS: usize = 4;
+const CHANNEL_PLOT_SIZE: Vec2 = Vec2::new(160.0, 60.0);
+
 #[derive(Default, PartialEq, Eq)]
 pub(crate) enum OscilloscopeMode {
     #[default]
     Off,
     Channels,
     Master,
+    Spectrum,
 }
 
 pub(crate) struct Oscilloscope {
@@ -28,26 +38,105 @@ pub(crate) struct Oscilloscope {
     pub(crate) mode: OscilloscopeMode,
     buffer: VecDeque<SoundOutputChannels>,
     pub(crate) channel_outputs: Consumer<SoundOutputChannels>,
-    // TODO: We need to convert this to unique plots for each channel
-    // in order to support "channels" view
-    points: Vec<f64>,
-    next_points: Vec<f64>,
+    master_points: ScopePointBuffer<1>,
+    channel_points: ScopePointBuffer<TOTAL_CHANNELS>,
+    sfx_channels: usize,
+    spectrum: SpectrumAnalyzer,
+
+    /// Reads the same output, so it's updated from here.
+    pub(crate) vu_meter: VuMeter,
 }
 
-// struct ScopePointBuffer<const N: usize> {
-//     points: Vec<[f32; N]>,
-//     next_points: Vec<[f32; N]>,
-// }
+struct ScopePointBuffer<const N: usize> {
+    points: Vec<[f32; N]>,
+    next_points: Vec<[f32; N]>,
+}
+
+impl<const N: usize> ScopePointBuffer<N> {
+    fn new() -> Self {
+        Self {
+            points: Vec::with_capacity(OSCILLOSCOPE_FRAMES),
+            next_points: Vec::with_capacity(OSCILLOSCOPE_FRAMES),
+        }
+    }
+
+    /// Fills the next set of points from the buffer. Once full, they replace the
+    /// drawn points, and the next set starts from a rising zero crossing of the mix
+    /// so the waveform stays still.
+    fn update(
+        &mut self,
+        buffer: &mut VecDeque<SoundOutputChannels>,
+        frame: impl Fn(&SoundOutputChannels) -> [f32; N],
+    ) {
+        if self.next_points.len() == OSCILLOSCOPE_FRAMES {
+            self.points = std::mem::take(&mut self.next_points);
+
+            //Find the zero cross
+            while let (Some(prev), Some(next)) = (buffer.get(0), buffer.get(1)) {
+                let crossed = mix(prev) < 0.0 && mix(next) > 0.0;
+                buffer.pop_front();
+
+                if crossed {
+                    break;
+                }
+            }
+        }
+
+        (self.next_points.len()..OSCILLOSCOPE_FRAMES).for_each(|_| {
+            if let Some(next_frame) = buffer.pop_front() {
+                self.next_points.push(frame(&next_frame))
+            }
+        });
+    }
+
+    /// Returns the plot points of a single channel.
+    fn plot_points(&self, channel: usize) -> PlotPoints {
+        self.points
+            .iter()
+            .enumerate()
+            .map(|(index, val)| [index as f64, val[channel] as f64])
+            .collect()
+    }
+}
+
+fn mix(output: &SoundOutputChannels) -> f32 {
+    output.get_sfx_output() + output.get_bgm_output()
+}
+
+/// A plot which can't be moved or zoomed around.
+fn scope_plot(id: impl std::hash::Hash) -> Plot {
+    Plot::new(id)
+        .allow_boxed_zoom(false)
+        .allow_drag(false)
+        .allow_scroll(false)
+        .allow_zoom(false)
+        .show_axes([false, false])
+        .set_margin_fraction(Vec2::ZERO)
+}
+
+fn waveform_plot(id: impl std::hash::Hash, max_wave_height: f32) -> Plot {
+    scope_plot(id)
+        .include_x(OSCILLOSCOPE_FRAMES as f64)
+        .include_x(0)
+        .include_y(max_wave_height)
+        .include_y(-max_wave_height)
+}
 
 impl Oscilloscope {
-    pub(crate) fn new(channel_outputs: Consumer<SoundOutputChannels>) -> Self {
+    pub(crate) fn new(
+        channel_outputs: Consumer<SoundOutputChannels>,
+        output_sample_rate: usize,
+    ) -> Self {
         Self {
             open: false,
             buffer: VecDeque::with_capacity(BUFFER_LENGTH),
             mode: OscilloscopeMode::default(),
             channel_outputs,
-            points: Vec::with_capacity(OSCILLOSCOPE_FRAMES),
-            next_points: Vec::with_capacity(OSCILLOSCOPE_FRAMES),
+            master_points: ScopePointBuffer::new(),
+            channel_points: ScopePointBuffer::new(),
+            sfx_channels: SFX_CHANNELS,
+            spectrum: SpectrumAnalyzer::new(output_sample_rate),
+            vu_meter: VuMeter::new(output_sample_rate),
         }
     }
 
@@ -59,6 +148,8 @@ impl Oscilloscope {
         self.buffer.clear();
 
         while let Ok(frame) = self.channel_outputs.pop() {
+            self.sfx_channels = frame.sfx_channels;
+
             if self.buffer.len() < BUFFER_LENGTH {
                 self.buffer.push_back(frame);
             } else {
@@ -66,61 +157,95 @@ impl Oscilloscope {
             }
         }
 
-        if self.next_points.len() == OSCILLOSCOPE_FRAMES {
-            self.points = std::mem::take(&mut self.next_points);
-
-            //Find the zero cross
-            while let (Some(prev), Some(next)) = (self.buffer.get(0), self.buffer.get(1)) {
-                let prev = prev.get_sfx_output() + prev.get_bgm_output();
-                let next = next.get_sfx_output() + next.get_bgm_output();
-                if prev < 0.0 && next > 0.0 {
-                    self.next_points.push(next as f64);
-                    break;
-                } else {
-                    self.buffer.pop_front();
-                }
+        // Measured before the scope takes the frames it draws
+        self.vu_meter.update(self.buffer.iter());
+
+        match self.mode {
+            OscilloscopeMode::Off => (),
+            OscilloscopeMode::Master => self
+                .master_points
+                .update(&mut self.buffer, |frame| [mix(frame)]),
+            OscilloscopeMode::Channels => self.channel_points.update(&mut self.buffer, |frame| {
+                let mut channels = frame.sfx_output.iter().chain(frame.bgm_output.iter());
+                std::array::from_fn(|_| *channels.next().unwrap())
+            }),
+            OscilloscopeMode::Spectrum => {
+                self.spectrum.push_samples(self.buffer.iter().map(mix));
+                self.spectrum.update();
             }
         }
 
-        (self.next_points.len()..OSCILLOSCOPE_FRAMES).for_each(|_| {
-            if let Some(next_frame) = self.buffer.pop_front() {
-                let value = next_frame.get_sfx_output() + next_frame.get_bgm_output();
-                self.next_points.push(value as f64)
-            }
-        });
-
-        let max_wave_height = SFX_CHANNELS as f32;
-
         let ctx = ui.ctx();
         ctx.request_repaint();
+        self.vu_meter.draw(ctx);
+
+        let mode = &self.mode;
+        let master_points = &self.master_points;
+        let channel_points = &self.channel_points;
+        let spectrum = &mut self.spectrum;
+        let sfx_channels = self.sfx_channels;
 
         Window::new("Oscilloscope")
             .open(&mut self.open)
             .collapsible(false)
-            .show(ctx, |ui| {
-                let plot = Plot::new("oscilloscope")
-                    .allow_boxed_zoom(false)
-                    .allow_drag(false)
-                    .allow_scroll(false)
-                    .allow_zoom(false)
-                    .show_axes([false, false])
-                    .include_x(OSCILLOSCOPE_FRAMES as f64)
-                    .include_x(0)
-                    .include_y(max_wave_height)
-                    .include_y(-max_wave_height)
-                    .set_margin_fraction(Vec2::ZERO);
-
-                plot.show(ui, |plot_ui| {
-                    let points: PlotPoints = self
-                        .points
-                        .iter()
-                        .enumerate()
-                        .map(|(index, val)| [index as f64, *val])
-                        .collect();
-                    let line = Line::new(points).color(Color32::WHITE);
-
-                    plot_ui.line(line);
-                })
+            .show(ctx, |ui| match mode {
+                OscilloscopeMode::Off => (),
+                OscilloscopeMode::Master => {
+                    waveform_plot("oscilloscope", sfx_channels as f32).show(ui, |plot_ui| {
+                        let line = Line::new(master_points.plot_points(0)).color(Color32::WHITE);
+                        plot_ui.line(line);
+                    });
+                }
+                OscilloscopeMode::Channels => {
+                    Grid::new("oscilloscope_channels_grid").show(ui, |ui| {
+                        // Unused sfx channels are skipped over.
+                        let channels = (0..sfx_channels).chain(SFX_CHANNELS_MAX..TOTAL_CHANNELS);
+
+                        channels.enumerate().for_each(|(cell, channel)| {
+                            let name = if channel < SFX_CHANNELS_MAX {
+                                format!("Sfx {}", channel)
+                            } else {
+                                format!("Bgm {}", channel - SFX_CHANNELS_MAX)
+                            };
+
+                            ui.vertical(|ui| {
+                                ui.label(name);
+                                waveform_plot(("oscilloscope_channel", channel), 1.0)
+                                    .width(CHANNEL_PLOT_SIZE.x)
+                                    .height(CHANNEL_PLOT_SIZE.y)
+                                    .show(ui, |plot_ui| {
+                                        let line = Line::new(channel_points.plot_points(channel))
+                                            .color(Color32::WHITE);
+                                        plot_ui.line(line);
+                                    });
+                            });
+
+                            if (cell + 1) % CHANNEL_GRID_COLUMNS == 0 {
+                                ui.end_row();
+                            }
+                        });
+                    });
+                }
+                OscilloscopeMode::Spectrum => {
+                    ui.add(Checkbox::new(&mut spectrum.decibels, "Decibels"));
+
+                    let plot = scope_plot("spectrum")
+                        .include_x(SPECTRUM_MIN_FREQUENCY.log10())
+                        .include_x(spectrum.max_frequency().log10())
+                        .show_axes([true, false])
+                        .x_axis_formatter(|value, _| format!("{:.0}hz", 10.0f64.powf(value)));
+
+                    let plot = if spectrum.decibels {
+                        plot.include_y(SPECTRUM_DB_FLOOR).include_y(0.0)
+                    } else {
+                        plot.include_y(0.0).include_y(1.0)
+                    };
+
+                    plot.show(ui, |plot_ui| {
+                        let line = Line::new(spectrum.plot_points()).color(Color32::WHITE);
+                        plot_ui.line(line);
+                    });
+                }
             });
     }
 }
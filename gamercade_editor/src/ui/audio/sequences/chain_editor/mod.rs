@@ -1,8 +1,8 @@
 use eframe::egui::{Grid, InputState, Key, Slider, Ui};
 use gamercade_audio::{Chain, PhraseId, CHAIN_MAX_PHRASE_COUNT, DEFAULT_BPM};
-use gamercade_fs::EditorSoundData;
+use gamercade_fs::{EditorAudioDataEntry, EditorSoundData};
 
-use crate::ui::{AudioList, AudioSyncHelper};
+use crate::ui::{AudioList, AudioSyncHelper, SnapshotHistory, SnapshotTarget, Undoable};
 
 mod chain_list;
 mod chain_row;
@@ -20,6 +20,7 @@ pub(crate) struct ChainEditor {
 
     selected_index: usize,
     target_bpm: f32,
+    history: SnapshotHistory<Vec<EditorAudioDataEntry<Option<Chain>>>>,
 }
 
 impl Default for ChainEditor {
@@ -28,6 +29,7 @@ impl Default for ChainEditor {
             chain_list: Default::default(),
             selected_index: Default::default(),
             target_bpm: DEFAULT_BPM,
+            history: SnapshotHistory::default(),
         }
     }
 }
@@ -39,6 +41,8 @@ impl ChainEditor {
         data: &mut EditorSoundData,
         sync: &mut AudioSyncHelper,
     ) {
+        self.history.begin(data, 0);
+
         self.chain_list.draw(ui, data, sync);
 
         let selected_chain = &mut data.chains[self.chain_list.selected_chain];
@@ -50,7 +54,7 @@ impl ChainEditor {
         ui.add(Slider::new(&mut self.target_bpm, 0.0..=500.0));
 
         if ui.button("Play").clicked() || ui.input().key_pressed(Key::Space) {
-            sync.play_chain(self.chain_list.selected_chain, self.target_bpm);
+            sync.play_chain(self.chain_list.selected_chain, self.target_bpm, &data.sfx);
         }
 
         if ui.button("Stop").clicked() {
@@ -68,6 +72,8 @@ impl ChainEditor {
                 self.handle_input(&input)
             }
         };
+
+        self.history.end(ui, data, 0);
     }
 
     fn handle_shift_input(
@@ -182,3 +188,27 @@ impl ChainEditor {
             });
     }
 }
+
+impl SnapshotTarget<EditorSoundData> for Vec<EditorAudioDataEntry<Option<Chain>>> {
+    fn locate(data: &mut EditorSoundData, _: usize) -> Option<&mut Self> {
+        Some(&mut data.chains)
+    }
+}
+
+impl Undoable<EditorSoundData> for ChainEditor {
+    fn undo(&mut self, data: &mut EditorSoundData) -> bool {
+        let changed = self.history.undo(data);
+        self.chain_list.clamp_selection(data);
+        changed
+    }
+
+    fn redo(&mut self, data: &mut EditorSoundData) -> bool {
+        let changed = self.history.redo(data);
+        self.chain_list.clamp_selection(data);
+        changed
+    }
+
+    fn clear_history(&mut self) {
+        self.history.clear();
+    }
+}
@@ -16,3 +16,5 @@ use eframe::epaint::Color32;
 pub(crate) const DEFAULT_TEXT_COLOR: Color32 = Color32::GRAY;
 pub(crate) const SELECTED_BG_COLOR: Color32 = Color32::DARK_BLUE;
 pub(crate) const EDITING_BG_COLOR: Color32 = Color32::BLUE;
+pub(crate) const LOOP_BG_COLOR: Color32 = Color32::DARK_GREEN;
+pub(crate) const SELECTION_BG_COLOR: Color32 = Color32::from_rgb(0, 70, 90);
@@ -1,18 +1,22 @@
-use eframe::egui::{Grid, InputState, Key, Slider, Ui};
+use eframe::egui::{Event, Grid, InputState, Key, Slider, Ui};
 
-use gamercade_audio::{InstrumentId, NoteId, Phrase, PhraseEntry, DEFAULT_BPM, PHRASE_MAX_ENTRIES};
+use gamercade_audio::{
+    InstrumentId, NoteId, Phrase, PhraseEntry, DEFAULT_BPM, EFFECT_COUNT, PHRASE_MAX_ENTRIES,
+};
 
 use super::{
     HandleTrackerEditEntryCommand, TrackerEditCommand, TrackerEditEntryCommand,
     TrackerEditRowCommand, TRACKER_TEXT_FONT_SIZE,
 };
 
-use crate::ui::{AudioList, AudioSyncHelper};
-use gamercade_fs::EditorSoundData;
+use crate::ui::{AudioList, AudioSyncHelper, SnapshotHistory, SnapshotTarget, Undoable};
+use gamercade_fs::{EditorAudioDataEntry, EditorSoundData};
 
+mod phrase_clipboard;
 mod phrase_list;
 mod phrase_row;
 
+use phrase_clipboard::*;
 use phrase_list::*;
 use phrase_row::*;
 
@@ -21,7 +25,12 @@ pub(crate) struct PhraseEditor {
     phrase_list: PhraseList,
     selected_entry: SelectedEntry,
 
+    /// Where the selection started, which ends at the selected entry.
+    selection_anchor: Option<SelectedEntry>,
+    clipboard: PhraseClipboard,
+
     target_bpm: f32,
+    history: SnapshotHistory<Vec<EditorAudioDataEntry<Option<Phrase>>>>,
 }
 
 impl Default for PhraseEditor {
@@ -29,7 +38,10 @@ impl Default for PhraseEditor {
         Self {
             phrase_list: Default::default(),
             selected_entry: Default::default(),
+            selection_anchor: None,
+            clipboard: Default::default(),
             target_bpm: DEFAULT_BPM,
+            history: SnapshotHistory::default(),
         }
     }
 }
@@ -68,6 +80,7 @@ pub(crate) enum SelectedEntryMode {
     Note,
     Volume,
     Instrument,
+    Effect(usize),
 }
 
 impl SelectedEntryMode {
@@ -76,16 +89,20 @@ impl SelectedEntryMode {
             SelectedEntryMode::None => *self = SelectedEntryMode::Note,
             SelectedEntryMode::Note => *self = SelectedEntryMode::Volume,
             SelectedEntryMode::Volume => *self = SelectedEntryMode::Instrument,
-            SelectedEntryMode::Instrument => *self = SelectedEntryMode::Note,
+            SelectedEntryMode::Instrument => *self = SelectedEntryMode::Effect(0),
+            SelectedEntryMode::Effect(index) if *index + 1 < EFFECT_COUNT => *index += 1,
+            SelectedEntryMode::Effect(_) => *self = SelectedEntryMode::Note,
         }
     }
 
     fn left(&mut self) {
         match self {
             SelectedEntryMode::None => *self = SelectedEntryMode::Volume,
-            SelectedEntryMode::Note => *self = SelectedEntryMode::Instrument,
+            SelectedEntryMode::Note => *self = SelectedEntryMode::Effect(EFFECT_COUNT - 1),
             SelectedEntryMode::Volume => *self = SelectedEntryMode::Note,
             SelectedEntryMode::Instrument => *self = SelectedEntryMode::Volume,
+            SelectedEntryMode::Effect(0) => *self = SelectedEntryMode::Instrument,
+            SelectedEntryMode::Effect(index) => *index -= 1,
         }
     }
 }
@@ -99,6 +116,8 @@ impl PhraseEditor {
         data: &mut EditorSoundData,
         sync: &mut AudioSyncHelper,
     ) {
+        self.history.begin(data, 0);
+
         self.phrase_list.draw(ui, data, sync);
 
         let selected_phrase = &mut data.phrases[self.phrase_list.selected_phrase];
@@ -122,12 +141,63 @@ impl PhraseEditor {
 
             let input = ui.input();
 
+            self.handle_selection_input(&input, phrase, sync);
+
             if input.modifiers.shift {
                 self.handle_shift_input(&input, phrase, sync);
             } else {
                 self.handle_input(&input)
             }
         }
+
+        self.history.end(ui, data, 0);
+    }
+
+    /// The selected cells, or just the selected entry if nothing else is selected.
+    fn selection(&self) -> PhraseSelection {
+        PhraseSelection::new(
+            self.selection_anchor.unwrap_or(self.selected_entry),
+            self.selected_entry,
+        )
+    }
+
+    fn handle_selection_input(
+        &mut self,
+        input_state: &InputState,
+        phrase: &mut Phrase,
+        sync: &mut AudioSyncHelper,
+    ) {
+        let mut changed = false;
+
+        if input_state.key_pressed(Key::Escape) {
+            self.selection_anchor = None;
+        }
+
+        if input_state.modifiers.command {
+            if input_state.key_pressed(Key::C) {
+                self.clipboard.copy(phrase, self.selection());
+            } else if input_state.key_pressed(Key::X) {
+                changed = self.clipboard.cut(phrase, self.selection());
+            } else if input_state.key_pressed(Key::V) {
+                changed = self.clipboard.paste(phrase, self.selected_entry.index);
+            }
+        }
+
+        input_state.events.iter().for_each(|event| {
+            let semitones = match event {
+                Event::Text(text) if text == "+" => 1,
+                Event::Text(text) if text == "-" => -1,
+                Event::Text(text) if text == "*" => 12,
+                Event::Text(text) if text == "/" => -12,
+                _ => return,
+            };
+
+            changed |= transpose(phrase, self.selection(), semitones);
+        });
+
+        if changed {
+            sync.notify_rom_changed();
+        }
     }
 
     fn handle_shift_input(
@@ -143,6 +213,7 @@ impl PhraseEditor {
             SelectedEntryMode::Note => 12,
             SelectedEntryMode::Volume => 16,
             SelectedEntryMode::Instrument => 16,
+            SelectedEntryMode::Effect(_) => 16,
         };
 
         if input_state.key_pressed(Key::ArrowUp) {
@@ -165,6 +236,8 @@ impl PhraseEditor {
             command = Some(TrackerEditCommand::EditRow(
                 TrackerEditRowCommand::InsertOrDelete,
             ))
+        } else if input_state.key_pressed(Key::X) {
+            self.cycle_effect_kind(phrase, sync);
         };
 
         match command {
@@ -197,6 +270,10 @@ impl PhraseEditor {
                     phrase.instrument.handle_command(command);
                     true
                 }
+                SelectedEntryMode::Effect(index) => {
+                    phrase.effects[index].handle_command(command);
+                    true
+                }
             };
 
             if should_sync {
@@ -205,6 +282,19 @@ impl PhraseEditor {
         }
     }
 
+    /// Switches the selected effect to the next kind, keeping its value.
+    fn cycle_effect_kind(&self, phrase: &mut Phrase, sync: &mut AudioSyncHelper) {
+        if let (Some(entry), SelectedEntryMode::Effect(index)) = (
+            &mut phrase.entries[self.selected_entry.index],
+            self.selected_entry.mode,
+        ) {
+            if let Some(effect) = &mut entry.effects[index] {
+                *effect = effect.next_kind();
+                sync.notify_rom_changed();
+            }
+        }
+    }
+
     fn handle_edit_row(
         &self,
         command: TrackerEditRowCommand,
@@ -263,8 +353,17 @@ impl PhraseEditor {
                     .enumerate()
                     .for_each(|(row, entry)| {
                         ui.horizontal_centered(|ui| {
-                            let phrase_row = PhraseRow::new(row, entry, self.selected_entry);
+                            let selection = self.selection_anchor.map(|_| self.selection());
+                            let phrase_row =
+                                PhraseRow::new(row, entry, self.selected_entry, selection);
                             if let Some(selected) = phrase_row.draw(ui) {
+                                // Shift clicking extends the selection from the current entry
+                                if ui.input().modifiers.shift {
+                                    self.selection_anchor.get_or_insert(self.selected_entry);
+                                } else {
+                                    self.selection_anchor = None;
+                                }
+
                                 self.selected_entry.index = row;
                                 self.selected_entry.mode = selected;
                             }
@@ -274,3 +373,27 @@ impl PhraseEditor {
             });
     }
 }
+
+impl SnapshotTarget<EditorSoundData> for Vec<EditorAudioDataEntry<Option<Phrase>>> {
+    fn locate(data: &mut EditorSoundData, _: usize) -> Option<&mut Self> {
+        Some(&mut data.phrases)
+    }
+}
+
+impl Undoable<EditorSoundData> for PhraseEditor {
+    fn undo(&mut self, data: &mut EditorSoundData) -> bool {
+        let changed = self.history.undo(data);
+        self.phrase_list.clamp_selection(data);
+        changed
+    }
+
+    fn redo(&mut self, data: &mut EditorSoundData) -> bool {
+        let changed = self.history.redo(data);
+        self.phrase_list.clamp_selection(data);
+        changed
+    }
+
+    fn clear_history(&mut self) {
+        self.history.clear();
+    }
+}
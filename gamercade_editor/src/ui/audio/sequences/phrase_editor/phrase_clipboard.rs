@@ -0,0 +1,284 @@
+use gamercade_audio::{NoteId, Phrase, EFFECT_COUNT, PHRASE_MAX_ENTRIES, TOTAL_NOTES_COUNT};
+
+use super::{PhraseEntryType, SelectedEntry, SelectedEntryMode};
+
+/// The note, volume and instrument columns, followed by each effect.
+const COLUMN_COUNT: usize = 3 + EFFECT_COUNT;
+const NOTE_COLUMN: usize = 0;
+const VOLUME_COLUMN: usize = 1;
+const INSTRUMENT_COLUMN: usize = 2;
+const FIRST_EFFECT_COLUMN: usize = 3;
+
+/// Returns the columns covered by the mode. Selecting the row number covers every column.
+fn columns(mode: SelectedEntryMode) -> (usize, usize) {
+    match mode {
+        SelectedEntryMode::None => (0, COLUMN_COUNT - 1),
+        SelectedEntryMode::Note => (NOTE_COLUMN, NOTE_COLUMN),
+        SelectedEntryMode::Volume => (VOLUME_COLUMN, VOLUME_COLUMN),
+        SelectedEntryMode::Instrument => (INSTRUMENT_COLUMN, INSTRUMENT_COLUMN),
+        SelectedEntryMode::Effect(index) => {
+            (FIRST_EFFECT_COLUMN + index, FIRST_EFFECT_COLUMN + index)
+        }
+    }
+}
+
+/// A rectangle of cells within a phrase.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct PhraseSelection {
+    first_row: usize,
+    last_row: usize,
+    first_column: usize,
+    last_column: usize,
+}
+
+impl PhraseSelection {
+    /// Selects every cell between the two entries, including them.
+    pub(super) fn new(anchor: SelectedEntry, cursor: SelectedEntry) -> Self {
+        let anchor_columns = columns(anchor.mode);
+        let cursor_columns = columns(cursor.mode);
+
+        Self {
+            first_row: anchor.index.min(cursor.index),
+            last_row: anchor.index.max(cursor.index),
+            first_column: anchor_columns.0.min(cursor_columns.0),
+            last_column: anchor_columns.1.max(cursor_columns.1),
+        }
+    }
+
+    pub(super) fn contains(&self, row: usize, mode: SelectedEntryMode) -> bool {
+        let (first_column, last_column) = columns(mode);
+
+        (self.first_row..=self.last_row).contains(&row)
+            && first_column <= self.last_column
+            && last_column >= self.first_column
+    }
+
+    fn contains_column(&self, column: usize) -> bool {
+        (self.first_column..=self.last_column).contains(&column)
+    }
+
+    fn rows(&self) -> impl Iterator<Item = usize> {
+        self.first_row..=self.last_row.min(PHRASE_MAX_ENTRIES - 1)
+    }
+}
+
+/// Cells which have been copied or cut from a phrase.
+#[derive(Debug, Default)]
+pub(super) struct PhraseClipboard {
+    selection: Option<PhraseSelection>,
+    rows: Vec<Option<PhraseEntryType>>,
+}
+
+impl PhraseClipboard {
+    pub(super) fn copy(&mut self, phrase: &Phrase, selection: PhraseSelection) {
+        self.selection = Some(selection);
+        self.rows = selection
+            .rows()
+            .map(|row| phrase.entries[row].clone())
+            .collect();
+    }
+
+    /// Pastes the copied cells into the same columns, starting from the row.
+    /// Anything past the end of the phrase is dropped, and empty cells don't
+    /// overwrite what is already there. Cells without a note are only pasted
+    /// into rows which already have one. Returns true if the phrase changed.
+    pub(super) fn paste(&self, phrase: &mut Phrase, row: usize) -> bool {
+        let selection = match &self.selection {
+            Some(selection) => selection,
+            None => return false,
+        };
+
+        let mut changed = false;
+
+        self.rows
+            .iter()
+            .zip(phrase.entries.iter_mut().skip(row))
+            .for_each(|(source, target)| {
+                let source = match source {
+                    Some(source) => source,
+                    None => return,
+                };
+
+                if target.is_none() && selection.contains_column(NOTE_COLUMN) {
+                    *target = Some(PhraseEntryType::default());
+                }
+
+                if let Some(target) = target {
+                    if selection.contains_column(NOTE_COLUMN) {
+                        target.note = source.note;
+                    }
+                    if selection.contains_column(VOLUME_COLUMN) {
+                        target.volume = source.volume;
+                    }
+                    if selection.contains_column(INSTRUMENT_COLUMN) {
+                        target.instrument = source.instrument;
+                    }
+
+                    target
+                        .effects
+                        .iter_mut()
+                        .zip(source.effects.iter())
+                        .enumerate()
+                        .for_each(|(index, (target, source))| {
+                            if selection.contains_column(FIRST_EFFECT_COLUMN + index)
+                                && source.is_some()
+                            {
+                                *target = *source;
+                            }
+                        });
+
+                    changed = true;
+                }
+            });
+
+        changed
+    }
+
+    /// Copies the selection, then clears it. Rows with their note selected are
+    /// removed entirely, otherwise only the selected effects are cleared.
+    /// Returns true if the phrase changed.
+    pub(super) fn cut(&mut self, phrase: &mut Phrase, selection: PhraseSelection) -> bool {
+        self.copy(phrase, selection);
+
+        let mut changed = false;
+
+        selection.rows().for_each(|row| {
+            let entry = &mut phrase.entries[row];
+
+            if selection.contains_column(NOTE_COLUMN) {
+                changed |= entry.take().is_some();
+            } else if let Some(entry) = entry {
+                entry
+                    .effects
+                    .iter_mut()
+                    .enumerate()
+                    .filter(|(index, _)| selection.contains_column(FIRST_EFFECT_COLUMN + index))
+                    .for_each(|(_, effect)| changed |= effect.take().is_some());
+            }
+        });
+
+        changed
+    }
+}
+
+/// Shifts the selected notes up or down by semitones, stopping at the
+/// lowest and highest notes. Returns true if the phrase changed.
+pub(super) fn transpose(phrase: &mut Phrase, selection: PhraseSelection, semitones: i32) -> bool {
+    if !selection.contains_column(NOTE_COLUMN) {
+        return false;
+    }
+
+    let mut changed = false;
+
+    selection.rows().for_each(|row| {
+        if let Some(entry) = &mut phrase.entries[row] {
+            let note = (entry.note.0 as i32 + semitones).clamp(0, TOTAL_NOTES_COUNT as i32 - 1);
+            changed |= note as usize != entry.note.0;
+            entry.note = NoteId(note as usize);
+        }
+    });
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use gamercade_audio::{Effect, InstrumentId};
+
+    use super::*;
+
+    fn entry(note: usize) -> Option<PhraseEntryType> {
+        Some(PhraseEntryType {
+            note: NoteId(note),
+            volume: 8,
+            instrument: InstrumentId(0),
+            effects: [Some(Effect::Glide { time: 2 }), None, None],
+        })
+    }
+
+    /// A phrase with a note on each of the first four rows.
+    fn phrase() -> Phrase {
+        let mut phrase = Phrase::default();
+        (0..4).for_each(|row| phrase.entries[row] = entry(10 + row));
+        phrase
+    }
+
+    fn select(first_row: usize, last_row: usize, mode: SelectedEntryMode) -> PhraseSelection {
+        PhraseSelection::new(
+            SelectedEntry {
+                index: first_row,
+                mode,
+            },
+            SelectedEntry {
+                index: last_row,
+                mode,
+            },
+        )
+    }
+
+    #[test]
+    fn test_paste_past_the_end_is_clipped() {
+        let mut phrase = phrase();
+        let mut clipboard = PhraseClipboard::default();
+        clipboard.copy(&phrase, select(0, 3, SelectedEntryMode::None));
+
+        assert!(clipboard.paste(&mut phrase, PHRASE_MAX_ENTRIES - 2));
+
+        assert_eq!(phrase.entries.len(), PHRASE_MAX_ENTRIES);
+        assert_eq!(phrase.entries[PHRASE_MAX_ENTRIES - 2], entry(10));
+        assert_eq!(phrase.entries[PHRASE_MAX_ENTRIES - 1], entry(11));
+        assert_eq!(phrase.entries[PHRASE_MAX_ENTRIES - 3], None);
+        assert_eq!(phrase.entries[0], entry(10));
+    }
+
+    #[test]
+    fn test_cut_leaves_empty_entries() {
+        let mut phrase = phrase();
+        let mut clipboard = PhraseClipboard::default();
+
+        assert!(clipboard.cut(&mut phrase, select(1, 2, SelectedEntryMode::Note)));
+        assert_eq!(phrase.entries[0], entry(10));
+        assert_eq!(phrase.entries[1], None);
+        assert_eq!(phrase.entries[2], None);
+        assert_eq!(phrase.entries[3], entry(13));
+
+        // Pasting puts the cut notes back.
+        assert!(clipboard.paste(&mut phrase, 1));
+        assert_eq!(phrase.entries[1].as_ref().unwrap().note, NoteId(11));
+        assert_eq!(phrase.entries[2].as_ref().unwrap().note, NoteId(12));
+
+        // Cutting only an effect clears it, but keeps the row.
+        assert!(clipboard.cut(&mut phrase, select(3, 3, SelectedEntryMode::Effect(0))));
+        let row = phrase.entries[3].as_ref().unwrap();
+        assert_eq!(row.note, NoteId(13));
+        assert_eq!(row.effects, [None, None, None]);
+    }
+
+    #[test]
+    fn test_transpose_clamps_to_note_range() {
+        let highest = TOTAL_NOTES_COUNT - 1;
+        let mut phrase = Phrase::default();
+        phrase.entries[0] = entry(2);
+        phrase.entries[1] = entry(highest - 2);
+        let selection = select(0, 1, SelectedEntryMode::Note);
+
+        assert!(transpose(&mut phrase, selection, 5));
+        assert_eq!(phrase.entries[0].as_ref().unwrap().note, NoteId(7));
+        assert_eq!(phrase.entries[1].as_ref().unwrap().note, NoteId(highest));
+
+        assert!(transpose(&mut phrase, selection, -10));
+        assert_eq!(phrase.entries[0].as_ref().unwrap().note, NoteId(0));
+        assert_eq!(
+            phrase.entries[1].as_ref().unwrap().note,
+            NoteId(highest - 10)
+        );
+
+        // Already at the bottom, so nothing changes.
+        let bottom = select(0, 0, SelectedEntryMode::Note);
+        assert!(!transpose(&mut phrase, bottom, -1));
+
+        // Notes can only be transposed when their column is selected.
+        let volume = select(0, 1, SelectedEntryMode::Volume);
+        assert!(!transpose(&mut phrase, volume, 1));
+    }
+}
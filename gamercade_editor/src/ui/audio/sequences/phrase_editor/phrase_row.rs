@@ -1,16 +1,18 @@
 use eframe::{egui::Ui, epaint::Color32};
+use gamercade_audio::EFFECT_COUNT;
 
 use crate::ui::audio::sequences::{
-    TrackerText, DEFAULT_TEXT_COLOR, EDITING_BG_COLOR, SELECTED_BG_COLOR,
+    TrackerText, DEFAULT_TEXT_COLOR, EDITING_BG_COLOR, SELECTED_BG_COLOR, SELECTION_BG_COLOR,
 };
 
-use super::{PhraseEntryType, SelectedEntry, SelectedEntryMode};
+use super::{PhraseEntryType, PhraseSelection, SelectedEntry, SelectedEntryMode};
 
 pub(super) struct PhraseRow {
     row_index: TrackerText<3>,
     note: TrackerText<3>,
     volume: TrackerText<2>,
     instrument: TrackerText<2>,
+    effects: [TrackerText<3>; EFFECT_COUNT],
     separator: TrackerText<2>,
 }
 
@@ -21,6 +23,9 @@ impl PhraseRow {
             note: TrackerText::new("N  ", Color32::GRAY, None),
             volume: TrackerText::new("V ", Color32::GRAY, None),
             instrument: TrackerText::new("I ", Color32::GRAY, None),
+            effects: std::array::from_fn(|index| {
+                TrackerText::new(&format!("E{} ", index), Color32::GRAY, None)
+            }),
             separator: TrackerText::separator(None),
         }
     }
@@ -29,6 +34,7 @@ impl PhraseRow {
         row: usize,
         entry: &Option<PhraseEntryType>,
         selected: SelectedEntry,
+        selection: Option<PhraseSelection>,
     ) -> Self {
         let bg_color = if selected.index == row {
             Some(SELECTED_BG_COLOR)
@@ -36,6 +42,16 @@ impl PhraseRow {
             None
         };
 
+        let cell_color = |mode| {
+            if selected.mode == mode && bg_color.is_some() {
+                Some(EDITING_BG_COLOR)
+            } else if selection.is_some_and(|selection| selection.contains(row, mode)) {
+                Some(SELECTION_BG_COLOR)
+            } else {
+                bg_color
+            }
+        };
+
         let row = TrackerText::new(&format!("{:X}:", row), DEFAULT_TEXT_COLOR, bg_color);
         let separator = TrackerText::separator(bg_color);
 
@@ -45,45 +61,46 @@ impl PhraseRow {
                 note: TrackerText::new(
                     &gamercade_audio::get_note(entry.note).name,
                     DEFAULT_TEXT_COLOR,
-                    if selected.mode == SelectedEntryMode::Note && bg_color.is_some() {
-                        Some(EDITING_BG_COLOR)
-                    } else {
-                        bg_color
-                    },
+                    cell_color(SelectedEntryMode::Note),
                 ),
                 volume: TrackerText::new(
                     &format!("{:02X}", entry.volume),
                     DEFAULT_TEXT_COLOR,
-                    if selected.mode == SelectedEntryMode::Volume && bg_color.is_some() {
-                        Some(EDITING_BG_COLOR)
-                    } else {
-                        bg_color
-                    },
+                    cell_color(SelectedEntryMode::Volume),
                 ),
                 instrument: TrackerText::new(
                     &format!("{:02X}", entry.instrument.0),
                     DEFAULT_TEXT_COLOR,
-                    if selected.mode == SelectedEntryMode::Instrument && bg_color.is_some() {
-                        Some(EDITING_BG_COLOR)
-                    } else {
-                        bg_color
-                    },
+                    cell_color(SelectedEntryMode::Instrument),
                 ),
+                effects: std::array::from_fn(|index| {
+                    let bg_color = cell_color(SelectedEntryMode::Effect(index));
+
+                    match &entry.effects[index] {
+                        Some(effect) => {
+                            TrackerText::new(&effect.to_string(), DEFAULT_TEXT_COLOR, bg_color)
+                        }
+                        None => TrackerText::new_empty(bg_color),
+                    }
+                }),
                 separator,
             }
         } else {
             Self {
                 row_index: row,
-                note: TrackerText::new_empty(bg_color),
-                volume: TrackerText::new_empty(bg_color),
-                instrument: TrackerText::new_empty(bg_color),
+                note: TrackerText::new_empty(cell_color(SelectedEntryMode::Note)),
+                volume: TrackerText::new_empty(cell_color(SelectedEntryMode::Volume)),
+                instrument: TrackerText::new_empty(cell_color(SelectedEntryMode::Instrument)),
+                effects: std::array::from_fn(|index| {
+                    TrackerText::new_empty(cell_color(SelectedEntryMode::Effect(index)))
+                }),
                 separator,
             }
         }
     }
 
     pub(crate) fn draw(&self, ui: &mut Ui) -> Option<SelectedEntryMode> {
-        let results = [
+        let mut results = vec![
             self.row_index.draw(ui),
             self.separator.draw(ui),
             self.note.draw(ui),
@@ -93,11 +110,19 @@ impl PhraseRow {
             self.instrument.draw(ui),
         ];
 
+        self.effects.iter().for_each(|effect| {
+            results.push(self.separator.draw(ui));
+            results.push(effect.draw(ui));
+        });
+
         if let Some((index, _)) = results.into_iter().enumerate().find(|(_, result)| *result) {
             match index {
                 2 => Some(SelectedEntryMode::Note),
                 4 => Some(SelectedEntryMode::Volume),
                 6 => Some(SelectedEntryMode::Instrument),
+                index if index > 6 && index % 2 == 0 => {
+                    Some(SelectedEntryMode::Effect((index - 8) / 2))
+                }
                 _ => Some(SelectedEntryMode::None),
             }
         } else {
@@ -1,7 +1,8 @@
 use eframe::egui::{Key, Slider, Ui};
-use gamercade_fs::EditorSoundData;
+use gamercade_audio::Sfx;
+use gamercade_fs::{EditorAudioDataEntry, EditorSoundData};
 
-use crate::ui::{AudioList, AudioSyncHelper};
+use crate::ui::{AudioList, AudioSyncHelper, SnapshotHistory, SnapshotTarget, Undoable};
 
 mod sfx_list;
 
@@ -10,6 +11,8 @@ use sfx_list::*;
 #[derive(Default)]
 pub(crate) struct SfxEditor {
     sfx_list: SfxList,
+    preview_pan: f32,
+    history: SnapshotHistory<Vec<EditorAudioDataEntry<Sfx>>>,
 }
 
 // TODO:
@@ -24,6 +27,8 @@ impl SfxEditor {
         data: &mut EditorSoundData,
         sync: &mut AudioSyncHelper,
     ) {
+        self.history.begin(data, 0);
+
         self.sfx_list.draw(ui, data, sync);
 
         if let Some(selected_sfx) = data.sfx.get_mut(self.sfx_list.selected_sfx) {
@@ -45,6 +50,14 @@ impl SfxEditor {
                 sync.notify_rom_changed()
             }
 
+            ui.label("Gain: ");
+            if ui
+                .add(Slider::new(&mut selected_sfx.data.gain, 0.0..=2.0))
+                .changed()
+            {
+                sync.notify_rom_changed()
+            }
+
             let length_text = match data
                 .chains
                 .get(selected_sfx.data.chain.0)
@@ -64,8 +77,41 @@ impl SfxEditor {
             if ui.button("Stop").clicked() {
                 sync.stop_sfx();
             }
+
+            if ui
+                .add(Slider::new(&mut self.preview_pan, -1.0..=1.0).text("Preview Pan"))
+                .changed()
+            {
+                sync.set_pan(0, self.preview_pan);
+            }
         } else {
             ui.label("No Sfx exist! Please create one.");
         }
+
+        self.history.end(ui, data, 0);
+    }
+}
+
+impl SnapshotTarget<EditorSoundData> for Vec<EditorAudioDataEntry<Sfx>> {
+    fn locate(data: &mut EditorSoundData, _: usize) -> Option<&mut Self> {
+        Some(&mut data.sfx)
+    }
+}
+
+impl Undoable<EditorSoundData> for SfxEditor {
+    fn undo(&mut self, data: &mut EditorSoundData) -> bool {
+        let changed = self.history.undo(data);
+        self.sfx_list.clamp_selection(data);
+        changed
+    }
+
+    fn redo(&mut self, data: &mut EditorSoundData) -> bool {
+        let changed = self.history.redo(data);
+        self.sfx_list.clamp_selection(data);
+        changed
+    }
+
+    fn clear_history(&mut self) {
+        self.history.clear();
     }
 }
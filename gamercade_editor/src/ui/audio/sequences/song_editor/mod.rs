@@ -1,14 +1,20 @@
 use eframe::egui::{Grid, InputState, Key, Slider, Ui};
 
 mod song_list;
+mod song_package_import;
+mod song_render_export;
 mod song_row;
-use gamercade_audio::{Chain, ChainId, Song, PHRASE_STEPS_PER_BEAT, SONG_TRACK_CHANNELS};
+use gamercade_audio::{
+    Chain, ChainId, Song, PHRASE_STEPS_PER_BEAT, SONG_TRACK_CHANNELS, SWING_MAX,
+};
 use song_list::*;
+use song_package_import::*;
+use song_render_export::*;
 use song_row::*;
 
 use gamercade_fs::{EditorAudioDataEntry, EditorSoundData};
 
-use crate::ui::{AudioList, AudioSyncHelper};
+use crate::ui::{AudioList, AudioSyncHelper, SnapshotHistory, SnapshotTarget, Undoable};
 
 use super::{
     HandleTrackerEditEntryCommand, TrackerEditCommand, TrackerEditEntryCommand,
@@ -19,6 +25,15 @@ use super::{
 pub(crate) struct SongEditor {
     song_list: SongList,
     selected_entry: SelectedEntry,
+    package_import: Option<SongPackageImport>,
+    render_export: Option<SongRenderTask>,
+
+    /// The rows to repeat during playback. This only affects
+    /// the editor, so it isn't saved into the song.
+    loop_enabled: bool,
+    loop_region: Option<(usize, usize)>,
+    loop_drag_start: Option<usize>,
+    history: SnapshotHistory<Vec<EditorAudioDataEntry<Song>>>,
 }
 
 #[derive(Default, Clone, Debug)]
@@ -34,8 +49,38 @@ impl SongEditor {
         data: &mut EditorSoundData,
         sync: &mut AudioSyncHelper,
     ) {
+        self.history.begin(data, 0);
+
         self.song_list.draw(ui, data, sync);
 
+        ui.horizontal(|ui| {
+            if ui.button("Export song package...").clicked() {
+                if let Err(e) = try_export_song_package(data, self.song_list.selected_song) {
+                    println!("{}", e);
+                }
+            }
+
+            if ui.button("Import song package...").clicked() {
+                match SongPackageImport::try_open(data) {
+                    Ok(Some(import)) => self.package_import = Some(import),
+                    Ok(None) => (),
+                    Err(e) => println!("{}", e),
+                }
+            }
+
+            if ui.button("Export Song .wav...").clicked() && self.render_export.is_none() {
+                self.render_export = SongRenderTask::try_spawn(data, self.song_list.selected_song);
+            }
+        });
+
+        if let Some(Err(e)) = SongRenderTask::draw(&mut self.render_export, ui) {
+            println!("{}", e);
+        }
+
+        if let Some(song) = SongPackageImport::draw(&mut self.package_import, ui, data, sync) {
+            self.song_list.selected_song = song;
+        }
+
         if let Some(song) = data.songs.get_mut(self.song_list.selected_song) {
             ui.label("Song Name: ");
             ui.text_edit_singleline(&mut song.name);
@@ -46,20 +91,47 @@ impl SongEditor {
                 sync.notify_rom_changed();
             }
 
+            if ui
+                .add(Slider::new(&mut song.gain, 0.0..=2.0).text("Gain"))
+                .changed()
+            {
+                sync.notify_rom_changed();
+            }
+
+            if ui
+                .add(
+                    Slider::new(&mut song.swing, 0.0..=SWING_MAX)
+                        .text("Swing")
+                        .custom_formatter(|value, _| format!("{:.0}%", value * 100.0)),
+                )
+                .changed()
+            {
+                sync.notify_rom_changed();
+            }
+
             ui.label(format!(
                 "Song Length (secs): {}",
                 song_length_seconds(song, &data.chains)
             ));
 
-            if ui.button("Play").clicked() || ui.input().key_pressed(Key::Space) {
-                sync.play_bgm(self.song_list.selected_song);
-            }
+            ui.horizontal(|ui| {
+                if ui.button("Play").clicked() || ui.input().key_pressed(Key::Space) {
+                    sync.set_bgm_loop(self.active_loop_region(song));
+                    sync.play_bgm(self.song_list.selected_song);
+                }
 
-            if ui.button("Stop").clicked() {
-                sync.stop_bgm();
-            }
+                if ui.button("Stop").clicked() {
+                    sync.stop_bgm();
+                }
 
-            self.song_editor_inner(ui, song);
+                if ui.toggle_value(&mut self.loop_enabled, "Loop").changed() {
+                    sync.set_bgm_loop(self.active_loop_region(song));
+                }
+
+                ui.label("Drag over the row numbers to choose which rows loop.");
+            });
+
+            self.song_editor_inner(ui, song, sync);
 
             let tracks = &mut song.tracks;
 
@@ -97,9 +169,25 @@ impl SongEditor {
         } else {
             ui.label("No Songs exist! Please create one.");
         }
+
+        self.history.end(ui, data, 0);
+    }
+
+    /// The rows to loop, or the whole song if looping without a region.
+    fn active_loop_region(&self, song: &Song) -> Option<(usize, usize)> {
+        if self.loop_enabled {
+            Some(
+                self.loop_region
+                    .unwrap_or((0, song.tracks.len().saturating_sub(1))),
+            )
+        } else {
+            None
+        }
     }
 
-    fn song_editor_inner(&mut self, ui: &mut Ui, song: &mut Song) {
+    fn song_editor_inner(&mut self, ui: &mut Ui, song: &mut Song, sync: &mut AudioSyncHelper) {
+        let pointer = ui.input().pointer.interact_pos();
+
         Grid::new("song_editor_grid")
             .min_row_height(TRACKER_TEXT_FONT_SIZE)
             .striped(true)
@@ -116,8 +204,25 @@ impl SongEditor {
                 // Draw the individual entries
                 song.tracks.iter_mut().enumerate().for_each(|(row, entry)| {
                     ui.horizontal_centered(|ui| {
-                        let song_row = SongRow::new(row, entry, self.selected_entry.clone());
-                        match song_row.draw(ui) {
+                        let in_loop = self
+                            .loop_region
+                            .is_some_and(|(start, end)| row >= start && row <= end);
+                        let song_row =
+                            SongRow::new(row, entry, self.selected_entry.clone(), in_loop);
+                        let response = song_row.draw(ui);
+
+                        if response.row_index.drag_started() {
+                            self.loop_drag_start = Some(row);
+                        }
+
+                        // Extends the region to whichever row is under the pointer
+                        if let (Some(start), Some(pointer)) = (self.loop_drag_start, pointer) {
+                            if response.row_index.rect.y_range().contains(&pointer.y) {
+                                self.loop_region = Some((start.min(row), start.max(row)));
+                            }
+                        }
+
+                        match response.selected {
                             Some(Some(channel)) => {
                                 self.selected_entry.selected_row = row;
                                 self.selected_entry.selected_channel = Some(channel);
@@ -132,6 +237,12 @@ impl SongEditor {
                     ui.end_row();
                 });
             });
+
+        if self.loop_drag_start.is_some() && !ui.input().pointer.primary_down() {
+            self.loop_drag_start = None;
+            self.loop_enabled = true;
+            sync.set_bgm_loop(self.active_loop_region(song));
+        }
     }
 
     fn handle_shift_input(
@@ -270,3 +381,27 @@ fn song_length_seconds(song: &Song, chains: &[EditorAudioDataEntry<Option<Chain>
 
     sum
 }
+
+impl SnapshotTarget<EditorSoundData> for Vec<EditorAudioDataEntry<Song>> {
+    fn locate(data: &mut EditorSoundData, _: usize) -> Option<&mut Self> {
+        Some(&mut data.songs)
+    }
+}
+
+impl Undoable<EditorSoundData> for SongEditor {
+    fn undo(&mut self, data: &mut EditorSoundData) -> bool {
+        let changed = self.history.undo(data);
+        self.song_list.clamp_selection(data);
+        changed
+    }
+
+    fn redo(&mut self, data: &mut EditorSoundData) -> bool {
+        let changed = self.history.redo(data);
+        self.song_list.clamp_selection(data);
+        changed
+    }
+
+    fn clear_history(&mut self) {
+        self.history.clear();
+    }
+}
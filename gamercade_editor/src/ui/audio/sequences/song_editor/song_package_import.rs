@@ -0,0 +1,136 @@
+use std::path::PathBuf;
+
+use eframe::egui::{Grid, Ui, Window};
+use rfd::FileDialog;
+
+use gamercade_fs::{EditorSoundData, InstrumentImport, SongPackage};
+
+use crate::ui::AudioSyncHelper;
+
+const SONG_PACKAGE_EXTENSION: &str = "gcsong";
+
+/// A loaded song package waiting for the user to choose
+/// how its instruments should be brought in.
+pub(super) struct SongPackageImport {
+    package: SongPackage,
+    matches: Vec<Option<usize>>,
+    imports: Vec<InstrumentImport>,
+}
+
+impl SongPackageImport {
+    pub(super) fn try_open(data: &EditorSoundData) -> Result<Option<Self>, String> {
+        let path = match pick_package_file() {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        let package = SongPackage::try_load(&path)?;
+        let matches = package.matching_instruments(data);
+        let imports = package.default_instrument_imports(data);
+
+        Ok(Some(Self {
+            package,
+            matches,
+            imports,
+        }))
+    }
+
+    /// Draws the import window. Returns the index of the imported song
+    /// once it has been merged, and clears the import when finished.
+    pub(super) fn draw(
+        import: &mut Option<Self>,
+        ui: &mut Ui,
+        data: &mut EditorSoundData,
+        sync: &mut AudioSyncHelper,
+    ) -> Option<usize> {
+        let mut finished = false;
+        let mut imported_song = None;
+
+        if let Some(inner) = import {
+            Window::new("Import Song Package")
+                .collapsible(false)
+                .show(ui.ctx(), |ui| {
+                    ui.label(format!("Song: {}", inner.package.song.name));
+                    ui.label(format!(
+                        "{} chains, {} phrases, {} instruments",
+                        inner.package.chains.len(),
+                        inner.package.phrases.len(),
+                        inner.package.instruments.len()
+                    ));
+
+                    Grid::new("song_package_instruments").show(ui, |ui| {
+                        inner
+                            .package
+                            .instruments
+                            .iter()
+                            .zip(inner.matches.iter())
+                            .zip(inner.imports.iter_mut())
+                            .for_each(|((instrument, existing), import)| {
+                                ui.label(&instrument.name);
+                                match existing {
+                                    Some(existing) => {
+                                        ui.radio_value(
+                                            import,
+                                            InstrumentImport::Reuse(*existing),
+                                            format!("Reuse {}", data.instruments[*existing].name),
+                                        );
+                                        ui.radio_value(import, InstrumentImport::Copy, "Copy");
+                                    }
+                                    None => {
+                                        ui.label("New");
+                                    }
+                                }
+                                ui.end_row();
+                            });
+                    });
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Import").clicked() {
+                            match inner.package.import(data, &inner.imports) {
+                                Ok(song) => {
+                                    sync.notify_rom_changed();
+                                    imported_song = Some(song);
+                                }
+                                Err(e) => println!("{}", e),
+                            }
+                            finished = true;
+                        }
+
+                        if ui.button("Cancel").clicked() {
+                            finished = true;
+                        }
+                    });
+                });
+        }
+
+        if finished {
+            *import = None;
+        }
+
+        imported_song
+    }
+}
+
+pub(super) fn try_export_song_package(
+    data: &EditorSoundData,
+    song_index: usize,
+) -> Result<(), String> {
+    let package = SongPackage::export(data, song_index)?;
+
+    if let Some(path) = FileDialog::new()
+        .add_filter("song package (.gcsong)", &[SONG_PACKAGE_EXTENSION])
+        .set_title("Export Song Package")
+        .save_file()
+    {
+        package.try_save(&path)?;
+    }
+
+    Ok(())
+}
+
+fn pick_package_file() -> Option<PathBuf> {
+    FileDialog::new()
+        .add_filter("song package (.gcsong)", &[SONG_PACKAGE_EXTENSION])
+        .set_title("Import Song Package")
+        .pick_file()
+}
@@ -0,0 +1,96 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use eframe::egui::{ProgressBar, Ui, Window};
+use rfd::FileDialog;
+
+use gamercade_audio::SongId;
+use gamercade_core::FrameRate;
+use gamercade_fs::EditorSoundData;
+use gamercade_sound_engine::{OfflineSongRender, SoundRomInstance};
+
+/// The sample rate songs are exported at.
+const EXPORT_SAMPLE_RATE: usize = 44_100;
+
+/// A song being rendered to a .wav on a background thread.
+pub(crate) struct SongRenderTask {
+    progress: Arc<AtomicU32>,
+    cancelled: Arc<AtomicBool>,
+    handle: JoinHandle<Result<(), String>>,
+}
+
+impl SongRenderTask {
+    /// Asks where to save the song, then starts rendering it.
+    pub(crate) fn try_spawn(data: &EditorSoundData, song_index: usize) -> Option<Self> {
+        data.songs.get(song_index)?;
+
+        let path = FileDialog::new()
+            .add_filter("wave (.wav)", &["wav"])
+            .set_title("Export Song")
+            .save_file()?;
+
+        let rom = Arc::new(SoundRomInstance::from(data));
+        let progress = Arc::new(AtomicU32::new(0.0f32.to_bits()));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let thread_progress = progress.clone();
+        let thread_cancelled = cancelled.clone();
+        let handle = thread::spawn(move || {
+            // Rendered at the same frame rate the editor plays songs at
+            let render = OfflineSongRender::new(
+                &rom,
+                SongId(song_index),
+                EXPORT_SAMPLE_RATE,
+                FrameRate::default(),
+            );
+            render.render_to_wav(&path, |fraction| {
+                thread_progress.store(fraction.to_bits(), Ordering::Relaxed);
+                !thread_cancelled.load(Ordering::Relaxed)
+            })
+        });
+
+        Some(Self {
+            progress,
+            cancelled,
+            handle,
+        })
+    }
+
+    /// Draws the progress window. Takes the task out of the option when
+    /// it has finished, returning the result.
+    pub(crate) fn draw(task: &mut Option<Self>, ui: &mut Ui) -> Option<Result<(), String>> {
+        let current = task.take()?;
+
+        if current.handle.is_finished() {
+            return Some(
+                current
+                    .handle
+                    .join()
+                    .unwrap_or_else(|_| Err(String::from("Render thread panicked"))),
+            );
+        }
+
+        Window::new("Exporting Song")
+            .collapsible(false)
+            .resizable(false)
+            .show(ui.ctx(), |ui| {
+                let fraction = f32::from_bits(current.progress.load(Ordering::Relaxed));
+                ui.add(ProgressBar::new(fraction).show_percentage());
+
+                if ui.button("Cancel").clicked() {
+                    current.cancelled.store(true, Ordering::Relaxed);
+                }
+            });
+
+        // Keep redrawing so the progress bar updates.
+        ui.ctx().request_repaint();
+        *task = Some(current);
+
+        None
+    }
+}
@@ -1,8 +1,11 @@
-use eframe::{egui::Ui, epaint::Color32};
+use eframe::{
+    egui::{Response, Sense, Ui},
+    epaint::Color32,
+};
 use gamercade_audio::{ChainId, SONG_TRACK_CHANNELS};
 
 use crate::ui::audio::sequences::{
-    TrackerText, DEFAULT_TEXT_COLOR, EDITING_BG_COLOR, SELECTED_BG_COLOR,
+    TrackerText, DEFAULT_TEXT_COLOR, EDITING_BG_COLOR, LOOP_BG_COLOR, SELECTED_BG_COLOR,
 };
 
 use super::SelectedEntry;
@@ -13,6 +16,14 @@ pub struct SongRow {
     channels: [TrackerText<2>; SONG_TRACK_CHANNELS],
 }
 
+pub(super) struct SongRowResponse {
+    /// The row, or the channel within it, which was clicked.
+    pub(super) selected: Option<Option<usize>>,
+
+    /// The row number, which can be dragged over to set the loop region.
+    pub(super) row_index: Response,
+}
+
 impl SongRow {
     pub(super) fn header() -> Self {
         Self {
@@ -28,6 +39,7 @@ impl SongRow {
         row: usize,
         song_entry: &[Option<ChainId>; SONG_TRACK_CHANNELS],
         selected_entry: SelectedEntry,
+        in_loop: bool,
     ) -> Self {
         let bg_color = if selected_entry.selected_row == row {
             Some(SELECTED_BG_COLOR)
@@ -35,7 +47,13 @@ impl SongRow {
             None
         };
 
-        let row_index = TrackerText::new(&format!("{:X}:", row), DEFAULT_TEXT_COLOR, bg_color);
+        let row_index_color = if in_loop {
+            Some(LOOP_BG_COLOR)
+        } else {
+            bg_color
+        };
+        let row_index =
+            TrackerText::new(&format!("{:X}:", row), DEFAULT_TEXT_COLOR, row_index_color);
         let separator = TrackerText::separator(bg_color);
 
         let channels = std::array::from_fn(|index| {
@@ -61,13 +79,13 @@ impl SongRow {
         }
     }
 
-    pub(crate) fn draw(&self, ui: &mut Ui) -> Option<Option<usize>> {
+    pub(super) fn draw(&self, ui: &mut Ui) -> SongRowResponse {
         let mut output = None;
 
-        let row_name_clicked = self.row_index.draw(ui);
+        let row_index = self.row_index.draw_with_sense(ui, Sense::click_and_drag());
         let separator_clicked = self.separator.draw(ui);
 
-        if row_name_clicked || separator_clicked {
+        if row_index.clicked() || separator_clicked {
             output = Some(None);
         }
 
@@ -84,6 +102,9 @@ impl SongRow {
                 }
             });
 
-        output
+        SongRowResponse {
+            selected: output,
+            row_index,
+        }
     }
 }
@@ -1,5 +1,5 @@
 use gamercade_audio::{
-    ChainId, InstrumentId, NoteId, PhraseId, CHAINS_MAX_COUNT, INSTRUMENTS_MAX_COUNT,
+    ChainId, Effect, InstrumentId, NoteId, PhraseId, CHAINS_MAX_COUNT, INSTRUMENTS_MAX_COUNT,
     PHRASES_MAX_COUNT, TOTAL_NOTES_COUNT,
 };
 
@@ -78,3 +78,24 @@ impl HandleTrackerEditEntryCommand for ChainId {
         self.0 = self.0.saturating_sub(amount)
     }
 }
+
+/// Adding to an empty effect creates a vibrato, while subtracting
+/// from an effect with a value of zero removes it.
+impl HandleTrackerEditEntryCommand for Option<Effect> {
+    fn add(&mut self, amount: usize) {
+        let effect = self.get_or_insert(Effect::Vibrato { depth: 0, speed: 0 });
+        let value = effect.value().saturating_add(amount as u8);
+        effect.set_value(value)
+    }
+
+    fn sub(&mut self, amount: usize) {
+        match self {
+            Some(effect) if effect.value() == 0 => *self = None,
+            Some(effect) => {
+                let value = effect.value().saturating_sub(amount as u8);
+                effect.set_value(value)
+            }
+            None => (),
+        }
+    }
+}
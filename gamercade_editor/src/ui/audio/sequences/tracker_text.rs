@@ -1,5 +1,5 @@
 use eframe::{
-    egui::{Label, RichText, Sense, Ui},
+    egui::{Label, Response, RichText, Sense, Ui},
     epaint::Color32,
 };
 use tinystr::TinyAsciiStr;
@@ -22,6 +22,10 @@ impl<const N: usize> TrackerText<N> {
     }
 
     pub fn draw(&self, ui: &mut Ui) -> bool {
+        self.draw_with_sense(ui, Sense::click()).clicked()
+    }
+
+    pub fn draw_with_sense(&self, ui: &mut Ui, sense: Sense) -> Response {
         let mut text = RichText::new(self.text.as_str())
             .color(self.text_color)
             .monospace()
@@ -29,7 +33,7 @@ impl<const N: usize> TrackerText<N> {
         if let Some(bg_color) = self.bg_color {
             text = text.background_color(bg_color)
         };
-        ui.add(Label::new(text).sense(Sense::click())).clicked()
+        ui.add(Label::new(text).sense(sense))
     }
 
     pub fn separator(bg_color: Option<Color32>) -> Self {
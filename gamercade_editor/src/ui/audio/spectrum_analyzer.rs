@@ -0,0 +1,130 @@
+use std::{
+    collections::VecDeque,
+    f32::consts::TAU,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use eframe::egui::plot::PlotPoints;
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+
+/// How many samples are analyzed at once.
+const SPECTRUM_SIZE: usize = 4096;
+
+/// How often the spectrum is recalculated.
+const SPECTRUM_UPDATE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The lowest frequency drawn, anything below this isn't really audible.
+pub(crate) const SPECTRUM_MIN_FREQUENCY: f32 = 20.0;
+
+/// The quietest level shown in decibel mode.
+pub(crate) const SPECTRUM_DB_FLOOR: f32 = -96.0;
+
+/// Calculates the frequency spectrum of the master output.
+pub(crate) struct SpectrumAnalyzer {
+    pub(crate) decibels: bool,
+    output_sample_rate: f32,
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    window_sum: f32,
+    samples: VecDeque<f32>,
+    spectrum: Vec<Complex<f32>>,
+    magnitudes: Vec<f32>,
+    last_update: Instant,
+}
+
+impl SpectrumAnalyzer {
+    pub(crate) fn new(output_sample_rate: usize) -> Self {
+        // A hann window, which cuts down on the smearing between frequencies.
+        let window: Vec<f32> = (0..SPECTRUM_SIZE)
+            .map(|index| 0.5 - 0.5 * (TAU * index as f32 / SPECTRUM_SIZE as f32).cos())
+            .collect();
+
+        Self {
+            decibels: true,
+            output_sample_rate: output_sample_rate as f32,
+            fft: FftPlanner::new().plan_fft_forward(SPECTRUM_SIZE),
+            window_sum: window.iter().sum(),
+            window,
+            samples: VecDeque::with_capacity(SPECTRUM_SIZE),
+            spectrum: vec![Complex::default(); SPECTRUM_SIZE],
+            magnitudes: vec![0.0; SPECTRUM_SIZE / 2],
+            last_update: Instant::now(),
+        }
+    }
+
+    /// Adds new samples, only keeping the most recent ones.
+    pub(crate) fn push_samples(&mut self, samples: impl Iterator<Item = f32>) {
+        samples.for_each(|sample| {
+            if self.samples.len() == SPECTRUM_SIZE {
+                self.samples.pop_front();
+            }
+            self.samples.push_back(sample);
+        });
+    }
+
+    /// Recalculates the spectrum, but only if enough time has passed since the
+    /// last update, so the plot stays readable and doesn't hog the ui thread.
+    pub(crate) fn update(&mut self) {
+        if self.last_update.elapsed() < SPECTRUM_UPDATE_INTERVAL {
+            return;
+        }
+        self.last_update = Instant::now();
+
+        // Pads with silence until there are enough samples.
+        let padding = SPECTRUM_SIZE - self.samples.len();
+        self.spectrum
+            .iter_mut()
+            .zip(self.window.iter())
+            .enumerate()
+            .for_each(|(index, (bin, window))| {
+                let sample = index
+                    .checked_sub(padding)
+                    .and_then(|index| self.samples.get(index))
+                    .copied()
+                    .unwrap_or_default();
+                *bin = Complex::new(sample * window, 0.0);
+            });
+
+        self.fft.process(&mut self.spectrum);
+
+        // Scaled so a full volume sine wave has a magnitude of 1.0.
+        let scale = 2.0 / self.window_sum;
+        self.magnitudes
+            .iter_mut()
+            .zip(self.spectrum.iter())
+            .for_each(|(magnitude, bin)| *magnitude = bin.norm() * scale);
+    }
+
+    /// The highest frequency which can be shown.
+    pub(crate) fn max_frequency(&self) -> f32 {
+        self.output_sample_rate / 2.0
+    }
+
+    /// Returns the points to plot. The x axis is log10 of the frequency, and
+    /// the y axis is either the magnitude or its level in decibels.
+    pub(crate) fn plot_points(&self) -> PlotPoints {
+        let bin_width = self.output_sample_rate / SPECTRUM_SIZE as f32;
+
+        self.magnitudes
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter_map(|(index, magnitude)| {
+                let frequency = index as f32 * bin_width;
+
+                if frequency < SPECTRUM_MIN_FREQUENCY {
+                    return None;
+                }
+
+                let value = if self.decibels {
+                    (20.0 * magnitude.log10()).max(SPECTRUM_DB_FLOOR)
+                } else {
+                    *magnitude
+                };
+
+                Some([frequency.log10() as f64, value as f64])
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,156 @@
+use eframe::{
+    egui::{Context, Sense, Slider, Ui, Window},
+    epaint::{pos2, Color32, Rect, Stroke, Vec2},
+};
+use gamercade_sound_engine::SoundOutputChannels;
+
+/// The quietest and loudest levels the meter shows, in dBFS. Anything above 0.0 clips.
+const METER_DB_FLOOR: f32 = -60.0;
+const METER_DB_CEILING: f32 = 6.0;
+
+/// Levels above this are shown as getting close to clipping.
+const METER_DB_WARNING: f32 = -6.0;
+
+/// How much of the recent output the RMS level averages over, like an analog VU meter.
+const RMS_WINDOW_SECONDS: f32 = 0.3;
+
+const DEFAULT_PEAK_DECAY_SECONDS: f32 = 1.5;
+const MAX_PEAK_DECAY_SECONDS: f32 = 10.0;
+
+const METER_SIZE: Vec2 = Vec2::new(24.0, 160.0);
+
+/// The level of a single output channel.
+#[derive(Default)]
+struct ChannelLevel {
+    /// The running mean of the squared samples.
+    mean_square: f32,
+
+    /// The loudest recent sample, which falls back down over the peak decay time.
+    peak_hold: f32,
+}
+
+/// Shows the RMS and peak levels of the left and right master output,
+/// to help set levels so the mix doesn't clip.
+pub(crate) struct VuMeter {
+    pub(crate) open: bool,
+
+    /// How long the held peak takes to fall from full scale to silence.
+    peak_decay_seconds: f32,
+    output_sample_rate: usize,
+    rms_coefficient: f32,
+    channels: [ChannelLevel; 2],
+}
+
+impl VuMeter {
+    pub(crate) fn new(output_sample_rate: usize) -> Self {
+        Self {
+            open: false,
+            peak_decay_seconds: DEFAULT_PEAK_DECAY_SECONDS,
+            output_sample_rate,
+            rms_coefficient: (-1.0 / (RMS_WINDOW_SECONDS * output_sample_rate as f32)).exp(),
+            channels: Default::default(),
+        }
+    }
+
+    /// Measures frames read from the output, without taking them from anyone else.
+    pub(crate) fn update<'a>(&mut self, frames: impl Iterator<Item = &'a SoundOutputChannels>) {
+        let peak_step = (self.peak_decay_seconds * self.output_sample_rate as f32).recip();
+        let rms_coefficient = self.rms_coefficient;
+
+        frames.for_each(|frame| {
+            let (left, right) = frame.get_stereo_output();
+
+            self.channels
+                .iter_mut()
+                .zip([left, right])
+                .for_each(|(channel, sample)| {
+                    channel.mean_square =
+                        sample * sample + (channel.mean_square - sample * sample) * rms_coefficient;
+                    channel.peak_hold = (channel.peak_hold - peak_step).max(sample.abs());
+                });
+        });
+    }
+
+    pub(crate) fn draw(&mut self, ctx: &Context) {
+        let channels = &self.channels;
+        let peak_decay_seconds = &mut self.peak_decay_seconds;
+
+        Window::new("VU Meter")
+            .open(&mut self.open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ["L", "R"]
+                        .iter()
+                        .zip(channels.iter())
+                        .for_each(|(name, channel)| draw_channel(ui, name, channel));
+                });
+
+                ui.add(
+                    Slider::new(peak_decay_seconds, 0.1..=MAX_PEAK_DECAY_SECONDS)
+                        .text("Peak Decay (s)"),
+                );
+            });
+    }
+}
+
+fn draw_channel(ui: &mut Ui, name: &str, channel: &ChannelLevel) {
+    let rms = to_decibels(channel.mean_square.sqrt());
+    let peak = to_decibels(channel.peak_hold);
+
+    ui.vertical(|ui| {
+        let (rect, _) = ui.allocate_exact_size(METER_SIZE, Sense::hover());
+        let painter = ui.painter();
+        let height_of = |db: f32| rect.bottom() - meter_fraction(db) * rect.height();
+
+        painter.rect_filled(rect, 0.0, Color32::from_gray(24));
+
+        let level = Rect::from_min_max(pos2(rect.left(), height_of(rms)), rect.max);
+        painter.rect_filled(level, 0.0, level_color(rms));
+
+        let peak_color = if peak > 0.0 {
+            Color32::RED
+        } else {
+            Color32::WHITE
+        };
+        painter.hline(
+            rect.x_range(),
+            height_of(peak),
+            Stroke::new(2.0, peak_color),
+        );
+
+        // Mark where clipping starts
+        painter.hline(
+            rect.x_range(),
+            height_of(0.0),
+            Stroke::new(1.0, Color32::DARK_RED),
+        );
+
+        ui.add_space(2.0);
+        ui.label(name);
+        ui.label(format!("{:.1}", rms))
+            .on_hover_text("RMS level, in dBFS");
+        ui.label(format!("{:.1}", peak))
+            .on_hover_text("Peak level, in dBFS");
+    });
+}
+
+fn to_decibels(amplitude: f32) -> f32 {
+    (20.0 * amplitude.log10()).max(METER_DB_FLOOR)
+}
+
+/// How far up the meter the level is, from 0.0 to 1.0.
+fn meter_fraction(db: f32) -> f32 {
+    ((db - METER_DB_FLOOR) / (METER_DB_CEILING - METER_DB_FLOOR)).clamp(0.0, 1.0)
+}
+
+fn level_color(db: f32) -> Color32 {
+    if db > 0.0 {
+        Color32::RED
+    } else if db > METER_DB_WARNING {
+        Color32::YELLOW
+    } else {
+        Color32::GREEN
+    }
+}
@@ -0,0 +1,178 @@
+use std::{
+    ffi::OsString,
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
+
+use eframe::egui::{Align2, Context, Window};
+use gamercade_fs::EditorRom;
+
+/// Where projects which haven't been saved yet are autosaved, in the temp directory.
+const UNTITLED_AUTOSAVE_NAME: &str = "gamercade_untitled.gce.autosave";
+
+/// The autosave of a project is kept next to it, such as "game.gce.autosave".
+/// Projects which haven't been saved yet are autosaved to the temp directory.
+pub fn autosave_path(project: Option<&PathBuf>) -> PathBuf {
+    match project {
+        Some(project) => {
+            let mut name = project.file_name().map(OsString::from).unwrap_or_default();
+            name.push(".autosave");
+            project.with_file_name(name)
+        }
+        None => std::env::temp_dir().join(UNTITLED_AUTOSAVE_NAME),
+    }
+}
+
+/// An autosave found at startup which is newer than the last save of its project,
+/// likely left behind by a crash.
+struct Recovery {
+    autosave: PathBuf,
+    project: Option<PathBuf>,
+}
+
+impl Recovery {
+    /// Looks for an autosave of the last project, then for one of an untitled project.
+    fn find(last_project: Option<&PathBuf>) -> Option<Self> {
+        let modified = |path: &PathBuf| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+
+        let candidates = last_project
+            .map(|project| Some(project.clone()))
+            .into_iter()
+            .chain(Some(None));
+
+        candidates
+            .map(|project| Self {
+                autosave: autosave_path(project.as_ref()),
+                project,
+            })
+            .find(|recovery| {
+                let autosaved = match modified(&recovery.autosave) {
+                    Some(autosaved) => autosaved,
+                    None => return false,
+                };
+
+                let saved = recovery
+                    .project
+                    .as_ref()
+                    .and_then(modified)
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+                autosaved > saved
+            })
+    }
+}
+
+/// Writes the project to its autosave file every so often, so a crash doesn't lose work.
+pub struct Autosave {
+    last_write: Instant,
+
+    /// The project as of the last save or autosave, so it's only written again once changed.
+    saved: EditorRom,
+
+    recovery: Option<Recovery>,
+}
+
+impl Autosave {
+    pub fn new(rom: &EditorRom, last_project: Option<&PathBuf>) -> Self {
+        Self {
+            last_write: Instant::now(),
+            saved: rom.clone(),
+            recovery: Recovery::find(last_project),
+        }
+    }
+
+    /// Autosaves the project if it changed and enough time has passed.
+    pub fn update(
+        &mut self,
+        ctx: &Context,
+        rom: &EditorRom,
+        project: Option<&PathBuf>,
+        interval: Option<Duration>,
+    ) {
+        // Don't overwrite an autosave which hasn't been recovered or discarded yet
+        let interval = match interval {
+            Some(interval) if self.recovery.is_none() => interval,
+            _ => return,
+        };
+
+        // Autosave even if the editor is left alone after an edit
+        ctx.request_repaint_after(interval.saturating_sub(self.last_write.elapsed()));
+
+        if self.last_write.elapsed() < interval {
+            return;
+        }
+        self.last_write = Instant::now();
+
+        if *rom == self.saved {
+            return;
+        }
+
+        match rom.try_save(&autosave_path(project)) {
+            Ok(()) => self.saved = rom.clone(),
+            Err(e) => println!("Failed to autosave: {}", e),
+        }
+    }
+
+    /// Called once the project is saved or opened. Its autosave is removed,
+    /// as it's no newer than the project, along with any autosave of an untitled one.
+    pub fn saved(&mut self, rom: &EditorRom, project: Option<&PathBuf>) {
+        self.saved = rom.clone();
+        self.last_write = Instant::now();
+        let _ = fs::remove_file(autosave_path(project));
+        let _ = fs::remove_file(autosave_path(None));
+    }
+
+    /// Offers to recover an autosave found at startup. Returns the recovered
+    /// project, along with the path it was last saved to.
+    pub fn draw_recovery(&mut self, ctx: &Context) -> Option<(EditorRom, Option<PathBuf>)> {
+        let recovery = self.recovery.as_ref()?;
+        let mut recover = None;
+
+        Window::new("Recover Unsaved Work?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                match &recovery.project {
+                    Some(project) => ui.label(format!(
+                        "{} has an autosave which is newer than its last save.",
+                        project.display()
+                    )),
+                    None => ui.label("An unsaved project has an autosave."),
+                };
+
+                ui.horizontal(|ui| {
+                    if ui.button("Recover").clicked() {
+                        recover = Some(true);
+                    }
+
+                    if ui.button("Discard").clicked() {
+                        recover = Some(false);
+                    }
+                });
+            });
+
+        let recovery = match recover {
+            Some(_) => self.recovery.take()?,
+            None => return None,
+        };
+
+        if recover == Some(false) {
+            let _ = fs::remove_file(&recovery.autosave);
+            return None;
+        }
+
+        // The autosave is kept until the recovered project is saved
+        match EditorRom::try_load(&recovery.autosave) {
+            Ok(rom) => {
+                self.saved = rom.clone();
+                self.last_write = Instant::now();
+                Some((rom, recovery.project))
+            }
+            Err(e) => {
+                println!("Failed to recover autosave: {}", e);
+                None
+            }
+        }
+    }
+}
@@ -1,11 +1,14 @@
 use std::path::PathBuf;
 
-use eframe::egui::{self, menu, Context};
+use eframe::egui::{self, menu, Context, DragValue, Ui};
 use rfd::FileDialog;
 
 use gamercade_fs::EditorRom;
 
-use super::{AudioEditor, GraphicsEditor, RomEditor};
+use super::{
+    take_undo_redo_keys, AudioEditor, Autosave, EditorSettings, GraphicsEditor, QuickPlay,
+    RomEditor, Undoable,
+};
 
 pub struct Editor {
     pub rom: EditorRom,
@@ -14,8 +17,14 @@ pub struct Editor {
     rom_editor: RomEditor,
     graphics_editor: GraphicsEditor,
     audio_editor: AudioEditor,
+    quick_play: QuickPlay,
 
     wasm_path: Option<PathBuf>,
+
+    /// Where the project was last opened from or saved to.
+    rom_path: Option<PathBuf>,
+    settings: EditorSettings,
+    autosave: Autosave,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -28,12 +37,17 @@ pub enum EditorMode {
 impl Default for Editor {
     fn default() -> Self {
         let rom = EditorRom::default();
+        let settings = EditorSettings::load();
         Self {
             mode: EditorMode::Rom,
             rom_editor: RomEditor::default(),
             graphics_editor: GraphicsEditor::default(),
             audio_editor: AudioEditor::new(&rom.sounds),
+            quick_play: QuickPlay::default(),
             wasm_path: None,
+            rom_path: None,
+            autosave: Autosave::new(&rom, settings.last_project.as_ref()),
+            settings,
             rom,
         }
     }
@@ -42,8 +56,25 @@ impl Default for Editor {
 impl eframe::App for Editor {
     fn update(&mut self, ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
         self.draw_menu_panel(ctx);
-        self.draw_bottom_panel(ctx);
-        self.draw_central_panel(ctx);
+        self.quick_play.draw_error(ctx);
+        self.draw_recovery(ctx);
+
+        // The game gets the whole window, so editor hotkeys don't steal its input.
+        if self.quick_play.is_running() {
+            egui::CentralPanel::default()
+                .show(ctx, |ui| self.quick_play.draw(ui, &mut self.rom.meta));
+        } else {
+            self.handle_undo_redo(ctx);
+            self.draw_bottom_panel(ctx);
+            self.draw_central_panel(ctx);
+        }
+
+        self.autosave.update(
+            ctx,
+            &self.rom,
+            self.rom_path.as_ref(),
+            self.settings.autosave_interval(),
+        );
     }
 }
 
@@ -58,24 +89,50 @@ impl Editor {
                     }
 
                     if ui.button("Open").clicked() {
-                        if let Err(e) = try_load_editor_rom(&mut self.rom) {
-                            println!("{}", e);
+                        if let Some(path) = try_pick_editor_rom() {
+                            self.open_project(path);
                         }
-                        self.audio_editor.audio_sync_helper.notify_rom_changed();
                         ui.close_menu();
                     }
 
+                    ui.add_enabled_ui(!self.settings.recent_projects.is_empty(), |ui| {
+                        ui.menu_button("Open Recent", |ui| self.draw_recent_projects(ui));
+                    });
+
                     if ui.button("Save").clicked() {
-                        if let Err(e) = try_save_editor_rom(&self.rom) {
-                            println!("{}", e);
+                        match try_save_editor_rom(&self.rom) {
+                            Ok(Some(path)) => self.project_saved(path),
+                            Ok(None) => (),
+                            Err(e) => println!("{}", e),
                         }
                         ui.close_menu();
                     }
+
+                    ui.separator();
+                    ui.menu_button("Autosave", |ui| {
+                        ui.label("Seconds between autosaves, or 0 for off:");
+                        let seconds = ui.add(
+                            DragValue::new(&mut self.settings.autosave_seconds)
+                                .clamp_range(0..=3600),
+                        );
+
+                        if seconds.changed() {
+                            self.save_settings();
+                        }
+                    });
                 });
 
                 ui.menu_button("Game", |ui| {
-                    if ui.button("Local Test Game").clicked() {
-                        println!("TODO: Test Local Game!");
+                    if self.quick_play.is_running() {
+                        if ui.button("Stop").clicked() {
+                            self.quick_play.stop();
+                            ui.close_menu();
+                        }
+                    } else if ui.button("Play").clicked() {
+                        match try_get_wasm(&mut self.wasm_path) {
+                            Ok(path) => self.quick_play.start(path, &self.rom),
+                            Err(e) => println!("{}", e),
+                        }
                         ui.close_menu();
                     }
 
@@ -98,6 +155,84 @@ impl Editor {
         });
     }
 
+    /// Offers to recover the project from an autosave left behind by a crash.
+    fn draw_recovery(&mut self, ctx: &Context) {
+        if let Some((rom, project)) = self.autosave.draw_recovery(ctx) {
+            self.rom = rom;
+            self.rom_path = project;
+            self.graphics_editor.clear_history();
+            self.audio_editor.clear_history();
+            self.audio_editor.audio_sync_helper.notify_rom_changed();
+        }
+    }
+
+    fn draw_recent_projects(&mut self, ui: &mut Ui) {
+        let mut selected = None;
+        for path in self.settings.recent_projects.iter() {
+            let name = path.file_name().unwrap_or(path.as_os_str());
+            let button = ui
+                .button(name.to_string_lossy())
+                .on_hover_text(path.display().to_string());
+
+            if button.clicked() {
+                selected = Some(path.clone());
+            }
+        }
+
+        if let Some(path) = selected {
+            self.open_project(path);
+            ui.close_menu();
+        }
+    }
+
+    fn open_project(&mut self, path: PathBuf) {
+        match EditorRom::try_load(&path) {
+            Ok(rom) => {
+                self.rom = rom;
+                self.graphics_editor.clear_history();
+                self.audio_editor.clear_history();
+                self.audio_editor.audio_sync_helper.notify_rom_changed();
+                self.project_saved(path);
+            }
+            Err(e) => {
+                println!("Failed to load editor rom: {}", e);
+                self.settings.prune_recent_projects();
+                self.save_settings();
+            }
+        }
+    }
+
+    /// Remembers where the project is, so its autosave goes next to it.
+    fn project_saved(&mut self, path: PathBuf) {
+        self.autosave.saved(&self.rom, Some(&path));
+        self.settings.last_project = Some(path.clone());
+        self.settings.add_recent_project(path.clone());
+        self.rom_path = Some(path);
+        self.save_settings();
+    }
+
+    fn save_settings(&self) {
+        if let Err(e) = self.settings.try_save() {
+            println!("Failed to save editor settings: {}", e);
+        }
+    }
+
+    /// Ctrl+Z and Ctrl+Shift+Z undo and redo the edits of the active editor.
+    fn handle_undo_redo(&mut self, ctx: &Context) {
+        let (undo, redo) = take_undo_redo_keys(ctx);
+        if !undo && !redo {
+            return;
+        }
+
+        match self.mode {
+            EditorMode::Rom => (),
+            EditorMode::Graphics => {
+                undo_or_redo(&mut self.graphics_editor, &mut self.rom.graphics, undo)
+            }
+            EditorMode::Audio => undo_or_redo(&mut self.audio_editor, &mut self.rom.sounds, undo),
+        }
+    }
+
     pub fn draw_central_panel(&mut self, ctx: &Context) {
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.horizontal(|ui| {
@@ -133,32 +268,31 @@ impl Editor {
     }
 }
 
-fn try_load_editor_rom(rom: &mut EditorRom) -> Result<(), &'static str> {
-    if let Some(path) = FileDialog::new()
-        .add_filter("gce (.gce)", &["gce"])
-        .pick_file()
-    {
-        match EditorRom::try_load(&path) {
-            Ok(new_rom) => {
-                *rom = new_rom;
-                return Ok(());
-            }
-            Err(_) => return Err("Failed to load editor rom."),
-        }
+fn undo_or_redo<D>(editor: &mut impl Undoable<D>, data: &mut D, undo: bool) {
+    if undo {
+        editor.undo(data);
+    } else {
+        editor.redo(data);
     }
+}
 
-    Ok(())
+fn try_pick_editor_rom() -> Option<PathBuf> {
+    FileDialog::new()
+        .add_filter("gce (.gce)", &["gce"])
+        .pick_file()
 }
 
-fn try_save_editor_rom(rom: &EditorRom) -> Result<(), &'static str> {
+/// Returns the path the rom was saved to, if it was saved.
+fn try_save_editor_rom(rom: &EditorRom) -> Result<Option<PathBuf>, &'static str> {
     if let Some(path) = FileDialog::new()
         .add_filter("gce (.gce)", &["gce"])
         .save_file()
     {
         rom.try_save(&path)
-            .map_err(|_| "Failed to save editor rom.")
+            .map_err(|_| "Failed to save editor rom.")?;
+        Ok(Some(path))
     } else {
-        Ok(())
+        Ok(None)
     }
 }
 
@@ -173,27 +307,27 @@ fn try_select_wasm(wasm_path: &mut Option<PathBuf>) -> Result<(), &'static str>
     Ok(())
 }
 
+/// Returns the selected .wasm, asking for one if none has been selected yet.
+fn try_get_wasm(wasm_path: &mut Option<PathBuf>) -> Result<&PathBuf, &'static str> {
+    if wasm_path.is_none() {
+        *wasm_path = try_pick_wasm();
+    }
+
+    wasm_path.as_ref().ok_or("didn't select a .wasm file")
+}
+
 fn try_export_rom(rom: &EditorRom, wasm_path: &mut Option<PathBuf>) -> Result<(), &'static str> {
-    *wasm_path = match wasm_path {
-        Some(path) => Some(path.to_path_buf()),
-        None => match try_pick_wasm() {
-            Some(path) => Some(path),
-            None => return Err("didn't select a .wasm file"),
-        },
-    };
+    let path = try_get_wasm(wasm_path)?;
+    let wasm = gamercade_fs::try_load_wasm(path).map_err(|_| "failed to read as bytes")?;
 
-    if let Some(path) = wasm_path {
-        let wasm = gamercade_fs::try_load_wasm(path).map_err(|_| "failed to read as bytes")?;
-
-        if let Some(path) = FileDialog::new()
-            .add_filter("gcrom (.gcrom)", &["gcrom"])
-            .set_title("Export Game .gcrom")
-            .save_file()
-        {
-            let rom = gamercade_fs::bundle(&wasm, rom);
-            rom.try_save(&path)
-                .map_err(|_| "failed to finish writing")?;
-        }
+    if let Some(path) = FileDialog::new()
+        .add_filter("gcrom (.gcrom)", &["gcrom"])
+        .set_title("Export Game .gcrom")
+        .save_file()
+    {
+        let rom = gamercade_fs::bundle(&wasm, rom);
+        rom.try_save(&path)
+            .map_err(|_| "failed to finish writing")?;
     }
 
     Ok(())
@@ -0,0 +1,77 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+const EDITOR_SETTINGS_PATH: &str = "editor_settings.json";
+
+/// The most projects kept in the recent projects list.
+const MAX_RECENT_PROJECTS: usize = 10;
+
+/// How often to autosave, unless changed.
+const DEFAULT_AUTOSAVE_SECONDS: u64 = 60;
+
+/// Editor preferences, which persist between sessions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EditorSettings {
+    /// How often the project is autosaved. Zero turns autosave off.
+    pub autosave_seconds: u64,
+
+    /// The project which was last opened or saved.
+    pub last_project: Option<PathBuf>,
+
+    /// Projects which were opened or saved, the most recent first.
+    pub recent_projects: Vec<PathBuf>,
+}
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        Self {
+            autosave_seconds: DEFAULT_AUTOSAVE_SECONDS,
+            last_project: None,
+            recent_projects: Vec::new(),
+        }
+    }
+}
+
+impl EditorSettings {
+    pub fn load() -> Self {
+        match Self::try_load(&PathBuf::from(EDITOR_SETTINGS_PATH)) {
+            Ok(mut settings) => {
+                settings.prune_recent_projects();
+                settings
+            }
+            Err(e) => {
+                println!("Using default editor settings: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    fn try_load(path: &PathBuf) -> Result<Self, String> {
+        let file = fs::read(path).map_err(|e| e.to_string())?;
+        serde_json::from_slice(&file).map_err(|e| e.to_string())
+    }
+
+    pub fn try_save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(EDITOR_SETTINGS_PATH, json).map_err(|e| e.to_string())
+    }
+
+    /// Moves the project to the top of the recent projects list.
+    pub fn add_recent_project(&mut self, path: PathBuf) {
+        self.recent_projects.retain(|recent| *recent != path);
+        self.recent_projects.insert(0, path);
+        self.recent_projects.truncate(MAX_RECENT_PROJECTS);
+    }
+
+    /// Removes projects which have since been moved or deleted.
+    pub fn prune_recent_projects(&mut self) {
+        self.recent_projects.retain(|recent| recent.is_file());
+    }
+
+    /// None if autosave is off.
+    pub fn autosave_interval(&self) -> Option<Duration> {
+        (self.autosave_seconds > 0).then(|| Duration::from_secs(self.autosave_seconds))
+    }
+}
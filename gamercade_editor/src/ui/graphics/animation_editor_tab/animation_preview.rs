@@ -0,0 +1,101 @@
+use eframe::egui::{Image, Slider, TextureHandle, Ui, Vec2};
+use gamercade_core::Palette;
+use gamercade_fs::{EditorAnimation, EditorSpriteSheet};
+
+use crate::ui::{load_buffered_image, sprite_to_image};
+
+#[derive(Clone)]
+pub struct AnimationPreview {
+    playing: bool,
+    fps: f32,
+
+    /// How long the animation has been playing, in seconds.
+    elapsed: f64,
+
+    rgb_buffer: Vec<u8>,
+    texture_handle: Option<TextureHandle>,
+}
+
+impl Default for AnimationPreview {
+    fn default() -> Self {
+        Self {
+            playing: true,
+            fps: 12.0,
+            elapsed: 0.0,
+            rgb_buffer: Vec::new(),
+            texture_handle: None,
+        }
+    }
+}
+
+impl AnimationPreview {
+    pub(crate) fn draw(
+        &mut self,
+        ui: &mut Ui,
+        animation: &EditorAnimation,
+        sheets: &[EditorSpriteSheet],
+        palette: &Palette,
+        scale: f32,
+    ) {
+        if self.playing {
+            self.elapsed += ui.input().stable_dt as f64;
+            ui.ctx().request_repaint();
+        }
+
+        let tick = (self.elapsed * self.fps as f64) as usize;
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Animation Preview");
+
+                ui.horizontal(|ui| {
+                    let label = if self.playing { "Pause" } else { "Play" };
+                    if ui.button(label).clicked() {
+                        self.playing = !self.playing;
+                    }
+
+                    if ui.button("Restart").clicked() {
+                        self.elapsed = 0.0;
+                    }
+
+                    ui.add(Slider::new(&mut self.fps, 1.0..=60.0).text("FPS"));
+                });
+
+                let sheet = match sheets.get(animation.sprite_sheet.0 as usize) {
+                    Some(sheet) => &sheet.sprite_sheet,
+                    None => {
+                        ui.label("Sprite sheet doesn't exist.");
+                        return;
+                    }
+                };
+
+                let frame = match animation.frame_at(tick) {
+                    Some(frame) if frame.sprite.0 < sheet.count => frame,
+                    _ => {
+                        ui.label("No frames to show.");
+                        return;
+                    }
+                };
+
+                ui.label(format!("Sprite: {}", frame.sprite.0));
+
+                let rgb = sprite_to_image(
+                    &mut self.rgb_buffer,
+                    &sheet[frame.sprite],
+                    (sheet.width, sheet.height),
+                    palette,
+                );
+                let image =
+                    load_buffered_image(ui, &mut self.texture_handle, "animation preview", rgb);
+
+                ui.add(Image::new(
+                    image,
+                    Vec2 {
+                        x: sheet.width as f32 * scale,
+                        y: sheet.height as f32 * scale,
+                    },
+                ));
+            });
+        });
+    }
+}
@@ -0,0 +1,208 @@
+// Own imports
+mod animation_preview;
+
+use animation_preview::AnimationPreview;
+
+// Externals
+use eframe::egui::{Button, ComboBox, DragValue, ScrollArea, Ui};
+
+use super::{PaletteEditor, SpriteSheetEditor};
+use crate::ui::{SnapshotHistory, SnapshotTarget, Undoable};
+use gamercade_core::{SpriteIndex, SpriteSheetIndex};
+use gamercade_fs::{EditorAnimation, EditorAnimationFrame, EditorGraphicsData, EditorSpriteSheet};
+
+#[derive(Clone, Default)]
+pub struct AnimationEditor {
+    selected_animation: usize,
+    preview: AnimationPreview,
+    history: SnapshotHistory<Vec<EditorAnimation>>,
+}
+
+impl AnimationEditor {
+    pub fn draw(
+        &mut self,
+        ui: &mut Ui,
+        data: &mut EditorGraphicsData,
+        sprite_sheet_editor: &SpriteSheetEditor,
+        palette_editor: &mut PaletteEditor,
+        scale: f32,
+    ) {
+        self.history.begin(data, 0);
+
+        ui.horizontal(|ui| {
+            self.draw_list(ui, data, sprite_sheet_editor.selected_sheet());
+
+            let animation = match data.animations.get_mut(self.selected_animation) {
+                Some(animation) => animation,
+                None => return,
+            };
+
+            self.draw_frames(ui, animation, &data.sprite_sheets, sprite_sheet_editor);
+
+            let palette = &data.palettes[*palette_editor.selected_palette_mut()].palette;
+            self.preview
+                .draw(ui, animation, &data.sprite_sheets, palette, scale);
+        });
+
+        self.history.end(ui, data, 0);
+    }
+
+    fn draw_list(&mut self, ui: &mut Ui, data: &mut EditorGraphicsData, sheet: SpriteSheetIndex) {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Animation List");
+
+                ui.group(|ui| {
+                    data.animations
+                        .iter()
+                        .enumerate()
+                        .for_each(|(index, animation)| {
+                            let is_checked = self.selected_animation == index;
+
+                            if ui.selectable_label(is_checked, &animation.name).clicked() {
+                                self.selected_animation = index
+                            };
+                        });
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button("New").clicked() {
+                        let count = data.animations.len() + 1;
+                        data.animations.push(EditorAnimation {
+                            name: format!("Animation {}", count),
+                            sprite_sheet: sheet,
+                            frames: vec![EditorAnimationFrame::default()],
+                        });
+                        self.selected_animation = data.animations.len() - 1;
+                    };
+
+                    if ui.button("Delete").clicked()
+                        && self.selected_animation < data.animations.len()
+                    {
+                        data.animations.remove(self.selected_animation);
+                        self.selected_animation = self
+                            .selected_animation
+                            .min(data.animations.len().saturating_sub(1));
+                    }
+                });
+            });
+        });
+    }
+
+    fn draw_frames(
+        &self,
+        ui: &mut Ui,
+        animation: &mut EditorAnimation,
+        sheets: &[EditorSpriteSheet],
+        sprite_sheet_editor: &SpriteSheetEditor,
+    ) {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Animation Editor");
+
+                ui.horizontal(|ui| {
+                    ui.label("Name: ");
+                    ui.text_edit_singleline(&mut animation.name);
+                });
+
+                let selected_name = sheets
+                    .get(animation.sprite_sheet.0 as usize)
+                    .map(|sheet| sheet.name.as_str())
+                    .unwrap_or("Missing");
+
+                ComboBox::from_label("Sprite Sheet")
+                    .selected_text(selected_name)
+                    .show_ui(ui, |ui| {
+                        sheets.iter().enumerate().for_each(|(index, sheet)| {
+                            ui.selectable_value(
+                                &mut animation.sprite_sheet,
+                                SpriteSheetIndex(index as u8),
+                                &sheet.name,
+                            );
+                        });
+                    });
+
+                let sprite_count = sheets
+                    .get(animation.sprite_sheet.0 as usize)
+                    .map(|sheet| sheet.sprite_sheet.count)
+                    .unwrap_or_default();
+
+                let mut swap = None;
+                let mut remove = None;
+                let last_frame = animation.frames.len().saturating_sub(1);
+
+                ScrollArea::vertical().max_height(480.0).show(ui, |ui| {
+                    animation
+                        .frames
+                        .iter_mut()
+                        .enumerate()
+                        .for_each(|(index, frame)| {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("Frame {}:", index));
+
+                                ui.label("Sprite");
+                                ui.add(
+                                    DragValue::new(&mut frame.sprite.0)
+                                        .clamp_range(0..=sprite_count.saturating_sub(1)),
+                                );
+
+                                ui.label("Duration");
+                                ui.add(DragValue::new(&mut frame.duration).clamp_range(1..=255));
+
+                                if ui.add_enabled(index != 0, Button::new("Up")).clicked() {
+                                    swap = Some((index - 1, index));
+                                }
+
+                                if ui
+                                    .add_enabled(index != last_frame, Button::new("Down"))
+                                    .clicked()
+                                {
+                                    swap = Some((index, index + 1));
+                                }
+
+                                if ui.button("Remove").clicked() {
+                                    remove = Some(index);
+                                }
+                            });
+                        });
+                });
+
+                if let Some((a, b)) = swap {
+                    animation.frames.swap(a, b);
+                }
+
+                if let Some(index) = remove {
+                    animation.frames.remove(index);
+                }
+
+                if ui.button("Add Selected Sprite").clicked() {
+                    let sprite = sprite_sheet_editor.selected_sprite();
+                    animation.frames.push(EditorAnimationFrame {
+                        sprite: SpriteIndex(sprite.0.min(sprite_count.saturating_sub(1))),
+                        ..EditorAnimationFrame::default()
+                    });
+                }
+            });
+        });
+    }
+}
+
+impl SnapshotTarget<EditorGraphicsData> for Vec<EditorAnimation> {
+    fn locate(data: &mut EditorGraphicsData, _: usize) -> Option<&mut Self> {
+        Some(&mut data.animations)
+    }
+}
+
+impl Undoable<EditorGraphicsData> for AnimationEditor {
+    fn undo(&mut self, data: &mut EditorGraphicsData) -> bool {
+        self.history.undo(data)
+    }
+
+    fn redo(&mut self, data: &mut EditorGraphicsData) -> bool {
+        self.history.redo(data)
+    }
+
+    fn clear_history(&mut self) {
+        self.history.clear();
+    }
+}
@@ -2,7 +2,8 @@ use eframe::egui::{
     Color32, ColorImage, Image, Slider, TextureFilter, TextureHandle, TextureId, Ui, Vec2,
 };
 
-use super::{PaletteEditor, SpriteEditor, SpriteSheetEditor};
+use super::{AnimationEditor, PaletteEditor, SpriteEditor, SpriteSheetEditor, TilemapEditor};
+use crate::ui::Undoable;
 use gamercade_fs::EditorGraphicsData;
 
 use gamercade_core::{Palette, PALETTE_COLORS};
@@ -14,6 +15,8 @@ pub enum GraphicsEditorMode {
     Palette,
     SpriteSheet,
     Sprite,
+    Tilemap,
+    Animation,
 }
 
 impl Default for GraphicsEditor {
@@ -23,6 +26,8 @@ impl Default for GraphicsEditor {
             palette_editor: PaletteEditor::default(),
             sprite_sheet_editor: SpriteSheetEditor::default(),
             sprite_editor: SpriteEditor::default(),
+            tilemap_editor: TilemapEditor::default(),
+            animation_editor: AnimationEditor::default(),
 
             scale: 16.0,
             default_palette_texture: None,
@@ -36,6 +41,8 @@ pub struct GraphicsEditor {
     pub palette_editor: PaletteEditor,
     pub sprite_sheet_editor: SpriteSheetEditor,
     pub sprite_editor: SpriteEditor,
+    pub tilemap_editor: TilemapEditor,
+    pub animation_editor: AnimationEditor,
 
     pub scale: f32,
     default_palette_texture: Option<TextureHandle>,
@@ -50,6 +57,8 @@ impl GraphicsEditor {
             "Sprite Sheets",
         );
         ui.selectable_value(&mut self.mode, GraphicsEditorMode::Sprite, "Sprite Editor");
+        ui.selectable_value(&mut self.mode, GraphicsEditorMode::Tilemap, "Tilemaps");
+        ui.selectable_value(&mut self.mode, GraphicsEditorMode::Animation, "Animations");
     }
 
     pub fn draw_contents(&mut self, ui: &mut Ui, data: &mut EditorGraphicsData) {
@@ -79,7 +88,28 @@ impl GraphicsEditor {
                 self.scale,
                 texture_id,
             ),
-            GraphicsEditorMode::Sprite => self.sprite_editor.draw(ui),
+            GraphicsEditorMode::Sprite => self.sprite_editor.draw(
+                ui,
+                data,
+                &self.sprite_sheet_editor,
+                &mut self.palette_editor,
+                self.scale,
+                texture_id,
+            ),
+            GraphicsEditorMode::Tilemap => self.tilemap_editor.draw(
+                ui,
+                data,
+                &self.sprite_sheet_editor,
+                &mut self.palette_editor,
+                self.scale,
+            ),
+            GraphicsEditorMode::Animation => self.animation_editor.draw(
+                ui,
+                data,
+                &self.sprite_sheet_editor,
+                &mut self.palette_editor,
+                self.scale,
+            ),
         };
     }
 
@@ -91,6 +121,36 @@ impl GraphicsEditor {
     }
 }
 
+impl Undoable<EditorGraphicsData> for GraphicsEditor {
+    fn undo(&mut self, data: &mut EditorGraphicsData) -> bool {
+        match self.mode {
+            GraphicsEditorMode::Palette => self.palette_editor.undo(data),
+            GraphicsEditorMode::SpriteSheet => self.sprite_sheet_editor.undo(data),
+            GraphicsEditorMode::Sprite => self.sprite_editor.undo(data),
+            GraphicsEditorMode::Tilemap => self.tilemap_editor.undo(data),
+            GraphicsEditorMode::Animation => self.animation_editor.undo(data),
+        }
+    }
+
+    fn redo(&mut self, data: &mut EditorGraphicsData) -> bool {
+        match self.mode {
+            GraphicsEditorMode::Palette => self.palette_editor.redo(data),
+            GraphicsEditorMode::SpriteSheet => self.sprite_sheet_editor.redo(data),
+            GraphicsEditorMode::Sprite => self.sprite_editor.redo(data),
+            GraphicsEditorMode::Tilemap => self.tilemap_editor.redo(data),
+            GraphicsEditorMode::Animation => self.animation_editor.redo(data),
+        }
+    }
+
+    fn clear_history(&mut self) {
+        self.palette_editor.clear_history();
+        self.sprite_sheet_editor.clear_history();
+        self.sprite_editor.clear_history();
+        self.tilemap_editor.clear_history();
+        self.animation_editor.clear_history();
+    }
+}
+
 pub(crate) fn draw_palette_preview(ui: &mut Ui, palette: &Palette, texture_id: TextureId) {
     ui.spacing_mut().item_spacing = Vec2 { x: 0.0, y: 0.0 };
     ui.horizontal(|ui| {
@@ -1,13 +1,18 @@
+mod animation_editor_tab;
 mod graphics_editor;
 mod palette_editor_tab;
 mod sprite_editor_tab;
 mod sprite_sheet_editor_tab;
+mod tilemap_editor_tab;
 
-use eframe::egui::TextureFilter;
+pub use animation_editor_tab::*;
+use eframe::egui::{ColorImage, TextureFilter};
+use gamercade_core::{ColorIndex, Palette};
 pub use graphics_editor::*;
 pub use palette_editor_tab::*;
 pub use sprite_editor_tab::*;
 pub use sprite_sheet_editor_tab::*;
+pub use tilemap_editor_tab::*;
 
 pub(crate) fn import_image_dialog(title: &str) -> Result<(image::RgbaImage, String), String> {
     let path = match rfd::FileDialog::new()
@@ -63,6 +68,26 @@ pub(crate) fn import_many_images_dialog(title: &str) -> Result<Vec<image::RgbaIm
     Ok(out)
 }
 
+pub(crate) fn export_image_dialog(
+    title: &str,
+    file_name: &str,
+    image: &image::RgbaImage,
+) -> Result<(), String> {
+    let path = match rfd::FileDialog::new()
+        .set_title(title)
+        .add_filter("image (.png)", &["png"])
+        .set_file_name(&format!("{}.png", file_name))
+        .save_file()
+    {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+
+    image
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to save image: {:?}", e))
+}
+
 pub(crate) fn load_buffered_image<'a>(
     ui: &mut eframe::egui::Ui,
     handle: &'a mut Option<eframe::egui::TextureHandle>,
@@ -80,3 +105,18 @@ pub(crate) fn load_buffered_image<'a>(
         }
     }
 }
+
+/// Colors in the sprite using the palette, reusing the buffer to hold the pixels.
+pub(crate) fn sprite_to_image(
+    buffer: &mut Vec<u8>,
+    sprite: &[ColorIndex],
+    (width, height): (usize, usize),
+    palette: &Palette,
+) -> ColorImage {
+    buffer.clear();
+    sprite.iter().for_each(|color_index| {
+        buffer.extend(palette[*color_index].into_pixel_data());
+    });
+
+    ColorImage::from_rgba_unmultiplied([width, height], buffer)
+}
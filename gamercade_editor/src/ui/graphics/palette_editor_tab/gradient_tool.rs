@@ -0,0 +1,230 @@
+use eframe::egui::{DragValue, Ui};
+use gamercade_core::{Color, Palette, PALETTE_COLORS};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+enum GradientSpace {
+    #[default]
+    Rgb,
+    Hsv,
+}
+
+/// Fills the colors between two palette entries with a smooth ramp.
+#[derive(Clone, Debug, Default)]
+pub struct GradientTool {
+    start: usize,
+    end: usize,
+    space: GradientSpace,
+}
+
+impl GradientTool {
+    pub(crate) fn draw(&mut self, ui: &mut Ui, palette: &mut Palette, selected_color: usize) {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Gradient");
+
+                ui.horizontal(|ui| {
+                    ui.label("Start:");
+                    ui.add(DragValue::new(&mut self.start).clamp_range(0..=PALETTE_COLORS - 1));
+                    if ui.button("Use Selected").clicked() {
+                        self.start = selected_color;
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("End:");
+                    ui.add(DragValue::new(&mut self.end).clamp_range(0..=PALETTE_COLORS - 1));
+                    if ui.button("Use Selected").clicked() {
+                        self.end = selected_color;
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.space, GradientSpace::Rgb, "RGB");
+                    ui.selectable_value(&mut self.space, GradientSpace::Hsv, "HSV");
+                });
+
+                let steps = self.start.abs_diff(self.end).saturating_sub(1);
+                ui.label(format!("Fills {} colors in between", steps));
+
+                if ui.button("Fill").clicked() {
+                    self.fill(palette);
+                }
+            });
+        });
+    }
+
+    /// Replaces the colors between start and end, keeping both ends as they are.
+    fn fill(&self, palette: &mut Palette) {
+        let (first, last) = (self.start.min(self.end), self.start.max(self.end));
+        let from = palette.colors[first];
+        let to = palette.colors[last];
+        let length = (last - first) as f32;
+
+        (first + 1..last).for_each(|index| {
+            let t = (index - first) as f32 / length;
+            palette.colors[index] = match self.space {
+                GradientSpace::Rgb => lerp_rgb(from, to, t),
+                GradientSpace::Hsv => lerp_hsv(from, to, t),
+            };
+        });
+    }
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+fn lerp_rgb(from: Color, to: Color, t: f32) -> Color {
+    let channel = |from: u8, to: u8| lerp(from as f32, to as f32, t).round() as u8;
+
+    Color::new(
+        channel(from.r, to.r),
+        channel(from.g, to.g),
+        channel(from.b, to.b),
+        channel(from.a, to.a),
+    )
+}
+
+/// Interpolates around the shorter side of the hue circle.
+fn lerp_hsv(from: Color, to: Color, t: f32) -> Color {
+    let (from_h, from_s, from_v) = to_hsv(from);
+    let (to_h, to_s, to_v) = to_hsv(to);
+
+    let mut delta = to_h - from_h;
+    if delta > 180.0 {
+        delta -= 360.0;
+    } else if delta < -180.0 {
+        delta += 360.0;
+    }
+
+    let hue = (from_h + delta * t).rem_euclid(360.0);
+    let (r, g, b) = from_hsv(hue, lerp(from_s, to_s, t), lerp(from_v, to_v, t));
+    let alpha = lerp(from.a as f32, to.a as f32, t).round() as u8;
+
+    Color::new(r, g, b, alpha)
+}
+
+/// Returns the hue in degrees, with saturation and value between 0.0 and 1.0.
+fn to_hsv(color: Color) -> (f32, f32, f32) {
+    let r = color.r as f32 / 255.0;
+    let g = color.g as f32 / 255.0;
+    let b = color.b as f32 / 255.0;
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+    (hue, saturation, max)
+}
+
+fn from_hsv(hue: f32, saturation: f32, value: f32) -> (u8, u8, u8) {
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let m = value - chroma;
+
+    let (r, g, b) = match (hue / 60.0) as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    let channel = |value: f32| ((value + m) * 255.0).round() as u8;
+    (channel(r), channel(g), channel(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = Color {
+        r: 255,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+    const MAGENTA: Color = Color {
+        r: 255,
+        g: 0,
+        b: 255,
+        a: 255,
+    };
+
+    #[test]
+    fn test_hsv_round_trip() {
+        let colors = [
+            (RED, 0.0),
+            (Color::new(255, 255, 0, 255), 60.0),
+            (Color::new(0, 255, 0, 255), 120.0),
+            (Color::new(0, 255, 255, 255), 180.0),
+            (Color::new(0, 0, 255, 255), 240.0),
+            (MAGENTA, 300.0),
+        ];
+
+        colors.iter().for_each(|(color, expected_hue)| {
+            let (hue, saturation, value) = to_hsv(*color);
+            assert_eq!((hue, saturation, value), (*expected_hue, 1.0, 1.0));
+            assert_eq!(
+                from_hsv(hue, saturation, value),
+                (color.r, color.g, color.b)
+            );
+        });
+
+        // Grays have no hue or saturation
+        assert_eq!(to_hsv(Color::new(51, 51, 51, 255)), (0.0, 0.0, 0.2));
+        assert_eq!(from_hsv(0.0, 0.0, 0.2), (51, 51, 51));
+    }
+
+    fn fill(space: GradientSpace, from: Color, to: Color) -> Palette {
+        let mut palette = Palette::default();
+        palette.colors[2] = from;
+        palette.colors[6] = to;
+
+        // The ends can be picked in either order
+        let tool = GradientTool {
+            start: 6,
+            end: 2,
+            space,
+        };
+        tool.fill(&mut palette);
+        palette
+    }
+
+    #[test]
+    fn test_rgb_gradient() {
+        let black = Color::new(0, 0, 0, 255);
+        let white = Color::new(255, 255, 255, 255);
+        let palette = fill(GradientSpace::Rgb, black, white);
+
+        assert_eq!(palette.colors[2], black);
+        assert_eq!(palette.colors[4], Color::new(128, 128, 128, 255));
+        assert_eq!(palette.colors[6], white);
+        assert_eq!(palette.colors[7], Palette::default().colors[7]);
+    }
+
+    #[test]
+    fn test_hsv_gradient_wraps_around_hue() {
+        let palette = fill(GradientSpace::Hsv, RED, MAGENTA);
+
+        assert_eq!(palette.colors[2], RED);
+        assert_eq!(palette.colors[6], MAGENTA);
+
+        // Red is at 0 degrees and magenta at 300, so the shorter way is through 330
+        assert_eq!(palette.colors[4], Color::new(255, 0, 128, 255));
+        assert_eq!(lerp_hsv(MAGENTA, RED, 0.5), Color::new(255, 0, 128, 255));
+    }
+}
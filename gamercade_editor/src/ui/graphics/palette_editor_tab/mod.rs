@@ -1,10 +1,12 @@
 // Own imports
 mod color_editor;
+mod gradient_tool;
 mod palette_list;
 mod palette_viewer;
 mod sprite_preview;
 
 use color_editor::ColorEditor;
+use gradient_tool::GradientTool;
 use palette_list::PaletteList;
 use palette_viewer::PaletteViewer;
 use sprite_preview::SpritePreview;
@@ -13,6 +15,7 @@ use sprite_preview::SpritePreview;
 use eframe::egui::{SidePanel, TextureId, TopBottomPanel, Ui};
 
 use super::SpriteSheetEditor;
+use crate::ui::{SnapshotHistory, SnapshotTarget, Undoable};
 use gamercade_fs::{EditorGraphicsData, EditorPalette};
 
 #[derive(Clone, Default)]
@@ -20,7 +23,11 @@ pub struct PaletteEditor {
     palette_list: PaletteList,
     palette_viewer: PaletteViewer,
     color_editor: ColorEditor,
+    gradient_tool: GradientTool,
     sprite_preview: SpritePreview,
+
+    /// Each edit is to a single palette, or adds, removes or moves one.
+    history: SnapshotHistory<EditorPalette>,
 }
 
 impl PaletteEditor {
@@ -32,12 +39,15 @@ impl PaletteEditor {
         scale: f32,
         texture_id: TextureId,
     ) {
+        self.history.begin(data, self.palette_list.selected_palette);
+
         // Draw Palette List
         SidePanel::left("palette_list_left_panel")
             .resizable(false)
             .show_inside(ui, |ui| {
-                TopBottomPanel::bottom("palette_list_bottom_panel")
-                    .show_inside(ui, |ui| self.palette_list.draw_buttons(ui, data));
+                TopBottomPanel::bottom("palette_list_bottom_panel").show_inside(ui, |ui| {
+                    self.palette_list.draw_buttons(ui, data, &mut self.history)
+                });
                 self.palette_list.draw(ui, texture_id, data);
             });
 
@@ -65,7 +75,10 @@ impl PaletteEditor {
                 );
             });
 
-        self.draw_color_editor(ui, texture_id, palette)
+        self.draw_color_editor(ui, texture_id, palette);
+
+        self.history
+            .end(ui, data, self.palette_list.selected_palette);
     }
 
     // Draws the right side panel which includes palette viewer, color
@@ -84,10 +97,47 @@ impl PaletteEditor {
                 self.color_editor
                     .draw(ui, color, texture_id, self.palette_viewer.selected_color);
             });
+
+            self.gradient_tool
+                .draw(ui, &mut palette.palette, self.palette_viewer.selected_color);
         });
     }
 
+    /// Keeps the selected palette in range, such as after undoing a new palette.
+    fn clamp_selection(&mut self, data: &EditorGraphicsData) {
+        let last = data.palettes.len().saturating_sub(1);
+        self.palette_list.selected_palette = self.palette_list.selected_palette.min(last);
+    }
+
     pub fn selected_palette_mut(&mut self) -> &mut usize {
         &mut self.palette_list.selected_palette
     }
 }
+
+impl SnapshotTarget<EditorGraphicsData> for EditorPalette {
+    fn locate(data: &mut EditorGraphicsData, index: usize) -> Option<&mut Self> {
+        data.palettes.get_mut(index)
+    }
+
+    fn list(data: &mut EditorGraphicsData) -> Option<&mut Vec<Self>> {
+        Some(&mut data.palettes)
+    }
+}
+
+impl Undoable<EditorGraphicsData> for PaletteEditor {
+    fn undo(&mut self, data: &mut EditorGraphicsData) -> bool {
+        let changed = self.history.undo(data);
+        self.clamp_selection(data);
+        changed
+    }
+
+    fn redo(&mut self, data: &mut EditorGraphicsData) -> bool {
+        let changed = self.history.redo(data);
+        self.clamp_selection(data);
+        changed
+    }
+
+    fn clear_history(&mut self) {
+        self.history.clear();
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ui::{draw_palette_preview, import_image_dialog};
+use crate::ui::{draw_palette_preview, import_image_dialog, SnapshotHistory};
 use eframe::egui::{ScrollArea, TextureId, Ui};
 use gamercade_core::{Color, Palette, PALETTE_COLORS};
 use gamercade_fs::{EditorGraphicsData, EditorPalette};
@@ -46,7 +46,12 @@ impl PaletteList {
     }
 
     // Draws the buttons
-    pub(crate) fn draw_buttons(&mut self, ui: &mut Ui, data: &mut EditorGraphicsData) {
+    pub(crate) fn draw_buttons(
+        &mut self,
+        ui: &mut Ui,
+        data: &mut EditorGraphicsData,
+        history: &mut SnapshotHistory<EditorPalette>,
+    ) {
         let index = self.selected_palette;
 
         ui.horizontal(|ui| {
@@ -57,17 +62,18 @@ impl PaletteList {
                     if count == u8::MAX as usize + 1 {
                         println!("Max of 256 Palettes");
                     } else {
-                        data.palettes.push(EditorPalette {
+                        let palette = EditorPalette {
                             name: format!("Palette {}", count),
                             palette: Palette::default(),
-                        })
+                        };
+                        history.push(&mut data.palettes, palette);
                     }
                 };
                 let btn_delete = ui.button("Delete");
 
                 if btn_delete.clicked() {
                     if data.palettes.len() != 1 {
-                        data.palettes.remove(index);
+                        history.remove(&mut data.palettes, index);
 
                         if index == data.palettes.len() {
                             self.selected_palette = index - 1;
@@ -89,7 +95,7 @@ impl PaletteList {
                         match try_load_palette() {
                             Ok(loaded) => {
                                 let new_index = index + 1;
-                                data.palettes.insert(new_index, loaded);
+                                history.insert(&mut data.palettes, new_index, loaded);
                                 self.selected_palette = new_index;
                             }
                             Err(e) => println!("{}", e),
@@ -105,7 +111,7 @@ impl PaletteList {
                         cloned.name = format!("{} Copy", cloned.name);
 
                         let new_index = index + 1;
-                        data.palettes.insert(new_index, cloned);
+                        history.insert(&mut data.palettes, new_index, cloned);
                         self.selected_palette = new_index;
                     }
                 };
@@ -116,12 +122,12 @@ impl PaletteList {
                 let btn_down = ui.button("Down");
 
                 if btn_up.clicked() && index != 0 {
-                    data.palettes.swap(index, index - 1);
+                    history.swap(&mut data.palettes, index, index - 1);
                     self.selected_palette = index - 1;
                 }
 
                 if btn_down.clicked() && index != data.palettes.len() - 1 {
-                    data.palettes.swap(index, index + 1);
+                    history.swap(&mut data.palettes, index, index + 1);
                     self.selected_palette = index + 1;
                 }
             });
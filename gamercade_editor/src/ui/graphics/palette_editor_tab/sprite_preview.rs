@@ -1,7 +1,7 @@
-use eframe::egui::{ColorImage, Image, ScrollArea, TextureHandle, Ui, Vec2};
+use eframe::egui::{Image, ScrollArea, TextureHandle, Ui, Vec2};
 use gamercade_core::{ColorIndex, Palette, SpriteIndex, SpriteSheet};
 
-use crate::ui::load_buffered_image;
+use crate::ui::{load_buffered_image, sprite_to_image};
 
 #[derive(Clone)]
 pub struct SpritePreview {
@@ -83,15 +83,12 @@ fn add_image(
 ) {
     ui.group(|ui| {
         ui.label(entry.label);
-        entry.rgb_buffer.clear();
-
-        sprite.iter().for_each(|color_index| {
-            let rgba = palette[*color_index].into_pixel_data();
-            entry.rgb_buffer.extend(rgba);
-        });
-
-        let rgb =
-            ColorImage::from_rgba_unmultiplied([sheet.width, sheet.height], &entry.rgb_buffer);
+        let rgb = sprite_to_image(
+            &mut entry.rgb_buffer,
+            sprite,
+            (sheet.width, sheet.height),
+            palette,
+        );
 
         let image = load_buffered_image(ui, &mut entry.texture_handle, entry.label, rgb);
 
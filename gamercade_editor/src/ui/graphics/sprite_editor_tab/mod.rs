@@ -1,10 +1,292 @@
-use eframe::egui::Ui;
+// Own imports
+mod sprite_edit;
 
-#[derive(Debug, Clone, Default)]
-pub struct SpriteEditor {}
+use sprite_edit::SpriteEdit;
+
+// Externals
+use eframe::egui::{
+    Color32, Image, ImageButton, ScrollArea, Sense, TextureHandle, TextureId, Ui, Vec2,
+};
+
+use super::{PaletteEditor, SpriteSheetEditor};
+use crate::ui::{load_buffered_image, sprite_to_image, History, Undoable};
+use gamercade_core::{ColorIndex, Palette, PALETTE_COLORS};
+use gamercade_fs::EditorGraphicsData;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum SpriteTool {
+    /// Paints each pixel under the cursor.
+    #[default]
+    Pencil,
+
+    /// Fills the area of matching color around the clicked pixel.
+    Fill,
+
+    /// Selects the color of the clicked pixel.
+    Eyedropper,
+}
+
+#[derive(Clone, Default)]
+pub struct SpriteEditor {
+    selected_color: ColorIndex,
+    tool: SpriteTool,
+    history: History<SpriteEdit>,
+
+    /// The edit being painted, which is added to the history once released.
+    stroke: Option<SpriteEdit>,
+
+    rgb_buffer: Vec<u8>,
+    texture_handle: Option<TextureHandle>,
+}
 
 impl SpriteEditor {
-    pub fn draw(&mut self, _ui: &mut Ui) {
-        // TODO: Write this!
+    pub fn draw(
+        &mut self,
+        ui: &mut Ui,
+        data: &mut EditorGraphicsData,
+        sprite_sheet_editor: &SpriteSheetEditor,
+        palette_editor: &mut PaletteEditor,
+        scale: f32,
+        texture_id: TextureId,
+    ) {
+        let palette = &data.palettes[*palette_editor.selected_palette_mut()].palette;
+        let sheet_index = sprite_sheet_editor.selected_sheet();
+        let sprite_index = sprite_sheet_editor.selected_sprite();
+        let sheet = &mut data.sprite_sheets[sheet_index.0 as usize].sprite_sheet;
+
+        ui.horizontal(|ui| {
+            self.draw_color_picker(ui, palette, texture_id);
+
+            ui.group(|ui| {
+                ui.vertical(|ui| {
+                    ui.label("Sprite Editor");
+                    ui.label("Ctrl+Z to undo, Ctrl+Shift+Z to redo. Alt+Click to pick a color.");
+
+                    ui.horizontal(|ui| {
+                        ui.label("Tool:");
+                        ui.selectable_value(&mut self.tool, SpriteTool::Pencil, "Pencil");
+                        ui.selectable_value(&mut self.tool, SpriteTool::Fill, "Fill");
+                        ui.selectable_value(&mut self.tool, SpriteTool::Eyedropper, "Eyedropper");
+                    });
+
+                    let range = sheet.get_indices(sprite_index);
+                    let (width, height) = (sheet.width, sheet.height);
+                    let sprite = &mut sheet.sprites[range];
+
+                    let rgb =
+                        sprite_to_image(&mut self.rgb_buffer, sprite, (width, height), palette);
+                    let image =
+                        load_buffered_image(ui, &mut self.texture_handle, "sprite editor", rgb)
+                            .id();
+
+                    ScrollArea::both().show(ui, |ui| {
+                        let size = Vec2 {
+                            x: width as f32 * scale,
+                            y: height as f32 * scale,
+                        };
+                        let response = ui.add(Image::new(image, size).sense(Sense::drag()));
+                        let rect = response.rect;
+
+                        let painting = response.is_pointer_button_down_on()
+                            && ui.input().pointer.primary_down();
+
+                        let pixel = match response.interact_pointer_pos() {
+                            Some(pos) if painting => {
+                                let x = ((pos.x - rect.min.x) / scale) as usize;
+                                let y = ((pos.y - rect.min.y) / scale) as usize;
+                                (x < width && y < height).then_some(x + (y * width))
+                            }
+                            _ => None,
+                        };
+
+                        // Holding alt picks colors with any tool
+                        let picking =
+                            self.tool == SpriteTool::Eyedropper || ui.input().modifiers.alt;
+
+                        match (self.tool, pixel) {
+                            (_, Some(index)) if picking => self.selected_color = sprite[index],
+                            (SpriteTool::Pencil, Some(index)) => {
+                                let stroke = match &mut self.stroke {
+                                    Some(stroke) if stroke.is_for(sheet_index, sprite_index) => {
+                                        stroke
+                                    }
+                                    stroke => {
+                                        stroke.insert(SpriteEdit::new(sheet_index, sprite_index))
+                                    }
+                                };
+
+                                stroke.record(index, sprite[index], self.selected_color);
+                                sprite[index] = self.selected_color;
+                            }
+                            (SpriteTool::Fill, Some(index)) if response.drag_started() => {
+                                let mut edit = SpriteEdit::new(sheet_index, sprite_index);
+
+                                flood_fill(sprite, width, index)
+                                    .into_iter()
+                                    .for_each(|index| {
+                                        edit.record(index, sprite[index], self.selected_color);
+                                        sprite[index] = self.selected_color;
+                                    });
+
+                                self.history.push(edit);
+                            }
+                            (_, None) => {
+                                self.finish_stroke();
+                            }
+                            _ => (),
+                        }
+                    });
+                });
+            });
+        });
+    }
+
+    /// Finishes any stroke in progress, so it can be undone too.
+    fn finish_stroke(&mut self) {
+        if let Some(stroke) = self.stroke.take() {
+            self.history.push(stroke);
+        }
+    }
+
+    fn draw_color_picker(&mut self, ui: &mut Ui, palette: &Palette, texture_id: TextureId) {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label(format!("Color: {}", self.selected_color.0));
+
+                ui.horizontal(|ui| {
+                    ui.spacing_mut().item_spacing = Vec2 { x: 0.0, y: 0.0 };
+
+                    (0..PALETTE_COLORS / 8).for_each(|x| {
+                        ui.vertical(|ui| {
+                            (0..8).for_each(|y| {
+                                let index = x + (y * 8);
+                                let color = palette.colors[index];
+                                let image_button =
+                                    ImageButton::new(texture_id, Vec2 { x: 24.0, y: 24.0 })
+                                        .selected(index == self.selected_color.0 as usize)
+                                        .tint(Color32::from_rgba_unmultiplied(
+                                            color.r, color.g, color.b, color.a,
+                                        ));
+                                if ui.add(image_button).clicked() {
+                                    self.selected_color = ColorIndex(index as u8)
+                                };
+                            });
+                        });
+                    })
+                });
+            });
+        });
+    }
+}
+
+impl Undoable<EditorGraphicsData> for SpriteEditor {
+    fn undo(&mut self, data: &mut EditorGraphicsData) -> bool {
+        self.finish_stroke();
+        self.history.undo(data)
+    }
+
+    fn redo(&mut self, data: &mut EditorGraphicsData) -> bool {
+        self.finish_stroke();
+        self.history.redo(data)
+    }
+
+    fn clear_history(&mut self) {
+        self.stroke = None;
+        self.history.clear();
+    }
+}
+
+/// Returns every pixel connected to the start pixel by the same color,
+/// including the start pixel. Pixels only connect to their four neighbors.
+fn flood_fill(sprite: &[ColorIndex], width: usize, start: usize) -> Vec<usize> {
+    let target = sprite[start];
+    let mut visited = vec![false; sprite.len()];
+    let mut pending = vec![start];
+    let mut filled = Vec::new();
+
+    visited[start] = true;
+
+    while let Some(index) = pending.pop() {
+        filled.push(index);
+
+        let (x, y) = (index % width, index / width);
+        let neighbors = [
+            (x > 0).then(|| index - 1),
+            (x + 1 < width).then_some(index + 1),
+            (y > 0).then(|| index - width),
+            Some(index + width).filter(|below| *below < sprite.len()),
+        ];
+
+        neighbors.into_iter().flatten().for_each(|neighbor| {
+            if !visited[neighbor] && sprite[neighbor] == target {
+                visited[neighbor] = true;
+                pending.push(neighbor);
+            }
+        });
+    }
+
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use gamercade_core::{SpriteIndex, SpriteSheetIndex};
+
+    use super::*;
+    use crate::ui::Command;
+
+    /// Builds a sprite from rows of color indices.
+    fn sprite(rows: &[&[u8]]) -> Vec<ColorIndex> {
+        rows.iter()
+            .flat_map(|row| row.iter().map(|color| ColorIndex(*color)))
+            .collect()
+    }
+
+    fn sorted_fill(sprite: &[ColorIndex], width: usize, start: usize) -> Vec<usize> {
+        let mut filled = flood_fill(sprite, width, start);
+        filled.sort_unstable();
+        filled
+    }
+
+    #[test]
+    fn test_flood_fill_stays_in_bounded_region() {
+        let sprite = sprite(&[
+            &[0, 0, 1, 0], //
+            &[0, 1, 0, 0],
+            &[1, 0, 0, 1],
+        ]);
+
+        // The corner region is walled off, and diagonals don't connect.
+        assert_eq!(sorted_fill(&sprite, 4, 0), [0, 1, 4]);
+        assert_eq!(sorted_fill(&sprite, 4, 6), [3, 6, 7, 9, 10]);
+    }
+
+    #[test]
+    fn test_flood_fill_from_edge() {
+        let sprite = sprite(&[
+            &[2, 2, 0], //
+            &[0, 2, 2],
+            &[2, 2, 2],
+        ]);
+
+        // Doesn't wrap from the end of one row onto the start of the next.
+        assert_eq!(sorted_fill(&sprite, 3, 2), [2]);
+        assert_eq!(sorted_fill(&sprite, 3, 3), [3]);
+        assert_eq!(sorted_fill(&sprite, 3, 8), [0, 1, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_flood_fill_same_color_is_no_op() {
+        let sprite = sprite(&[
+            &[3, 3], //
+            &[3, 3],
+        ]);
+
+        let mut edit = SpriteEdit::new(SpriteSheetIndex(0), SpriteIndex(0));
+        flood_fill(&sprite, 2, 0)
+            .into_iter()
+            .for_each(|index| edit.record(index, sprite[index], ColorIndex(3)));
+
+        assert!(edit.is_empty());
     }
 }
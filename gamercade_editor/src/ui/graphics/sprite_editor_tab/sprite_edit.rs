@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use gamercade_core::{ColorIndex, SpriteIndex, SpriteSheetIndex};
+use gamercade_fs::EditorGraphicsData;
+
+use crate::ui::Command;
+
+/// A single pixel which was changed by an edit.
+#[derive(Debug, Clone, Copy)]
+struct PixelChange {
+    index: usize,
+    before: ColorIndex,
+    after: ColorIndex,
+}
+
+/// All of the pixels changed by one stroke, within one sprite.
+#[derive(Debug, Clone)]
+pub(super) struct SpriteEdit {
+    sheet: SpriteSheetIndex,
+    sprite: SpriteIndex,
+    pixels: Vec<PixelChange>,
+
+    /// Where each changed pixel is in `pixels`, so long strokes and large
+    /// fills don't search every change recorded so far.
+    positions: HashMap<usize, usize>,
+}
+
+impl SpriteEdit {
+    pub(super) fn new(sheet: SpriteSheetIndex, sprite: SpriteIndex) -> Self {
+        Self {
+            sheet,
+            sprite,
+            pixels: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    pub(super) fn is_for(&self, sheet: SpriteSheetIndex, sprite: SpriteIndex) -> bool {
+        self.sheet == sheet && self.sprite == sprite
+    }
+
+    /// Records a pixel change. Pixels changed more than once keep their
+    /// original color, so undoing restores the sprite from before the stroke.
+    pub(super) fn record(&mut self, index: usize, before: ColorIndex, after: ColorIndex) {
+        match self.positions.get(&index) {
+            Some(position) => self.pixels[*position].after = after,
+            None => {
+                self.positions.insert(index, self.pixels.len());
+                self.pixels.push(PixelChange {
+                    index,
+                    before,
+                    after,
+                });
+            }
+        }
+    }
+}
+
+impl Command<EditorGraphicsData> for SpriteEdit {
+    /// Writes either the before or after colors back into the sprite. Edits to
+    /// sprites which no longer exist, or have since been resized, are ignored.
+    fn apply(&self, data: &mut EditorGraphicsData, undo: bool) -> bool {
+        let sheet = match data.sprite_sheets.get_mut(self.sheet.0 as usize) {
+            Some(sheet) => &mut sheet.sprite_sheet,
+            None => return false,
+        };
+
+        if self.sprite.0 >= sheet.count {
+            return false;
+        }
+
+        let range = sheet.get_indices(self.sprite);
+        let sprite = &mut sheet.sprites[range];
+
+        if self.pixels.iter().any(|pixel| pixel.index >= sprite.len()) {
+            return false;
+        }
+
+        self.pixels.iter().for_each(|pixel| {
+            sprite[pixel.index] = if undo { pixel.before } else { pixel.after };
+        });
+
+        true
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pixels.iter().all(|pixel| pixel.before == pixel.after)
+    }
+}
@@ -7,7 +7,7 @@ mod sprite_sheet_importer;
 
 use std::{fmt::Display, str::FromStr};
 
-use gamercade_core::{ColorIndex, Palette, SpriteIndex, SpriteSheetIndex};
+use gamercade_core::{ColorIndex, Palette, SpriteIndex, SpriteSheet, SpriteSheetIndex};
 use hashbrown::HashMap;
 use palette_preview::PalettePreview;
 use sheet_editor::SheetEditor;
@@ -18,7 +18,8 @@ use sheet_settings::SheetSettings;
 use eframe::egui::{TextEdit, TextureId, Ui};
 
 use super::PaletteEditor;
-use gamercade_fs::EditorGraphicsData;
+use crate::ui::{SnapshotHistory, SnapshotTarget, Undoable};
+use gamercade_fs::{EditorGraphicsData, EditorSpriteSheet};
 
 #[derive(Clone, Default)]
 pub struct SpriteSheetEditor {
@@ -26,6 +27,9 @@ pub struct SpriteSheetEditor {
     settings: SheetSettings,
     editor: SheetEditor,
     palette_preview: PalettePreview,
+
+    /// Each edit is to a single sheet, or adds, removes or moves one.
+    history: SnapshotHistory<EditorSpriteSheet>,
 }
 
 impl SpriteSheetEditor {
@@ -37,10 +41,14 @@ impl SpriteSheetEditor {
         scale: f32,
         texture_id: TextureId,
     ) {
+        self.history
+            .begin(data, self.list.selected_sheet.0 as usize);
+
         ui.horizontal(|ui| {
             let selected_palette = palette_editor.selected_palette_mut();
             let palette = &data.palettes[*selected_palette].palette;
-            self.list.draw(ui, &mut data.sprite_sheets, palette);
+            self.list
+                .draw(ui, &mut data.sprite_sheets, palette, &mut self.history);
 
             let sheet = &mut data.sprite_sheets[self.list.selected_sheet.0 as usize];
 
@@ -52,6 +60,9 @@ impl SpriteSheetEditor {
                     .draw(ui, &data.palettes, selected_palette, texture_id);
             });
         });
+
+        self.history
+            .end(ui, data, self.list.selected_sheet.0 as usize);
     }
 
     pub fn selected_sheet(&self) -> SpriteSheetIndex {
@@ -61,6 +72,79 @@ impl SpriteSheetEditor {
     pub fn selected_sprite(&self) -> SpriteIndex {
         self.editor.selected_sprite
     }
+
+    /// Keeps the selected sheet and sprite in range, such as after undoing a new one.
+    fn clamp_selection(&mut self, data: &EditorGraphicsData) {
+        let last_sheet = data.sprite_sheets.len().saturating_sub(1);
+        let sheet = (self.list.selected_sheet.0 as usize).min(last_sheet);
+        self.list.selected_sheet = SpriteSheetIndex(sheet as u8);
+
+        let last_sprite = data.sprite_sheets[sheet]
+            .sprite_sheet
+            .count
+            .saturating_sub(1);
+        self.editor.selected_sprite.0 = self.editor.selected_sprite.0.min(last_sprite);
+    }
+}
+
+impl SnapshotTarget<EditorGraphicsData> for EditorSpriteSheet {
+    fn locate(data: &mut EditorGraphicsData, index: usize) -> Option<&mut Self> {
+        data.sprite_sheets.get_mut(index)
+    }
+
+    fn list(data: &mut EditorGraphicsData) -> Option<&mut Vec<Self>> {
+        Some(&mut data.sprite_sheets)
+    }
+}
+
+impl Undoable<EditorGraphicsData> for SpriteSheetEditor {
+    fn undo(&mut self, data: &mut EditorGraphicsData) -> bool {
+        let changed = self.history.undo(data);
+        self.clamp_selection(data);
+        changed
+    }
+
+    fn redo(&mut self, data: &mut EditorGraphicsData) -> bool {
+        let changed = self.history.redo(data);
+        self.clamp_selection(data);
+        changed
+    }
+
+    fn clear_history(&mut self) {
+        self.history.clear();
+    }
+}
+
+/// Lays the sprites out in a grid, as close to square as possible,
+/// using the palette to color them in. Any unused cells at the end are left
+/// transparent, and are dropped again when the atlas is imported without
+/// keeping empty frames.
+pub(crate) fn sheet_to_atlas(sheet: &SpriteSheet, palette: &Palette) -> image::RgbaImage {
+    let count = sheet.count as usize;
+    let columns = (count as f32).sqrt().ceil().max(1.0) as usize;
+    let rows = count.div_ceil(columns).max(1);
+
+    let mut atlas =
+        image::RgbaImage::new((columns * sheet.width) as u32, (rows * sheet.height) as u32);
+
+    sheet
+        .iter_sprites()
+        .enumerate()
+        .for_each(|(index, sprite)| {
+            let left = (index % columns) * sheet.width;
+            let top = (index / columns) * sheet.height;
+
+            sprite.iter().enumerate().for_each(|(pixel, color_index)| {
+                let color = palette.colors[color_index.0 as usize];
+                atlas.put_pixel(
+                    (left + pixel % sheet.width) as u32,
+                    (top + pixel / sheet.width) as u32,
+                    image::Rgba([color.r, color.g, color.b, color.a]),
+                );
+            });
+        });
+
+    atlas
 }
 
 pub(crate) fn palette_to_map(palette: &Palette) -> HashMap<image::Rgba<u8>, ColorIndex> {
@@ -1,10 +1,10 @@
 use eframe::egui::Ui;
 use gamercade_core::{Palette, SpriteSheet, SpriteSheetIndex};
 
-use crate::ui::import_image_dialog;
+use crate::ui::{export_image_dialog, import_image_dialog, SnapshotHistory};
 use gamercade_fs::EditorSpriteSheet;
 
-use super::sprite_sheet_importer::SpriteSheetImporter;
+use super::{sheet_to_atlas, sprite_sheet_importer::SpriteSheetImporter};
 
 #[derive(Debug, Clone, Default)]
 pub struct SheetList {
@@ -19,8 +19,9 @@ impl SheetList {
         ui: &mut Ui,
         data: &mut Vec<EditorSpriteSheet>,
         palette: &Palette,
+        history: &mut SnapshotHistory<EditorSpriteSheet>,
     ) {
-        self.importer.draw(ui, data, palette);
+        self.importer.draw(ui, data, palette, history);
 
         let index = self.selected_sheet;
 
@@ -47,10 +48,11 @@ impl SheetList {
                     ui.horizontal(|ui| {
                         if ui.button("New").clicked() {
                             let count = data.len() + 1;
-                            data.push(EditorSpriteSheet {
+                            let sheet = EditorSpriteSheet {
                                 name: format!("Sprite Sheet {}", count),
                                 sprite_sheet: SpriteSheet::default(),
-                            })
+                            };
+                            history.push(data, sheet);
                         };
 
                         if ui.button("Import Sprite Sheet").clicked() {
@@ -60,9 +62,20 @@ impl SheetList {
                             }
                         };
 
+                        if ui.button("Export PNG").clicked() {
+                            let sheet = &data[index.0 as usize];
+                            let atlas = sheet_to_atlas(&sheet.sprite_sheet, palette);
+
+                            if let Err(e) =
+                                export_image_dialog("Export Sprite Sheet...", &sheet.name, &atlas)
+                            {
+                                println!("{}", e)
+                            }
+                        };
+
                         if ui.button("Delete").clicked() {
                             if data.len() != 1 {
-                                data.remove(index.0 as usize);
+                                history.remove(data, index.0 as usize);
 
                                 if index.0 as usize == data.len() {
                                     self.selected_sheet = SpriteSheetIndex(index.0 - 1);
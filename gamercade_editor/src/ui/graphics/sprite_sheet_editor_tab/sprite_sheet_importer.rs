@@ -1,10 +1,11 @@
 use eframe::egui::{Ui, Window};
-use gamercade_core::{Palette, SpriteSheet};
+use gamercade_core::{ColorIndex, Palette, SpriteSheet};
 use image::{ImageBuffer, Rgba};
 
 use gamercade_fs::EditorSpriteSheet;
 
 use super::{palette_to_map, typed_text_entry};
+use crate::ui::SnapshotHistory;
 
 type ImageBufferBuffer = Option<(ImageBuffer<Rgba<u8>, Vec<u8>>, String)>;
 
@@ -37,6 +38,7 @@ impl SpriteSheetImporter {
         ui: &mut Ui,
         data: &mut Vec<EditorSpriteSheet>,
         palette: &Palette,
+        history: &mut SnapshotHistory<EditorSpriteSheet>,
     ) {
         let mut done = false;
 
@@ -104,10 +106,11 @@ impl SpriteSheetImporter {
                                 self.keep_empty_frames,
                             ) {
                                 Ok(new_sheet) => {
-                                    data.push(EditorSpriteSheet {
+                                    let sheet = EditorSpriteSheet {
                                         name: image.1.clone(),
                                         sprite_sheet: new_sheet,
-                                    });
+                                    };
+                                    history.push(data, sheet);
                                     done = true;
                                 }
                                 Err(e) => println!("{}", e),
@@ -144,6 +147,14 @@ impl SheetDefinition {
     }
 }
 
+struct ImportedFrame {
+    pixels: Vec<ColorIndex>,
+    has_colors: bool,
+
+    /// Set if the frame has transparent pixels, but the palette has no transparent color.
+    unmatched_transparency: bool,
+}
+
 fn try_import_sprite_sheet(
     image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
     palette: &Palette,
@@ -178,9 +189,7 @@ fn try_import_sprite_sheet(
         .find(|(color, _)| color.0[3] == 0)
         .map(|(_, index)| *index);
 
-    let mut final_output = Vec::with_capacity(total_width * total_height);
-    let mut frame = Vec::with_capacity((definition.width * definition.height) as usize);
-    let mut frame_count = 0;
+    let mut frames = Vec::with_capacity((definition.rows * definition.columns) as usize);
 
     for row in 0..definition.rows {
         let row_offset = row * definition.height;
@@ -188,37 +197,52 @@ fn try_import_sprite_sheet(
             let column_offset = column * definition.width;
 
             // Generate the individual frame
-            let mut has_colors = false;
+            let mut frame = ImportedFrame {
+                pixels: Vec::with_capacity((definition.width * definition.height) as usize),
+                has_colors: false,
+                unmatched_transparency: false,
+            };
+
             for y in 0..definition.height {
                 for x in 0..definition.width {
                     let color = image.get_pixel(x + column_offset, y + row_offset);
-                    if let (Some(no_alpha_color), 0) = (no_alpha_color_index, color.0[3]) {
-                        frame.push(no_alpha_color);
+                    if color.0[3] == 0 {
+                        // Fully transparent pixels, such as the padding of an exported
+                        // atlas, only need a palette color if the frame is kept.
+                        frame.unmatched_transparency |= no_alpha_color_index.is_none();
+                        frame.pixels.push(no_alpha_color_index.unwrap_or_default());
                     } else if let Some(index) = colors.get(color) {
-                        has_colors = true;
-                        frame.push(*index);
+                        frame.has_colors = true;
+                        frame.pixels.push(*index);
                     } else {
                         return Err("Image contains a color not found in the palette.");
                     }
                 }
             }
 
-            // We only want to add sprites which have actual colors in them,
-            // and can remove any which are completely transparent
-            if has_colors || keep_empty_frames {
-                frame_count += 1;
+            frames.push(frame);
+        }
+    }
 
-                if frame_count > u8::MAX as usize {
-                    return Err("Sprite Sheet can only have 256 entries.");
-                }
+    // We only want to add sprites which have actual colors in them,
+    // and can remove any which are completely transparent. This also
+    // drops the padding cells at the end of an exported atlas.
+    frames.retain(|frame| frame.has_colors || keep_empty_frames);
 
-                final_output.append(&mut frame);
-            } else {
-                frame.clear()
-            }
-        }
+    if frames.iter().any(|frame| frame.unmatched_transparency) {
+        return Err("Image contains a color not found in the palette.");
     }
 
+    if frames.len() > u8::MAX as usize {
+        return Err("Sprite Sheet can only have 256 entries.");
+    }
+
+    let frame_count = frames.len();
+    let final_output = frames
+        .into_iter()
+        .flat_map(|frame| frame.pixels)
+        .collect::<Vec<_>>();
+
     Ok(SpriteSheet {
         height: definition.height as usize,
         width: definition.width as usize,
@@ -226,3 +250,111 @@ fn try_import_sprite_sheet(
         count: frame_count as u8,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use gamercade_core::{Color, PALETTE_COLORS};
+
+    use super::super::sheet_to_atlas;
+    use super::*;
+
+    fn opaque_palette() -> Palette {
+        let mut palette = Palette::default();
+        palette
+            .colors
+            .iter_mut()
+            .enumerate()
+            .for_each(|(index, color)| *color = Color::new(index as u8, 0, 0, 255));
+        palette
+    }
+
+    fn test_sheet(count: usize, width: usize, height: usize) -> SpriteSheet {
+        SpriteSheet {
+            width,
+            height,
+            sprites: (0..count * width * height)
+                .map(|pixel| ColorIndex((pixel % PALETTE_COLORS) as u8))
+                .collect(),
+            count: count as u8,
+        }
+    }
+
+    fn transparent_palette() -> Palette {
+        let mut palette = opaque_palette();
+        palette.colors[0] = Color::new(0, 0, 0, 0);
+        palette
+    }
+
+    fn round_trip(
+        sheet: &SpriteSheet,
+        palette: &Palette,
+        keep_empty_frames: bool,
+    ) -> Result<SpriteSheet, &'static str> {
+        let atlas = sheet_to_atlas(sheet, palette);
+        let mode = ImportMode::Pixels {
+            width: sheet.width as u32,
+            height: sheet.height as u32,
+        };
+        try_import_sprite_sheet(&atlas, palette, mode, keep_empty_frames)
+    }
+
+    #[test]
+    fn test_atlas_round_trip_trims_padding() {
+        // 5 sprites are laid out in a 3x2 grid, leaving one empty cell.
+        let sheet = test_sheet(5, 2, 3);
+        assert_eq!(
+            round_trip(&sheet, &opaque_palette(), false),
+            Ok(sheet.clone())
+        );
+        assert_eq!(
+            round_trip(&sheet, &transparent_palette(), false),
+            Ok(sheet.clone())
+        );
+    }
+
+    #[test]
+    fn test_atlas_round_trip_keeps_empty_frames() {
+        let sheet = test_sheet(5, 2, 3);
+
+        // The empty cell is kept as a transparent sprite.
+        let mut sprites = sheet.sprites.to_vec();
+        sprites.resize(6 * 2 * 3, ColorIndex(0));
+        let padded = SpriteSheet {
+            sprites: sprites.into_boxed_slice(),
+            count: 6,
+            ..sheet.clone()
+        };
+        assert_eq!(round_trip(&sheet, &transparent_palette(), true), Ok(padded));
+
+        // Without a transparent color, there's no color to keep it with.
+        assert!(round_trip(&sheet, &opaque_palette(), true).is_err());
+    }
+
+    #[test]
+    fn test_atlas_round_trip_full_sheet() {
+        // 255 sprites are laid out in a 16x16 grid, leaving one empty cell.
+        let sheet = test_sheet(255, 1, 1);
+        assert_eq!(
+            round_trip(&sheet, &opaque_palette(), false),
+            Ok(sheet.clone())
+        );
+
+        // Keeping the empty cell would make one sprite too many.
+        assert!(round_trip(&sheet, &transparent_palette(), true).is_err());
+    }
+
+    #[test]
+    fn test_atlas_round_trip_keeps_transparent_pixels() {
+        let palette = transparent_palette();
+        let sheet = test_sheet(3, 4, 4);
+        let atlas = sheet_to_atlas(&sheet, &palette);
+        let mode = ImportMode::RowsCols {
+            columns: 2,
+            rows: 2,
+        };
+        assert_eq!(
+            try_import_sprite_sheet(&atlas, &palette, mode, false),
+            Ok(sheet)
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use eframe::egui::{
+    ColorImage, ComboBox, DragValue, Image, ScrollArea, Sense, TextureHandle, Ui, Vec2,
+};
+
+use super::{PaletteEditor, SpriteSheetEditor};
+use crate::ui::{load_buffered_image, SnapshotHistory, SnapshotTarget, Undoable};
+use gamercade_core::{
+    Palette, SpriteIndex, SpriteSheet, SpriteSheetIndex, Tilemap, TILEMAP_MAX_SIZE,
+};
+use gamercade_fs::{EditorGraphicsData, EditorSpriteSheet, EditorTilemap};
+
+#[derive(Clone, Default)]
+pub struct TilemapEditor {
+    selected_tilemap: usize,
+    rgb_buffer: Vec<u8>,
+    texture_handle: Option<TextureHandle>,
+
+    /// Each edit is to a single tilemap, or adds or removes one.
+    history: SnapshotHistory<EditorTilemap>,
+}
+
+impl TilemapEditor {
+    pub fn draw(
+        &mut self,
+        ui: &mut Ui,
+        data: &mut EditorGraphicsData,
+        sprite_sheet_editor: &SpriteSheetEditor,
+        palette_editor: &mut PaletteEditor,
+        scale: f32,
+    ) {
+        self.history.begin(data, self.selected_tilemap);
+
+        ui.horizontal(|ui| {
+            self.draw_list(ui, data, sprite_sheet_editor.selected_sheet());
+
+            let tilemap = match data.tilemaps.get_mut(self.selected_tilemap) {
+                Some(tilemap) => tilemap,
+                None => return,
+            };
+
+            let palette = &data.palettes[*palette_editor.selected_palette_mut()].palette;
+
+            ui.group(|ui| {
+                ui.vertical(|ui| {
+                    ui.label("Tilemap Editor");
+                    draw_settings(ui, tilemap, &data.sprite_sheets);
+
+                    let sheet_index = tilemap.tilemap.sprite_sheet.0 as usize;
+                    let sheet = match data.sprite_sheets.get(sheet_index) {
+                        Some(sheet) => &sheet.sprite_sheet,
+                        None => {
+                            ui.label("Sprite sheet doesn't exist.");
+                            return;
+                        }
+                    };
+
+                    let sprite = sprite_sheet_editor.selected_sprite();
+                    ui.label(format!("Left click to paint sprite {}.", sprite.0));
+                    ui.label("Select the sprite in the Sprite Sheets tab. Right click to erase.");
+
+                    // Sprites past the end of the sheet can't be painted, but can still be erased
+                    let sprite = Some(sprite).filter(|sprite| sprite.0 < sheet.count);
+                    self.draw_canvas(ui, &mut tilemap.tilemap, sheet, palette, sprite, scale);
+                });
+            });
+        });
+
+        self.history.end(ui, data, self.selected_tilemap);
+    }
+
+    fn draw_list(&mut self, ui: &mut Ui, data: &mut EditorGraphicsData, sheet: SpriteSheetIndex) {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Tilemap List");
+
+                ui.group(|ui| {
+                    data.tilemaps
+                        .iter()
+                        .enumerate()
+                        .for_each(|(index, tilemap)| {
+                            let is_checked = self.selected_tilemap == index;
+
+                            if ui.selectable_label(is_checked, &tilemap.name).clicked() {
+                                self.selected_tilemap = index
+                            };
+                        });
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button("New").clicked() {
+                        let count = data.tilemaps.len() + 1;
+                        let tilemap = EditorTilemap {
+                            name: format!("Tilemap {}", count),
+                            tilemap: Tilemap {
+                                sprite_sheet: sheet,
+                                ..Tilemap::default()
+                            },
+                        };
+                        self.history.push(&mut data.tilemaps, tilemap);
+                        self.selected_tilemap = data.tilemaps.len() - 1;
+                    };
+
+                    if ui.button("Delete").clicked() && self.selected_tilemap < data.tilemaps.len()
+                    {
+                        self.history
+                            .remove(&mut data.tilemaps, self.selected_tilemap);
+                        self.selected_tilemap = self
+                            .selected_tilemap
+                            .min(data.tilemaps.len().saturating_sub(1));
+                    }
+                });
+            });
+        });
+    }
+
+    fn draw_canvas(
+        &mut self,
+        ui: &mut Ui,
+        tilemap: &mut Tilemap,
+        sheet: &SpriteSheet,
+        palette: &Palette,
+        sprite: Option<SpriteIndex>,
+        scale: f32,
+    ) {
+        let image_width = tilemap.width * sheet.width;
+        let image_height = tilemap.height * sheet.height;
+
+        if image_width == 0 || image_height == 0 {
+            return;
+        }
+
+        // Empty tiles are left fully transparent
+        self.rgb_buffer.clear();
+        self.rgb_buffer.resize(image_width * image_height * 4, 0);
+
+        (0..tilemap.height).for_each(|tile_y| {
+            (0..tilemap.width).for_each(|tile_x| {
+                let sprite = match tilemap.tile(tile_x, tile_y) {
+                    Some(sprite) if sprite.0 < sheet.count => &sheet[sprite],
+                    _ => return,
+                };
+
+                sprite.iter().enumerate().for_each(|(pixel, color_index)| {
+                    let x = tile_x * sheet.width + pixel % sheet.width;
+                    let y = tile_y * sheet.height + pixel / sheet.width;
+                    let target = (x + y * image_width) * 4;
+
+                    self.rgb_buffer[target..target + 4]
+                        .copy_from_slice(&palette[*color_index].into_pixel_data());
+                });
+            });
+        });
+
+        let rgb = ColorImage::from_rgba_unmultiplied([image_width, image_height], &self.rgb_buffer);
+        let image = load_buffered_image(ui, &mut self.texture_handle, "tilemap editor", rgb).id();
+
+        ScrollArea::both().show(ui, |ui| {
+            let size = Vec2 {
+                x: image_width as f32 * scale,
+                y: image_height as f32 * scale,
+            };
+            let response = ui.add(Image::new(image, size).sense(Sense::drag()));
+
+            let tile = {
+                let input = ui.input();
+                if input.pointer.primary_down() {
+                    sprite.map(Some)
+                } else if input.pointer.secondary_down() {
+                    Some(None)
+                } else {
+                    None
+                }
+            };
+
+            if let (Some(tile), Some(pos), true) = (
+                tile,
+                response.interact_pointer_pos(),
+                response.is_pointer_button_down_on(),
+            ) {
+                let x = ((pos.x - response.rect.min.x) / scale) as usize / sheet.width;
+                let y = ((pos.y - response.rect.min.y) / scale) as usize / sheet.height;
+                tilemap.set_tile(x, y, tile);
+            }
+        });
+    }
+}
+
+fn draw_settings(ui: &mut Ui, tilemap: &mut EditorTilemap, sheets: &[EditorSpriteSheet]) {
+    ui.horizontal(|ui| {
+        ui.label("Name: ");
+        ui.text_edit_singleline(&mut tilemap.name);
+    });
+
+    let tilemap = &mut tilemap.tilemap;
+
+    let selected_name = sheets
+        .get(tilemap.sprite_sheet.0 as usize)
+        .map(|sheet| sheet.name.as_str())
+        .unwrap_or("Missing");
+
+    ComboBox::from_label("Sprite Sheet")
+        .selected_text(selected_name)
+        .show_ui(ui, |ui| {
+            sheets.iter().enumerate().for_each(|(index, sheet)| {
+                ui.selectable_value(
+                    &mut tilemap.sprite_sheet,
+                    SpriteSheetIndex(index as u8),
+                    &sheet.name,
+                );
+            });
+        });
+
+    let (mut width, mut height) = (tilemap.width, tilemap.height);
+
+    ui.horizontal(|ui| {
+        ui.label("Width:");
+        ui.add(DragValue::new(&mut width).clamp_range(1..=TILEMAP_MAX_SIZE));
+        ui.label("Height:");
+        ui.add(DragValue::new(&mut height).clamp_range(1..=TILEMAP_MAX_SIZE));
+    });
+
+    if width != tilemap.width || height != tilemap.height {
+        tilemap.resize(width, height);
+    }
+}
+
+impl SnapshotTarget<EditorGraphicsData> for EditorTilemap {
+    fn locate(data: &mut EditorGraphicsData, index: usize) -> Option<&mut Self> {
+        data.tilemaps.get_mut(index)
+    }
+
+    fn list(data: &mut EditorGraphicsData) -> Option<&mut Vec<Self>> {
+        Some(&mut data.tilemaps)
+    }
+}
+
+impl Undoable<EditorGraphicsData> for TilemapEditor {
+    fn undo(&mut self, data: &mut EditorGraphicsData) -> bool {
+        self.history.undo(data)
+    }
+
+    fn redo(&mut self, data: &mut EditorGraphicsData) -> bool {
+        self.history.redo(data)
+    }
+
+    fn clear_history(&mut self) {
+        self.history.clear();
+    }
+}
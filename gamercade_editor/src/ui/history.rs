@@ -0,0 +1,541 @@
+use std::collections::VecDeque;
+
+use eframe::egui::{Context, Key, Modifiers, Ui};
+
+/// The most edits each editor can undo.
+const HISTORY_MAX_STEPS: usize = 64;
+
+/// An edit to the editor's data, which can be undone and redone.
+pub trait Command<D: ?Sized> {
+    /// Makes the edit again, or reverses it when undoing. Returns false if what it
+    /// edited no longer exists, such as a sprite which has since been removed.
+    fn apply(&self, data: &mut D, undo: bool) -> bool;
+
+    /// Edits which don't change anything aren't worth undoing.
+    fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+/// An editor which keeps a history of its edits to the data.
+/// Ctrl+Z and Ctrl+Shift+Z are routed to the active editor.
+pub trait Undoable<D: ?Sized> {
+    fn undo(&mut self, data: &mut D) -> bool;
+    fn redo(&mut self, data: &mut D) -> bool;
+
+    /// Forgets every edit, such as when a different rom is opened.
+    fn clear_history(&mut self);
+}
+
+/// A bounded undo and redo stack of commands.
+#[derive(Debug, Clone)]
+pub struct History<C> {
+    undo: VecDeque<C>,
+    redo: Vec<C>,
+}
+
+impl<C> Default for History<C> {
+    fn default() -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+        }
+    }
+}
+
+impl<C> History<C> {
+    /// Adds a finished edit, dropping the oldest one if the history is full.
+    /// Any undone edits can no longer be redone.
+    pub fn push<D: ?Sized>(&mut self, command: C)
+    where
+        C: Command<D>,
+    {
+        if !command.is_empty() {
+            self.record(command);
+        }
+    }
+
+    fn record(&mut self, command: C) {
+        if self.undo.len() == HISTORY_MAX_STEPS {
+            self.undo.pop_front();
+        }
+
+        self.undo.push_back(command);
+        self.redo.clear();
+    }
+
+    /// Returns true if the data changed.
+    pub fn undo<D: ?Sized>(&mut self, data: &mut D) -> bool
+    where
+        C: Command<D>,
+    {
+        match self.undo.pop_back() {
+            Some(command) if command.apply(data, true) => {
+                self.redo.push(command);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if the data changed.
+    pub fn redo<D: ?Sized>(&mut self, data: &mut D) -> bool
+    where
+        C: Command<D>,
+    {
+        match self.redo.pop() {
+            Some(command) if command.apply(data, false) => {
+                self.undo.push_back(command);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+/// A piece of the data which can be edited as a whole, such as a palette,
+/// or a single instrument. Each is found by its index, if it has one.
+pub trait SnapshotTarget<D: ?Sized>: Clone + PartialEq {
+    fn locate(data: &mut D, index: usize) -> Option<&mut Self>;
+
+    /// The list the pieces are kept in, if they can be added, removed or
+    /// reordered through the [`SnapshotHistory`].
+    fn list(_data: &mut D) -> Option<&mut Vec<Self>> {
+        None
+    }
+}
+
+/// An edit recorded by a [`SnapshotHistory`].
+#[derive(Debug, Clone)]
+enum SnapshotEdit<T> {
+    /// Replaces a piece of the data with a copy of it from before or after the edit.
+    Replace { index: usize, before: T, after: T },
+
+    /// Adds a piece to the list, or removes it again when undoing.
+    Insert { index: usize, item: T },
+
+    /// Removes a piece from the list, or adds it back when undoing.
+    Remove { index: usize, item: T },
+
+    /// Swaps two pieces of the list, which is its own undo.
+    Swap { first: usize, second: usize },
+}
+
+impl<D: ?Sized, T: SnapshotTarget<D>> Command<D> for SnapshotEdit<T> {
+    fn apply(&self, data: &mut D, undo: bool) -> bool {
+        if let Self::Replace {
+            index,
+            before,
+            after,
+        } = self
+        {
+            return match T::locate(data, *index) {
+                Some(target) => {
+                    *target = if undo { before } else { after }.clone();
+                    true
+                }
+                None => false,
+            };
+        }
+
+        let list = match T::list(data) {
+            Some(list) => list,
+            None => return false,
+        };
+
+        match (self, undo) {
+            (Self::Insert { index, item }, false) | (Self::Remove { index, item }, true)
+                if *index <= list.len() =>
+            {
+                list.insert(*index, item.clone());
+                true
+            }
+            (Self::Insert { index, .. }, true) | (Self::Remove { index, .. }, false)
+                if *index < list.len() =>
+            {
+                list.remove(*index);
+                true
+            }
+            (Self::Swap { first, second }, _) if *first.max(second) < list.len() => {
+                list.swap(*first, *second);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Replace { before, after, .. } => before == after,
+            Self::Swap { first, second } => first == second,
+            Self::Insert { .. } | Self::Remove { .. } => false,
+        }
+    }
+}
+
+/// The history of an editor which doesn't record its edits as it makes them. Instead,
+/// the data is compared to a copy from before the editor was drawn, and any change is
+/// recorded as a snapshot. Changes made while dragging or typing are grouped into one.
+/// Only the piece being edited is copied, so pieces kept in a list are added, removed
+/// and reordered through the history instead, which records just what moved.
+#[derive(Debug, Clone)]
+pub struct SnapshotHistory<T> {
+    history: History<SnapshotEdit<T>>,
+
+    /// The piece of the data as of the last recorded edit, along with its index.
+    last: Option<(usize, T)>,
+
+    /// Set while an edit is in progress, which is recorded once it's done.
+    editing: bool,
+}
+
+impl<T> Default for SnapshotHistory<T> {
+    fn default() -> Self {
+        Self {
+            history: History::default(),
+            last: None,
+            editing: false,
+        }
+    }
+}
+
+impl<T> SnapshotHistory<T> {
+    /// Called before the editor is drawn. Changes made elsewhere, such as
+    /// by a different editor, aren't recorded here. The data is only copied
+    /// again if it was changed elsewhere, or a different piece is being edited.
+    pub fn begin<D: ?Sized>(&mut self, data: &mut D, index: usize)
+    where
+        T: SnapshotTarget<D>,
+    {
+        if self.editing {
+            return;
+        }
+
+        let changed = match (&self.last, T::locate(data, index)) {
+            (Some((last_index, last)), Some(current)) => *last_index != index || last != current,
+            (None, None) => false,
+            _ => true,
+        };
+
+        if changed {
+            self.sync(data, index);
+        }
+    }
+
+    /// Called after the editor is drawn, recording what it changed.
+    pub fn end<D: ?Sized>(&mut self, ui: &Ui, data: &mut D, index: usize)
+    where
+        T: SnapshotTarget<D>,
+    {
+        self.record_changes(data, index, || {
+            ui.input().pointer.any_down() || ui.ctx().wants_keyboard_input()
+        });
+    }
+
+    /// Records what changed since the last copy, unless the edit is still in progress.
+    fn record_changes<D: ?Sized>(
+        &mut self,
+        data: &mut D,
+        index: usize,
+        still_editing: impl FnOnce() -> bool,
+    ) where
+        T: SnapshotTarget<D>,
+    {
+        let current = match T::locate(data, index) {
+            Some(current) => current,
+            None => return self.clear_last(),
+        };
+
+        let before = match &self.last {
+            Some((last_index, before)) if *last_index == index => before,
+            _ => return self.sync(data, index),
+        };
+
+        if before == current {
+            self.editing = false;
+            return;
+        }
+
+        self.editing = still_editing();
+        if self.editing {
+            return;
+        }
+
+        if let Some((_, before)) = self.last.take() {
+            self.history.push::<D>(SnapshotEdit::Replace {
+                index,
+                before,
+                after: current.clone(),
+            });
+        }
+        self.last = Some((index, current.clone()));
+    }
+
+    /// Takes a new copy of the data, so only changes after this are recorded.
+    fn sync<D: ?Sized>(&mut self, data: &mut D, index: usize)
+    where
+        T: SnapshotTarget<D>,
+    {
+        self.editing = false;
+        self.last = T::locate(data, index).map(|current| (index, current.clone()));
+    }
+
+    fn clear_last(&mut self) {
+        self.editing = false;
+        self.last = None;
+    }
+
+    /// Adds the piece to the list at the index, so it can be undone.
+    pub fn insert(&mut self, list: &mut Vec<T>, index: usize, item: T)
+    where
+        T: Clone,
+    {
+        self.clear_last();
+        list.insert(index, item.clone());
+        self.history.record(SnapshotEdit::Insert { index, item });
+    }
+
+    /// Adds the piece to the end of the list, so it can be undone.
+    pub fn push(&mut self, list: &mut Vec<T>, item: T)
+    where
+        T: Clone,
+    {
+        self.insert(list, list.len(), item);
+    }
+
+    /// Removes the piece from the list, so it can be undone.
+    pub fn remove(&mut self, list: &mut Vec<T>, index: usize) {
+        if index < list.len() {
+            self.clear_last();
+            let item = list.remove(index);
+            self.history.record(SnapshotEdit::Remove { index, item });
+        }
+    }
+
+    /// Swaps two pieces of the list, so it can be undone.
+    pub fn swap(&mut self, list: &mut [T], first: usize, second: usize) {
+        if first != second && first.max(second) < list.len() {
+            self.clear_last();
+            list.swap(first, second);
+            self.history.record(SnapshotEdit::Swap { first, second });
+        }
+    }
+
+    pub fn undo<D: ?Sized>(&mut self, data: &mut D) -> bool
+    where
+        T: SnapshotTarget<D>,
+    {
+        self.clear_last();
+        self.history.undo(data)
+    }
+
+    pub fn redo<D: ?Sized>(&mut self, data: &mut D) -> bool
+    where
+        T: SnapshotTarget<D>,
+    {
+        self.clear_last();
+        self.history.redo(data)
+    }
+
+    pub fn clear(&mut self) {
+        self.clear_last();
+        self.history.clear();
+    }
+}
+
+/// Takes Ctrl+Z and Ctrl+Shift+Z from the input, so editors don't also treat them as
+/// their own hotkeys. Returns whether to undo or redo. Text fields keep their own undo.
+pub(crate) fn take_undo_redo_keys(ctx: &Context) -> (bool, bool) {
+    if ctx.wants_keyboard_input() {
+        return (false, false);
+    }
+
+    let mut input = ctx.input_mut();
+    let undo = input.consume_key(Modifiers::COMMAND, Key::Z);
+    let redo = input.consume_key(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z);
+    (undo, redo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Adds to the item at the index, or subtracts when undoing.
+    struct Add {
+        index: usize,
+        amount: i32,
+    }
+
+    impl Command<Vec<i32>> for Add {
+        fn apply(&self, data: &mut Vec<i32>, undo: bool) -> bool {
+            match data.get_mut(self.index) {
+                Some(item) => {
+                    *item += if undo { -self.amount } else { self.amount };
+                    true
+                }
+                None => false,
+            }
+        }
+
+        fn is_empty(&self) -> bool {
+            self.amount == 0
+        }
+    }
+
+    fn add(history: &mut History<Add>, data: &mut Vec<i32>, index: usize, amount: i32) {
+        let command = Add { index, amount };
+        command.apply(data, false);
+        history.push(command);
+    }
+
+    impl SnapshotTarget<Vec<i32>> for i32 {
+        fn locate(data: &mut Vec<i32>, index: usize) -> Option<&mut Self> {
+            data.get_mut(index)
+        }
+
+        fn list(data: &mut Vec<i32>) -> Option<&mut Vec<Self>> {
+            Some(data)
+        }
+    }
+
+    /// Draws a frame of an editor which sets the item at the index.
+    fn edit(
+        history: &mut SnapshotHistory<i32>,
+        data: &mut Vec<i32>,
+        index: usize,
+        value: i32,
+        still_editing: bool,
+    ) {
+        history.begin(data, index);
+        data[index] = value;
+        history.record_changes(data, index, || still_editing);
+    }
+
+    #[test]
+    fn test_push_undo_redo() {
+        let mut history = History::default();
+        let mut data = vec![0];
+
+        add(&mut history, &mut data, 0, 1);
+        add(&mut history, &mut data, 0, 2);
+        add(&mut history, &mut data, 0, 0);
+        assert_eq!(data, [3]);
+
+        assert!(history.undo(&mut data));
+        assert_eq!(data, [1]);
+        assert!(history.undo(&mut data));
+        assert_eq!(data, [0]);
+        assert!(!history.undo(&mut data));
+
+        assert!(history.redo(&mut data));
+        assert!(history.redo(&mut data));
+        assert_eq!(data, [3]);
+        assert!(!history.redo(&mut data));
+    }
+
+    #[test]
+    fn test_push_clears_redo() {
+        let mut history = History::default();
+        let mut data = vec![0];
+
+        add(&mut history, &mut data, 0, 1);
+        add(&mut history, &mut data, 0, 2);
+        history.undo(&mut data);
+        add(&mut history, &mut data, 0, 4);
+
+        assert!(!history.redo(&mut data));
+        assert_eq!(data, [5]);
+    }
+
+    #[test]
+    fn test_full_history_drops_oldest() {
+        let mut history = History::default();
+        let mut data = vec![0];
+
+        (0..=HISTORY_MAX_STEPS).for_each(|_| add(&mut history, &mut data, 0, 1));
+        while history.undo(&mut data) {}
+
+        assert_eq!(data, [1]);
+    }
+
+    #[test]
+    fn test_failed_apply_keeps_stacks() {
+        let mut history = History::default();
+        let mut data = vec![0, 0];
+
+        add(&mut history, &mut data, 0, 1);
+        add(&mut history, &mut data, 1, 1);
+        data.pop();
+
+        // The second edit's target is gone, so it's neither undone nor redoable.
+        assert!(!history.undo(&mut data));
+        assert!(!history.redo(&mut data));
+
+        assert!(history.undo(&mut data));
+        assert_eq!(data, [0]);
+        assert!(history.redo(&mut data));
+        assert_eq!(data, [1]);
+    }
+
+    #[test]
+    fn test_snapshots_group_edits_in_progress() {
+        let mut history = SnapshotHistory::default();
+        let mut data = vec![0];
+
+        edit(&mut history, &mut data, 0, 1, true);
+        edit(&mut history, &mut data, 0, 2, true);
+        edit(&mut history, &mut data, 0, 3, false);
+
+        assert!(history.undo(&mut data));
+        assert_eq!(data, [0]);
+        assert!(!history.undo(&mut data));
+
+        assert!(history.redo(&mut data));
+        assert_eq!(data, [3]);
+    }
+
+    #[test]
+    fn test_snapshots_resync_on_index_change() {
+        let mut history = SnapshotHistory::default();
+        let mut data = vec![0, 0];
+
+        edit(&mut history, &mut data, 0, 1, false);
+
+        // Changed elsewhere, so not recorded when it's selected.
+        data[1] = 5;
+        history.begin(&mut data, 1);
+        history.record_changes(&mut data, 1, || false);
+
+        // The selection changed while drawing, so the change can't be compared.
+        history.begin(&mut data, 1);
+        data[0] = 7;
+        history.record_changes(&mut data, 0, || false);
+
+        assert!(history.undo(&mut data));
+        assert_eq!(data, [0, 5]);
+        assert!(!history.undo(&mut data));
+    }
+
+    #[test]
+    fn test_snapshot_list_edits() {
+        let mut history = SnapshotHistory::default();
+        let mut data = vec![1, 2];
+
+        history.push(&mut data, 3);
+        history.swap(&mut data, 0, 2);
+        history.remove(&mut data, 1);
+        history.insert(&mut data, 0, 4);
+        assert_eq!(data, [4, 3, 1]);
+
+        (0..4).for_each(|_| assert!(history.undo(&mut data)));
+        assert_eq!(data, [1, 2]);
+
+        (0..4).for_each(|_| assert!(history.redo(&mut data)));
+        assert_eq!(data, [4, 3, 1]);
+    }
+}
@@ -1,9 +1,17 @@
 mod audio;
+mod autosave;
 mod editor;
+mod editor_settings;
 mod graphics;
+mod history;
+mod quick_play;
 mod rom_editor;
 
 pub use audio::*;
+pub use autosave::*;
 pub use editor::*;
+pub use editor_settings::*;
 pub use graphics::*;
+pub use history::*;
+pub use quick_play::*;
 pub use rom_editor::*;
@@ -0,0 +1,286 @@
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use eframe::egui::{
+    self, ColorImage, Context, Image, InputState as EguiInputState, Key, Slider, TextureFilter,
+    TextureHandle, Ui, Vec2,
+};
+use gamercade_console::console::{EmbeddedRunner, InputMode, LocalInputManager, VirtualKeyCode};
+use gamercade_core::InputState;
+use gamercade_fs::{EditorRom, RomMeta, Thumbnail};
+use gilrs::Gilrs;
+
+/// Same as the default seed used by the console.
+const QUICK_PLAY_SEED: u64 = 0xa12cade;
+
+/// The most frames we'll catch up on in a single editor update.
+const MAX_FRAMES_PER_UPDATE: usize = 4;
+
+/// Runs the current project inside the editor, without
+/// having to export it and load it in the console.
+pub struct QuickPlay {
+    runner: Option<EmbeddedRunner>,
+    error: Option<String>,
+    texture: Option<TextureHandle>,
+    input_manager: LocalInputManager,
+    gilrs: Option<Gilrs>,
+    last_update: Instant,
+    accumulated: Duration,
+    scale: usize,
+
+    /// Set to capture the next frame as the rom's thumbnail.
+    capture_thumbnail: bool,
+}
+
+impl Default for QuickPlay {
+    fn default() -> Self {
+        Self {
+            runner: None,
+            error: None,
+            texture: None,
+            input_manager: LocalInputManager::new(InputMode::Emulated),
+            gilrs: Gilrs::new().ok(),
+            last_update: Instant::now(),
+            accumulated: Duration::ZERO,
+            scale: 2,
+            capture_thumbnail: false,
+        }
+    }
+}
+
+impl QuickPlay {
+    pub fn is_running(&self) -> bool {
+        self.runner.is_some()
+    }
+
+    /// Bundles the wasm and editor rom and starts running it.
+    pub fn start(&mut self, wasm_path: &PathBuf, rom: &EditorRom) {
+        self.stop();
+
+        let result = gamercade_fs::try_load_wasm(wasm_path).and_then(|wasm| {
+            catch_unwind(AssertUnwindSafe(|| {
+                EmbeddedRunner::new(&wasm, rom, QUICK_PLAY_SEED)
+            }))
+            .unwrap_or_else(|_| Err(String::from("Game panicked while starting.")))
+        });
+
+        match result {
+            Ok(runner) => {
+                self.runner = Some(runner);
+                self.error = None;
+                self.last_update = Instant::now();
+                self.accumulated = Duration::ZERO;
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    /// Stops the game, and tears down its audio.
+    pub fn stop(&mut self) {
+        self.runner = None;
+        self.texture = None;
+    }
+
+    /// Draws the error from the last run, if any.
+    pub fn draw_error(&mut self, ctx: &Context) {
+        let mut open = self.error.is_some();
+
+        if let Some(error) = &self.error {
+            egui::Window::new("Quick Play Error")
+                .open(&mut open)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.label(error.as_str());
+                });
+        }
+
+        if !open {
+            self.error = None;
+        }
+    }
+
+    /// Advances and draws the running game. Capturing a thumbnail stores it in the metadata.
+    pub fn draw(&mut self, ui: &mut Ui, meta: &mut RomMeta) {
+        ui.horizontal(|ui| {
+            if ui.button("Stop").clicked() {
+                self.stop();
+            }
+            if ui.button("Capture Thumbnail").clicked() {
+                self.capture_thumbnail = true;
+            }
+            ui.add(Slider::new(&mut self.scale, 1..=8).text("Scale"));
+        });
+
+        let frame_time = match &self.runner {
+            Some(runner) => Duration::from_secs_f32(runner.rom().frame_rate.frame_time()),
+            None => return,
+        };
+
+        let now = Instant::now();
+        self.accumulated += now - self.last_update;
+        self.last_update = now;
+
+        let frames = ((self.accumulated.as_secs_f32() / frame_time.as_secs_f32()) as usize)
+            .min(MAX_FRAMES_PER_UPDATE);
+        self.accumulated = if frames == MAX_FRAMES_PER_UPDATE {
+            Duration::ZERO
+        } else {
+            self.accumulated.saturating_sub(frame_time * frames as u32)
+        };
+
+        let input = self.generate_input_state(&ui.input());
+        let runner = self.runner.as_mut().unwrap();
+        let (width, height) = (runner.width(), runner.height());
+
+        for _ in 0..frames {
+            let capture = self.capture_thumbnail;
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                runner.tick(&[input]).map(|buffer| {
+                    let thumbnail = capture.then(|| Thumbnail::from_rgba(width, height, buffer));
+                    (
+                        ColorImage::from_rgba_unmultiplied([width, height], buffer),
+                        thumbnail,
+                    )
+                })
+            }))
+            .unwrap_or_else(|_| Err(String::from("Game panicked while running.")));
+
+            if let Ok((_, Some(thumbnail))) = &result {
+                self.capture_thumbnail = false;
+                match thumbnail {
+                    Ok(thumbnail) => meta.thumbnail = Some(thumbnail.clone()),
+                    Err(e) => self.error = Some(e.clone()),
+                }
+            }
+
+            match result {
+                Ok((image, _)) => match &mut self.texture {
+                    Some(texture) => texture.set(image, TextureFilter::Nearest),
+                    None => {
+                        self.texture = Some(ui.ctx().load_texture(
+                            "quick play frame",
+                            image,
+                            TextureFilter::Nearest,
+                        ))
+                    }
+                },
+                Err(e) => {
+                    self.error = Some(e);
+                    self.stop();
+                    return;
+                }
+            }
+        }
+
+        if let Some(texture) = &self.texture {
+            let size = Vec2::new((width * self.scale) as f32, (height * self.scale) as f32);
+            ui.add(Image::new(texture.id(), size));
+        }
+
+        ui.ctx().request_repaint();
+    }
+
+    /// Uses the keyboard if any keys are held, otherwise the first connected gamepad.
+    fn generate_input_state(&mut self, input: &EguiInputState) -> InputState {
+        // Uses the console's key bindings, so games play the same in both.
+        let keyboard = self
+            .input_manager
+            .new_keyboard_state(|code| egui_key(code).is_some_and(|key| input.key_down(key)));
+
+        if keyboard != InputState::default() {
+            return keyboard;
+        }
+
+        if let Some(gilrs) = &mut self.gilrs {
+            // Drain the events so gilrs updates the gamepad states.
+            while gilrs.next_event().is_some() {}
+
+            if let Some((id, _)) = gilrs.gamepads().next() {
+                return self.input_manager.new_gamepad_state(id, gilrs);
+            }
+        }
+
+        keyboard
+    }
+}
+
+/// The egui key for a console key, if egui has one.
+fn egui_key(code: VirtualKeyCode) -> Option<Key> {
+    Some(match code {
+        VirtualKeyCode::A => Key::A,
+        VirtualKeyCode::B => Key::B,
+        VirtualKeyCode::C => Key::C,
+        VirtualKeyCode::D => Key::D,
+        VirtualKeyCode::E => Key::E,
+        VirtualKeyCode::F => Key::F,
+        VirtualKeyCode::G => Key::G,
+        VirtualKeyCode::H => Key::H,
+        VirtualKeyCode::I => Key::I,
+        VirtualKeyCode::J => Key::J,
+        VirtualKeyCode::K => Key::K,
+        VirtualKeyCode::L => Key::L,
+        VirtualKeyCode::M => Key::M,
+        VirtualKeyCode::N => Key::N,
+        VirtualKeyCode::O => Key::O,
+        VirtualKeyCode::P => Key::P,
+        VirtualKeyCode::Q => Key::Q,
+        VirtualKeyCode::R => Key::R,
+        VirtualKeyCode::S => Key::S,
+        VirtualKeyCode::T => Key::T,
+        VirtualKeyCode::U => Key::U,
+        VirtualKeyCode::V => Key::V,
+        VirtualKeyCode::W => Key::W,
+        VirtualKeyCode::X => Key::X,
+        VirtualKeyCode::Y => Key::Y,
+        VirtualKeyCode::Z => Key::Z,
+        VirtualKeyCode::Key0 => Key::Num0,
+        VirtualKeyCode::Key1 => Key::Num1,
+        VirtualKeyCode::Key2 => Key::Num2,
+        VirtualKeyCode::Key3 => Key::Num3,
+        VirtualKeyCode::Key4 => Key::Num4,
+        VirtualKeyCode::Key5 => Key::Num5,
+        VirtualKeyCode::Key6 => Key::Num6,
+        VirtualKeyCode::Key7 => Key::Num7,
+        VirtualKeyCode::Key8 => Key::Num8,
+        VirtualKeyCode::Key9 => Key::Num9,
+        VirtualKeyCode::Up => Key::ArrowUp,
+        VirtualKeyCode::Down => Key::ArrowDown,
+        VirtualKeyCode::Left => Key::ArrowLeft,
+        VirtualKeyCode::Right => Key::ArrowRight,
+        VirtualKeyCode::Escape => Key::Escape,
+        VirtualKeyCode::Tab => Key::Tab,
+        VirtualKeyCode::Back => Key::Backspace,
+        VirtualKeyCode::Return => Key::Enter,
+        VirtualKeyCode::Space => Key::Space,
+        VirtualKeyCode::Insert => Key::Insert,
+        VirtualKeyCode::Delete => Key::Delete,
+        VirtualKeyCode::Home => Key::Home,
+        VirtualKeyCode::End => Key::End,
+        VirtualKeyCode::PageUp => Key::PageUp,
+        VirtualKeyCode::PageDown => Key::PageDown,
+        VirtualKeyCode::F1 => Key::F1,
+        VirtualKeyCode::F2 => Key::F2,
+        VirtualKeyCode::F3 => Key::F3,
+        VirtualKeyCode::F4 => Key::F4,
+        VirtualKeyCode::F5 => Key::F5,
+        VirtualKeyCode::F6 => Key::F6,
+        VirtualKeyCode::F7 => Key::F7,
+        VirtualKeyCode::F8 => Key::F8,
+        VirtualKeyCode::F9 => Key::F9,
+        VirtualKeyCode::F10 => Key::F10,
+        VirtualKeyCode::F11 => Key::F11,
+        VirtualKeyCode::F12 => Key::F12,
+        VirtualKeyCode::F13 => Key::F13,
+        VirtualKeyCode::F14 => Key::F14,
+        VirtualKeyCode::F15 => Key::F15,
+        VirtualKeyCode::F16 => Key::F16,
+        VirtualKeyCode::F17 => Key::F17,
+        VirtualKeyCode::F18 => Key::F18,
+        VirtualKeyCode::F19 => Key::F19,
+        VirtualKeyCode::F20 => Key::F20,
+        _ => return None,
+    })
+}
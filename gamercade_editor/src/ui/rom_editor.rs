@@ -1,7 +1,7 @@
 use eframe::egui::{self, Ui};
 use gamercade_core::{
     FrameRate,
-    Resolution::{High, Low, Medium, UltraHigh, UltraLow, VeryHigh, VeryLow},
+    Resolution::{self, Custom, High, Low, Medium, UltraHigh, UltraLow, VeryHigh, VeryLow},
 };
 use gamercade_fs::EditorRom;
 
@@ -24,7 +24,33 @@ impl RomEditor {
                 ui.selectable_value(&mut rom.resolution, High, "High");
                 ui.selectable_value(&mut rom.resolution, VeryHigh, "Very High");
                 ui.selectable_value(&mut rom.resolution, UltraHigh, "Ultra High");
+
+                if ui
+                    .selectable_label(matches!(rom.resolution, Custom { .. }), "Custom")
+                    .clicked()
+                    && !matches!(rom.resolution, Custom { .. })
+                {
+                    rom.resolution = Custom {
+                        width: rom.resolution.width(),
+                        height: rom.resolution.height(),
+                    };
+                }
             });
+
+            if let Custom { width, height } = &mut rom.resolution {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(width)
+                            .clamp_range(1..=Resolution::CUSTOM_MAX.0)
+                            .prefix("Width: "),
+                    );
+                    ui.add(
+                        egui::DragValue::new(height)
+                            .clamp_range(1..=Resolution::CUSTOM_MAX.1)
+                            .prefix("Height: "),
+                    );
+                });
+            }
         });
 
         ui.group(|ui| {
@@ -57,5 +83,26 @@ impl RomEditor {
                 );
             });
         });
+
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                match &rom.meta.thumbnail {
+                    Some(thumbnail) => {
+                        ui.label(format!(
+                            "Thumbnail: {} x {}",
+                            thumbnail.width(),
+                            thumbnail.height()
+                        ));
+                        if ui.button("Clear").clicked() {
+                            rom.meta.thumbnail = None;
+                        }
+                    }
+                    None => {
+                        ui.label("Thumbnail: None");
+                    }
+                };
+            });
+            ui.label("Capture one from a running game with Game > Play.");
+        });
     }
 }
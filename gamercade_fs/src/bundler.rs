@@ -1,7 +1,7 @@
 use gamercade_audio::SoundRom;
 use gamercade_core::{FrameRate, GraphicsData, Resolution};
 
-use crate::Rom;
+use crate::{Rom, RomMeta};
 
 /// Provides .wasm game code to produce a game Rom
 pub trait GameCodeProvider {
@@ -24,6 +24,7 @@ pub trait GameAssetProvider {
     fn player_count(&self) -> (usize, usize);
     fn graphics(&self) -> GraphicsData;
     fn sounds(&self) -> SoundRom;
+    fn meta(&self) -> RomMeta;
 }
 
 /// Generates a ready-to-use Rom.
@@ -32,6 +33,9 @@ where
     C: GameCodeProvider,
     A: GameAssetProvider,
 {
+    let mut meta = asset_provider.meta();
+    meta.touch();
+
     Rom {
         resolution: asset_provider.resolution(),
         frame_rate: asset_provider.frame_rate(),
@@ -39,5 +43,6 @@ where
         graphics: asset_provider.graphics(),
         sounds: asset_provider.sounds(),
         code: code_provider.code().into(),
+        meta,
     }
 }
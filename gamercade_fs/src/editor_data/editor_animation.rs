@@ -0,0 +1,81 @@
+use gamercade_core::{SpriteIndex, SpriteSheetIndex};
+use serde::{Deserialize, Serialize};
+
+/// A sequence of sprites from a single sprite sheet, played in a loop.
+#[derive(Clone, Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct EditorAnimation {
+    pub name: String,
+    pub sprite_sheet: SpriteSheetIndex,
+    pub frames: Vec<EditorAnimationFrame>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EditorAnimationFrame {
+    pub sprite: SpriteIndex,
+
+    /// How many ticks this frame is shown for.
+    pub duration: u8,
+}
+
+impl Default for EditorAnimationFrame {
+    fn default() -> Self {
+        Self {
+            sprite: SpriteIndex::default(),
+            duration: 1,
+        }
+    }
+}
+
+impl EditorAnimation {
+    /// The total number of ticks before the animation loops.
+    pub fn length(&self) -> usize {
+        self.frames
+            .iter()
+            .map(|frame| frame.duration as usize)
+            .sum()
+    }
+
+    /// Returns the frame shown at the tick, wrapping around once the
+    /// animation has finished. Empty animations have no frames to show.
+    pub fn frame_at(&self, tick: usize) -> Option<&EditorAnimationFrame> {
+        let length = self.length();
+
+        if length == 0 {
+            return None;
+        }
+
+        let mut tick = tick % length;
+        self.frames.iter().find(|frame| {
+            if tick < frame.duration as usize {
+                true
+            } else {
+                tick -= frame.duration as usize;
+                false
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_at() {
+        let frame = |sprite, duration| EditorAnimationFrame {
+            sprite: SpriteIndex(sprite),
+            duration,
+        };
+
+        let mut animation = EditorAnimation::default();
+        assert_eq!(animation.frame_at(0), None);
+
+        animation.frames = vec![frame(0, 2), frame(1, 0), frame(2, 1)];
+        assert_eq!(animation.length(), 3);
+
+        let sprites = (0..6)
+            .map(|tick| animation.frame_at(tick).unwrap().sprite.0)
+            .collect::<Vec<_>>();
+        assert_eq!(sprites, vec![0, 0, 2, 0, 0, 2]);
+    }
+}
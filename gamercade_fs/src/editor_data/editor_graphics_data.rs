@@ -1,12 +1,20 @@
 use serde::{Deserialize, Serialize};
 
-use super::{EditorPalette, EditorSpriteSheet};
+use super::{EditorAnimation, EditorPalette, EditorSpriteSheet, EditorTilemap};
 use gamercade_core::{GraphicsData, Palette, SpriteSheet};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EditorGraphicsData {
     pub palettes: Vec<EditorPalette>,
     pub sprite_sheets: Vec<EditorSpriteSheet>,
+
+    #[serde(default)]
+    pub tilemaps: Vec<EditorTilemap>,
+
+    /// Animations are only used for previewing in the editor, and
+    /// aren't included in the rom.
+    #[serde(default)]
+    pub animations: Vec<EditorAnimation>,
 }
 
 impl From<&EditorGraphicsData> for GraphicsData {
@@ -24,6 +32,12 @@ impl From<&EditorGraphicsData> for GraphicsData {
                 .map(|palette| palette.palette.clone())
                 .collect::<Vec<_>>()
                 .into_boxed_slice(),
+            tilemaps: data
+                .tilemaps
+                .iter()
+                .map(|tilemap| tilemap.tilemap.clone())
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
         }
     }
 }
@@ -42,6 +56,8 @@ impl Default for EditorGraphicsData {
                 name: "Sprite Sheet 1".to_string(),
                 sprite_sheet: SpriteSheet::default(),
             }],
+            tilemaps: Vec::new(),
+            animations: Vec::new(),
         }
     }
 }
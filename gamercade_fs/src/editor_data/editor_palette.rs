@@ -1,7 +1,7 @@
 use gamercade_core::Palette;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EditorPalette {
     pub name: String,
     pub palette: Palette,
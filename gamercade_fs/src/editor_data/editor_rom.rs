@@ -4,17 +4,20 @@ use gamercade_audio::SoundRom;
 use gamercade_core::{FrameRate, GraphicsData, Resolution};
 use serde::{Deserialize, Serialize};
 
-use crate::GameAssetProvider;
+use crate::{GameAssetProvider, RomMeta};
 
 use super::{EditorGraphicsData, EditorSoundData};
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct EditorRom {
     pub resolution: Resolution,
     pub frame_rate: FrameRate,
     pub player_count: (usize, usize),
     pub graphics: EditorGraphicsData,
     pub sounds: EditorSoundData,
+
+    #[serde(default)]
+    pub meta: RomMeta,
 }
 
 impl EditorRom {
@@ -40,6 +43,7 @@ impl Default for EditorRom {
             frame_rate: FrameRate::default(),
             graphics: EditorGraphicsData::default(),
             sounds: EditorSoundData::default(),
+            meta: RomMeta::default(),
         }
     }
 }
@@ -64,4 +68,8 @@ impl GameAssetProvider for EditorRom {
     fn sounds(&self) -> SoundRom {
         (&self.sounds).into()
     }
+
+    fn meta(&self) -> RomMeta {
+        self.meta.clone()
+    }
 }
@@ -1,14 +1,25 @@
-use gamercade_audio::{Chain, InstrumentDataDefinition, Phrase, Sfx, Song, SoundRom};
+use gamercade_audio::{
+    clamp_sfx_channels, default_sfx_channels, BusGains, Chain, DelayDefinition,
+    InstrumentDataDefinition, Phrase, Sfx, Song, SoundRom, Tuning,
+};
 use gamercade_sound_engine::{InstrumentDefinition, InstrumentDefinitionKind, SoundRomInstance};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EditorSoundData {
     pub songs: Vec<EditorAudioDataEntry<Song>>,
     pub chains: Vec<EditorAudioDataEntry<Option<Chain>>>,
     pub phrases: Vec<EditorAudioDataEntry<Option<Phrase>>>,
     pub instruments: Vec<EditorAudioDataEntry<Option<InstrumentDataDefinition>>>,
     pub sfx: Vec<EditorAudioDataEntry<Sfx>>,
+    #[serde(default)]
+    pub bus_gains: BusGains,
+    #[serde(default)]
+    pub delay: DelayDefinition,
+    #[serde(default = "default_sfx_channels")]
+    pub sfx_channels: usize,
+    #[serde(default)]
+    pub tuning: Tuning,
 }
 
 impl Default for EditorSoundData {
@@ -20,11 +31,15 @@ impl Default for EditorSoundData {
             phrases: from_rom(&sound_rom.phrases, "Phrase"),
             instruments: from_rom(&sound_rom.instruments, "Instrument"),
             sfx: from_rom(&sound_rom.sfx, "Sfx"),
+            bus_gains: sound_rom.bus_gains,
+            delay: sound_rom.delay,
+            sfx_channels: sound_rom.sfx_channels,
+            tuning: sound_rom.tuning.clone(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Default)]
 pub struct EditorAudioDataEntry<T> {
     pub name: String,
     pub data: T,
@@ -57,6 +72,10 @@ impl From<&EditorSoundData> for SoundRom {
             phrases: extract_data(&data.phrases),
             instruments: extract_data(&data.instruments),
             sfx: extract_data(&data.sfx),
+            bus_gains: data.bus_gains,
+            delay: data.delay,
+            sfx_channels: data.sfx_channels,
+            tuning: data.tuning.clone(),
         }
     }
 }
@@ -83,6 +102,10 @@ impl From<&EditorSoundData> for SoundRomInstance {
                 .collect::<Vec<_>>()
                 .into_boxed_slice(),
             sfx: extract_data(&data.sfx),
+            bus_gains: data.bus_gains,
+            delay: data.delay,
+            sfx_channels: clamp_sfx_channels(data.sfx_channels),
+            tuning: data.tuning.clone(),
         }
     }
 }
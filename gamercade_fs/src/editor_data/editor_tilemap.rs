@@ -0,0 +1,8 @@
+use gamercade_core::Tilemap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct EditorTilemap {
+    pub name: String,
+    pub tilemap: Tilemap,
+}
@@ -0,0 +1,127 @@
+use std::path::PathBuf;
+
+use gamercade_audio::{InstrumentDataDefinition, INSTRUMENTS_MAX_COUNT};
+use serde::{Deserialize, Serialize};
+
+use super::{EditorAudioDataEntry, EditorSoundData};
+
+/// The version written into new presets. Presets from a newer
+/// version of the editor are rejected instead of being misread.
+pub const INSTRUMENT_PRESET_VERSION: u32 = 1;
+
+/// A single instrument saved on its own, so it can be reused across projects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstrumentPreset {
+    pub version: u32,
+    pub name: String,
+    pub instrument: InstrumentDataDefinition,
+}
+
+impl InstrumentPreset {
+    pub fn export(data: &EditorSoundData, instrument_index: usize) -> Result<Self, String> {
+        let entry = data
+            .instruments
+            .get(instrument_index)
+            .ok_or_else(|| format!("Instrument {} doesn't exist", instrument_index))?;
+
+        let instrument = entry
+            .data
+            .clone()
+            .ok_or_else(|| format!("Instrument {} is not initialized", instrument_index))?;
+
+        Ok(Self {
+            version: INSTRUMENT_PRESET_VERSION,
+            name: entry.name.clone(),
+            instrument,
+        })
+    }
+
+    /// Appends the instrument to the project, returning its index.
+    pub fn import(self, data: &mut EditorSoundData) -> Result<usize, String> {
+        if data.instruments.len() >= INSTRUMENTS_MAX_COUNT {
+            return Err(format!(
+                "Project already has the maximum of {} instruments",
+                INSTRUMENTS_MAX_COUNT
+            ));
+        }
+
+        data.instruments.push(EditorAudioDataEntry {
+            name: self.name,
+            data: Some(self.instrument),
+        });
+
+        Ok(data.instruments.len() - 1)
+    }
+
+    pub fn try_load(path: &PathBuf) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::try_parse(&text)
+    }
+
+    pub fn try_save(&self, path: &PathBuf) -> Result<(), String> {
+        std::fs::write(
+            path,
+            serde_json::to_string_pretty(self)
+                .expect("failed to serialize instrument preset to json"),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    /// Checks the version before reading the rest of the preset,
+    /// so newer presets give a useful error.
+    fn try_parse(text: &str) -> Result<Self, String> {
+        let value = serde_json::from_str::<serde_json::Value>(text)
+            .map_err(|e| format!("Not a valid instrument preset: {}", e))?;
+
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or("Not a valid instrument preset: missing version")?;
+
+        if version > INSTRUMENT_PRESET_VERSION as u64 {
+            return Err(format!(
+                "Instrument preset is version {}, but only up to version {} is supported. \
+                Try updating the editor.",
+                version, INSTRUMENT_PRESET_VERSION
+            ));
+        }
+
+        serde_json::from_value(value).map_err(|e| format!("Not a valid instrument preset: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_round_trip() {
+        let mut data = EditorSoundData::default();
+        let preset = InstrumentPreset::export(&data, 0).unwrap();
+
+        let text = serde_json::to_string(&preset).unwrap();
+        let index = InstrumentPreset::try_parse(&text)
+            .unwrap()
+            .import(&mut data)
+            .unwrap();
+
+        assert_eq!(index, 1);
+        assert_eq!(data.instruments[1].name, data.instruments[0].name);
+        assert_eq!(
+            bincode::serialize(&data.instruments[1].data).unwrap(),
+            bincode::serialize(&data.instruments[0].data).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_preset_version_mismatch() {
+        let data = EditorSoundData::default();
+        let mut preset = InstrumentPreset::export(&data, 0).unwrap();
+        preset.version = INSTRUMENT_PRESET_VERSION + 1;
+
+        let text = serde_json::to_string(&preset).unwrap();
+        assert!(InstrumentPreset::try_parse(&text).is_err());
+        assert!(InstrumentPreset::try_parse("{}").is_err());
+        assert!(InstrumentPreset::try_parse("not json").is_err());
+    }
+}
@@ -1,11 +1,19 @@
+mod editor_animation;
 mod editor_graphics_data;
 mod editor_palette;
 mod editor_rom;
 mod editor_sounds_data;
 mod editor_sprite_sheet;
+mod editor_tilemap;
+mod instrument_preset;
+mod song_package;
 
+pub use editor_animation::*;
 pub use editor_graphics_data::*;
 pub use editor_palette::*;
 pub use editor_rom::*;
 pub use editor_sounds_data::*;
 pub use editor_sprite_sheet::*;
+pub use editor_tilemap::*;
+pub use instrument_preset::*;
+pub use song_package::*;
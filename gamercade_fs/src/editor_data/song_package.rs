@@ -0,0 +1,453 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    path::PathBuf,
+};
+
+use gamercade_audio::{
+    Chain, ChainId, InstrumentDataDefinition, InstrumentId, Phrase, PhraseId, Song,
+    CHAINS_MAX_COUNT, INSTRUMENTS_MAX_COUNT, PHRASES_MAX_COUNT, SONGS_MAX_COUNT,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{EditorAudioDataEntry, EditorSoundData};
+
+/// The version written into new song packages. Packages from a newer
+/// version of the editor are rejected instead of being misread.
+pub const SONG_PACKAGE_VERSION: u32 = 1;
+
+/// A song bundled with every chain, phrase, and instrument it uses, so it can
+/// be moved between projects. All indices inside the package are local to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SongPackage {
+    pub version: u32,
+    pub song: EditorAudioDataEntry<Song>,
+    pub chains: Vec<EditorAudioDataEntry<Option<Chain>>>,
+    pub phrases: Vec<EditorAudioDataEntry<Option<Phrase>>>,
+    pub instruments: Vec<EditorAudioDataEntry<Option<InstrumentDataDefinition>>>,
+}
+
+/// How an instrument from a package is brought into a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstrumentImport {
+    /// Use the instrument already in the project at this index.
+    Reuse(usize),
+    /// Append a copy of the packaged instrument.
+    Copy,
+}
+
+/// Maps project indices to package indices, in order of first use.
+#[derive(Default)]
+struct LocalIndices {
+    map: HashMap<usize, usize>,
+    order: Vec<usize>,
+}
+
+impl LocalIndices {
+    fn insert(&mut self, index: usize) -> usize {
+        let order = &mut self.order;
+        *self.map.entry(index).or_insert_with(|| {
+            order.push(index);
+            order.len() - 1
+        })
+    }
+}
+
+fn gather<T: Clone>(
+    entries: &[EditorAudioDataEntry<T>],
+    indices: &LocalIndices,
+    kind: &str,
+) -> Result<Vec<EditorAudioDataEntry<T>>, String> {
+    indices
+        .order
+        .iter()
+        .map(|index| {
+            entries
+                .get(*index)
+                .cloned()
+                .ok_or_else(|| format!("Song references missing {} {}", kind, index))
+        })
+        .collect()
+}
+
+fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn instrument_bytes(instrument: &Option<InstrumentDataDefinition>) -> Vec<u8> {
+    bincode::serialize(instrument).expect("failed to serialize instrument")
+}
+
+impl SongPackage {
+    pub fn try_load(path: &PathBuf) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::try_parse(&text)
+    }
+
+    pub fn try_save(&self, path: &PathBuf) -> Result<(), String> {
+        std::fs::write(
+            path,
+            serde_json::to_string_pretty(self).expect("failed to serialize song package to json"),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    /// Checks the version before reading the rest of the package,
+    /// so newer packages give a useful error.
+    fn try_parse(text: &str) -> Result<Self, String> {
+        let value = serde_json::from_str::<serde_json::Value>(text)
+            .map_err(|e| format!("Not a valid song package: {}", e))?;
+
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or("Not a valid song package: missing version")?;
+
+        if version > SONG_PACKAGE_VERSION as u64 {
+            return Err(format!(
+                "Song package is version {}, but only up to version {} is supported. \
+                Try updating the editor.",
+                version, SONG_PACKAGE_VERSION
+            ));
+        }
+
+        serde_json::from_value(value).map_err(|e| format!("Not a valid song package: {}", e))
+    }
+
+    /// Gathers the song and everything it transitively references,
+    /// rewriting the indices into the package's own namespace.
+    pub fn export(data: &EditorSoundData, song_index: usize) -> Result<Self, String> {
+        let song = data
+            .songs
+            .get(song_index)
+            .ok_or_else(|| format!("Song {} doesn't exist", song_index))?;
+
+        let mut chain_indices = LocalIndices::default();
+        let mut phrase_indices = LocalIndices::default();
+        let mut instrument_indices = LocalIndices::default();
+
+        let mut song = song.clone();
+        song.data
+            .tracks
+            .iter_mut()
+            .flatten()
+            .flatten()
+            .for_each(|chain| {
+                chain.0 = chain_indices.insert(chain.0);
+            });
+
+        let mut chains = gather(&data.chains, &chain_indices, "chain")?;
+        chains
+            .iter_mut()
+            .filter_map(|chain| chain.data.as_mut())
+            .flat_map(|chain| chain.entries.iter_mut().flatten())
+            .for_each(|phrase| phrase.0 = phrase_indices.insert(phrase.0));
+
+        let mut phrases = gather(&data.phrases, &phrase_indices, "phrase")?;
+        phrases
+            .iter_mut()
+            .filter_map(|phrase| phrase.data.as_mut())
+            .flat_map(|phrase| phrase.entries.iter_mut().flatten())
+            .for_each(|entry| entry.instrument.0 = instrument_indices.insert(entry.instrument.0));
+
+        let instruments = gather(&data.instruments, &instrument_indices, "instrument")?;
+
+        Ok(Self {
+            version: SONG_PACKAGE_VERSION,
+            song,
+            chains,
+            phrases,
+            instruments,
+        })
+    }
+
+    /// Finds instruments in the project which are identical to the packaged ones.
+    /// Returns one entry per packaged instrument.
+    pub fn matching_instruments(&self, data: &EditorSoundData) -> Vec<Option<usize>> {
+        let existing = data
+            .instruments
+            .iter()
+            .map(|instrument| instrument_bytes(&instrument.data))
+            .collect::<Vec<_>>();
+
+        let mut by_hash = HashMap::new();
+        existing
+            .iter()
+            .enumerate()
+            .rev()
+            .for_each(|(index, bytes)| {
+                by_hash.insert(content_hash(bytes), index);
+            });
+
+        self.instruments
+            .iter()
+            .map(|instrument| {
+                let bytes = instrument_bytes(&instrument.data);
+                by_hash
+                    .get(&content_hash(&bytes))
+                    .copied()
+                    .filter(|index| existing[*index] == bytes)
+            })
+            .collect()
+    }
+
+    /// The default choices for importing: reuse identical instruments, copy the rest.
+    pub fn default_instrument_imports(&self, data: &EditorSoundData) -> Vec<InstrumentImport> {
+        self.matching_instruments(data)
+            .into_iter()
+            .map(|existing| existing.map_or(InstrumentImport::Copy, InstrumentImport::Reuse))
+            .collect()
+    }
+
+    /// Merges the package into the project, appending its assets and remapping
+    /// their indices. Everything is validated up front, so the project is only
+    /// changed if the whole import succeeds. Returns the index of the new song.
+    pub fn import(
+        &self,
+        data: &mut EditorSoundData,
+        instrument_imports: &[InstrumentImport],
+    ) -> Result<usize, String> {
+        if instrument_imports.len() != self.instruments.len() {
+            return Err("Expected one import choice per instrument".to_string());
+        }
+
+        let copies = instrument_imports
+            .iter()
+            .filter(|import| **import == InstrumentImport::Copy)
+            .count();
+
+        let limits = [
+            ("songs", data.songs.len() + 1, SONGS_MAX_COUNT),
+            (
+                "chains",
+                data.chains.len() + self.chains.len(),
+                CHAINS_MAX_COUNT,
+            ),
+            (
+                "phrases",
+                data.phrases.len() + self.phrases.len(),
+                PHRASES_MAX_COUNT,
+            ),
+            (
+                "instruments",
+                data.instruments.len() + copies,
+                INSTRUMENTS_MAX_COUNT,
+            ),
+        ];
+
+        if let Some((kind, count, max)) = limits.iter().find(|(_, count, max)| count > max) {
+            return Err(format!(
+                "Importing would exceed the maximum of {} {} ({})",
+                max, kind, count
+            ));
+        }
+
+        let mut next_instrument = data.instruments.len();
+        let instrument_map = instrument_imports
+            .iter()
+            .map(|import| match import {
+                InstrumentImport::Reuse(index) if *index < data.instruments.len() => Ok(*index),
+                InstrumentImport::Reuse(index) => {
+                    Err(format!("Instrument {} doesn't exist", index))
+                }
+                InstrumentImport::Copy => {
+                    next_instrument += 1;
+                    Ok(next_instrument - 1)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let chain_offset = data.chains.len();
+        let phrase_offset = data.phrases.len();
+
+        let mut song = self.song.clone();
+        song.data
+            .tracks
+            .iter_mut()
+            .flatten()
+            .flatten()
+            .for_each(|chain| *chain = ChainId(chain.0 + chain_offset));
+
+        let chains = self.chains.iter().cloned().map(|mut chain| {
+            chain
+                .data
+                .iter_mut()
+                .flat_map(|chain| chain.entries.iter_mut().flatten())
+                .for_each(|phrase| *phrase = PhraseId(phrase.0 + phrase_offset));
+            chain
+        });
+
+        let mut phrases = self.phrases.clone();
+        for entry in phrases
+            .iter_mut()
+            .filter_map(|phrase| phrase.data.as_mut())
+            .flat_map(|phrase| phrase.entries.iter_mut().flatten())
+        {
+            let instrument = instrument_map
+                .get(entry.instrument.0)
+                .ok_or_else(|| format!("Package is missing instrument {}", entry.instrument.0))?;
+            entry.instrument = InstrumentId(*instrument);
+        }
+
+        let instruments = self
+            .instruments
+            .iter()
+            .zip(instrument_imports.iter())
+            .filter(|(_, import)| **import == InstrumentImport::Copy)
+            .map(|(instrument, _)| instrument.clone());
+
+        data.chains.extend(chains);
+        data.phrases.extend(phrases);
+        data.instruments.extend(instruments);
+        data.songs.push(song);
+
+        Ok(data.songs.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use gamercade_audio::{
+        WavetableDefinition, WavetableGenerator, WavetableWaveform, SONG_TRACK_CHANNELS,
+    };
+    use gamercade_core::FrameRate;
+    use gamercade_sound_engine::{initialize_globals, SoundEngineData, SoundRomInstance};
+
+    use super::*;
+
+    fn render_song(data: &EditorSoundData, song_index: usize) -> Vec<f32> {
+        let rom = Arc::new(SoundRomInstance::from(data));
+        let mut engine = SoundEngineData::new(48_000, FrameRate::default(), &rom);
+        engine.play_bgm(Some(gamercade_audio::SongId(song_index)));
+
+        (0..48_000)
+            .map(|_| engine.tick().get_bgm_output())
+            .collect()
+    }
+
+    /// A project with an unused chain, and a song using the default
+    /// sine instrument alongside a new square one.
+    fn project_a() -> EditorSoundData {
+        let mut data = EditorSoundData::default();
+
+        let mut square = data.instruments[0].clone();
+        if let Some(InstrumentDataDefinition::Wavetable(wavetable)) = &mut square.data {
+            *wavetable = WavetableDefinition {
+                data: WavetableGenerator {
+                    waveform: WavetableWaveform::Square,
+                    size: 64,
+                }
+                .generate(),
+                ..wavetable.clone()
+            };
+        }
+        data.instruments.push(square);
+
+        data.phrases.push(EditorAudioDataEntry {
+            name: "Reverse".to_string(),
+            data: Some(Phrase::c_scale_reverse(InstrumentId(1))),
+        });
+
+        let mut chain = Chain::default();
+        chain.entries[0] = Some(PhraseId(1));
+        chain.entries[1] = Some(PhraseId(0));
+        data.chains.push(EditorAudioDataEntry {
+            name: "Used".to_string(),
+            data: Some(chain),
+        });
+
+        let mut row: [Option<ChainId>; SONG_TRACK_CHANNELS] = std::array::from_fn(|_| None);
+        row[0] = Some(ChainId(1));
+        row[3] = Some(ChainId(1));
+        data.songs.push(EditorAudioDataEntry {
+            name: "Song".to_string(),
+            data: Song {
+                tracks: vec![row].into_boxed_slice(),
+                ..Default::default()
+            },
+        });
+
+        data
+    }
+
+    #[test]
+    fn test_song_package_round_trip() {
+        initialize_globals();
+        let project_a = project_a();
+        let package = SongPackage::export(&project_a, 0).unwrap();
+
+        assert_eq!(package.chains.len(), 1);
+        assert_eq!(package.phrases.len(), 2);
+        assert_eq!(package.instruments.len(), 2);
+
+        let mut project_b = EditorSoundData::default();
+        let imports = package.default_instrument_imports(&project_b);
+        assert_eq!(
+            imports,
+            vec![InstrumentImport::Copy, InstrumentImport::Reuse(0)]
+        );
+
+        let song_index = package.import(&mut project_b, &imports).unwrap();
+        assert_eq!(project_b.instruments.len(), 2);
+
+        let expected = render_song(&project_a, 0);
+        assert!(expected.iter().any(|sample| *sample != 0.0));
+        assert_eq!(render_song(&project_b, song_index), expected);
+    }
+
+    #[test]
+    fn test_song_package_save_load_round_trip() {
+        let path = std::env::temp_dir().join("gamercade_fs_song_package.json");
+        let package = SongPackage::export(&project_a(), 0).unwrap();
+        package.try_save(&path).unwrap();
+
+        let loaded = SongPackage::try_load(&path).unwrap();
+        assert_eq!(loaded.version, SONG_PACKAGE_VERSION);
+        assert_eq!(loaded.song, package.song);
+        assert_eq!(loaded.chains, package.chains);
+        assert_eq!(
+            bincode::serialize(&loaded.phrases).unwrap(),
+            bincode::serialize(&package.phrases).unwrap()
+        );
+        assert_eq!(
+            bincode::serialize(&loaded.instruments).unwrap(),
+            bincode::serialize(&package.instruments).unwrap()
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_song_package_version_mismatch() {
+        let mut package = SongPackage::export(&project_a(), 0).unwrap();
+        package.version = SONG_PACKAGE_VERSION + 1;
+
+        let text = serde_json::to_string(&package).unwrap();
+        assert!(SongPackage::try_parse(&text)
+            .unwrap_err()
+            .contains("version"));
+        assert!(SongPackage::try_parse("{}").is_err());
+        assert!(SongPackage::try_parse("not json").is_err());
+    }
+
+    #[test]
+    fn test_song_package_import_is_all_or_nothing() {
+        let package = SongPackage::export(&project_a(), 0).unwrap();
+        let mut project_b = EditorSoundData::default();
+
+        let result = package.import(
+            &mut project_b,
+            &[InstrumentImport::Copy, InstrumentImport::Reuse(5)],
+        );
+
+        assert!(result.is_err());
+        assert!(project_b.songs.is_empty());
+        assert_eq!(project_b.chains.len(), 1);
+        assert_eq!(project_b.phrases.len(), 1);
+        assert_eq!(project_b.instruments.len(), 1);
+    }
+}
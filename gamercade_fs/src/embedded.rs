@@ -0,0 +1,140 @@
+use std::{
+    fs,
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+};
+
+/// Ends a console binary which has a rom appended to it. The layout is the
+/// binary, then the rom file as is, then the rom's length and this magic.
+const EMBED_MAGIC: &[u8; 8] = b"GCEMBED1";
+
+/// The length of the rom followed by the magic.
+const TRAILER_SIZE: u64 = 16;
+
+/// Finds where the rom appended to the file starts and how long it is,
+/// or returns None if there isn't one.
+fn find_embedded_rom(file: &mut fs::File) -> Result<Option<(u64, u64)>, String> {
+    let file_len = file.metadata().map_err(|e| e.to_string())?.len();
+    if file_len < TRAILER_SIZE {
+        return Ok(None);
+    }
+
+    let mut trailer = [0; TRAILER_SIZE as usize];
+    file.seek(SeekFrom::End(-(TRAILER_SIZE as i64)))
+        .map_err(|e| e.to_string())?;
+    file.read_exact(&mut trailer).map_err(|e| e.to_string())?;
+
+    let (length, magic) = trailer.split_at(8);
+    if magic != EMBED_MAGIC {
+        return Ok(None);
+    }
+
+    let length = u64::from_le_bytes(length.try_into().unwrap());
+    match (file_len - TRAILER_SIZE).checked_sub(length) {
+        Some(start) => Ok(Some((start, length))),
+        None => Err(String::from("The embedded rom is corrupt.")),
+    }
+}
+
+/// Opens a rom, either a .gcrom file or one embedded in a console binary.
+/// Returns a reader limited to the rom, along with its length in bytes.
+pub(crate) fn open_rom_file(path: &PathBuf) -> Result<(io::Take<fs::File>, u64), String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+
+    let (start, length) = match find_embedded_rom(&mut file)? {
+        Some(range) => range,
+        None => (0, file.metadata().map_err(|e| e.to_string())?.len()),
+    };
+
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| e.to_string())?;
+    Ok((file.take(length), length))
+}
+
+/// Returns true if the file is a console binary with a rom embedded in it.
+pub fn has_embedded_rom(path: &PathBuf) -> bool {
+    fs::File::open(path)
+        .map_err(|e| e.to_string())
+        .and_then(|mut file| find_embedded_rom(&mut file))
+        .is_ok_and(|range| range.is_some())
+}
+
+/// Writes a copy of the console binary with the rom appended, which launches
+/// straight into the game. Any rom already embedded in the console is replaced.
+pub fn embed_rom(console: &PathBuf, rom: &PathBuf, output: &PathBuf) -> Result<(), String> {
+    let mut console_file = fs::File::open(console).map_err(|e| e.to_string())?;
+    let console_len = match find_embedded_rom(&mut console_file)? {
+        Some((start, _)) => start,
+        None => console_file.metadata().map_err(|e| e.to_string())?.len(),
+    };
+    console_file
+        .seek(SeekFrom::Start(0))
+        .map_err(|e| e.to_string())?;
+
+    let rom = fs::read(rom).map_err(|e| e.to_string())?;
+
+    let mut target = fs::File::create(output).map_err(|e| e.to_string())?;
+    io::copy(
+        &mut Read::by_ref(&mut console_file).take(console_len),
+        &mut target,
+    )
+    .map_err(|e| e.to_string())?;
+    target.write_all(&rom).map_err(|e| e.to_string())?;
+    target
+        .write_all(&(rom.len() as u64).to_le_bytes())
+        .map_err(|e| e.to_string())?;
+    target.write_all(EMBED_MAGIC).map_err(|e| e.to_string())?;
+
+    // Keep the console runnable, as creating the file drops its permissions
+    let permissions = console_file
+        .metadata()
+        .map_err(|e| e.to_string())?
+        .permissions();
+    fs::set_permissions(output, permissions).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rom, RomMeta};
+
+    #[test]
+    fn test_embedded_rom_loads() {
+        let dir = std::env::temp_dir();
+        let console = dir.join("gamercade_fs_embed_console");
+        let rom_path = dir.join("gamercade_fs_embed.gcrom");
+        let output = dir.join("gamercade_fs_embed_output");
+
+        fs::write(&console, b"not really a console binary").unwrap();
+        let rom = Rom {
+            code: vec![1, 2, 3].into_boxed_slice(),
+            meta: RomMeta {
+                title: String::from("Embedded"),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        rom.try_save(&rom_path).unwrap();
+
+        assert!(!has_embedded_rom(&console));
+        embed_rom(&console, &rom_path, &output).unwrap();
+        assert!(has_embedded_rom(&output));
+
+        let loaded = Rom::try_load(&output).unwrap();
+        assert_eq!(loaded.code, rom.code);
+        assert_eq!(Rom::try_load_meta(&output).unwrap(), rom.meta);
+
+        // Exporting from an exported game replaces its rom
+        embed_rom(&output, &rom_path, &console).unwrap();
+        let original_len = b"not really a console binary".len() as u64;
+        let rom_len = fs::metadata(&rom_path).unwrap().len();
+        assert_eq!(
+            fs::metadata(&console).unwrap().len(),
+            original_len + rom_len + TRAILER_SIZE
+        );
+
+        for path in [console, rom_path, output] {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
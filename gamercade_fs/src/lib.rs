@@ -1,10 +1,18 @@
 mod bundler;
 mod editor_data;
+mod embedded;
+mod load_progress;
+mod replay;
 mod rom;
+mod rom_meta;
 
 pub use bundler::*;
 pub use editor_data::*;
+pub use embedded::{embed_rom, has_embedded_rom};
+pub use load_progress::*;
+pub use replay::*;
 pub use rom::*;
+pub use rom_meta::*;
 
 pub fn try_load_wasm(path: &std::path::PathBuf) -> Result<Vec<u8>, String> {
     std::fs::read(path).map_err(|e| e.to_string())
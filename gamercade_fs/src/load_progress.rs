@@ -0,0 +1,84 @@
+use std::{
+    io::Read,
+    sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering},
+};
+
+/// The current step of loading a rom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadPhase {
+    Decompressing,
+    Deserializing,
+    /// Set by the console while the game code is being compiled.
+    Compiling,
+}
+
+/// Shared progress of a rom load. Can be read from other threads
+/// while the load is happening, and used to cancel it.
+#[derive(Debug, Default)]
+pub struct LoadProgress {
+    bytes_read: AtomicU64,
+    total_bytes: AtomicU64,
+    phase: AtomicU8,
+    cancelled: AtomicBool,
+}
+
+impl LoadProgress {
+    pub fn phase(&self) -> LoadPhase {
+        match self.phase.load(Ordering::Relaxed) {
+            0 => LoadPhase::Decompressing,
+            1 => LoadPhase::Deserializing,
+            _ => LoadPhase::Compiling,
+        }
+    }
+
+    pub fn set_phase(&self, phase: LoadPhase) {
+        self.phase.store(phase as u8, Ordering::Relaxed);
+    }
+
+    /// The number of compressed bytes read so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read.load(Ordering::Relaxed)
+    }
+
+    /// The size of the compressed file.
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Returns how far along decompression is, between 0.0 and 1.0.
+    pub fn fraction(&self) -> f32 {
+        match self.total_bytes() {
+            0 => 0.0,
+            total => (self.bytes_read() as f32 / total as f32).min(1.0),
+        }
+    }
+
+    /// Requests the load to stop. Checked between each chunk read.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_total_bytes(&self, total: u64) {
+        self.total_bytes.store(total, Ordering::Relaxed);
+    }
+}
+
+/// Wraps a reader and reports the bytes read to the progress.
+pub(crate) struct ProgressReader<'a, R> {
+    pub(crate) inner: R,
+    pub(crate) progress: &'a LoadProgress,
+}
+
+impl<'a, R: Read> Read for ProgressReader<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        self.progress
+            .bytes_read
+            .fetch_add(count as u64, Ordering::Relaxed);
+        Ok(count)
+    }
+}
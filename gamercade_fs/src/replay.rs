@@ -0,0 +1,218 @@
+use std::{
+    fs,
+    io::{Read, Write},
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+
+use gamercade_core::{InputState, MAX_PLAYERS};
+
+use crate::Rom;
+
+/// Starts every replay, followed by the format version.
+const REPLAY_MAGIC: &[u8; 4] = b"GCRP";
+
+/// The version of the replay format written by this build. Increase this when
+/// changing the layout of the replay, so older builds reject it rather than misread it.
+pub const REPLAY_FORMAT_VERSION: u32 = 1;
+
+/// Every input of a game, frame by frame. Games are deterministic, so running
+/// the same rom with the same seed, save data and inputs reproduces it exactly.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Replay {
+    /// The checksum of the rom which was recorded, see [`Rom::checksum`].
+    pub rom_checksum: u64,
+    pub seed: u64,
+    pub num_players: usize,
+
+    /// The save data when the game started, as the game may read it.
+    pub save_data: Vec<u8>,
+
+    /// Each frame's inputs, with one entry per player.
+    inputs: Vec<InputState>,
+}
+
+impl Replay {
+    pub fn new(rom: &Rom, seed: u64, num_players: usize, save_data: Vec<u8>) -> Self {
+        Self {
+            rom_checksum: rom.checksum(),
+            seed,
+            num_players,
+            save_data,
+            inputs: Vec::new(),
+        }
+    }
+
+    /// The number of recorded frames.
+    pub fn len(&self) -> usize {
+        self.inputs.len().checked_div(self.num_players).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// Records the inputs of the frame. Recording a frame again, such as after a rollback,
+    /// drops it and every frame after it first.
+    pub fn record(&mut self, frame: usize, inputs: &[InputState]) {
+        if frame > self.len() || inputs.len() != self.num_players {
+            return;
+        }
+
+        self.inputs.truncate(frame * self.num_players);
+        self.inputs.extend_from_slice(inputs);
+    }
+
+    /// The inputs of the frame, one per player.
+    pub fn frame(&self, frame: usize) -> Option<&[InputState]> {
+        let start = frame * self.num_players;
+        self.inputs.get(start..start + self.num_players)
+    }
+
+    pub fn try_load(path: &PathBuf) -> Result<Self, String> {
+        let file = fs::File::open(path).map_err(|e| e.to_string())?;
+        let mut reader = zstd::Decoder::new(file).map_err(|e| e.to_string())?;
+
+        let mut buffer = Vec::new();
+        reader.read_to_end(&mut buffer).map_err(|e| e.to_string())?;
+
+        let payload = buffer
+            .strip_prefix(REPLAY_MAGIC.as_slice())
+            .filter(|rest| rest.len() >= 4)
+            .ok_or_else(|| String::from("The file isn't a replay."))?;
+        let (version, payload) = payload.split_at(4);
+
+        match u32::from_le_bytes(version.try_into().unwrap()) {
+            REPLAY_FORMAT_VERSION => (),
+            version => {
+                return Err(format!(
+                    "This replay uses format version {}, but the supported version is {}.",
+                    version, REPLAY_FORMAT_VERSION
+                ))
+            }
+        }
+
+        let replay = bincode::deserialize::<Replay>(payload).map_err(|e| e.to_string())?;
+        replay.validate()?;
+        Ok(replay)
+    }
+
+    /// Checks the replay can be played back, as it may come from a corrupt file.
+    fn validate(&self) -> Result<(), String> {
+        if self.num_players == 0 || self.num_players > MAX_PLAYERS {
+            return Err(format!(
+                "The replay has {} players, but it must have between 1 and {}.",
+                self.num_players, MAX_PLAYERS
+            ));
+        }
+
+        if self.inputs.len() % self.num_players != 0 {
+            return Err(String::from("The replay's last frame is incomplete."));
+        }
+
+        Ok(())
+    }
+
+    pub fn try_save(&self, path: &PathBuf) -> Result<(), String> {
+        let replay = bincode::serialize(self).map_err(|e| e.to_string())?;
+        let target = fs::File::create(path).map_err(|e| e.to_string())?;
+        let mut encoder = zstd::Encoder::new(target, zstd::DEFAULT_COMPRESSION_LEVEL)
+            .map_err(|e| e.to_string())?;
+
+        encoder.write_all(REPLAY_MAGIC).map_err(|e| e.to_string())?;
+        encoder
+            .write_all(&REPLAY_FORMAT_VERSION.to_le_bytes())
+            .map_err(|e| e.to_string())?;
+        encoder.write_all(&replay).map_err(|e| e.to_string())?;
+
+        encoder.finish().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(x: usize) -> InputState {
+        let mut input = InputState::default();
+        input.mouse.set_position(x, 0);
+        input
+    }
+
+    #[test]
+    fn test_rerecording_a_frame_drops_later_frames() {
+        let mut replay = Replay::new(&Rom::default(), 0, 2, Vec::new());
+
+        replay.record(0, &[input(0), input(1)]);
+        replay.record(1, &[input(2), input(3)]);
+        replay.record(2, &[input(4), input(5)]);
+        assert_eq!(replay.len(), 3);
+
+        replay.record(1, &[input(6), input(7)]);
+        assert_eq!(replay.len(), 2);
+        assert_eq!(replay.frame(1), Some(&[input(6), input(7)][..]));
+        assert_eq!(replay.frame(2), None);
+    }
+
+    #[test]
+    fn test_frames_must_be_recorded_in_order() {
+        let mut replay = Replay::new(&Rom::default(), 0, 1, Vec::new());
+
+        replay.record(1, &[input(0)]);
+        assert!(replay.is_empty());
+
+        replay.record(0, &[input(0), input(1)]);
+        assert!(replay.is_empty());
+    }
+
+    #[test]
+    fn test_save_load_round_trip() {
+        let path = std::env::temp_dir().join("gamercade_fs_replay.gcrp");
+        let mut replay = Replay::new(&Rom::default(), 7, 2, vec![1, 2, 3]);
+        replay.record(0, &[input(0), input(1)]);
+        replay.record(1, &[input(2), input(3)]);
+        replay.try_save(&path).unwrap();
+
+        let loaded = Replay::try_load(&path).unwrap();
+        assert_eq!(loaded.seed, 7);
+        assert_eq!(loaded.save_data, [1, 2, 3]);
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.frame(1), Some(&[input(2), input(3)][..]));
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_corrupt_replays_are_an_error() {
+        let path = std::env::temp_dir().join("gamercade_fs_corrupt_replay.gcrp");
+
+        let no_players = Replay::new(&Rom::default(), 0, 0, Vec::new());
+        no_players.try_save(&path).unwrap();
+        assert!(Replay::try_load(&path).is_err());
+
+        let too_many_players = Replay::new(&Rom::default(), 0, usize::MAX, Vec::new());
+        too_many_players.try_save(&path).unwrap();
+        assert!(Replay::try_load(&path).is_err());
+
+        let mut partial_frame = Replay::new(&Rom::default(), 0, 2, Vec::new());
+        partial_frame.inputs = vec![input(0), input(1), input(2)];
+        partial_frame.try_save(&path).unwrap();
+        assert!(Replay::try_load(&path).is_err());
+
+        let bytes = bincode::serialize(&Replay::new(&Rom::default(), 0, 1, Vec::new())).unwrap();
+        let headerless = zstd::encode_all(&*bytes, zstd::DEFAULT_COMPRESSION_LEVEL).unwrap();
+        fs::write(&path, headerless).unwrap();
+        assert!(Replay::try_load(&path).is_err());
+
+        let mut newer = REPLAY_MAGIC.to_vec();
+        newer.extend_from_slice(&(REPLAY_FORMAT_VERSION + 1).to_le_bytes());
+        newer.extend_from_slice(&bytes);
+        let newer = zstd::encode_all(&*newer, zstd::DEFAULT_COMPRESSION_LEVEL).unwrap();
+        fs::write(&path, newer).unwrap();
+        assert!(Replay::try_load(&path).unwrap_err().contains("version"));
+
+        let _ = fs::remove_file(path);
+    }
+}
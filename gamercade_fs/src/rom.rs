@@ -5,11 +5,27 @@ use std::{
 };
 
 use serde::{Deserialize, Serialize};
+use wasmparser::{ExternalKind, Payload, TypeRef, Validator};
 
-use gamercade_audio::SoundRom;
-use gamercade_core::{FrameRate, GraphicsData, Resolution};
+use gamercade_audio::{SoundRom, SoundRomV0};
+use gamercade_core::{FrameRate, GraphicsData, Palette, Resolution, SpriteSheet};
 
-use crate::{GameAssetProvider, GameCodeProvider};
+use crate::{
+    embedded::open_rom_file, GameAssetProvider, GameCodeProvider, LoadPhase, LoadProgress,
+    ProgressReader, RomMeta,
+};
+
+/// How many decompressed bytes are read between each cancellation check.
+const LOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Starts every rom, followed by the format version. Roms from before the header
+/// start with the resolution instead, which can never match this.
+const ROM_MAGIC: &[u8; 4] = b"GCRM";
+
+/// The version of the rom format written by this build. Roms from before the
+/// header was added are version 0. Increase this when changing the layout of the
+/// rom, and add a step to `migrate` which upgrades the previous version.
+pub const ROM_FORMAT_VERSION: u32 = 3;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Rom {
@@ -19,6 +35,11 @@ pub struct Rom {
     pub graphics: GraphicsData,
     pub sounds: SoundRom,
     pub code: Box<[u8]>,
+
+    /// Stored ahead of the rest of the rom so it can be read on its own,
+    /// see [`Rom::try_load_meta`]. It isn't part of the [`Rom::checksum`].
+    #[serde(skip)]
+    pub meta: RomMeta,
 }
 
 impl Default for Rom {
@@ -30,6 +51,7 @@ impl Default for Rom {
             sounds: Default::default(),
             code: Default::default(),
             player_count: (1, 1),
+            meta: Default::default(),
         }
     }
 }
@@ -43,24 +65,99 @@ impl Rom {
         self.resolution.width()
     }
 
-    pub fn try_load(path: &PathBuf) -> Result<Self, String> {
-        let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    pub fn meta(&self) -> &RomMeta {
+        &self.meta
+    }
+
+    /// Reads only the metadata of the rom, without decompressing the rest of it.
+    /// Roms from before metadata was added have the default metadata.
+    pub fn try_load_meta(path: &PathBuf) -> Result<RomMeta, String> {
+        let (file, _) = open_rom_file(path)?;
         let mut reader = zstd::Decoder::new(file).map_err(|e| e.to_string())?;
 
+        let mut header = [0; 8];
+        reader.read_exact(&mut header).map_err(|e| e.to_string())?;
+
+        match split_header(&header).0 {
+            0 | 1 => Ok(RomMeta::default()),
+            version @ (2 | 3) => read_meta(&mut reader, version),
+            newer => Err(unsupported_version(newer)),
+        }
+    }
+
+    pub fn try_load(path: &PathBuf) -> Result<Self, String> {
+        Self::try_load_with_progress(path, &LoadProgress::default())
+    }
+
+    /// Loads the rom, reporting progress as it goes. Intended to be called
+    /// from a background thread. Returns an error if the load was cancelled.
+    /// The path can also be a console binary with the rom embedded in it.
+    pub fn try_load_with_progress(path: &PathBuf, progress: &LoadProgress) -> Result<Self, String> {
+        let (file, total) = open_rom_file(path)?;
+        progress.set_total_bytes(total);
+        progress.set_phase(LoadPhase::Decompressing);
+
+        let reader = ProgressReader {
+            inner: file,
+            progress,
+        };
+        let mut reader = zstd::Decoder::new(reader).map_err(|e| e.to_string())?;
+
         let mut buffer = Vec::new();
+        let mut chunk = vec![0; LOAD_CHUNK_SIZE];
+
+        loop {
+            if progress.is_cancelled() {
+                return Err(String::from("Loading cancelled"));
+            }
+
+            let count = reader.read(&mut chunk).map_err(|e| e.to_string())?;
+            if count == 0 {
+                break;
+            }
+            buffer.extend_from_slice(&chunk[..count]);
+        }
 
-        // We don't care about how many bytes are read
-        let _ = reader.read_to_end(&mut buffer).map_err(|e| e.to_string());
+        progress.set_phase(LoadPhase::Deserializing);
+        let (version, payload) = split_header(&buffer);
+        migrate(version, payload)
+    }
 
-        bincode::deserialize_from::<_, Rom>(&*buffer).map_err(|e| e.to_string())
+    /// A hash of the whole rom, to check if two roms are the same game.
+    pub fn checksum(&self) -> u64 {
+        fnv1a(&bincode::serialize(self).unwrap_or_default())
     }
 
     pub fn try_save(&self, path: &PathBuf) -> Result<(), String> {
+        self.try_save_with_level(path, zstd::DEFAULT_COMPRESSION_LEVEL)
+    }
+
+    /// Saves the rom compressed at the zstd level. Higher levels make smaller roms
+    /// but take longer to save, which suits distribution builds.
+    pub fn try_save_with_level(&self, path: &PathBuf, level: i32) -> Result<(), String> {
+        let range = zstd::compression_level_range();
+        if !range.contains(&level) {
+            return Err(format!(
+                "Compression level {} is out of range, it must be between {} and {}.",
+                level,
+                range.start(),
+                range.end()
+            ));
+        }
+
+        let meta = bincode::serialize(&self.meta).map_err(|e| e.to_string())?;
         let rom = bincode::serialize(self).map_err(|e| e.to_string())?;
         let target = fs::File::create(path).map_err(|e| e.to_string())?;
-        let mut encoder = zstd::Encoder::new(target, zstd::DEFAULT_COMPRESSION_LEVEL)
-            .map_err(|e| e.to_string())?;
+        let mut encoder = zstd::Encoder::new(target, level).map_err(|e| e.to_string())?;
 
+        encoder.write_all(ROM_MAGIC).map_err(|e| e.to_string())?;
+        encoder
+            .write_all(&ROM_FORMAT_VERSION.to_le_bytes())
+            .map_err(|e| e.to_string())?;
+        encoder
+            .write_all(&(meta.len() as u32).to_le_bytes())
+            .map_err(|e| e.to_string())?;
+        encoder.write_all(&meta).map_err(|e| e.to_string())?;
         encoder.write_all(&rom).map_err(|e| e.to_string())?;
 
         encoder.finish().map_err(|e| e.to_string())?;
@@ -68,6 +165,178 @@ impl Rom {
     }
 }
 
+/// Hashes the bytes with FNV-1a, which is stable across builds, unlike the std hasher.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The module the console provides its api under.
+const API_MODULE: &str = "env";
+
+/// The functions the console calls, of which a game must export at least one.
+const GAME_FUNCTIONS: [&str; 3] = ["init", "update", "draw"];
+
+impl Rom {
+    /// Checks the code is a valid WebAssembly module which the console can run,
+    /// so a corrupt or incompatible rom fails when loaded rather than when started.
+    pub fn validate(&self) -> Result<(), String> {
+        Validator::new()
+            .validate_all(&self.code)
+            .map_err(|e| format!("The rom's code isn't a valid WebAssembly module: {}", e))?;
+
+        let mut exports_game_function = false;
+
+        for payload in wasmparser::Parser::new(0).parse_all(&self.code) {
+            match payload.map_err(|e| e.to_string())? {
+                Payload::ImportSection(imports) => {
+                    for import in imports {
+                        let import = import.map_err(|e| e.to_string())?;
+                        if import.module != API_MODULE || !matches!(import.ty, TypeRef::Func(_)) {
+                            return Err(format!(
+                                "The rom imports {}::{}, which the console doesn't provide.",
+                                import.module, import.name
+                            ));
+                        }
+                    }
+                }
+                Payload::ExportSection(exports) => {
+                    for export in exports {
+                        let export = export.map_err(|e| e.to_string())?;
+                        if export.kind == ExternalKind::Func
+                            && GAME_FUNCTIONS.contains(&export.name)
+                        {
+                            exports_game_function = true;
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if exports_game_function {
+            Ok(())
+        } else {
+            Err(String::from(
+                "Loaded rom doesn't export an init, update or draw function.",
+            ))
+        }
+    }
+}
+
+/// Splits the decompressed rom into its format version and the serialized rom.
+fn split_header(buffer: &[u8]) -> (u32, &[u8]) {
+    match buffer.strip_prefix(ROM_MAGIC.as_slice()) {
+        Some(rest) if rest.len() >= 4 => {
+            let (version, payload) = rest.split_at(4);
+            (u32::from_le_bytes(version.try_into().unwrap()), payload)
+        }
+        _ => (0, buffer),
+    }
+}
+
+/// The metadata of version 2 roms, which had no thumbnail.
+#[derive(Deserialize)]
+struct RomMetaV2 {
+    title: String,
+    authors: Vec<String>,
+    version: String,
+    description: String,
+    created: u64,
+}
+
+/// Reads the length prefixed metadata which starts roms since version 2.
+fn read_meta(reader: &mut impl Read, version: u32) -> Result<RomMeta, String> {
+    let mut length = [0; 4];
+    reader.read_exact(&mut length).map_err(|e| e.to_string())?;
+    let length = u32::from_le_bytes(length) as u64;
+
+    let mut meta = Vec::new();
+    reader
+        .take(length)
+        .read_to_end(&mut meta)
+        .map_err(|e| e.to_string())?;
+
+    if version == 2 {
+        let meta = bincode::deserialize::<RomMetaV2>(&meta).map_err(|e| e.to_string())?;
+        Ok(RomMeta {
+            title: meta.title,
+            authors: meta.authors,
+            version: meta.version,
+            description: meta.description,
+            created: meta.created,
+            thumbnail: None,
+        })
+    } else {
+        bincode::deserialize(&meta).map_err(|e| e.to_string())
+    }
+}
+
+/// The layout of roms from before the header was added. Bincode can't skip
+/// missing fields, so it's frozen as it was rather than using the current types.
+#[derive(Deserialize)]
+struct RomV0 {
+    resolution: Resolution,
+    frame_rate: FrameRate,
+    player_count: (usize, usize),
+    graphics: GraphicsDataV0,
+    sounds: SoundRomV0,
+    code: Box<[u8]>,
+}
+
+/// The graphics of version 0 roms, which had no tilemaps.
+#[derive(Deserialize)]
+struct GraphicsDataV0 {
+    sprite_sheets: Box<[SpriteSheet]>,
+    palettes: Box<[Palette]>,
+}
+
+impl From<RomV0> for Rom {
+    fn from(rom: RomV0) -> Self {
+        Self {
+            resolution: rom.resolution,
+            frame_rate: rom.frame_rate,
+            player_count: rom.player_count,
+            graphics: GraphicsData {
+                sprite_sheets: rom.graphics.sprite_sheets,
+                palettes: rom.graphics.palettes,
+                tilemaps: Box::default(),
+            },
+            sounds: rom.sounds.into(),
+            code: rom.code,
+            meta: RomMeta::default(),
+        }
+    }
+}
+
+/// Reads a rom of any supported version, upgrading it to the current format.
+fn migrate(version: u32, mut payload: &[u8]) -> Result<Rom, String> {
+    match version {
+        0 => bincode::deserialize::<RomV0>(payload)
+            .map(Rom::from)
+            .map_err(|e| e.to_string()),
+        // Version 1 added the header, along with the sound and graphics changes
+        // made since, and has no metadata
+        1 => bincode::deserialize::<Rom>(payload).map_err(|e| e.to_string()),
+        // Version 3 added the thumbnail to the metadata
+        2 | 3 => {
+            let meta = read_meta(&mut payload, version)?;
+            let mut rom = bincode::deserialize::<Rom>(payload).map_err(|e| e.to_string())?;
+            rom.meta = meta;
+            Ok(rom)
+        }
+        newer => Err(unsupported_version(newer)),
+    }
+}
+
+fn unsupported_version(version: u32) -> String {
+    format!(
+        "This rom uses format version {}, but the newest supported is {}. Try updating to a newer version.",
+        version, ROM_FORMAT_VERSION
+    )
+}
+
 impl GameCodeProvider for Rom {
     fn code(&self) -> &[u8] {
         &self.code
@@ -94,4 +363,281 @@ impl GameAssetProvider for Rom {
     fn sounds(&self) -> SoundRom {
         self.sounds.clone()
     }
+
+    fn meta(&self) -> RomMeta {
+        self.meta.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Thumbnail;
+
+    /// Writes a compressed rom file, as older or newer versions would have.
+    fn write_rom_file(path: &PathBuf, contents: &[u8]) {
+        let compressed = zstd::encode_all(contents, zstd::DEFAULT_COMPRESSION_LEVEL).unwrap();
+        fs::write(path, compressed).unwrap();
+    }
+
+    #[test]
+    fn test_loads_roms_from_before_versions() {
+        // Saved by the console before roms had a format version
+        let path = PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/test_data/baseline_v0.gcrom"
+        ));
+
+        let loaded = Rom::try_load(&path).unwrap();
+        assert_eq!(loaded.resolution, Resolution::Medium);
+        assert_eq!(loaded.frame_rate, FrameRate::Fast);
+        assert_eq!(loaded.player_count, (1, 2));
+        assert_eq!(loaded.code.as_ref(), [0, 0x61, 0x73, 0x6d, 1, 0, 0, 0]);
+        assert!(loaded.graphics.tilemaps.is_empty());
+        assert_eq!(Rom::try_load_meta(&path).unwrap(), RomMeta::default());
+
+        let song = &loaded.sounds.songs[0];
+        assert_eq!(song.bpm, 140.0);
+        assert_eq!(song.tracks.len(), 2);
+        assert_eq!(song.tracks[0][0], Some(gamercade_audio::ChainId(0)));
+
+        let instruments = &loaded.sounds.instruments;
+        assert!(matches!(
+            instruments[instruments.len() - 3],
+            Some(gamercade_audio::InstrumentDataDefinition::FMSynth(_))
+        ));
+        match &instruments[instruments.len() - 2] {
+            Some(gamercade_audio::InstrumentDataDefinition::Sampler(sample)) => {
+                assert_eq!(sample.data.as_ref(), [0, 1000, -1000, 32767]);
+                assert_eq!(sample.source_sample_rate, 22050);
+                assert_eq!(sample.sample_frequency, Some(440.0));
+            }
+            _ => panic!("expected the sampler instrument"),
+        }
+        assert!(instruments[instruments.len() - 1].is_none());
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn test_meta_round_trip() {
+        let path = std::env::temp_dir().join("gamercade_fs_meta.gcrom");
+        let rom = Rom {
+            code: vec![7, 8, 9].into_boxed_slice(),
+            meta: RomMeta {
+                title: String::from("Arcade Game"),
+                authors: vec![String::from("Alice"), String::from("Bob")],
+                version: String::from("1.2.0"),
+                description: String::from("Some game."),
+                created: 1_700_000_000,
+                thumbnail: Thumbnail::from_rgba(2, 1, &[255, 0, 0, 255, 0, 0, 255, 255]).ok(),
+            },
+            ..Default::default()
+        };
+        rom.try_save(&path).unwrap();
+
+        assert_eq!(Rom::try_load_meta(&path).unwrap(), rom.meta);
+
+        let loaded = Rom::try_load(&path).unwrap();
+        assert_eq!(loaded.meta, rom.meta);
+        assert_eq!(loaded.code, rom.code);
+
+        let without_meta = Rom {
+            meta: RomMeta::default(),
+            ..loaded.clone()
+        };
+        assert_eq!(loaded.checksum(), without_meta.checksum());
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_migrates_meta_without_thumbnail() {
+        #[derive(Serialize)]
+        struct RomMetaV2 {
+            title: String,
+            authors: Vec<String>,
+            version: String,
+            description: String,
+            created: u64,
+        }
+
+        let path = std::env::temp_dir().join("gamercade_fs_meta_v2.gcrom");
+        let meta = bincode::serialize(&RomMetaV2 {
+            title: String::from("Old Game"),
+            authors: vec![String::from("Alice")],
+            version: String::from("0.9.0"),
+            description: String::new(),
+            created: 1_600_000_000,
+        })
+        .unwrap();
+
+        let mut contents = ROM_MAGIC.to_vec();
+        contents.extend_from_slice(&2_u32.to_le_bytes());
+        contents.extend_from_slice(&(meta.len() as u32).to_le_bytes());
+        contents.extend_from_slice(&meta);
+        contents.extend_from_slice(&bincode::serialize(&Rom::default()).unwrap());
+        write_rom_file(&path, &contents);
+
+        let loaded = Rom::try_load_meta(&path).unwrap();
+        assert_eq!(loaded.title, "Old Game");
+        assert_eq!(loaded.created, 1_600_000_000);
+        assert_eq!(loaded.thumbnail, None);
+        assert_eq!(Rom::try_load(&path).unwrap().meta, loaded);
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_newer_versions_are_an_error() {
+        let path = std::env::temp_dir().join("gamercade_fs_newer_version.gcrom");
+        let mut contents = ROM_MAGIC.to_vec();
+        contents.extend_from_slice(&(ROM_FORMAT_VERSION + 1).to_le_bytes());
+        contents.extend_from_slice(&bincode::serialize(&Rom::default()).unwrap());
+        write_rom_file(&path, &contents);
+
+        let error = Rom::try_load(&path).unwrap_err();
+        assert!(error.contains(&format!("version {}", ROM_FORMAT_VERSION + 1)));
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_compression_level_is_validated() {
+        let path = std::env::temp_dir().join("gamercade_fs_compression_level.gcrom");
+        let rom = Rom {
+            code: vec![4, 5, 6].into_boxed_slice(),
+            ..Default::default()
+        };
+
+        let max_level = *zstd::compression_level_range().end();
+        rom.try_save_with_level(&path, max_level).unwrap();
+        assert_eq!(Rom::try_load(&path).unwrap().code, rom.code);
+
+        assert!(rom.try_save_with_level(&path, max_level + 1).is_err());
+
+        let _ = fs::remove_file(path);
+    }
+
+    fn rom_with_code(wat: &str) -> Rom {
+        Rom {
+            code: wat::parse_str(wat).unwrap().into_boxed_slice(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let valid = rom_with_code(
+            r#"(module
+                (import "env" "clear_screen" (func (param i32)))
+                (func (export "draw")))"#,
+        );
+        assert!(valid.validate().is_ok());
+
+        let garbage = Rom {
+            code: vec![1, 2, 3].into_boxed_slice(),
+            ..Default::default()
+        };
+        assert!(garbage
+            .validate()
+            .unwrap_err()
+            .contains("valid WebAssembly"));
+
+        let wrong_import = rom_with_code(
+            r#"(module
+                (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32)))
+                (func (export "draw")))"#,
+        );
+        assert!(wrong_import.validate().unwrap_err().contains("fd_write"));
+
+        let no_functions = rom_with_code(r#"(module (func (export "main")))"#);
+        assert!(no_functions.validate().is_err());
+    }
+
+    #[test]
+    fn test_load_progress_and_cancel() {
+        let path = std::env::temp_dir().join("gamercade_fs_load_progress.gcrom");
+        Rom::default().try_save(&path).unwrap();
+
+        let progress = LoadProgress::default();
+        assert!(Rom::try_load_with_progress(&path, &progress).is_ok());
+        assert_eq!(progress.phase(), LoadPhase::Deserializing);
+        assert_eq!(progress.bytes_read(), progress.total_bytes());
+        assert_eq!(progress.fraction(), 1.0);
+
+        let cancelled = LoadProgress::default();
+        cancelled.cancel();
+        assert!(Rom::try_load_with_progress(&path, &cancelled).is_err());
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_round_trip_preserves_every_field() {
+        let path = std::env::temp_dir().join("gamercade_fs_round_trip.gcrom");
+        let mut rom = Rom {
+            resolution: Resolution::High,
+            frame_rate: FrameRate::Fast,
+            player_count: (2, 4),
+            code: vec![0, 97, 115, 109].into_boxed_slice(),
+            meta: RomMeta {
+                title: String::from("Round Trip"),
+                authors: vec![String::from("Alice")],
+                version: String::from("0.1.0"),
+                description: String::from("Every field set."),
+                created: 1_700_000_000,
+                thumbnail: Thumbnail::from_rgba(1, 1, &[0, 255, 0, 255]).ok(),
+            },
+            ..Default::default()
+        };
+        rom.graphics.palettes = rom.graphics.palettes[1..].into();
+        rom.sounds.sfx_channels += 1;
+        rom.try_save(&path).unwrap();
+
+        let loaded = Rom::try_load(&path).unwrap();
+        assert_eq!(loaded.resolution, rom.resolution);
+        assert_eq!(loaded.frame_rate, rom.frame_rate);
+        assert_eq!(loaded.player_count, rom.player_count);
+        assert_eq!(loaded.graphics.palettes.len(), rom.graphics.palettes.len());
+        assert_eq!(loaded.sounds.sfx_channels, rom.sounds.sfx_channels);
+        assert_eq!(loaded.code, rom.code);
+        assert_eq!(loaded.meta, rom.meta);
+
+        // Catches any field the assertions above miss
+        assert_eq!(
+            bincode::serialize(&loaded).unwrap(),
+            bincode::serialize(&rom).unwrap()
+        );
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_phrase_effects_round_trip() {
+        let path = std::env::temp_dir().join("gamercade_fs_phrase_effects.gcrom");
+        let vibrato = Some(gamercade_audio::Effect::Vibrato { depth: 3, speed: 7 });
+
+        let mut rom = Rom::default();
+        let mut phrases = rom.sounds.phrases.to_vec();
+        phrases[0].as_mut().unwrap().entries[0]
+            .as_mut()
+            .unwrap()
+            .effects[1] = vibrato;
+        rom.sounds.phrases = phrases.into_boxed_slice();
+        rom.try_save(&path).unwrap();
+
+        let loaded = Rom::try_load(&path).unwrap();
+        let entry = loaded.sounds.phrases[0].as_ref().unwrap().entries[0]
+            .as_ref()
+            .unwrap();
+        assert_eq!(entry.effects, [None, vibrato, None]);
+
+        let _ = fs::remove_file(path);
+    }
 }
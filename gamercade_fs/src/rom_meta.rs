@@ -0,0 +1,163 @@
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use gamercade_core::{Color, BYTES_PER_PIXEL};
+use serde::{Deserialize, Serialize};
+
+/// The largest thumbnail. Bigger images are scaled down to fit.
+pub const THUMBNAIL_MAX_SIZE: (usize, usize) = (160, 90);
+
+/// Describes a game to players, such as in a library or launcher.
+/// None of it changes how the game plays.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RomMeta {
+    pub title: String,
+    pub authors: Vec<String>,
+    pub version: String,
+    pub description: String,
+
+    /// When the rom was bundled, in seconds since the unix epoch.
+    pub created: u64,
+
+    /// Cover art for launchers. Roms don't need one.
+    #[serde(default)]
+    pub thumbnail: Option<Thumbnail>,
+}
+
+impl RomMeta {
+    /// The authors as a single line, such as "Alice, Bob".
+    pub fn authors_line(&self) -> String {
+        self.authors.join(", ")
+    }
+
+    /// Sets the creation time to now.
+    pub fn touch(&mut self) {
+        self.created = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+    }
+}
+
+/// A small picture of the game. Stored as an indexed image with its own colors,
+/// so it can be shown without loading the palettes from the rest of the rom.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Thumbnail {
+    width: usize,
+    height: usize,
+    colors: Vec<Color>,
+    pixels: Vec<u8>,
+}
+
+impl Thumbnail {
+    /// Makes a thumbnail from an rgba image, such as a frame drawn by the game,
+    /// scaling it down to fit in [`THUMBNAIL_MAX_SIZE`]. Fails if the image has
+    /// more than 256 colors.
+    pub fn from_rgba(width: usize, height: usize, rgba: &[u8]) -> Result<Self, String> {
+        if width == 0 || height == 0 || rgba.len() != width * height * BYTES_PER_PIXEL {
+            return Err(String::from("The image doesn't match its size."));
+        }
+
+        let scale = width
+            .div_ceil(THUMBNAIL_MAX_SIZE.0)
+            .max(height.div_ceil(THUMBNAIL_MAX_SIZE.1));
+        let (thumbnail_width, thumbnail_height) = (width / scale, height / scale);
+
+        let mut colors = Vec::new();
+        let mut indices = HashMap::new();
+        let mut pixels = Vec::with_capacity(thumbnail_width * thumbnail_height);
+
+        for y in 0..thumbnail_height {
+            for x in 0..thumbnail_width {
+                let start = (y * scale * width + x * scale) * BYTES_PER_PIXEL;
+                let pixel: [u8; BYTES_PER_PIXEL] =
+                    rgba[start..start + BYTES_PER_PIXEL].try_into().unwrap();
+
+                let index = match indices.get(&pixel) {
+                    Some(index) => *index,
+                    None => {
+                        let index = u8::try_from(colors.len()).map_err(|_| {
+                            String::from("The image has too many colors for a thumbnail.")
+                        })?;
+                        indices.insert(pixel, index);
+                        colors.push(Color::from(pixel));
+                        index
+                    }
+                };
+                pixels.push(index);
+            }
+        }
+
+        Ok(Self {
+            width: thumbnail_width,
+            height: thumbnail_height,
+            colors,
+            pixels,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The thumbnail as rgba, ready to draw.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|index| {
+                self.colors
+                    .get(*index as usize)
+                    .copied()
+                    .unwrap_or_default()
+                    .into_pixel_data()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumbnail_scales_down() {
+        let (width, height) = (THUMBNAIL_MAX_SIZE.0 * 4, THUMBNAIL_MAX_SIZE.1 * 4);
+        let rgba: Vec<u8> = (0..width * height)
+            .flat_map(|i| {
+                if (i % width) < width / 2 {
+                    [255, 0, 0, 255]
+                } else {
+                    [0, 0, 255, 255]
+                }
+            })
+            .collect();
+
+        let thumbnail = Thumbnail::from_rgba(width, height, &rgba).unwrap();
+        assert_eq!(thumbnail.width(), THUMBNAIL_MAX_SIZE.0);
+        assert_eq!(thumbnail.height(), THUMBNAIL_MAX_SIZE.1);
+
+        let thumbnail_rgba = thumbnail.to_rgba();
+        assert_eq!(&thumbnail_rgba[..4], &[255, 0, 0, 255]);
+        assert_eq!(
+            &thumbnail_rgba[thumbnail_rgba.len() - 4..],
+            &[0, 0, 255, 255]
+        );
+    }
+
+    #[test]
+    fn test_thumbnail_has_at_most_256_colors() {
+        // Every pixel is a different color
+        let rgba: Vec<u8> = (0..258)
+            .flat_map(|i: usize| [i as u8, (i / 256) as u8, 0, 255])
+            .collect();
+
+        assert!(Thumbnail::from_rgba(128, 2, &rgba[..256 * 4]).is_ok());
+        assert!(Thumbnail::from_rgba(129, 2, &rgba).is_err());
+    }
+}
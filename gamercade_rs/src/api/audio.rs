@@ -1,6 +1,8 @@
 use crate::raw;
 
-const SFX_CHANNELS: usize = 8;
+/// The most sfx channels a rom can have. Channels past the rom's
+/// own channel count are ignored by the console.
+const SFX_CHANNELS: usize = 16;
 const TOTAL_NOTES_COUNT: usize = 96;
 
 /// Starts playing the passed in BGM index.
@@ -50,3 +52,49 @@ pub fn play_frequency(frequency: f32, instrument_index: usize, channel: usize) {
         unsafe { raw::play_frequency(frequency, instrument_index as i32, channel as i32) }
     }
 }
+
+/// Sets the volume of the music, where 1.0 is the volume authored in the editor.
+/// This is layered on top of the song and project gains. Negative values are ignored.
+pub fn set_music_gain(gain: f32) {
+    unsafe { raw::set_music_gain(gain) }
+}
+
+/// Sets the volume of the sound effects, where 1.0 is the volume authored in the editor.
+/// This is layered on top of the sfx and project gains. Negative values are ignored.
+pub fn set_sfx_gain(gain: f32) {
+    unsafe { raw::set_sfx_gain(gain) }
+}
+
+/// Sets the stereo position of the channel, from -1.0 (left) to 1.0 (right).
+/// The pan stays with the channel until changed. An invalid channel will have no effect.
+pub fn set_pan(channel: usize, pan: f32) {
+    if channel < SFX_CHANNELS {
+        unsafe { raw::set_pan(channel as i32, pan) }
+    }
+}
+
+/// Sets the volume of all audio output, from 0.0 (silent) to 1.0 (full volume).
+/// Changes fade in over a few milliseconds, so this can be called every frame to fade out.
+pub fn set_master_volume(volume: f32) {
+    unsafe { raw::set_master_volume(volume) }
+}
+
+/// Turns the echo on or off. Turning it on starts from silence.
+/// The delay starts with the settings from the editor.
+pub fn set_delay_enabled(enabled: bool) {
+    unsafe { raw::set_delay_enabled(enabled as i32) }
+}
+
+/// Changes the echo. Time is in seconds up to 1.0, feedback is how much of the echo
+/// repeats, up to 0.95, and wet is the volume of the echo. Values are clamped to these ranges.
+pub fn set_delay_parameters(time: f32, feedback: f32, wet: f32) {
+    unsafe { raw::set_delay_parameters(time, feedback, wet) }
+}
+
+/// Sets how much of the channel is sent into the echo, from 0.0 (none) to 1.0 (all).
+/// This is layered on top of the sfx send from the editor. An invalid channel will have no effect.
+pub fn set_delay_send(channel: usize, send: f32) {
+    if channel < SFX_CHANNELS {
+        unsafe { raw::set_delay_send(channel as i32, send) }
+    }
+}
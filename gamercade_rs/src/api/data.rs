@@ -53,6 +53,32 @@ pub fn sprite_count(sprite_sheet: usize) -> Option<u32> {
     i32_u32_to_option(val)
 }
 
+/// Returns the number of tilemaps present in the ROM.
+pub fn tilemap_count() -> usize {
+    unsafe { raw::tilemap_count() as usize }
+}
+
+/// Returns the width of the requested tilemap, in tiles.
+/// If the index is invalid, will return None.
+pub fn tilemap_width(tilemap_index: usize) -> Option<u32> {
+    let val = unsafe { raw::tilemap_width(tilemap_index as i32) };
+    i32_u32_to_option(val)
+}
+
+/// Returns the height of the requested tilemap, in tiles.
+/// If the index is invalid, will return None.
+pub fn tilemap_height(tilemap_index: usize) -> Option<u32> {
+    let val = unsafe { raw::tilemap_height(tilemap_index as i32) };
+    i32_u32_to_option(val)
+}
+
+/// Returns the sprite sheet the requested tilemap draws its tiles from.
+/// If the index is invalid, will return None.
+pub fn tilemap_sprite_sheet(tilemap_index: usize) -> Option<u32> {
+    let val = unsafe { raw::tilemap_sprite_sheet(tilemap_index as i32) };
+    i32_u32_to_option(val)
+}
+
 /// Returns the length of the requested song in seconds.
 /// If the requested song is invalid, will return None.
 pub fn bgm_length_secs(bgm_index: usize) -> Option<f32> {
@@ -28,14 +28,35 @@ pub fn circle_filled(graphics_parameters: GraphicsParameters, x: i32, y: i32, ra
     unsafe { raw::circle_filled(graphics_parameters.0, x, y, radius as i32) }
 }
 
-/// Draws an empty rectangle with the top left point (x, y) with width and height.
+/// Draws an ellipse around point (x, y) on the screen with the passed in horizontal
+/// and vertical radii. Uses palette_index and color_index. A transparent color will
+/// still have it's RGB values used to color the screen.
+pub fn ellipse(
+    graphics_parameters: GraphicsParameters,
+    x: i32,
+    y: i32,
+    radius_x: u32,
+    radius_y: u32,
+) {
+    unsafe {
+        raw::ellipse(
+            graphics_parameters.0,
+            x,
+            y,
+            radius_x as i32,
+            radius_y as i32,
+        )
+    }
+}
+
+/// Draws an empty rectangle with the top left point (x, y), covering width x height pixels.
 /// Uses palette_index and color_index. A transparent color will still have it's
 /// RGB values used to color the screen.
 pub fn rect(graphics_parameters: GraphicsParameters, x: i32, y: i32, width: u32, height: u32) {
     unsafe { raw::rect(graphics_parameters.0, x, y, width as i32, height as i32) }
 }
 
-/// Draws a filled rectangle with the top left point (x, y) with width and height.
+/// Draws a filled rectangle with the top left point (x, y), covering width x height pixels.
 /// Uses palette_index and color_index. A transparent color will still have it's
 /// RGB values used to color the screen.
 pub fn rect_filled(
@@ -62,3 +83,104 @@ pub fn line(graphics_parameters: GraphicsParameters, x0: i32, y0: i32, x1: i32,
 pub fn sprite(graphics_parameters: GraphicsParameters, transparency_mask: u64, x: i32, y: i32) {
     unsafe { raw::sprite(graphics_parameters.0, transparency_mask as i64, x, y) }
 }
+
+/// Draws a sprite like `sprite`, but rotated clockwise about its center by the angle, in radians.
+/// Pixels are not smoothed, so the sprite keeps its original colors.
+/// An angle of 0 draws exactly the same as `sprite`.
+pub fn sprite_rotated(
+    graphics_parameters: GraphicsParameters,
+    transparency_mask: u64,
+    x: i32,
+    y: i32,
+    angle: f32,
+) {
+    unsafe { raw::sprite_rotated(graphics_parameters.0, transparency_mask as i64, x, y, angle) }
+}
+
+/// Draws a sprite like `sprite`, but stretched by the scales, with the top left point (x, y).
+/// Scales are rounded to the nearest whole number to keep pixels crisp, so 1.6 draws at 2x.
+/// Negative scales flip the sprite, combined with the flip in the graphics parameters.
+pub fn sprite_scaled(
+    graphics_parameters: GraphicsParameters,
+    transparency_mask: u64,
+    x: i32,
+    y: i32,
+    scale_x: f32,
+    scale_y: f32,
+) {
+    unsafe {
+        raw::sprite_scaled(
+            graphics_parameters.0,
+            transparency_mask as i64,
+            x,
+            y,
+            scale_x,
+            scale_y,
+        )
+    }
+}
+
+/// Draws a sprite like `sprite`, but ignores the camera, so it stays in place on the screen.
+/// Useful for drawing the HUD or UI of scrolling games.
+pub fn sprite_absolute(
+    graphics_parameters: GraphicsParameters,
+    transparency_mask: u64,
+    x: i32,
+    y: i32,
+) {
+    unsafe { raw::sprite_absolute(graphics_parameters.0, transparency_mask as i64, x, y) }
+}
+
+/// Draws the tilemap from the rom, with the point (camera_x, camera_y) of the map at the
+/// top left of the screen. Only the visible tiles are drawn, using the tilemap's sprite sheet.
+/// Uses palette_index and flipping from the graphics parameters, and the transparency mask
+/// works the same as for `sprite`. Uses the camera passed in instead of the one set by `set_camera`.
+pub fn draw_tilemap(
+    graphics_parameters: GraphicsParameters,
+    transparency_mask: u64,
+    tilemap_index: usize,
+    camera_x: i32,
+    camera_y: i32,
+) {
+    unsafe {
+        raw::draw_tilemap(
+            graphics_parameters.0,
+            transparency_mask as i64,
+            tilemap_index as i32,
+            camera_x,
+            camera_y,
+        )
+    }
+}
+
+/// Moves the camera to the point (x, y), which is subtracted from the position of every
+/// following sprite, primitive and text draw, except for `sprite_absolute`.
+/// The camera stays in place until it is moved again. Set it to (0, 0) to draw in screen space.
+pub fn set_camera(x: i32, y: i32) {
+    unsafe { raw::set_camera(x, y) }
+}
+
+/// Restricts all following draws, including `clear_screen`, to the rectangle with the
+/// top left point (x, y) and width and height. The rectangle is in screen space, so the
+/// camera doesn't move it. Useful for split screen games or windowed UI.
+pub fn set_clip_rect(x: i32, y: i32, width: u32, height: u32) {
+    unsafe { raw::set_clip_rect(x, y, width as i32, height as i32) }
+}
+
+/// Clears the clip rectangle, allowing draws to cover the whole screen again.
+pub fn clear_clip() {
+    unsafe { raw::clear_clip() }
+}
+
+/// Draws the color index from_color_index of every following sprite using to_color_index instead,
+/// which allows recoloring a few colors of a sprite without needing another palette.
+/// The transparency mask still applies to the original color indices.
+/// Remaps last until they are cleared with `clear_palette_remap`.
+pub fn set_palette_remap(from_color_index: u8, to_color_index: u8) {
+    unsafe { raw::set_palette_remap(from_color_index as i32, to_color_index as i32) }
+}
+
+/// Clears all palette remaps, so sprites are drawn with their own colors again.
+pub fn clear_palette_remap() {
+    unsafe { raw::clear_palette_remap() }
+}
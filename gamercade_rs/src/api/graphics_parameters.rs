@@ -50,7 +50,7 @@ impl GraphicsParameters {
     /// Sets the Flip X to the desired value.
     pub const fn flip_x(mut self, flip_x: bool) -> Self {
         let flip_x = flip_x as i32;
-        self.0 &= !(flip_x << FLIP_X_POSITION);
+        self.0 &= !(1 << FLIP_X_POSITION);
         self.0 |= flip_x << FLIP_X_POSITION;
         self
     }
@@ -58,7 +58,7 @@ impl GraphicsParameters {
     /// Sets the Flip Y to the desired value
     pub const fn flip_y(mut self, flip_y: bool) -> Self {
         let flip_y = flip_y as i32;
-        self.0 &= !(flip_y << FLIP_Y_POSITION);
+        self.0 &= !(1 << FLIP_Y_POSITION);
         self.0 |= flip_y << FLIP_Y_POSITION;
         self
     }
@@ -81,4 +81,16 @@ mod tests {
         let parameter2 = parameter2.color_index(2);
         assert_eq!(parameter1.0, parameter2.0);
     }
+
+    #[test]
+    fn test_flip_can_be_cleared() {
+        use super::GraphicsParameters;
+
+        let parameter = GraphicsParameters::default().sprite_index(3);
+        let flipped = parameter.flip_x(true).flip_y(true);
+        assert_ne!(parameter.0, flipped.0);
+
+        let cleared = flipped.flip_x(false).flip_y(false);
+        assert_eq!(parameter.0, cleared.0);
+    }
 }
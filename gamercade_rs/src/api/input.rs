@@ -1,5 +1,5 @@
 #![allow(missing_docs)]
-use super::{f32_to_option, i32_bool_to_option};
+use super::{f32_to_option, i32_bool_to_option, i32_u32_to_option};
 use crate::raw;
 
 use paste::paste;
@@ -7,6 +7,10 @@ use paste::paste;
 #[derive(Clone, Copy)]
 pub struct RawInputState(pub i64);
 
+/// The buttons held on a frame from the input history.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct HistoryButtons(pub u32);
+
 impl RawInputState {
     pub fn is_valid(self) -> bool {
         self.0 & 1 << 63 == 0
@@ -61,6 +65,27 @@ macro_rules! derive_input_api {
                 }
             )*
             // END TRIGGER MACRO
+
+            // INPUT HISTORY MACRO
+            $(
+                pub fn [<input_history_analog_ $anlg_name _x>](player_id: usize, frames_ago: usize) -> Option<f32> {
+                    let val = unsafe { raw::[<input_history_analog_ $anlg_name _x>](player_id as i32, frames_ago as i32) };
+                    f32_to_option(val)
+                }
+
+                pub fn [<input_history_analog_ $anlg_name _y>](player_id: usize, frames_ago: usize) -> Option<f32> {
+                    let val = unsafe { raw::[<input_history_analog_ $anlg_name _y>](player_id as i32, frames_ago as i32) };
+                    f32_to_option(val)
+                }
+            )*
+
+            $(
+                pub fn [<input_history_trigger_ $trg_name>](player_id: usize, frames_ago: usize) -> Option<f32> {
+                    let val = unsafe { raw::[<input_history_trigger_ $trg_name>](player_id as i32, frames_ago as i32) };
+                    f32_to_option(val)
+                }
+            )*
+            // END INPUT HISTORY MACRO
         }
     };
 }
@@ -77,6 +102,84 @@ pub fn raw_input_state(player_id: usize) -> Option<RawInputState> {
     }
 }
 
+impl HistoryButtons {
+    pub const A: u32 = 0b1;
+    pub const B: u32 = 0b10;
+    pub const C: u32 = 0b100;
+    pub const D: u32 = 0b1000;
+    pub const START: u32 = 0b1_0000;
+    pub const SELECT: u32 = 0b10_0000;
+    pub const UP: u32 = 0b100_0000;
+    pub const DOWN: u32 = 0b1000_0000;
+    pub const LEFT: u32 = 0b1_0000_0000;
+    pub const RIGHT: u32 = 0b10_0000_0000;
+    pub const LEFT_SHOULDER: u32 = 0b100_0000_0000;
+    pub const RIGHT_SHOULDER: u32 = 0b1000_0000_0000;
+    pub const LEFT_STICK: u32 = 0b1_0000_0000_0000;
+    pub const RIGHT_STICK: u32 = 0b10_0000_0000_0000;
+    pub const LEFT_TRIGGER: u32 = 0b100_0000_0000_0000;
+    pub const RIGHT_TRIGGER: u32 = 0b1000_0000_0000_0000;
+
+    /// Returns true if all of the buttons in the mask are held.
+    pub fn held(self, mask: u32) -> bool {
+        self.0 & mask == mask
+    }
+}
+
+/// Returns how many frames of input history are available for the player, up to 60.
+/// The history is shorter during the first frames of the game.
+/// If the player_id is invalid, returns None.
+pub fn input_history_len(player_id: usize) -> Option<usize> {
+    let val = unsafe { raw::input_history_len(player_id as i32) };
+    usize::try_from(val).ok()
+}
+
+/// Returns the buttons held by the player a number of frames ago, where 0 is the current frame.
+/// Frames older than the available history return no buttons held.
+/// The history is part of the rollback state, so it is safe to use for detecting motions
+/// such as a quarter circle forward. If the player_id is invalid, returns None.
+pub fn input_history_buttons(player_id: usize, frames_ago: usize) -> Option<HistoryButtons> {
+    let val = unsafe { raw::input_history_buttons(player_id as i32, frames_ago as i32) };
+    u32::try_from(val).ok().map(HistoryButtons)
+}
+
+/// The mouse buttons held by a player.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct MouseButtons(pub u32);
+
+impl MouseButtons {
+    pub const LEFT: u32 = 0b1;
+    pub const RIGHT: u32 = 0b10;
+    pub const MIDDLE: u32 = 0b100;
+
+    /// Returns true if all of the buttons in the mask are held.
+    pub fn held(self, mask: u32) -> bool {
+        self.0 & mask == mask
+    }
+}
+
+/// Returns the x position of the player's mouse cursor, in screen pixels.
+/// The mouse is part of each player's input, so it is synchronized over the network
+/// and safe to use in multiplayer. If the player_id is invalid, returns None.
+pub fn mouse_x(player_id: usize) -> Option<u32> {
+    let val = unsafe { raw::mouse_x(player_id as i32) };
+    i32_u32_to_option(val)
+}
+
+/// Returns the y position of the player's mouse cursor, in screen pixels.
+/// If the player_id is invalid, returns None.
+pub fn mouse_y(player_id: usize) -> Option<u32> {
+    let val = unsafe { raw::mouse_y(player_id as i32) };
+    i32_u32_to_option(val)
+}
+
+/// Returns the mouse buttons held by the player this frame.
+/// If the player_id is invalid, returns None.
+pub fn mouse_buttons(player_id: usize) -> Option<MouseButtons> {
+    let val = unsafe { raw::mouse_buttons(player_id as i32) };
+    u32::try_from(val).ok().map(MouseButtons)
+}
+
 derive_input_api! {
     Buttons {
         a,
@@ -36,6 +36,12 @@ pub mod multiplayer;
 /// Functions to handle random number generation.
 pub mod random;
 
+/// Functions for rumbling the players' gamepads.
+pub mod rumble;
+
+/// Functions for reading and writing save data, which persists between sessions.
+pub mod save;
+
 /// Functions for dealing with text and strings.
 pub mod text;
 
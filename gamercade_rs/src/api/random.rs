@@ -1,13 +1,15 @@
 use crate::raw;
 
 /// Sets the random number generator to the desired seed.
+/// The generator is shared by all players, and is rolled back along with the game,
+/// so every player gets the same values as long as they call it the same way.
 pub fn set_seed(seed: i32) {
     unsafe { raw::set_seed(seed) }
 }
 
 /// Gets a random number from min, up to max. Max is non-inclusive.
 /// For example, random_int_range(0, 10) will return any value
-/// from 0 to 9, but not 10.
+/// from 0 to 9, but not 10. If max isn't greater than min, returns min.
 pub fn random_int_range(min: i32, max: i32) -> i32 {
     unsafe { raw::random_int_range(min, max) }
 }
@@ -0,0 +1,19 @@
+use crate::raw;
+
+/// Rumbles the player's gamepad for a number of frames, replacing any rumble
+/// already playing. The strength goes from 0.0 to 1.0, and 0.0 stops the rumble.
+/// Players without a gamepad, or on another machine, are ignored.
+///
+/// Rumble isn't part of the game state, so it's safe to call from `update()`.
+/// It only happens the first time a frame runs: when a rollback runs the frame
+/// again, the rumble isn't repeated. If that frame was mispredicted, the rumble
+/// can't be taken back either, so keep it for feedback rather than information.
+pub fn set_rumble(player_id: usize, strength: f32, frames: usize) {
+    unsafe {
+        raw::set_rumble(
+            player_id as i32,
+            strength,
+            frames.min(i32::MAX as usize) as i32,
+        )
+    }
+}
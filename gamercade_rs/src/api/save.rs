@@ -0,0 +1,24 @@
+use crate::raw;
+
+/// The maximum size, in bytes, of the game's save data.
+pub const SAVE_DATA_MAX_SIZE: usize = 4096;
+
+/// Returns the length of the game's save data, in bytes.
+pub fn save_data_len() -> usize {
+    unsafe { raw::save_data_len() as usize }
+}
+
+/// Reads the game's save data into the buffer, and returns the number of bytes read.
+/// Only reads as much as fits in the buffer.
+/// WARNING: Save data is stored on each player's machine, so it can differ between clients!
+/// Only read it for things which don't affect the game state, such as settings or high scores.
+pub fn read_save_data(buffer: &mut [u8]) -> usize {
+    unsafe { raw::read_save_data(buffer.as_mut_ptr() as i32, buffer.len() as i32) as usize }
+}
+
+/// Replaces the game's save data. Returns false if the data is larger than SAVE_DATA_MAX_SIZE.
+/// The data is written to disk once the frame is confirmed, so writes from
+/// frames which are rolled back are never saved.
+pub fn write_save_data(data: &[u8]) -> bool {
+    unsafe { raw::write_save_data(data.as_ptr() as i32, data.len() as i32) >= 0 }
+}
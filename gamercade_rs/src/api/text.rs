@@ -1,4 +1,4 @@
-use crate::raw;
+use crate::{prelude::GraphicsParameters, raw};
 
 /// Logs text to the console window.
 pub fn console_log(text: &str) {
@@ -6,6 +6,21 @@ pub fn console_log(text: &str) {
     unsafe { raw::console_log(text.0, text.1) }
 }
 
+/// Draws text using the built in 5 x 7 pixel font, with the top left point (x, y).
+/// Uses palette_index and color_index. New lines start again below the first line.
+/// Spacing is the number of extra pixels between each character and line.
+/// Characters outside of printable ascii are drawn as a question mark.
+pub fn draw_text(
+    text: &str,
+    graphics_parameters: GraphicsParameters,
+    x: i32,
+    y: i32,
+    spacing: i32,
+) {
+    let text = make_wasm_text_ptr(text);
+    unsafe { raw::draw_text(text.0, text.1, graphics_parameters.0, x, y, spacing) }
+}
+
 fn make_wasm_text_ptr(text: &str) -> (i32, i32) {
     (text.as_ptr() as i32, text.len() as i32)
 }
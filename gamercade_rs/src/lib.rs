@@ -28,5 +28,7 @@ pub mod prelude {
     pub use crate::api::input::*;
     pub use crate::api::multiplayer::*;
     pub use crate::api::random::*;
+    pub use crate::api::rumble::*;
+    pub use crate::api::save::*;
     pub use crate::api::text::*;
 }
@@ -8,6 +8,13 @@ extern "C" {
     pub fn stop_channel(channel: i32);
     pub fn play_note(note_id: i32, instrument_index: i32, channel: i32);
     pub fn play_frequency(frequency: f32, instrument_index: i32, channel: i32);
+    pub fn set_music_gain(gain: f32);
+    pub fn set_sfx_gain(gain: f32);
+    pub fn set_pan(channel: i32, pan: f32);
+    pub fn set_master_volume(volume: f32);
+    pub fn set_delay_enabled(enabled: i32);
+    pub fn set_delay_parameters(time: f32, feedback: f32, wet: f32);
+    pub fn set_delay_send(channel: i32, send: f32);
 }
 
 // Data
@@ -21,6 +28,10 @@ extern "C" {
     pub fn sprite_height(sprite_sheet: i32) -> i32;
     pub fn sprite_width(sprite_sheet: i32) -> i32;
     pub fn sprite_count(sprite_sheet: i32) -> i32;
+    pub fn tilemap_count() -> i32;
+    pub fn tilemap_width(tilemap_index: i32) -> i32;
+    pub fn tilemap_height(tilemap_index: i32) -> i32;
+    pub fn tilemap_sprite_sheet(tilemap_index: i32) -> i32;
     pub fn bgm_length_secs(bgm_index: i32) -> f32;
     pub fn bgm_length_frames(bgm_index: i32) -> i32;
     pub fn sfx_length_secs(sfx_index: i32) -> f32;
@@ -51,16 +62,61 @@ extern "C" {
     pub fn set_pixel(graphics_parameters: i32, x: i32, y: i32);
     pub fn circle(graphics_parameters: i32, x: i32, y: i32, radius: i32);
     pub fn circle_filled(graphics_parameters: i32, x: i32, y: i32, radius: i32);
+    pub fn ellipse(graphics_parameters: i32, x: i32, y: i32, radius_x: i32, radius_y: i32);
     pub fn rect(graphics_parameters: i32, x: i32, y: i32, width: i32, height: i32);
     pub fn rect_filled(graphics_parameters: i32, x: i32, y: i32, width: i32, height: i32);
     pub fn line(graphics_parameters: i32, x0: i32, y0: i32, x1: i32, y1: i32);
     pub fn sprite(graphics_parameters: i32, transparency_mask: i64, x: i32, y: i32);
+    pub fn sprite_rotated(
+        graphics_parameters: i32,
+        transparency_mask: i64,
+        x: i32,
+        y: i32,
+        angle: f32,
+    );
+    pub fn sprite_scaled(
+        graphics_parameters: i32,
+        transparency_mask: i64,
+        x: i32,
+        y: i32,
+        scale_x: f32,
+        scale_y: f32,
+    );
+    pub fn sprite_absolute(graphics_parameters: i32, transparency_mask: i64, x: i32, y: i32);
+    pub fn draw_tilemap(
+        graphics_parameters: i32,
+        transparency_mask: i64,
+        tilemap_index: i32,
+        camera_x: i32,
+        camera_y: i32,
+    );
+    pub fn set_camera(x: i32, y: i32);
+    pub fn set_clip_rect(x: i32, y: i32, width: i32, height: i32);
+    pub fn clear_clip();
+    pub fn set_palette_remap(from_color_index: i32, to_color_index: i32);
+    pub fn clear_palette_remap();
 }
 
 // Text
 extern "C" {
     pub fn console_log(text_ptr: i32, len: i32);
     pub fn console_log_utf16(text_ptr: i32, len: i32);
+    pub fn draw_text(
+        text_ptr: i32,
+        len: i32,
+        graphics_parameters: i32,
+        x: i32,
+        y: i32,
+        spacing: i32,
+    );
+    pub fn draw_text_utf16(
+        text_ptr: i32,
+        len: i32,
+        graphics_parameters: i32,
+        x: i32,
+        y: i32,
+        spacing: i32,
+    );
 }
 
 // Random
@@ -128,6 +184,17 @@ extern "C" {
     pub fn trigger_left(player_id: i32) -> f32;
     pub fn trigger_right(player_id: i32) -> f32;
     pub fn raw_input_state(player_id: i32) -> i64;
+    pub fn mouse_x(player_id: i32) -> i32;
+    pub fn mouse_y(player_id: i32) -> i32;
+    pub fn mouse_buttons(player_id: i32) -> i32;
+    pub fn input_history_len(player_id: i32) -> i32;
+    pub fn input_history_buttons(player_id: i32, frames_ago: i32) -> i32;
+    pub fn input_history_analog_left_x(player_id: i32, frames_ago: i32) -> f32;
+    pub fn input_history_analog_left_y(player_id: i32, frames_ago: i32) -> f32;
+    pub fn input_history_analog_right_x(player_id: i32, frames_ago: i32) -> f32;
+    pub fn input_history_analog_right_y(player_id: i32, frames_ago: i32) -> f32;
+    pub fn input_history_trigger_left(player_id: i32, frames_ago: i32) -> f32;
+    pub fn input_history_trigger_right(player_id: i32, frames_ago: i32) -> f32;
 }
 
 // Multiplayer
@@ -136,3 +203,15 @@ extern "C" {
     pub fn is_local_player(player_id: i32) -> i32;
     pub fn is_remote_player(player_id: i32) -> i32;
 }
+
+// Save
+extern "C" {
+    pub fn save_data_len() -> i32;
+    pub fn read_save_data(ptr: i32, len: i32) -> i32;
+    pub fn write_save_data(ptr: i32, len: i32) -> i32;
+}
+
+// Rumble
+extern "C" {
+    pub fn set_rumble(player_id: i32, strength: f32, frames: i32);
+}
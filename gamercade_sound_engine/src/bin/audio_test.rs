@@ -2,15 +2,15 @@ use std::{process, sync::Arc, time::Duration};
 
 use arrayvec::ArrayVec;
 use gamercade_audio::{
-    Chain, ChainId, EnvelopeDefinition, IndexInterpolator, InstrumentDataDefinition, InstrumentId,
-    LoopMode, PatchDefinition, Phrase, PhraseId, SampleBitDepth, SampleDefinition, Song, SongId,
-    SoundRom, WavetableDefinition, WavetableGenerator, WavetableWaveform,
+    BusGains, Chain, ChainId, EnvelopeDefinition, IndexInterpolator, InstrumentDataDefinition,
+    InstrumentId, LoopMode, PatchDefinition, Phrase, PhraseId, SampleBitDepth, SampleDefinition,
+    Song, SongId, SoundRom, Tuning, WavetableDefinition, WavetableGenerator, WavetableWaveform,
+    SFX_CHANNELS,
 };
+use gamercade_core::FrameRate;
 use gamercade_sound_engine::{SoundEngine, SoundEngineData, SoundRomInstance};
 use hound::WavReader;
 
-const FPS: usize = 60;
-
 pub fn main() {
     let panic_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
@@ -20,9 +20,9 @@ pub fn main() {
 
     let test_rom = Arc::new(test_rom());
 
-    let mut engine = SoundEngine::new(FPS, &test_rom, 8);
+    let mut engine = SoundEngine::new(FrameRate::Normal, &test_rom, 8);
     let output_sample_rate = engine.output_sample_rate();
-    let mut data = SoundEngineData::new(output_sample_rate, &test_rom);
+    let mut data = SoundEngineData::new(output_sample_rate, engine.frame_rate(), &test_rom);
 
     data.play_bgm(Some(SongId(0)));
     engine.sync_audio_thread(&data);
@@ -44,6 +44,10 @@ fn test_rom() -> SoundRomInstance {
             .generate(),
             envelope: EnvelopeDefinition::interesting(),
             interpolator: gamercade_audio::IndexInterpolator::Linear,
+            filter: None,
+            lfo: None,
+            morph_data: Box::default(),
+            morph: 0.0,
         })),
         Some(InstrumentDataDefinition::Sampler(sampler_no_pitch())),
         Some(InstrumentDataDefinition::Sampler(sampler_pitched())),
@@ -90,6 +94,8 @@ fn test_rom() -> SoundRomInstance {
             [Some(ChainId(3)), None, None, None, None, None, None, None],
         ]
         .into_boxed_slice(),
+        gain: 1.0,
+        swing: 0.0,
     }]
     .into_boxed_slice();
 
@@ -114,6 +120,10 @@ fn test_rom() -> SoundRomInstance {
         .into_boxed_slice(),
         instruments: instruments.into_boxed_slice(),
         sfx: vec![].into_boxed_slice(),
+        bus_gains: BusGains::default(),
+        delay: Default::default(),
+        sfx_channels: SFX_CHANNELS,
+        tuning: Tuning::default(),
     };
 
     SoundRomInstance::new(&rom)
@@ -143,6 +153,7 @@ fn sampler_no_pitch() -> SampleDefinition {
         envelope_definition: EnvelopeDefinition::always_on(),
         interpolator: IndexInterpolator::default(),
         loop_mode: LoopMode::Loop,
+        filter: None,
     }
 }
 
@@ -170,5 +181,6 @@ fn sampler_pitched() -> SampleDefinition {
         envelope_definition: EnvelopeDefinition::interesting(),
         interpolator: IndexInterpolator::default(),
         loop_mode: LoopMode::Oneshot,
+        filter: None,
     }
 }
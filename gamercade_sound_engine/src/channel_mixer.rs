@@ -0,0 +1,175 @@
+use gamercade_audio::{SFX_CHANNELS_MAX, SONG_TRACK_CHANNELS};
+
+/// A channel which can be adjusted in the mixer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MixerChannel {
+    Sfx(usize),
+    Bgm(usize),
+}
+
+/// The mixer settings for a single channel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MixerStrip {
+    pub volume: f32,
+    pub muted: bool,
+    pub soloed: bool,
+}
+
+impl Default for MixerStrip {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            muted: false,
+            soloed: false,
+        }
+    }
+}
+
+/// Mutes, solos and scales channels on top of their authored gains.
+/// If any channel is soloed, only soloed channels are heard, even if they are muted.
+#[derive(Debug, Clone)]
+pub struct ChannelMixer {
+    sfx: [MixerStrip; SFX_CHANNELS_MAX],
+    bgm: [MixerStrip; SONG_TRACK_CHANNELS],
+
+    /// The resulting gain of each channel, updated whenever a strip changes.
+    sfx_gains: [f32; SFX_CHANNELS_MAX],
+    bgm_gains: [f32; SONG_TRACK_CHANNELS],
+}
+
+impl Default for ChannelMixer {
+    fn default() -> Self {
+        Self {
+            sfx: Default::default(),
+            bgm: Default::default(),
+            sfx_gains: [1.0; SFX_CHANNELS_MAX],
+            bgm_gains: [1.0; SONG_TRACK_CHANNELS],
+        }
+    }
+}
+
+impl ChannelMixer {
+    pub fn strip(&self, channel: MixerChannel) -> Option<&MixerStrip> {
+        match channel {
+            MixerChannel::Sfx(index) => self.sfx.get(index),
+            MixerChannel::Bgm(index) => self.bgm.get(index),
+        }
+    }
+
+    /// Replaces the strip of the channel. Invalid channels are ignored,
+    /// and the volume is clamped between 0.0 and 1.0.
+    pub fn set_strip(&mut self, channel: MixerChannel, mut strip: MixerStrip) {
+        let target = match channel {
+            MixerChannel::Sfx(index) => self.sfx.get_mut(index),
+            MixerChannel::Bgm(index) => self.bgm.get_mut(index),
+        };
+
+        if let Some(target) = target {
+            strip.volume = if strip.volume.is_finite() {
+                strip.volume.clamp(0.0, 1.0)
+            } else {
+                target.volume
+            };
+            *target = strip;
+            self.update_gains();
+        }
+    }
+
+    pub fn sfx_gain(&self, channel: usize) -> f32 {
+        self.sfx_gains.get(channel).copied().unwrap_or_default()
+    }
+
+    pub fn bgm_gain(&self, track: usize) -> f32 {
+        self.bgm_gains.get(track).copied().unwrap_or_default()
+    }
+
+    fn update_gains(&mut self) {
+        let any_soloed = self
+            .sfx
+            .iter()
+            .chain(self.bgm.iter())
+            .any(|strip| strip.soloed);
+
+        let gain = |strip: &MixerStrip| {
+            let audible = if any_soloed {
+                strip.soloed
+            } else {
+                !strip.muted
+            };
+
+            if audible {
+                strip.volume
+            } else {
+                0.0
+            }
+        };
+
+        self.sfx_gains = std::array::from_fn(|index| gain(&self.sfx[index]));
+        self.bgm_gains = std::array::from_fn(|index| gain(&self.bgm[index]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solo_overrides_mute() {
+        let mut mixer = ChannelMixer::default();
+
+        mixer.set_strip(
+            MixerChannel::Sfx(0),
+            MixerStrip {
+                muted: true,
+                ..MixerStrip::default()
+            },
+        );
+        assert_eq!(mixer.sfx_gain(0), 0.0);
+        assert_eq!(mixer.sfx_gain(1), 1.0);
+
+        mixer.set_strip(
+            MixerChannel::Bgm(1),
+            MixerStrip {
+                volume: 0.5,
+                soloed: true,
+                ..MixerStrip::default()
+            },
+        );
+        assert_eq!(mixer.bgm_gain(1), 0.5);
+        assert_eq!(mixer.bgm_gain(0), 0.0);
+        assert_eq!(mixer.sfx_gain(1), 0.0);
+
+        mixer.set_strip(
+            MixerChannel::Sfx(0),
+            MixerStrip {
+                muted: true,
+                soloed: true,
+                ..MixerStrip::default()
+            },
+        );
+        assert_eq!(mixer.sfx_gain(0), 1.0);
+    }
+
+    #[test]
+    fn test_invalid_strips_are_ignored() {
+        let mut mixer = ChannelMixer::default();
+
+        mixer.set_strip(
+            MixerChannel::Sfx(SFX_CHANNELS_MAX),
+            MixerStrip {
+                soloed: true,
+                ..MixerStrip::default()
+            },
+        );
+        assert_eq!(mixer.sfx_gain(0), 1.0);
+
+        mixer.set_strip(
+            MixerChannel::Bgm(0),
+            MixerStrip {
+                volume: f32::NAN,
+                ..MixerStrip::default()
+            },
+        );
+        assert_eq!(mixer.bgm_gain(0), 1.0);
+    }
+}
@@ -0,0 +1,101 @@
+use gamercade_audio::{DelayDefinition, DELAY_MAX_SECONDS};
+
+/// A mono echo which the channels send into. The buffer is part of the
+/// engine state, so rolling back doesn't cut off echoes which are still playing.
+#[derive(Debug, Clone)]
+pub struct DelayLine {
+    definition: DelayDefinition,
+    buffer: Box<[f32]>,
+    write_index: usize,
+    output_sample_rate: usize,
+}
+
+impl DelayLine {
+    pub fn new(definition: DelayDefinition, output_sample_rate: usize) -> Self {
+        let length = (DELAY_MAX_SECONDS * output_sample_rate as f32) as usize + 1;
+
+        Self {
+            definition: definition.clamped(),
+            buffer: vec![0.0; length].into_boxed_slice(),
+            write_index: 0,
+            output_sample_rate,
+        }
+    }
+
+    pub fn definition(&self) -> &DelayDefinition {
+        &self.definition
+    }
+
+    /// Changes the delay parameters. Turning the delay
+    /// back on starts from silence, rather than old echoes.
+    pub fn set_definition(&mut self, definition: DelayDefinition) {
+        if definition.enabled && !self.definition.enabled {
+            self.buffer.iter_mut().for_each(|sample| *sample = 0.0);
+        }
+
+        self.definition = definition.clamped();
+    }
+
+    /// Feeds a sample into the delay, returning the echo to mix into the output.
+    pub fn tick(&mut self, input: f32) -> f32 {
+        if !self.definition.enabled {
+            return 0.0;
+        }
+
+        let length = self.buffer.len();
+        let delay_samples =
+            ((self.definition.time * self.output_sample_rate as f32) as usize).clamp(1, length - 1);
+        let read_index = (self.write_index + length - delay_samples) % length;
+
+        let delayed = self.buffer[read_index];
+        self.buffer[self.write_index] = input + delayed * self.definition.feedback;
+        self.write_index = (self.write_index + 1) % length;
+
+        delayed * self.definition.wet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled_delay() -> DelayDefinition {
+        DelayDefinition {
+            enabled: true,
+            time: 0.01,
+            feedback: 0.5,
+            wet: 1.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_delay_echoes_and_fades() {
+        let mut delay = DelayLine::new(enabled_delay(), 1_000);
+
+        let output = (0..40)
+            .map(|index| delay.tick(if index == 0 { 1.0 } else { 0.0 }))
+            .collect::<Vec<_>>();
+
+        // Echoes every 10 samples, halving each time.
+        assert_eq!(output[10], 1.0);
+        assert_eq!(output[20], 0.5);
+        assert_eq!(output[30], 0.25);
+        assert_eq!(output.iter().filter(|sample| **sample != 0.0).count(), 3);
+    }
+
+    #[test]
+    fn test_disabled_delay_is_silent() {
+        let mut delay = DelayLine::new(DelayDefinition::default(), 1_000);
+        assert!((0..100).all(|_| delay.tick(1.0) == 0.0));
+
+        // Out of range values are clamped.
+        delay.set_definition(DelayDefinition {
+            feedback: 10.0,
+            time: f32::NAN,
+            ..enabled_delay()
+        });
+        assert!(delay.definition().feedback < 1.0);
+        assert_eq!(delay.definition().time, 0.0);
+    }
+}
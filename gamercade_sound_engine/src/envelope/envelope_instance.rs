@@ -1,4 +1,4 @@
-use crate::{ActiveState, EnvelopePhase, ExponentialRamp};
+use crate::{ActiveState, EnvelopePhase, EnvelopeRamp};
 use gamercade_audio::{EnvelopeDefinition, EnvelopeValue};
 
 /// Max length in seconds, ~4.267mins.
@@ -8,7 +8,7 @@ pub(crate) const ENVELOPE_TIME_SCALE: f32 = 256.0;
 #[derive(Clone, Debug)]
 pub struct EnvelopeInstance {
     definition: EnvelopeDefinition,
-    ramp: ExponentialRamp,
+    ramp: EnvelopeRamp,
     state: EnvelopePhase,
 }
 
@@ -21,11 +21,19 @@ impl EnvelopeInstance {
     pub fn new(definition: &EnvelopeDefinition, output_sample_rate: usize) -> Self {
         Self {
             definition: definition.clone(),
-            ramp: ExponentialRamp::new(output_sample_rate),
+            ramp: EnvelopeRamp::new(output_sample_rate),
             state: EnvelopePhase::Off,
         }
     }
 
+    /// Whether the envelope is still playing its note, rather than releasing or finished.
+    pub fn is_held(&self) -> bool {
+        matches!(
+            self.state,
+            EnvelopePhase::Attack | EnvelopePhase::Decay | EnvelopePhase::Sustain
+        )
+    }
+
     /// Advances the envelope forward one tick and returns the output value.
     pub fn tick(&mut self, active: ActiveState) -> f32 {
         if self.definition.total_level == EnvelopeValue(0) {
@@ -1,39 +1,43 @@
-use crate::{EnvelopeDefinition, EnvelopePhase, ENVELOPE_TIME_SCALE};
+use crate::{EnvelopePhase, ENVELOPE_TIME_SCALE};
+use gamercade_audio::{EnvelopeCurve, EnvelopeDefinition};
 
-const OVERSHOOT: f32 = 1.001;
-
-/// An exponential ramp which, when ticked, travels from one value to the target one.
+/// A ramp which, when ticked, travels from one value to the target one
+/// following the shape of an envelope curve.
 #[derive(Clone, Debug)]
-pub struct ExponentialRamp {
+pub struct EnvelopeRamp {
     output_sample_rate: usize,
-    value: f32,              // The current value
-    target_value: f32,       // The "end" value
-    overshoot_value: f32,    // The "overshoot" value since we are dealing a small margin of error
-    decaying_increment: f32, // The increment which changes over time
-    multiplier: f32,         // The multiplier for the increment
+    curve: EnvelopeCurve,
+    value: f32,        // The current value
+    start_value: f32,  // The value when the ramp started
+    target_value: f32, // The "end" value
+    total_ticks: usize,
+    elapsed_ticks: usize,
     is_constant: bool,
 }
 
-impl ExponentialRamp {
-    /// Generates a new exponential ramp with the default values of 0.
+impl EnvelopeRamp {
+    /// Generates a new envelope ramp with the default values of 0.
     pub fn new(output_sample_rate: usize) -> Self {
         Self {
             output_sample_rate,
+            curve: EnvelopeCurve::default(),
             value: 0.0,
+            start_value: 0.0,
             target_value: 0.0,
-            overshoot_value: 0.0,
-            decaying_increment: 0.0,
-            multiplier: 0.0,
+            total_ticks: 0,
+            elapsed_ticks: 0,
             is_constant: true,
         }
     }
 
-    /// Generates a new exponential ramp to be used with the ADSR envelope.
+    /// Sets the ramp to the next phase of the ADSR envelope.
     pub(crate) fn set_from_envelope(
         &mut self,
         phase: EnvelopePhase,
         definition: &EnvelopeDefinition,
     ) {
+        self.curve = definition.curve;
+
         match phase {
             EnvelopePhase::Attack => self.ramp_to(
                 definition.total_level.to_scaled_value(),
@@ -77,37 +81,38 @@ impl ExponentialRamp {
     /// Causes the ramp to hold at the passed in value
     pub fn set_constant_value(&mut self, new_value: f32) {
         self.value = new_value;
+        self.start_value = new_value;
         self.target_value = new_value;
-        self.overshoot_value = new_value;
-        self.decaying_increment = 0.0;
-        self.multiplier = 0.0;
+        self.total_ticks = 0;
+        self.elapsed_ticks = 0;
         self.is_constant = true;
     }
 
     /// Sets the next target value for the ramp and how long it should take to get there.
     pub fn ramp_to(&mut self, target_value: f32, time: f32) {
+        self.start_value = self.value;
         self.target_value = target_value;
-
-        let distance_to_target = target_value - self.value;
-        self.overshoot_value = self.value + (distance_to_target * OVERSHOOT);
-
-        self.decaying_increment = self.value - self.overshoot_value;
-
-        let time = (-1.0 * time) / (1.0 - OVERSHOOT.recip()).ln();
-        self.multiplier = f32::powf(
-            f32::exp(-1.0 / time),
-            (self.output_sample_rate as f32).recip(),
-        );
-
+        self.total_ticks = (time * self.output_sample_rate as f32).ceil() as usize;
+        self.elapsed_ticks = 0;
         self.is_constant = false;
+
+        if self.total_ticks == 0 {
+            self.value = target_value;
+        }
     }
 
     /// Ticks the ramp, advancing it forward once and returing the resulting value.
     pub(crate) fn tick(&mut self) -> f32 {
-        self.value = self.overshoot_value + self.decaying_increment;
-
         if !self.is_finished() {
-            self.decaying_increment *= self.multiplier;
+            self.elapsed_ticks += 1;
+
+            self.value = if self.elapsed_ticks >= self.total_ticks {
+                self.target_value
+            } else {
+                let progress = self.elapsed_ticks as f32 / self.total_ticks as f32;
+                let distance = self.target_value - self.start_value;
+                self.start_value + (distance * self.curve.apply(progress))
+            };
         }
 
         self.value
@@ -115,16 +120,6 @@ impl ExponentialRamp {
 
     /// Returns true if the ramp is done advancing.
     pub fn is_finished(&self) -> bool {
-        if self.is_constant {
-            false
-        } else {
-            // Going up
-            if self.value >= self.target_value && self.value <= self.overshoot_value {
-                true
-            } else {
-                // Going Down
-                self.value <= self.target_value && self.value >= self.overshoot_value
-            }
-        }
+        !self.is_constant && self.elapsed_ticks >= self.total_ticks
     }
 }
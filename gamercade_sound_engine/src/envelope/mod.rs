@@ -1,9 +1,9 @@
 mod envelope_instance;
 mod envelope_phase;
-mod exponential_ramp;
+mod envelope_ramp;
 mod linear_ramp;
 
 pub use envelope_instance::*;
 pub(crate) use envelope_phase::*;
-pub use exponential_ramp::*;
+pub use envelope_ramp::*;
 pub use linear_ramp::*;
@@ -0,0 +1,78 @@
+use std::f32::consts::PI;
+
+use gamercade_audio::FilterDefinition;
+
+/// The lowest damping value, prevents the filter from self oscillating.
+const MIN_DAMPING: f32 = 0.05;
+
+/// A running instance of a resonant low-pass filter.
+/// Implemented as a topology preserving state variable filter.
+#[derive(Clone, Debug)]
+pub struct FilterInstance {
+    definition: FilterDefinition,
+    output_sample_rate: usize,
+
+    // The values used to calculate the current coefficients
+    cutoff: f32,
+    resonance: f32,
+
+    // Coefficients
+    a1: f32,
+    a2: f32,
+    a3: f32,
+
+    // State
+    ic1eq: f32,
+    ic2eq: f32,
+}
+
+impl FilterInstance {
+    /// Generates a new filter with the given sample rate.
+    pub fn new(definition: &FilterDefinition, output_sample_rate: usize) -> Self {
+        let mut out = Self {
+            definition: definition.clone(),
+            output_sample_rate,
+            cutoff: f32::NAN,
+            resonance: f32::NAN,
+            a1: 0.0,
+            a2: 0.0,
+            a3: 0.0,
+            ic1eq: 0.0,
+            ic2eq: 0.0,
+        };
+        out.update_coefficients(definition.cutoff, definition.resonance);
+        out
+    }
+
+    /// Recalculates the coefficients, but only if the parameters have changed.
+    fn update_coefficients(&mut self, cutoff: f32, resonance: f32) {
+        if cutoff == self.cutoff && resonance == self.resonance {
+            return;
+        }
+
+        self.cutoff = cutoff;
+        self.resonance = resonance;
+
+        let nyquist = self.output_sample_rate as f32 * 0.49;
+        let g = (PI * cutoff.min(nyquist) / self.output_sample_rate as f32).tan();
+        let k = (2.0 - (2.0 * resonance.clamp(0.0, 1.0))).max(MIN_DAMPING);
+
+        self.a1 = (1.0 + g * (g + k)).recip();
+        self.a2 = g * self.a1;
+        self.a3 = g * self.a2;
+    }
+
+    /// Filters the input sample. The envelope value is used to modulate the cutoff.
+    pub fn tick(&mut self, input: f32, envelope: f32) -> f32 {
+        let cutoff = self.definition.modulated_cutoff(envelope);
+        self.update_coefficients(cutoff, self.definition.resonance);
+
+        let v3 = input - self.ic2eq;
+        let v1 = self.a1 * self.ic1eq + self.a2 * v3;
+        let v2 = self.ic2eq + self.a2 * self.ic1eq + self.a3 * v3;
+        self.ic1eq = 2.0 * v1 - self.ic1eq;
+        self.ic2eq = 2.0 * v2 - self.ic2eq;
+
+        v2
+    }
+}
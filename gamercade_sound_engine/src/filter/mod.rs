@@ -0,0 +1,3 @@
+mod filter_instance;
+
+pub use filter_instance::*;
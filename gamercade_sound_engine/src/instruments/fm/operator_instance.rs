@@ -24,6 +24,10 @@ impl OperatorInstance {
         }
     }
 
+    pub(crate) fn is_held(&self) -> bool {
+        self.envelope.is_held()
+    }
+
     /// Sets the frequency
     pub fn set_frequency(&mut self, frequency: f32) {
         self.oscillator.set_frequency(frequency);
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use gamercade_audio::{ModulatedBy, PatchDefinition, OPERATOR_COUNT};
 
-use crate::{ActiveState, OperatorInstanceBundle};
+use crate::{ActiveState, OperatorInstance, OperatorInstanceBundle};
 
 #[derive(Clone, Debug)]
 pub struct PatchInstance {
@@ -22,7 +22,8 @@ impl PatchInstance {
         }
     }
 
-    /// Sets the base frequency of the entire patch
+    /// Sets the base frequency of the entire patch. Operators
+    /// with a fixed frequency are unaffected, other than their detune.
     pub fn set_frequency(&mut self, frequency: f32) {
         let instances = self.operators.operators.iter_mut();
         let definitions = self.definition.operators.operators.iter();
@@ -30,8 +31,9 @@ impl PatchInstance {
             .zip(definitions)
             .for_each(|(instance, definition)| {
                 let adjusted_frequency = definition
-                    .frequency_multiplier
-                    .multiply(frequency * definition.detune.as_multiplier());
+                    .frequency_mode
+                    .frequency(definition.frequency_multiplier, frequency)
+                    * definition.detune.as_multiplier();
                 instance.set_frequency(adjusted_frequency)
             });
     }
@@ -48,6 +50,16 @@ impl PatchInstance {
         self.active = ActiveState::Trigger;
     }
 
+    /// Whether the note is still held, rather than released or finished.
+    pub fn is_held(&self) -> bool {
+        self.active != ActiveState::Off
+            || self
+                .operators
+                .operators
+                .iter()
+                .any(OperatorInstance::is_held)
+    }
+
     pub fn tick(&mut self) -> f32 {
         let mut outputs = [0.0f32; OPERATOR_COUNT];
         let mut final_output = 0.0f32;
@@ -90,7 +102,13 @@ impl PatchInstance {
                 }
             };
 
-            let result = operator.tick(waveform, modulation, self.active);
+            // Ring modulated operators multiply with their modulators, rather
+            // than having their phase shifted by them.
+            let result = if algorithm.ring_modulated[i - 1] {
+                operator.tick(waveform, 0.0, self.active) * modulation
+            } else {
+                operator.tick(waveform, modulation, self.active)
+            };
 
             outputs[i] = result;
 
@@ -110,3 +128,60 @@ impl PatchInstance {
         self.operators.operators[0].oscillator.output_sample_rate
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gamercade_audio::{Algorithm, FrequencyMode};
+
+    use super::*;
+    use crate::initialize_globals;
+
+    fn render(algorithm: u8) -> Vec<f32> {
+        let definition = PatchDefinition {
+            algorithm: Algorithm(algorithm),
+            ..Default::default()
+        };
+        let mut patch = PatchInstance::new(Arc::new(definition), 48_000);
+        patch.set_frequency(440.0);
+        patch.set_active(true);
+
+        (0..4_800).map(|_| patch.tick()).collect()
+    }
+
+    #[test]
+    fn test_fixed_operators_ignore_note() {
+        initialize_globals();
+
+        let mut definition = PatchDefinition::default();
+        definition
+            .operators
+            .operators
+            .iter_mut()
+            .for_each(|operator| operator.frequency_mode = FrequencyMode::Fixed(330.0));
+        let definition = Arc::new(definition);
+
+        let render = |frequency| {
+            let mut patch = PatchInstance::new(definition.clone(), 48_000);
+            patch.set_frequency(frequency);
+            patch.set_active(true);
+            (0..4_800).map(|_| patch.tick()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(render(220.0), render(440.0));
+    }
+
+    #[test]
+    fn test_ring_modulation_differs() {
+        initialize_globals();
+
+        // [A * B] + [C * D]
+        let ring = render(12);
+        assert!(ring.iter().any(|sample| *sample != 0.0));
+
+        // A + B + C + D
+        assert_ne!(ring, render(11));
+
+        // [A > B] + [C > D]
+        assert_ne!(ring, render(7));
+    }
+}
@@ -1,10 +1,11 @@
 use gamercade_audio::{
-    get_note, to_scaled_value, InstrumentId, NoteId, PhraseEntry, PhraseStorageType,
-    PhraseVolumeType,
+    to_scaled_value, Effect, InstrumentId, NoteId, PhraseEntry, PhraseStorageType,
+    PhraseVolumeType, Tuning,
 };
+use gamercade_core::FrameRate;
 
 use crate::{
-    InstrumentDefinition, InstrumentDefinitionKind, PatchInstance, SamplerInstance,
+    InstrumentDefinition, InstrumentDefinitionKind, LfoInstance, PatchInstance, SamplerInstance,
     SoundRomInstance, WavetableInstance,
 };
 
@@ -13,6 +14,47 @@ pub struct InstrumentInstance {
     id: usize,
     kind: InstrumentInstanceKind,
     volume: PhraseVolumeType,
+
+    /// The frequency before any vibrato is applied.
+    frequency: f32,
+
+    /// Multiplies every frequency sent to the oscillator, from the global
+    /// transpose and tuning reference.
+    tuning: f32,
+
+    /// How often the game advances the sound. Arpeggios move to their next note once a frame.
+    frame_rate: FrameRate,
+
+    /// Vibrato started by a tracker effect, which stays
+    /// active on this channel until cancelled.
+    vibrato: Option<LfoInstance>,
+
+    /// How many samples it takes to glide into a new note. Set by a
+    /// tracker effect, and stays active on this channel until cancelled.
+    glide_samples: usize,
+
+    /// The frequency the current glide is heading towards.
+    target_frequency: f32,
+
+    /// How much the frequency is multiplied by each sample while gliding.
+    glide_step: f32,
+
+    /// How many samples are left in the current glide.
+    glide_remaining: usize,
+
+    /// Step offsets of an arpeggio started by a tracker
+    /// effect, which stays active on this channel until cancelled.
+    arpeggio: Option<[u8; 2]>,
+
+    /// The pitch multipliers of the arpeggio's offsets from the current
+    /// note, which depend on the tuning.
+    arpeggio_ratios: [f32; 2],
+
+    /// Which note of the arpeggio is playing, where zero is the base note.
+    arpeggio_index: usize,
+
+    /// How many samples are left until the arpeggio moves to its next note.
+    arpeggio_remaining: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -22,21 +64,20 @@ pub enum InstrumentInstanceKind {
     Sampler(SamplerInstance),
 }
 
-pub type InstrumentChannelType = PhraseEntry<f32, InstrumentDefinition>;
+pub type InstrumentChannelType = PhraseEntry<NoteId, InstrumentDefinition>;
 
 pub fn new_instrument_channel_message(
     entry: &PhraseStorageType,
     rom: &SoundRomInstance,
 ) -> Option<InstrumentChannelType> {
     if let Some(instrument) = &rom[entry.instrument] {
-        let note = get_note(entry.note).frequency;
         let instrument = instrument.clone();
 
         Some(InstrumentChannelType {
-            note,
+            note: entry.note,
             volume: entry.volume,
             instrument,
-            effects: entry.effects.clone(),
+            effects: entry.effects,
         })
     } else {
         None
@@ -51,6 +92,18 @@ impl InstrumentInstance {
                 output_sample_rate,
             )),
             volume: 0,
+            frequency: 0.0,
+            tuning: 1.0,
+            frame_rate: FrameRate::default(),
+            vibrato: None,
+            glide_samples: 0,
+            target_frequency: 0.0,
+            glide_step: 1.0,
+            glide_remaining: 0,
+            arpeggio: None,
+            arpeggio_ratios: [1.0; 2],
+            arpeggio_index: 0,
+            arpeggio_remaining: 0,
         }
     }
 
@@ -80,43 +133,152 @@ impl InstrumentInstance {
             id: source.id,
             kind,
             volume: PhraseVolumeType::MAX,
+            frequency: 0.0,
+            tuning: 1.0,
+            frame_rate: FrameRate::default(),
+            vibrato: None,
+            glide_samples: 0,
+            target_frequency: 0.0,
+            glide_step: 1.0,
+            glide_remaining: 0,
+            arpeggio: None,
+            arpeggio_ratios: [1.0; 2],
+            arpeggio_index: 0,
+            arpeggio_remaining: 0,
         }
     }
 
     pub(crate) fn update_from_instrument(&mut self, instrument: &InstrumentDefinition) {
-        let output_sample_rate = match &self.kind {
-            InstrumentInstanceKind::Wavetable(wv) => wv.oscillator.output_sample_rate,
-            InstrumentInstanceKind::FMSynth(fm) => fm.output_sample_rate(),
-            InstrumentInstanceKind::Sampler(sm) => sm.oscillator.output_sample_rate,
-        };
+        let tuning = self.tuning;
+        let frame_rate = self.frame_rate;
+        *self = Self::new_from_instrument(instrument, self.output_sample_rate());
+        self.tuning = tuning;
+        self.frame_rate = frame_rate;
+    }
+
+    pub(crate) fn set_frame_rate(&mut self, frame_rate: FrameRate) {
+        self.frame_rate = frame_rate;
+    }
 
-        *self = Self::new_from_instrument(instrument, output_sample_rate)
+    /// Changes the tuning, retuning the current note if it changed.
+    pub(crate) fn set_tuning(&mut self, tuning: f32) {
+        if self.tuning != tuning {
+            self.tuning = tuning;
+
+            if self.frequency > 0.0 {
+                self.apply_frequency(self.frequency);
+            }
+        }
     }
 
-    pub(crate) fn update_from_tracker(&mut self, entry: &InstrumentChannelType) {
+    pub(crate) fn update_from_tracker(&mut self, entry: &InstrumentChannelType, tuning: &Tuning) {
         if self.id != entry.instrument.id {
-            self.update_from_instrument(&entry.instrument)
+            let vibrato = self.vibrato.take();
+            let glide_samples = self.glide_samples;
+            let arpeggio = self.arpeggio;
+            self.update_from_instrument(&entry.instrument);
+            self.vibrato = vibrato;
+            self.glide_samples = glide_samples;
+            self.arpeggio = arpeggio;
         }
 
         self.volume = entry.volume;
 
-        match &mut self.kind {
-            InstrumentInstanceKind::Wavetable(wave) => {
-                wave.set_frequency(entry.note);
-                wave.trigger();
-            }
-            InstrumentInstanceKind::FMSynth(fm) => {
-                fm.set_frequency(entry.note);
-                fm.trigger();
-            }
-            InstrumentInstanceKind::Sampler(sampler) => {
-                sampler.set_frequency(entry.note);
-                sampler.trigger();
-            }
+        entry
+            .effects
+            .iter()
+            .flatten()
+            .for_each(|effect| match effect {
+                Effect::Vibrato { .. } => {
+                    let output_sample_rate = self.output_sample_rate();
+                    self.vibrato = effect
+                        .vibrato_lfo()
+                        .map(|lfo| LfoInstance::new(&lfo, output_sample_rate));
+                }
+                Effect::Glide { .. } => {
+                    let seconds = effect.glide_seconds().unwrap_or_default();
+                    self.glide_samples = (seconds * self.output_sample_rate() as f32) as usize;
+                }
+                Effect::Arpeggio { .. } => self.arpeggio = effect.arpeggio_offsets(),
+            });
+
+        if let Some(offsets) = self.arpeggio {
+            self.arpeggio_ratios = offsets.map(|steps| tuning.interval(entry.note, steps as i32));
+        }
+
+        self.glide_to(tuning.frequency(entry.note));
+        self.arpeggio_index = 0;
+        self.arpeggio_remaining = self.arpeggio_step_samples();
+        self.trigger();
+    }
+
+    fn arpeggio_step_samples(&self) -> usize {
+        self.output_sample_rate() / self.frame_rate.frames_per_second()
+    }
+
+    /// Advances the glide, returning true if the frequency changed.
+    fn tick_glide(&mut self) -> bool {
+        if self.glide_remaining == 0 {
+            return false;
+        }
+
+        self.glide_remaining -= 1;
+        self.frequency = if self.glide_remaining == 0 {
+            self.target_frequency
+        } else {
+            self.frequency * self.glide_step
+        };
+
+        true
+    }
+
+    /// Advances the arpeggio, returning the pitch multiplier of its current note.
+    fn tick_arpeggio(&mut self) -> Option<f32> {
+        let offsets = self.arpeggio?;
+
+        if self.arpeggio_remaining == 0 {
+            self.arpeggio_index = (self.arpeggio_index + 1) % (offsets.len() + 1);
+            self.arpeggio_remaining = self.arpeggio_step_samples();
+        }
+        self.arpeggio_remaining = self.arpeggio_remaining.saturating_sub(1);
+
+        match self.arpeggio_index {
+            0 => Some(1.0),
+            index => Some(self.arpeggio_ratios[index - 1]),
+        }
+    }
+
+    /// Slides from the current frequency into the new one, or jumps straight to it
+    /// if there isn't a glide, or the previous note isn't still being held.
+    fn glide_to(&mut self, frequency: f32) {
+        if self.glide_samples == 0 || self.frequency <= 0.0 || !self.is_held() {
+            self.set_frequency(frequency);
+            return;
+        }
+
+        self.target_frequency = frequency;
+        self.glide_step = (frequency / self.frequency).powf(1.0 / self.glide_samples as f32);
+        self.glide_remaining = self.glide_samples;
+    }
+
+    fn output_sample_rate(&self) -> usize {
+        match &self.kind {
+            InstrumentInstanceKind::Wavetable(wv) => wv.oscillator.output_sample_rate,
+            InstrumentInstanceKind::FMSynth(fm) => fm.output_sample_rate(),
+            InstrumentInstanceKind::Sampler(sm) => sm.oscillator.output_sample_rate,
         }
     }
 
     pub(crate) fn tick(&mut self) -> f32 {
+        let gliding = self.tick_glide();
+        let arpeggio = self.tick_arpeggio();
+        let vibrato = self.vibrato.as_mut().map(LfoInstance::tick);
+
+        if gliding || arpeggio.is_some() || vibrato.is_some() {
+            let frequency = self.frequency * arpeggio.unwrap_or(1.0) * vibrato.unwrap_or(1.0);
+            self.apply_frequency(frequency);
+        }
+
         let raw_output = match &mut self.kind {
             InstrumentInstanceKind::Wavetable(wv) => wv.tick(),
             InstrumentInstanceKind::FMSynth(fm) => fm.tick(),
@@ -134,6 +296,14 @@ impl InstrumentInstance {
         }
     }
 
+    fn is_held(&self) -> bool {
+        match &self.kind {
+            InstrumentInstanceKind::Wavetable(wv) => wv.is_held(),
+            InstrumentInstanceKind::FMSynth(fm) => fm.is_held(),
+            InstrumentInstanceKind::Sampler(sm) => sm.is_held(),
+        }
+    }
+
     pub(crate) fn trigger(&mut self) {
         match &mut self.kind {
             InstrumentInstanceKind::Wavetable(wv) => wv.trigger(),
@@ -142,19 +312,24 @@ impl InstrumentInstance {
         }
     }
 
-    pub(crate) fn set_note(&mut self, note_id: i32) {
-        if let Ok(note) = NoteId::try_from(note_id) {
-            let frequency = gamercade_audio::get_note(note).frequency;
+    pub(crate) fn set_volume(&mut self, volume: PhraseVolumeType) {
+        self.volume = volume;
+    }
 
-            match &mut self.kind {
-                InstrumentInstanceKind::Wavetable(wv) => wv.set_frequency(frequency),
-                InstrumentInstanceKind::FMSynth(fm) => fm.set_frequency(frequency),
-                InstrumentInstanceKind::Sampler(sm) => sm.set_frequency(frequency),
-            }
+    pub(crate) fn set_note(&mut self, note_id: i32, tuning: &Tuning) {
+        if let Ok(note) = NoteId::try_from(note_id) {
+            self.set_frequency(tuning.frequency(note));
         }
     }
 
     pub(crate) fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency;
+        self.glide_remaining = 0;
+        self.apply_frequency(frequency);
+    }
+
+    fn apply_frequency(&mut self, frequency: f32) {
+        let frequency = frequency * self.tuning;
         match &mut self.kind {
             InstrumentInstanceKind::Wavetable(wv) => wv.set_frequency(frequency),
             InstrumentInstanceKind::FMSynth(fm) => fm.set_frequency(frequency),
@@ -162,3 +337,268 @@ impl InstrumentInstance {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gamercade_audio::{get_note, SoundRom, EFFECT_PARAMETER_MAX, TUNING_ROOT_NOTE};
+
+    use super::*;
+    use crate::initialize_globals;
+
+    fn message(rom: &SoundRomInstance, effect: Option<Effect>) -> InstrumentChannelType {
+        let mut entry = PhraseStorageType::default();
+        entry.effects[0] = effect;
+        new_instrument_channel_message(&entry, rom).unwrap()
+    }
+
+    /// Plays the instrument for a second, returning the frequency
+    /// of each cycle of its output, measured between rising zero crossings.
+    fn output_frequencies(instrument: &mut InstrumentInstance) -> Vec<f32> {
+        let mut previous = instrument.tick();
+        let mut last_crossing = None;
+
+        (1..48_000)
+            .filter_map(|index| {
+                let sample = instrument.tick();
+                let rising = previous < 0.0 && sample >= 0.0;
+                let crossing = index as f32 - sample / (sample - previous);
+                previous = sample;
+
+                if !rising {
+                    return None;
+                }
+
+                let period = last_crossing.map(|last| crossing - last);
+                last_crossing = Some(crossing);
+                period.map(|period| 48_000.0 / period)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_vibrato_persists_until_cancelled() {
+        initialize_globals();
+        let rom = SoundRomInstance::new(&SoundRom::default());
+        let mut instrument = InstrumentInstance::no_sound(48_000);
+        let base_frequency = get_note(NoteId(48)).frequency;
+
+        let note = |rom: &SoundRomInstance, effect| {
+            let mut message = message(rom, effect);
+            message.note = NoteId(48);
+            message
+        };
+        let play = |instrument: &mut InstrumentInstance, effect| {
+            instrument.update_from_tracker(&note(&rom, effect), &rom.tuning);
+            instrument.set_active(true);
+            let frequencies = output_frequencies(instrument);
+            let lowest = frequencies.iter().copied().fold(f32::MAX, f32::min);
+            let highest = frequencies.iter().copied().fold(f32::MIN, f32::max);
+            (lowest / base_frequency, highest / base_frequency)
+        };
+
+        // Full depth swings the pitch two semitones either way.
+        let (lowest, highest) = play(
+            &mut instrument,
+            Some(Effect::Vibrato {
+                depth: EFFECT_PARAMETER_MAX,
+                speed: EFFECT_PARAMETER_MAX / 4,
+            }),
+        );
+        assert!(lowest < 0.92 && lowest > 0.87);
+        assert!(highest > 1.08 && highest < 1.14);
+
+        // Carries over to the next rows.
+        let (lowest, highest) = play(&mut instrument, None);
+        assert!(lowest < 0.92 && highest > 1.08);
+
+        // Until it's cancelled, leaving the note steady.
+        let (lowest, highest) = play(
+            &mut instrument,
+            Some(Effect::Vibrato { depth: 0, speed: 0 }),
+        );
+        assert!(lowest > 0.99 && highest < 1.01);
+    }
+
+    #[test]
+    fn test_glide_slides_between_notes() {
+        initialize_globals();
+        let rom = SoundRomInstance::new(&SoundRom::default());
+        let mut instrument = InstrumentInstance::no_sound(48_000);
+
+        let note = |rom: &SoundRomInstance, note: usize, effect| {
+            let mut message = message(rom, effect);
+            message.note = NoteId(note);
+            message
+        };
+
+        // The first note has nothing to slide from.
+        instrument.update_from_tracker(
+            &note(&rom, 24, Some(Effect::Glide { time: 10 })),
+            &rom.tuning,
+        );
+        let low = instrument.frequency;
+        assert_eq!(low, get_note(NoteId(24)).frequency);
+
+        let high = get_note(NoteId(36)).frequency;
+        instrument.update_from_tracker(&note(&rom, 36, None), &rom.tuning);
+        assert_eq!(instrument.frequency, low);
+
+        instrument.tick();
+        assert!(instrument.frequency > low && instrument.frequency < high);
+
+        // A glide of 0.1 seconds at 48khz.
+        (1..4_800).for_each(|_| {
+            instrument.tick();
+        });
+        assert_eq!(instrument.frequency, high);
+
+        // Cancelling the glide jumps straight to the next note.
+        instrument.update_from_tracker(
+            &note(&rom, 24, Some(Effect::Glide { time: 0 })),
+            &rom.tuning,
+        );
+        assert_eq!(instrument.frequency, low);
+    }
+
+    #[test]
+    fn test_glide_needs_a_held_note() {
+        initialize_globals();
+        let rom = SoundRomInstance::new(&SoundRom::default());
+        let mut instrument = InstrumentInstance::no_sound(48_000);
+
+        let note = |rom: &SoundRomInstance, note: usize, effect| {
+            let mut message = message(rom, effect);
+            message.note = NoteId(note);
+            message
+        };
+        let low = get_note(NoteId(24)).frequency;
+        let high = get_note(NoteId(36)).frequency;
+
+        // Once the note has been released, the next one doesn't slide up from it.
+        instrument.update_from_tracker(
+            &note(&rom, 24, Some(Effect::Glide { time: 10 })),
+            &rom.tuning,
+        );
+        (0..48_000).for_each(|_| {
+            instrument.tick();
+        });
+        assert!(!instrument.is_held());
+
+        instrument.update_from_tracker(&note(&rom, 36, None), &rom.tuning);
+        assert_eq!(instrument.frequency, high);
+
+        // Notes held down still glide, no matter how long they've been playing.
+        instrument.update_from_tracker(&note(&rom, 24, None), &rom.tuning);
+        instrument.set_active(true);
+        (0..48_000).for_each(|_| {
+            instrument.tick();
+        });
+        assert!(instrument.is_held());
+
+        instrument.update_from_tracker(&note(&rom, 36, None), &rom.tuning);
+        assert_eq!(instrument.frequency, low);
+    }
+
+    /// The frequency the instrument's oscillator is playing at.
+    fn oscillator_frequency(instrument: &InstrumentInstance) -> f32 {
+        match &instrument.kind {
+            InstrumentInstanceKind::Wavetable(wv) => wv.oscillator.frequency(),
+            _ => unreachable!("tests only use wavetables"),
+        }
+    }
+
+    fn assert_frequencies(frequencies: &[f32], expected: &[f32]) {
+        assert_eq!(frequencies.len(), expected.len());
+        frequencies
+            .iter()
+            .zip(expected.iter())
+            .for_each(|(frequency, expected)| assert!((frequency - expected).abs() < 0.01));
+    }
+
+    #[test]
+    fn test_arpeggio_follows_tuning() {
+        initialize_globals();
+        let rom = SoundRomInstance::new(&SoundRom {
+            tuning: Tuning::Ratios(vec![1.25, 1.5, 2.0]),
+            ..Default::default()
+        });
+        let mut instrument = InstrumentInstance::no_sound(48_000);
+        let step = instrument.arpeggio_step_samples();
+
+        let mut play = |note: NoteId, effect| {
+            let mut message = message(&rom, effect);
+            message.note = note;
+            instrument.update_from_tracker(&message, &rom.tuning);
+
+            (0..step * 3)
+                .map(|_| {
+                    instrument.tick();
+                    oscillator_frequency(&instrument)
+                })
+                .step_by(step)
+                .collect::<Vec<_>>()
+        };
+
+        // The offsets are steps of the scale, rather than semitones.
+        let arpeggio = Some(Effect::Arpeggio {
+            first: 1,
+            second: 2,
+        });
+        let frequencies = play(TUNING_ROOT_NOTE, arpeggio);
+        assert_frequencies(&frequencies, &[440.0, 550.0, 660.0]);
+
+        let frequencies = play(NoteId(TUNING_ROOT_NOTE.0 + 1), None);
+        assert_frequencies(&frequencies, &[550.0, 660.0, 880.0]);
+    }
+
+    #[test]
+    fn test_arpeggio_cycles_notes() {
+        initialize_globals();
+        let rom = SoundRomInstance::new(&SoundRom::default());
+        let mut instrument = InstrumentInstance::no_sound(48_000);
+        let note = get_note(NoteId(48)).frequency;
+        let major = [0.0, 4.0, 7.0].map(|semitones: f32| note * (semitones / 12.0).exp2());
+
+        let play = |instrument: &mut InstrumentInstance, effect, samples: usize| {
+            let mut message = message(&rom, effect);
+            message.note = NoteId(48);
+            instrument.update_from_tracker(&message, &rom.tuning);
+
+            (0..samples)
+                .map(|_| {
+                    instrument.tick();
+                    oscillator_frequency(instrument)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Moves on to the next note once a frame, 800 samples at 60 fps.
+        let arpeggio = Some(Effect::Arpeggio {
+            first: 4,
+            second: 7,
+        });
+        let frequencies = play(&mut instrument, arpeggio, 3_200);
+        assert_frequencies(&frequencies[..800], &[major[0]; 800]);
+        assert_frequencies(&frequencies[800..1_600], &[major[1]; 800]);
+        assert_frequencies(&frequencies[1_600..2_400], &[major[2]; 800]);
+        assert_frequencies(&frequencies[2_400..], &[major[0]; 800]);
+
+        // Carries over to the next rows, and restarts on each note.
+        let frequencies = play(&mut instrument, None, 801);
+        assert_frequencies(&frequencies[..800], &[major[0]; 800]);
+        assert_frequencies(&frequencies[800..], &[major[1]]);
+
+        // Faster frame rates step through it faster.
+        instrument.set_frame_rate(FrameRate::Fast);
+        let frequencies = play(&mut instrument, None, 401);
+        assert_frequencies(&frequencies[..400], &[major[0]; 400]);
+        assert_frequencies(&frequencies[400..], &[major[1]]);
+
+        let cancel = Some(Effect::Arpeggio {
+            first: 0,
+            second: 0,
+        });
+        let frequencies = play(&mut instrument, cancel, 1_600);
+        assert_frequencies(&frequencies, &[major[0]; 1_600]);
+    }
+}
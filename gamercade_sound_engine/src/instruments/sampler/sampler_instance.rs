@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use gamercade_audio::{IndexInterpolatorResult, SampleBitDepth, SampleDefinition};
 
-use crate::{ActiveState, EnvelopeInstance, SampleOscillator};
+use crate::{ActiveState, EnvelopeInstance, FilterInstance, SampleOscillator};
 
 #[derive(Debug, Clone)]
 pub struct SamplerInstance {
@@ -10,6 +10,7 @@ pub struct SamplerInstance {
     definition: Arc<SampleDefinition>,
     active: ActiveState,
     envelope: EnvelopeInstance,
+    filter: Option<FilterInstance>,
 }
 
 impl SamplerInstance {
@@ -19,6 +20,10 @@ impl SamplerInstance {
             definition: definition.clone(),
             active: ActiveState::Off,
             envelope: EnvelopeInstance::new(&definition.envelope_definition, output_sample_rate),
+            filter: definition
+                .filter
+                .as_ref()
+                .map(|filter| FilterInstance::new(filter, output_sample_rate)),
         }
     }
 
@@ -47,6 +52,11 @@ impl SamplerInstance {
                 self.active = ActiveState::Off;
             }
 
+            let output = match &mut self.filter {
+                Some(filter) => filter.tick(output, envelope),
+                None => output,
+            };
+
             output * envelope
         } else {
             0.0
@@ -70,4 +80,9 @@ impl SamplerInstance {
         self.active = ActiveState::Trigger;
         self.oscillator.reset();
     }
+
+    /// Whether the note is still held, rather than released or finished.
+    pub fn is_held(&self) -> bool {
+        self.active != ActiveState::Off || self.envelope.is_held()
+    }
 }
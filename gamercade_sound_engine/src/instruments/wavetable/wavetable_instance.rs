@@ -1,28 +1,75 @@
-use std::{mem::MaybeUninit, sync::Arc};
+use std::sync::{Arc, OnceLock};
 
-use gamercade_audio::{IndexInterpolatorResult, WavetableBitDepth, WavetableDefinition};
+use gamercade_audio::{
+    EnvelopeDefinition, IndexInterpolator, IndexInterpolatorResult, LfoTarget, WavetableBitDepth,
+    WavetableDefinition,
+};
 
-use crate::{ActiveState, EnvelopeInstance, WavetableOscillator};
+use crate::{ActiveState, EnvelopeInstance, FilterInstance, LfoInstance, WavetableOscillator};
 
-pub(crate) static mut NO_SOUND_DEFINITION: MaybeUninit<Arc<WavetableDefinition>> =
-    MaybeUninit::uninit();
+/// The shortest time the output can take to fade in or out. Envelopes with an instant
+/// attack or release would otherwise jump straight to or from full volume, which clicks.
+/// This is short enough that it doesn't soften the attack of the note.
+const DECLICK_SECONDS: f32 = 0.003;
+
+static NO_SOUND_DEFINITION: OnceLock<Arc<WavetableDefinition>> = OnceLock::new();
+
+/// Returns the silent definition shared by every no sound instance,
+/// creating it the first time it's needed.
+pub(crate) fn no_sound_definition() -> &'static Arc<WavetableDefinition> {
+    NO_SOUND_DEFINITION.get_or_init(|| {
+        Arc::new(WavetableDefinition {
+            data: Box::new([0, 0]),
+            envelope: EnvelopeDefinition::default(),
+            interpolator: IndexInterpolator::Truncate,
+            filter: None,
+            lfo: None,
+            morph_data: Box::default(),
+            morph: 0.0,
+        })
+    })
+}
+
+/// Reads the interpolated value from the table, between -1.0 and 1.0.
+fn read_table(table: &[WavetableBitDepth], indices: IndexInterpolatorResult) -> f32 {
+    match indices {
+        IndexInterpolatorResult::Single(index) => {
+            table[index] as f32 / WavetableBitDepth::MAX as f32
+        }
+        IndexInterpolatorResult::Multiple(indices) => {
+            indices.into_iter().fold(0.0, |val, (index, scaling)| {
+                val + ((table[index] as f32 / WavetableBitDepth::MAX as f32) * scaling)
+            })
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct WavetableInstance {
     definition: Arc<WavetableDefinition>,
     envelope: EnvelopeInstance,
+    filter: Option<FilterInstance>,
+    lfo: Option<LfoInstance>,
     pub(crate) oscillator: WavetableOscillator,
     active: ActiveState,
+
+    /// The envelope's level, limited to change no faster than the declick fade allows.
+    declick_gain: f32,
+    declick_step: f32,
 }
 
 impl WavetableInstance {
     pub fn no_sound(output_sample_rate: usize) -> Self {
-        let definition = unsafe { NO_SOUND_DEFINITION.assume_init_ref().clone() };
+        let definition = no_sound_definition().clone();
         Self {
             envelope: EnvelopeInstance::no_sound(output_sample_rate),
+            filter: None,
+            lfo: None,
             oscillator: WavetableOscillator::new(1, output_sample_rate, definition.interpolator),
             definition,
             active: ActiveState::Off,
+            declick_gain: 0.0,
+            declick_step: declick_step(output_sample_rate),
         }
     }
 
@@ -30,6 +77,14 @@ impl WavetableInstance {
     pub fn new(definition: Arc<WavetableDefinition>, output_sample_rate: usize) -> Self {
         Self {
             envelope: EnvelopeInstance::new(&definition.envelope, output_sample_rate),
+            filter: definition
+                .filter
+                .as_ref()
+                .map(|filter| FilterInstance::new(filter, output_sample_rate)),
+            lfo: definition
+                .lfo
+                .as_ref()
+                .map(|lfo| LfoInstance::new(lfo, output_sample_rate)),
             oscillator: WavetableOscillator::new(
                 definition.len(),
                 output_sample_rate,
@@ -37,6 +92,8 @@ impl WavetableInstance {
             ),
             definition,
             active: ActiveState::Off,
+            declick_gain: 0.0,
+            declick_step: declick_step(output_sample_rate),
         }
     }
 
@@ -49,20 +106,31 @@ impl WavetableInstance {
     /// This interpolates between the current index and the next index
     /// Also increments the oscillator
     pub fn tick(&mut self) -> f32 {
-        let index = self.oscillator.tick();
+        let mut morph = self.definition.morph;
+
+        let (index, amplitude) = match &mut self.lfo {
+            Some(lfo) => match lfo.target() {
+                LfoTarget::Pitch => (self.oscillator.modulated_tick(lfo.tick()), 1.0),
+                LfoTarget::Amplitude => (self.oscillator.tick(), lfo.tick()),
+                LfoTarget::Morph => {
+                    morph += lfo.tick();
+                    (self.oscillator.tick(), 1.0)
+                }
+            },
+            None => (self.oscillator.tick(), 1.0),
+        };
 
         let indices = self.oscillator.get_interpolated_indices(index);
+        let output = read_table(&self.definition.data, indices);
 
-        let output = match indices {
-            IndexInterpolatorResult::Single(index) => {
-                self.definition.data[index] as f32 / WavetableBitDepth::MAX as f32
-            }
-            IndexInterpolatorResult::Multiple(indices) => {
-                indices.into_iter().fold(0.0, |val, (index, scaling)| {
-                    val + ((self.definition.data[index] as f32 / WavetableBitDepth::MAX as f32)
-                        * scaling)
-                })
-            }
+        // Crossfades into the morph table, which may be a different length.
+        let morph_data = &self.definition.morph_data;
+        let output = if morph_data.is_empty() {
+            output
+        } else {
+            let morph = morph.clamp(0.0, 1.0);
+            let indices = self.oscillator.get_scaled_indices(index, morph_data.len());
+            output + (read_table(morph_data, indices) - output) * morph
         };
 
         let envelope = self.envelope.tick(self.active);
@@ -71,10 +139,27 @@ impl WavetableInstance {
             self.active = ActiveState::Off;
         }
 
-        output * envelope
+        let output = match &mut self.filter {
+            Some(filter) => filter.tick(output, envelope),
+            None => output,
+        };
+
+        output * self.declick(envelope) * amplitude
+    }
+
+    /// Follows the envelope, but fades over at least the declick time when it jumps,
+    /// such as at the start of a note with no attack.
+    fn declick(&mut self, envelope: f32) -> f32 {
+        let change = (envelope - self.declick_gain).clamp(-self.declick_step, self.declick_step);
+        self.declick_gain += change;
+        self.declick_gain
     }
 
     pub fn set_active(&mut self, active: bool) {
+        if let (true, ActiveState::Off, Some(lfo)) = (active, self.active, &mut self.lfo) {
+            lfo.reset();
+        }
+
         self.active = if active {
             ActiveState::On
         } else {
@@ -83,6 +168,98 @@ impl WavetableInstance {
     }
 
     pub fn trigger(&mut self) {
+        if let Some(lfo) = &mut self.lfo {
+            lfo.reset();
+        }
+
         self.active = ActiveState::Trigger;
     }
+
+    /// Whether the note is still held, rather than released or finished.
+    pub fn is_held(&self) -> bool {
+        self.active != ActiveState::Off || self.envelope.is_held()
+    }
+}
+
+fn declick_step(output_sample_rate: usize) -> f32 {
+    (DECLICK_SECONDS * output_sample_rate as f32).recip()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(
+        data: &[WavetableBitDepth],
+        morph_data: &[WavetableBitDepth],
+        morph: f32,
+    ) -> Arc<WavetableDefinition> {
+        Arc::new(WavetableDefinition {
+            data: data.into(),
+            interpolator: IndexInterpolator::Linear,
+            morph_data: morph_data.into(),
+            morph,
+            ..WavetableDefinition::default()
+        })
+    }
+
+    fn render(definition: Arc<WavetableDefinition>) -> Vec<f32> {
+        let mut instance = WavetableInstance::new(definition, 48_000);
+        instance.set_frequency(440.0);
+        instance.set_active(true);
+        (0..512).map(|_| instance.tick()).collect()
+    }
+
+    fn approx_eq(a: &[f32], b: &[f32]) -> bool {
+        a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1e-5)
+    }
+
+    #[test]
+    fn test_morph_crossfades_between_tables() {
+        let saw = [-100, -50, 0, 50];
+        let square = [100, 100, -100, -100];
+
+        assert!(approx_eq(
+            &render(definition(&saw, &square, 0.0)),
+            &render(definition(&saw, &[], 0.0))
+        ));
+        assert!(approx_eq(
+            &render(definition(&saw, &square, 1.0)),
+            &render(definition(&square, &[], 0.0))
+        ));
+
+        let halfway = render(definition(&saw, &square, 0.5));
+        assert!(!approx_eq(&halfway, &render(definition(&saw, &[], 0.0))));
+        assert!(!approx_eq(&halfway, &render(definition(&square, &[], 0.0))));
+    }
+
+    #[test]
+    fn test_instant_envelopes_dont_click() {
+        // The default envelope has an instant attack and release, and
+        // a constant table makes the output follow the volume
+        let max = WavetableBitDepth::MAX;
+        let mut instance = WavetableInstance::new(definition(&[max, max], &[], 0.0), 48_000);
+        let fade_samples = (DECLICK_SECONDS * 48_000.0) as usize;
+        let max_step = declick_step(48_000) + 1e-6;
+
+        instance.set_active(true);
+        let attack = (0..fade_samples * 2)
+            .map(|_| instance.tick())
+            .collect::<Vec<_>>();
+
+        instance.set_active(false);
+        let release = (0..fade_samples * 2)
+            .map(|_| instance.tick())
+            .collect::<Vec<_>>();
+
+        let output = [&[0.0][..], &attack, &release].concat();
+        assert!(output
+            .windows(2)
+            .all(|pair| (pair[1] - pair[0]).abs() <= max_step));
+
+        // The fade is over quickly, leaving the envelope in charge
+        assert!((attack.last().unwrap() - 1.0).abs() < 1e-5);
+        assert!(release.last().unwrap().abs() < 1e-5);
+        assert!(attack[fade_samples / 2] < 0.75);
+    }
 }
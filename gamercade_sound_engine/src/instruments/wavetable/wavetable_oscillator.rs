@@ -36,6 +36,12 @@ impl WavetableOscillator {
         self.index_increment = increment / self.output_sample_rate as f32;
     }
 
+    /// The frequency the oscillator is playing at, in Hz.
+    #[cfg(test)]
+    pub(crate) fn frequency(&self) -> f32 {
+        self.index_increment * self.output_sample_rate as f32 / self.table_length as f32
+    }
+
     // Returns the modulation amount for this oscillator. Used with FM Synth
     pub(crate) fn modulation(&self, modulation: f32) -> f32 {
         FM_MODULATION * modulation * self.table_length as f32 / TAU
@@ -50,7 +56,27 @@ impl WavetableOscillator {
         out
     }
 
+    /// Returns the index, then
+    /// Increments the oscillator by its predefined amount, scaled by the ratio.
+    /// Used for pitch modulation.
+    pub(crate) fn modulated_tick(&mut self, ratio: f32) -> f32 {
+        let out = self.index;
+        self.index += self.index_increment * ratio;
+        self.index %= self.table_length as f32;
+        out
+    }
+
     pub(crate) fn get_interpolated_indices(&self, index: f32) -> IndexInterpolatorResult {
         self.interpolator.get_indices(index, self.table_length)
     }
+
+    /// Returns the indices for the same position within a table of a different length.
+    pub(crate) fn get_scaled_indices(
+        &self,
+        index: f32,
+        table_length: usize,
+    ) -> IndexInterpolatorResult {
+        let index = index * table_length as f32 / self.table_length as f32;
+        self.interpolator.get_indices(index, table_length)
+    }
 }
@@ -0,0 +1,131 @@
+use gamercade_audio::{Lfo, LfoTarget};
+
+/// How long a delayed lfo takes to fade in to its full depth once the delay is over,
+/// so the modulation doesn't jump in with a click.
+const LFO_FADE_IN_SECONDS: f32 = 0.05;
+
+/// A running instance of an lfo.
+#[derive(Clone, Debug)]
+pub struct LfoInstance {
+    definition: Lfo,
+    phase: f32,
+    phase_increment: f32,
+    delay_ticks: usize,
+    fade_in_ticks: usize,
+    elapsed_ticks: usize,
+}
+
+impl LfoInstance {
+    /// Generates a new lfo with the given sample rate.
+    pub fn new(definition: &Lfo, output_sample_rate: usize) -> Self {
+        let delay_ticks = (definition.delay * output_sample_rate as f32) as usize;
+
+        // Lfos without a delay start along with the note, so don't need to fade in
+        let fade_in_ticks = if delay_ticks > 0 {
+            (LFO_FADE_IN_SECONDS * output_sample_rate as f32) as usize
+        } else {
+            0
+        };
+
+        Self {
+            definition: definition.clone(),
+            phase: 0.0,
+            phase_increment: definition.rate / output_sample_rate as f32,
+            delay_ticks,
+            fade_in_ticks,
+            elapsed_ticks: 0,
+        }
+    }
+
+    /// Restarts the lfo, including the delay. Called when a new note starts.
+    pub fn reset(&mut self) {
+        self.phase = 0.0;
+        self.elapsed_ticks = 0;
+    }
+
+    pub fn target(&self) -> LfoTarget {
+        self.definition.target
+    }
+
+    /// Advances the lfo forward one tick and returns the multiplier
+    /// which should be applied to the target. Morph returns an offset instead.
+    pub fn tick(&mut self) -> f32 {
+        if self.elapsed_ticks < self.delay_ticks {
+            self.elapsed_ticks += 1;
+            return match self.definition.target {
+                LfoTarget::Morph => 0.0,
+                _ => 1.0,
+            };
+        }
+
+        let value = self.definition.waveform.func(self.phase);
+        self.phase = (self.phase + self.phase_increment).fract();
+
+        let fade_in = self.tick_fade_in();
+        match self.definition.target {
+            LfoTarget::Pitch => self.definition.pitch_ratio(value).powf(fade_in),
+            LfoTarget::Amplitude => 1.0 - ((1.0 - self.definition.amplitude(value)) * fade_in),
+            LfoTarget::Morph => self.definition.morph_offset(value) * fade_in,
+        }
+    }
+
+    /// Advances the fade in after the delay, returning how much
+    /// of the depth is applied, from 0.0 to 1.0.
+    fn tick_fade_in(&mut self) -> f32 {
+        let faded = self.elapsed_ticks - self.delay_ticks;
+
+        if faded < self.fade_in_ticks {
+            self.elapsed_ticks += 1;
+            (faded + 1) as f32 / (self.fade_in_ticks + 1) as f32
+        } else {
+            1.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_and_reset() {
+        let definition = Lfo {
+            target: LfoTarget::Amplitude,
+            depth: 1.0,
+            delay: 0.5,
+            ..Default::default()
+        };
+        let mut lfo = LfoInstance::new(&definition, 1_000);
+
+        (0..500).for_each(|_| assert_eq!(lfo.tick(), 1.0));
+
+        // Once the delay is over, the depth fades in rather than jumping
+        // straight from full volume to the waveform.
+        let output = (0..1_000).map(|_| lfo.tick()).collect::<Vec<_>>();
+        let mut previous = 1.0;
+        output.iter().for_each(|&value| {
+            assert!((value - previous).abs() < 0.05);
+            previous = value;
+        });
+
+        // Sine starts at 0.0, which is half volume at full depth,
+        // and swings down to silence.
+        assert!(output[0] > 0.95);
+        assert!(output.iter().any(|&value| value < 0.01));
+
+        lfo.reset();
+        assert_eq!(lfo.tick(), 1.0);
+    }
+
+    #[test]
+    fn test_undelayed_lfo_starts_at_full_depth() {
+        let definition = Lfo {
+            target: LfoTarget::Amplitude,
+            depth: 1.0,
+            ..Default::default()
+        };
+        let mut lfo = LfoInstance::new(&definition, 1_000);
+
+        assert_eq!(lfo.tick(), 0.5);
+    }
+}
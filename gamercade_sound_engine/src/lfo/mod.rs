@@ -0,0 +1,3 @@
+mod lfo_instance;
+
+pub use lfo_instance::*;
@@ -1,27 +1,37 @@
+mod channel_mixer;
+mod delay_line;
 mod envelope;
+mod filter;
 mod instruments;
+mod lfo;
+mod limiter;
+mod offline_render;
 mod playback;
 mod sound_engine;
 mod sound_output_channels;
 mod sound_rom_instance;
+mod voice_steal_policy;
 
+pub use channel_mixer::*;
+pub use delay_line::*;
 pub use envelope::*;
+pub use filter::*;
 pub use instruments::*;
+pub use lfo::*;
+pub use limiter::*;
+pub use offline_render::*;
 pub use playback::*;
 pub use sound_engine::*;
 pub use sound_output_channels::*;
 pub use sound_rom_instance::*;
+pub use voice_steal_policy::*;
 
-use gamercade_audio::{EnvelopeDefinition, WavetableDefinition};
-
-fn initialize_globals() {
+/// Initializes the lookup tables and shared definitions used by the engine.
+/// This is done automatically when creating a SoundEngine.
+pub fn initialize_globals() {
     init_fm_lut();
     gamercade_audio::initialize_notes();
-    unsafe {
-        NO_SOUND_DEFINITION.write(std::sync::Arc::new(WavetableDefinition {
-            data: Box::new([0, 0]),
-            envelope: EnvelopeDefinition::default(),
-            interpolator: gamercade_audio::IndexInterpolator::Truncate,
-        }));
-    }
+
+    // Created up front, so the audio thread never has to allocate it.
+    no_sound_definition();
 }
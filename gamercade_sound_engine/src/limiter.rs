@@ -0,0 +1,106 @@
+/// The highest level the limiter lets through.
+pub const LIMITER_CEILING: f32 = 0.5;
+
+/// How quickly the limiter turns the volume down when a sample is too loud.
+const LIMITER_ATTACK_SECONDS: f32 = 0.001;
+
+/// How quickly the limiter recovers once the output is quiet again.
+const LIMITER_RELEASE_SECONDS: f32 = 0.25;
+
+/// A fast stereo peak limiter, used to protect players from sudden loud sounds.
+/// This only processes the final output, and never affects the game's audio state.
+#[derive(Debug, Clone)]
+pub struct Limiter {
+    pub enabled: bool,
+    gain: f32,
+    attack: f32,
+    release: f32,
+}
+
+impl Limiter {
+    pub fn new(output_sample_rate: usize) -> Self {
+        let coefficient = |seconds: f32| (-1.0 / (seconds * output_sample_rate as f32)).exp();
+
+        Self {
+            enabled: false,
+            gain: 1.0,
+            attack: coefficient(LIMITER_ATTACK_SECONDS),
+            release: coefficient(LIMITER_RELEASE_SECONDS),
+        }
+    }
+
+    /// Processes a stereo sample. Does nothing if the limiter is disabled.
+    pub fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        if !self.enabled {
+            return (left, right);
+        }
+
+        let peak = left.abs().max(right.abs());
+        let target = if peak > LIMITER_CEILING {
+            LIMITER_CEILING / peak
+        } else {
+            1.0
+        };
+
+        let coefficient = if target < self.gain {
+            self.attack
+        } else {
+            self.release
+        };
+        self.gain = target + (self.gain - target) * coefficient;
+
+        // The smoothed gain can lag behind a sudden peak, so
+        // clamp anything which would still get through.
+        (
+            (left * self.gain).clamp(-LIMITER_CEILING, LIMITER_CEILING),
+            (right * self.gain).clamp(-LIMITER_CEILING, LIMITER_CEILING),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limiter_caps_level() {
+        let mut limiter = Limiter::new(48_000);
+        limiter.enabled = true;
+
+        // A quiet tone, which suddenly jumps to full volume.
+        let output = (0..48_000)
+            .map(|index| {
+                let amplitude = if index < 24_000 { 0.1 } else { 1.0 };
+                let sample = (index as f32 * 0.05).sin() * amplitude;
+                limiter.process(sample, -sample)
+            })
+            .collect::<Vec<_>>();
+
+        assert!(output
+            .iter()
+            .all(|(left, right)| left.abs() <= LIMITER_CEILING && right.abs() <= LIMITER_CEILING));
+
+        // The quiet part passes through untouched.
+        let quiet = (0..24_000).map(|index| (index as f32 * 0.05).sin() * 0.1);
+        assert!(output
+            .iter()
+            .zip(quiet)
+            .all(|((left, _), quiet)| *left == quiet));
+
+        // Once settled, the loud part is turned down to the ceiling rather than clipped.
+        output
+            .iter()
+            .enumerate()
+            .skip(36_000)
+            .for_each(|(index, (left, _))| {
+                let expected = (index as f32 * 0.05).sin() * LIMITER_CEILING;
+                assert!((left - expected).abs() < 0.02);
+            });
+    }
+
+    #[test]
+    fn test_limiter_disabled() {
+        let mut limiter = Limiter::new(48_000);
+        assert_eq!(limiter.process(1.0, -1.0), (1.0, -1.0));
+    }
+}
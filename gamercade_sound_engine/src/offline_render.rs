@@ -0,0 +1,193 @@
+use std::{path::Path, sync::Arc};
+
+use gamercade_core::FrameRate;
+use hound::{SampleFormat, WavSpec, WavWriter};
+
+use crate::{Limiter, SongId, SoundEngineData, SoundRomInstance};
+
+/// How long to keep rendering after a song ends, so notes can finish releasing.
+const SONG_TAIL_SECONDS: f32 = 1.0;
+
+/// The longest a render can be, in case a song never ends.
+const MAX_RENDER_SECONDS: usize = 60 * 30;
+
+/// How many samples are rendered between each progress report.
+const PROGRESS_INTERVAL: usize = 4096;
+
+/// Renders a song as fast as possible, rather than in real time. This doesn't
+/// use an output device, so the output is the same on every machine.
+pub struct OfflineSongRender {
+    data: SoundEngineData,
+    limiter: Limiter,
+    output_sample_rate: usize,
+    tail_samples: usize,
+    remaining_samples: usize,
+}
+
+impl OfflineSongRender {
+    pub fn new(
+        rom: &Arc<SoundRomInstance>,
+        song: SongId,
+        output_sample_rate: usize,
+        frame_rate: FrameRate,
+    ) -> Self {
+        let mut data = SoundEngineData::new(output_sample_rate, frame_rate, rom);
+        data.play_bgm(Some(song));
+
+        Self {
+            data,
+            limiter: Limiter::new(output_sample_rate),
+            output_sample_rate,
+            tail_samples: (output_sample_rate as f32 * SONG_TAIL_SECONDS) as usize,
+            remaining_samples: output_sample_rate * MAX_RENDER_SECONDS,
+        }
+    }
+
+    /// Enables or disables the output limiter, like the realtime output.
+    pub fn set_limiter_enabled(&mut self, enabled: bool) {
+        self.limiter.enabled = enabled;
+    }
+
+    /// How far through the song the render is, from 0.0 to 1.0.
+    pub fn progress(&self) -> f32 {
+        self.data.bgm.progress()
+    }
+
+    /// Renders the next stereo sample, or None once the song has
+    /// finished and its tail has been rendered.
+    pub fn next_sample(&mut self) -> Option<(f32, f32)> {
+        if self.data.bgm.is_finished() {
+            self.tail_samples = self.tail_samples.checked_sub(1)?;
+        }
+        self.remaining_samples = self.remaining_samples.checked_sub(1)?;
+
+        let output = self.data.tick();
+        Some(self.data.mix_output(&output, &mut self.limiter))
+    }
+
+    /// Renders the whole song into a 16 bit stereo .wav file. The progress callback is
+    /// called periodically with a value from 0.0 to 1.0, return false from it to cancel.
+    /// If the render is cancelled or fails, the partially written file is removed.
+    pub fn render_to_wav(
+        self,
+        path: &Path,
+        progress: impl FnMut(f32) -> bool,
+    ) -> Result<(), String> {
+        let result = self.write_wav(path, progress);
+        if result.is_err() {
+            let _ = std::fs::remove_file(path);
+        }
+        result
+    }
+
+    fn write_wav(
+        mut self,
+        path: &Path,
+        mut progress: impl FnMut(f32) -> bool,
+    ) -> Result<(), String> {
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: self.output_sample_rate as u32,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+
+        let mut writer = WavWriter::create(path, spec).map_err(|e| e.to_string())?;
+        let mut rendered = 0;
+
+        while let Some((left, right)) = self.next_sample() {
+            writer
+                .write_sample((left * i16::MAX as f32) as i16)
+                .map_err(|e| e.to_string())?;
+            writer
+                .write_sample((right * i16::MAX as f32) as i16)
+                .map_err(|e| e.to_string())?;
+
+            rendered += 1;
+            if rendered % PROGRESS_INTERVAL == 0 && !progress(self.progress()) {
+                return Err(String::from("Render cancelled"));
+            }
+        }
+
+        progress(1.0);
+        writer.finalize().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gamercade_audio::{Chain, ChainId, PhraseId, Song, SoundRom};
+    use hound::WavReader;
+
+    use super::*;
+    use crate::{initialize_globals, SONG_TRACK_CHANNELS};
+
+    fn test_rom() -> Arc<SoundRomInstance> {
+        let mut chain = Chain::default();
+        chain.entries[0] = Some(PhraseId(0));
+
+        let mut row = [None; SONG_TRACK_CHANNELS];
+        row[0] = Some(ChainId(0));
+
+        let rom = SoundRom {
+            songs: vec![Song {
+                tracks: vec![row, row].into_boxed_slice(),
+                ..Default::default()
+            }]
+            .into_boxed_slice(),
+            chains: vec![Some(chain)].into_boxed_slice(),
+            ..Default::default()
+        };
+
+        Arc::new(SoundRomInstance::new(&rom))
+    }
+
+    #[test]
+    fn test_render_is_deterministic() {
+        initialize_globals();
+        let rom = test_rom();
+
+        let render = || {
+            let mut render = OfflineSongRender::new(&rom, SongId(0), 22_050, FrameRate::default());
+            std::iter::from_fn(|| render.next_sample()).collect::<Vec<_>>()
+        };
+
+        let first = render();
+        let length_seconds = rom[SongId(0)].song_length_seconds(&rom.chains);
+
+        // The song plays, ends, and then has its tail rendered.
+        assert!(first.iter().any(|(left, _)| *left != 0.0));
+        assert!(first.len() as f32 >= (length_seconds + SONG_TAIL_SECONDS) * 22_050.0 * 0.9);
+        assert!((first.len() as f32) < (length_seconds + SONG_TAIL_SECONDS) * 22_050.0 * 1.1);
+        assert_eq!(first, render());
+    }
+
+    #[test]
+    fn test_render_to_wav() {
+        initialize_globals();
+        let rom = test_rom();
+        let path = std::env::temp_dir().join("gamercade_offline_render_test.wav");
+
+        let mut reports = Vec::new();
+        OfflineSongRender::new(&rom, SongId(0), 22_050, FrameRate::default())
+            .render_to_wav(&path, |progress| {
+                reports.push(progress);
+                true
+            })
+            .unwrap();
+
+        assert!(reports.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(reports.last(), Some(&1.0));
+
+        let reader = WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(reader.spec().sample_rate, 22_050);
+        assert!(reader.len() > 0);
+        std::fs::remove_file(&path).unwrap();
+
+        let cancelled = OfflineSongRender::new(&rom, SongId(0), 22_050, FrameRate::default())
+            .render_to_wav(&path, |_| false);
+        assert!(cancelled.is_err());
+        assert!(!path.exists());
+    }
+}
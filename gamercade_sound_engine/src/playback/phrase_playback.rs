@@ -43,7 +43,7 @@ impl PhrasePlayback {
         let next_entry = self.rom[phrase_id].as_ref()?;
         let next_entry = next_entry.entries[self.step_index].as_ref()?;
         let msg = new_instrument_channel_message(next_entry, &self.rom)?;
-        self.instrument.update_from_tracker(&msg);
+        self.instrument.update_from_tracker(&msg, &self.rom.tuning);
         Some(())
     }
 
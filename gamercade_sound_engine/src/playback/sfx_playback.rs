@@ -7,10 +7,39 @@ use crate::{
     TrackerOscillatorFlow,
 };
 
+/// How long a stolen voice takes to fade out, which avoids clicks.
+pub(crate) const STEAL_FADE_SECONDS: f32 = 0.005;
+
+/// How quickly the tracked output level falls, per sample.
+const LEVEL_DECAY: f32 = 0.999;
+
+/// Any channel quieter than this is considered free for a new voice.
+pub(crate) const VOICE_SILENT_LEVEL: f32 = 1e-4;
+
 #[derive(Debug, Clone)]
 pub struct SfxPlayback {
     pub(crate) oscillator: TrackerOscillator,
     pub(crate) chain_playback: ChainPlayback,
+    pub(crate) gain: f32,
+    pub(crate) pan: f32,
+
+    /// How much of this channel is sent into the delay, on top of the sfx bus send.
+    pub(crate) delay_send: f32,
+
+    /// The voice currently held on this channel, used to release it later.
+    pub(crate) voice: Option<usize>,
+
+    /// How many samples since the current voice started.
+    pub(crate) voice_age: usize,
+
+    /// A peak follower of the output, used to find the quietest voice.
+    pub(crate) level: f32,
+
+    /// A stolen voice, which keeps playing while it's faded
+    /// out underneath the new one.
+    stolen: Option<InstrumentInstance>,
+    steal_fade: f32,
+    steal_fade_step: f32,
 }
 
 impl SfxPlayback {
@@ -23,6 +52,36 @@ impl SfxPlayback {
         Self {
             oscillator: TrackerOscillator::new(output_sample_rate),
             chain_playback: ChainPlayback::new(chain, rom, instrument),
+            gain: 1.0,
+            pan: 0.0,
+            delay_send: 1.0,
+            voice: None,
+            voice_age: 0,
+            level: 0.0,
+            stolen: None,
+            steal_fade: 0.0,
+            steal_fade_step: 1.0 / (output_sample_rate as f32 * STEAL_FADE_SECONDS),
+        }
+    }
+
+    /// Whether a new voice can start here without cutting anything off.
+    pub(crate) fn is_free(&self) -> bool {
+        self.voice.is_none()
+            && self.chain_playback.chain.is_none()
+            && self.level < VOICE_SILENT_LEVEL
+    }
+
+    /// Marks the start of a new voice on this channel, before the new note is set.
+    /// If something was still sounding, it's faded out rather than cut off.
+    pub(crate) fn start_voice(&mut self, voice: Option<usize>) {
+        self.voice = voice;
+        self.voice_age = 0;
+
+        if self.level >= VOICE_SILENT_LEVEL {
+            self.stolen = Some(self.chain_playback.phrase_playback.instrument.clone());
+            self.steal_fade = 1.0;
+        } else {
+            self.stolen = None;
         }
     }
 
@@ -30,8 +89,11 @@ impl SfxPlayback {
         if let Some(sfx) = sfx {
             self.chain_playback.set_chain_id(Some(sfx.chain));
             self.oscillator.reset_bpm(sfx.bpm);
+            self.oscillator.set_swing(0.0);
+            self.gain = sfx.gain;
         } else {
             self.chain_playback.set_chain_id(None);
+            self.gain = 1.0;
         }
     }
 
@@ -47,7 +109,25 @@ impl SfxPlayback {
             },
         };
 
-        self.chain_playback.phrase_playback.instrument.tick()
+        let output = self.chain_playback.phrase_playback.instrument.tick();
+
+        // Fades out whatever was playing before the voice was stolen.
+        let output = match &mut self.stolen {
+            Some(stolen) => {
+                self.steal_fade = (self.steal_fade - self.steal_fade_step).max(0.0);
+                let stolen_output = stolen.tick() * self.steal_fade;
+
+                if self.steal_fade == 0.0 {
+                    self.stolen = None;
+                }
+                output + stolen_output
+            }
+            None => output,
+        };
+
+        self.level = output.abs().max(self.level * LEVEL_DECAY);
+        self.voice_age = self.voice_age.saturating_add(1);
+        output
     }
 
     pub fn replace_sound_rom_instance(&mut self, new_rom: &Arc<SoundRomInstance>) {
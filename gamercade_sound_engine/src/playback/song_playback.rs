@@ -13,6 +13,9 @@ pub struct SongPlayback {
     pub(crate) chain_states: [TrackerFlow; SONG_TRACK_CHANNELS],
     pub(crate) rom: Arc<SoundRomInstance>,
 
+    /// The first and last rows to repeat, instead of playing the whole song.
+    loop_region: Option<(usize, usize)>,
+
     oscillator: TrackerOscillator,
 }
 
@@ -33,6 +36,7 @@ impl SongPlayback {
             tracks,
             rom: rom.clone(),
             chain_states: default_chain_states(),
+            loop_region: None,
             oscillator: TrackerOscillator::new(output_sample_rate),
         };
 
@@ -40,6 +44,47 @@ impl SongPlayback {
         out
     }
 
+    /// Returns the authored gain of the current song.
+    pub(crate) fn gain(&self) -> f32 {
+        self.song.map(|song| self.rom[song].gain).unwrap_or(1.0)
+    }
+
+    /// Returns true once every row of the song has been played, or if no song is set.
+    pub fn is_finished(&self) -> bool {
+        match self.song {
+            Some(song) => self.chain_index >= self.rom[song].tracks.len(),
+            None => true,
+        }
+    }
+
+    /// How far through the song playback is, from 0.0 to 1.0.
+    pub fn progress(&self) -> f32 {
+        match self.song {
+            Some(song) => match self.rom[song].tracks.len() {
+                0 => 1.0,
+                rows => (self.chain_index as f32 / rows as f32).min(1.0),
+            },
+            None => 1.0,
+        }
+    }
+
+    /// Repeats the rows from start to end, both inclusive, once playback
+    /// reaches them. Passing in None plays the whole song as normal.
+    pub fn set_loop_region(&mut self, region: Option<(usize, usize)>) {
+        self.loop_region = region.map(|(start, end)| (start.min(end), start.max(end)));
+    }
+
+    /// Returns the loop region, clamped to the rows of the song.
+    fn loop_bounds(&self, rows: usize) -> Option<(usize, usize)> {
+        let (start, end) = self.loop_region?;
+
+        if start < rows {
+            Some((start, end.min(rows - 1)))
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn tick(&mut self) -> [f32; SONG_TRACK_CHANNELS] {
         match self.oscillator.tick() {
             TrackerOscillatorFlow::Continue => (),
@@ -57,14 +102,18 @@ impl SongPlayback {
     /// Passing in None will mute the playback.
     pub(crate) fn set_song_id(&mut self, song: Option<SongId>) {
         self.song = song;
-        self.chain_index = 0;
 
         // If the song is valid, update all chains to
         // use the correct indices and data
         if let Some(song) = song {
             let song = &self.rom[song];
+            self.chain_index = self
+                .loop_bounds(song.tracks.len())
+                .map(|(start, _)| start)
+                .unwrap_or_default();
             self.oscillator.reset_bpm(song.bpm);
-            let next_chain = song.tracks[0];
+            self.oscillator.set_swing(song.swing);
+            let next_chain = song.tracks[self.chain_index];
             self.chain_states = default_chain_states();
             self.tracks
                 .iter_mut()
@@ -116,6 +165,13 @@ impl SongPlayback {
 
         self.chain_index += 1;
 
+        // Jump back to the start of the loop once past the end
+        if let Some((start, end)) = self.loop_bounds(self.rom[song].tracks.len()) {
+            if self.chain_index > end {
+                self.chain_index = start;
+            }
+        }
+
         // Song doesn't have any more entries, so we're done
         let next_chain = self.rom[song].tracks.get(self.chain_index);
         if next_chain.is_none() {
@@ -143,3 +199,47 @@ impl SongPlayback {
             .for_each(|track| track.replace_sound_rom_instance(new_rom));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gamercade_audio::{Song, SoundRom};
+
+    use super::*;
+    use crate::{initialize_globals, InstrumentInstance};
+
+    #[test]
+    fn test_loop_region_repeats_rows() {
+        initialize_globals();
+        let rom = SoundRom {
+            songs: vec![Song {
+                tracks: vec![std::array::from_fn(|_| None); 4].into_boxed_slice(),
+                ..Default::default()
+            }]
+            .into_boxed_slice(),
+            ..Default::default()
+        };
+        let rom = Arc::new(SoundRomInstance::new(&rom));
+        let tracks = std::array::from_fn(|_| {
+            ChainPlayback::new(None, &rom, InstrumentInstance::no_sound(48_000))
+        });
+        let mut playback = SongPlayback::new(None, tracks, &rom, 48_000);
+
+        playback.set_loop_region(Some((2, 1)));
+        playback.set_song_id(Some(SongId(0)));
+        assert_eq!(playback.chain_index, 1);
+
+        let rows = (0..4)
+            .map(|_| {
+                playback.next_step();
+                playback.chain_index
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(rows, [2, 1, 2, 1]);
+
+        // Without a loop, the song plays through to the end.
+        playback.set_loop_region(None);
+        assert_eq!(playback.next_step(), TrackerFlow::Advance);
+        assert_eq!(playback.next_step(), TrackerFlow::Advance);
+        assert_eq!(playback.next_step(), TrackerFlow::Finished);
+    }
+}
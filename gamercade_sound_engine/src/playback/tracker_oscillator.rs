@@ -1,4 +1,4 @@
-use gamercade_audio::PHRASE_STEPS_PER_BEAT;
+use gamercade_audio::{PHRASE_STEPS_PER_BEAT, SWING_MAX};
 
 pub(crate) enum TrackerOscillatorFlow {
     Continue,
@@ -10,6 +10,12 @@ pub(crate) struct TrackerOscillator {
     pub(crate) phase: f32,
     pub(crate) increment: f32,
     pub(crate) output_sample_rate: f32,
+
+    /// How far every other step is delayed, as a fraction of a step.
+    pub(crate) swing: f32,
+
+    /// If the next step is one which gets delayed by the swing.
+    pub(crate) swung_step: bool,
 }
 
 impl TrackerOscillator {
@@ -18,6 +24,8 @@ impl TrackerOscillator {
             phase: 0.0,
             increment: 0.0,
             output_sample_rate: output_sample_rate as f32,
+            swing: 0.0,
+            swung_step: true,
         }
     }
 
@@ -28,13 +36,27 @@ impl TrackerOscillator {
 
     pub fn reset_bpm(&mut self, bpm: f32) {
         self.phase = 0.0;
+        self.swung_step = true;
         self.increment =
             ((60.0 / bpm / PHRASE_STEPS_PER_BEAT as f32) * (self.output_sample_rate)).recip();
     }
 
+    pub fn set_swing(&mut self, swing: f32) {
+        self.swing = swing.clamp(0.0, SWING_MAX);
+    }
+
     pub fn tick(&mut self) -> TrackerOscillatorFlow {
-        let output = if self.phase >= 1.0 {
-            self.phase -= 1.0;
+        // Swung steps are held longer, and the steps after them are cut
+        // short by the same amount, so the overall tempo stays the same.
+        let step_length = if self.swung_step {
+            1.0 + self.swing
+        } else {
+            1.0 - self.swing
+        };
+
+        let output = if self.phase >= step_length {
+            self.phase -= step_length;
+            self.swung_step = !self.swung_step;
             TrackerOscillatorFlow::UpdateTracker
         } else {
             TrackerOscillatorFlow::Continue
@@ -44,3 +66,43 @@ impl TrackerOscillator {
         output
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step_lengths(oscillator: &mut TrackerOscillator, steps: usize) -> Vec<usize> {
+        let mut lengths = Vec::new();
+        let mut samples = 0;
+
+        while lengths.len() < steps {
+            samples += 1;
+            if let TrackerOscillatorFlow::UpdateTracker = oscillator.tick() {
+                lengths.push(samples);
+                samples = 0;
+            }
+        }
+
+        lengths
+    }
+
+    #[test]
+    fn test_swing_delays_every_other_step() {
+        let mut straight = TrackerOscillator::new(48_000);
+        straight.reset_bpm(120.0);
+        let straight = step_lengths(&mut straight, 8);
+
+        let mut swung = TrackerOscillator::new(48_000);
+        swung.reset_bpm(120.0);
+        swung.set_swing(0.5);
+        let swung = step_lengths(&mut swung, 8);
+
+        assert!(swung[0] > straight[0]);
+        assert!(swung[1] < straight[1]);
+        assert!(swung[2] > straight[2]);
+
+        // The tempo stays the same over pairs of steps.
+        let difference = straight.iter().sum::<usize>() as i64 - swung.iter().sum::<usize>() as i64;
+        assert!(difference.abs() <= 2);
+    }
+}
@@ -5,20 +5,61 @@ use cpal::{
     traits::{DeviceTrait, HostTrait, StreamTrait},
     Device, SampleFormat, Stream, StreamConfig, SupportedStreamConfig,
 };
-use gamercade_audio::{InstrumentId, PhraseId};
+use gamercade_audio::{
+    clamp_sfx_channels, DelayDefinition, InstrumentId, PhraseId, PhraseVolumeType,
+    DEFAULT_TUNING_REFERENCE, TRANSPOSE_MAX_SEMITONES, TUNING_REFERENCE_MAX, TUNING_REFERENCE_MIN,
+    TUNING_ROOT_NOTE,
+};
+use gamercade_core::FrameRate;
 use rtrb::{Consumer, Producer, RingBuffer};
 
 use crate::{
-    initialize_globals, ChainPlayback, InstrumentInstance, SfxPlayback, SongPlayback,
-    SoundOutputChannels, SoundRomInstance,
+    initialize_globals, ChainPlayback, ChannelMixer, DelayLine, InstrumentInstance, Limiter,
+    MixerChannel, MixerStrip, SfxPlayback, SongPlayback, SoundOutputChannels, SoundRomInstance,
+    VoiceStealPolicy,
 };
-pub use gamercade_audio::{Sfx, SongId, SFX_CHANNELS, SONG_TRACK_CHANNELS};
+pub use gamercade_audio::{Sfx, SongId, SFX_CHANNELS, SFX_CHANNELS_MAX, SONG_TRACK_CHANNELS};
+
+/// How long it takes the master volume to reach a new value,
+/// which avoids zipper noise when it changes.
+const MASTER_VOLUME_FADE_SECONDS: f32 = 0.005;
 
 #[derive(Clone)]
 pub struct SoundEngineData {
     pub bgm: SongPlayback,
-    pub sfx: [SfxPlayback; SFX_CHANNELS],
+    pub sfx: Box<[SfxPlayback]>,
     rom: Arc<SoundRomInstance>,
+    output_sample_rate: usize,
+
+    /// Runtime gains, layered on top of the authored ones.
+    music_gain: f32,
+    sfx_gain: f32,
+
+    /// The shared echo, which the buses send into.
+    delay: DelayLine,
+
+    /// Per channel mutes, solos and volumes.
+    mixer: ChannelMixer,
+
+    /// Shifts every note by semitones, and the frequency A4 is tuned to.
+    transpose: i32,
+    tuning_reference: f32,
+
+    /// How channels are chosen for voices, and the next channel for round robin.
+    voice_steal_policy: VoiceStealPolicy,
+    next_voice_channel: usize,
+
+    /// The master volume, and the value currently being output while fading towards it.
+    master_volume: f32,
+    master_volume_output: f32,
+    master_volume_step: f32,
+
+    /// How often the game advances the sound, which effects such as arpeggios step at.
+    frame_rate: FrameRate,
+
+    /// The samples left over from the last frame, for frame rates which
+    /// don't divide the sample rate evenly.
+    frame_sample_remainder: usize,
 }
 
 pub enum SoundEngineChannelType {
@@ -27,15 +68,16 @@ pub enum SoundEngineChannelType {
     PianoKeyPressed {
         note_index: usize,
         instrument_index: usize,
-        channel: usize,
+        voice: usize,
+        velocity: PhraseVolumeType,
     },
     PianoKeyReleased {
-        channel: usize,
+        voice: usize,
     },
     TriggerNote {
         note_index: usize,
         instrument_index: usize,
-        channel: usize,
+        velocity: PhraseVolumeType,
     },
     UpdateOutputProducer(Option<Producer<SoundOutputChannels>>),
     PlayPhrase {
@@ -46,35 +88,239 @@ pub enum SoundEngineChannelType {
     StopSfx,
     PlayBgm(usize),
     StopBgm,
+    SetBgmLoop(Option<(usize, usize)>),
+    SetPan {
+        channel: usize,
+        pan: f32,
+    },
+    SetMasterVolume(f32),
+    SetLimiterEnabled(bool),
+    SetVoiceStealPolicy(VoiceStealPolicy),
+    SetMixerStrip {
+        channel: MixerChannel,
+        strip: MixerStrip,
+    },
+    SetTranspose(i32),
+    SetTuningReference(f32),
 }
 
 impl SoundEngineData {
-    pub fn new(output_sample_rate: usize, rom: &Arc<SoundRomInstance>) -> Self {
-        use std::array::from_fn;
-
-        let bgm_tracks = from_fn(|_| {
+    /// Creates the engine data, with as many sfx channels as the rom asks for.
+    pub fn new(
+        output_sample_rate: usize,
+        frame_rate: FrameRate,
+        rom: &Arc<SoundRomInstance>,
+    ) -> Self {
+        let bgm_tracks = std::array::from_fn(|_| {
             ChainPlayback::new(None, rom, InstrumentInstance::no_sound(output_sample_rate))
         });
 
         Self {
             bgm: SongPlayback::new(None, bgm_tracks, rom, output_sample_rate),
-            sfx: from_fn(|_| {
-                SfxPlayback::new(
-                    None,
-                    rom,
-                    InstrumentInstance::no_sound(output_sample_rate),
-                    output_sample_rate,
-                )
-            }),
+            sfx: (0..clamp_sfx_channels(rom.sfx_channels))
+                .map(|_| new_sfx_channel(rom, output_sample_rate))
+                .collect(),
             rom: rom.clone(),
+            output_sample_rate,
+            music_gain: 1.0,
+            sfx_gain: 1.0,
+            delay: DelayLine::new(rom.delay, output_sample_rate),
+            mixer: ChannelMixer::default(),
+            transpose: 0,
+            tuning_reference: DEFAULT_TUNING_REFERENCE,
+            voice_steal_policy: VoiceStealPolicy::default(),
+            next_voice_channel: 0,
+            master_volume: 1.0,
+            master_volume_output: 1.0,
+            master_volume_step: 1.0 / (output_sample_rate as f32 * MASTER_VOLUME_FADE_SECONDS),
+            frame_rate,
+            frame_sample_remainder: 0,
         }
     }
 
     pub fn tick(&mut self) -> SoundOutputChannels {
+        let music_gain = self.bgm.gain() * self.rom.bus_gains.music * self.music_gain;
+        let sfx_gain = self.rom.bus_gains.sfx * self.sfx_gain;
+
+        // Applied every tick, since instruments can be replaced at any time.
+        let tuning = self.tuning_ratio();
+        let frame_rate = self.frame_rate;
+        let update = |instrument: &mut InstrumentInstance| {
+            instrument.set_tuning(tuning);
+            instrument.set_frame_rate(frame_rate);
+        };
+        self.sfx
+            .iter_mut()
+            .for_each(|sfx| update(&mut sfx.chain_playback.phrase_playback.instrument));
+        self.bgm
+            .tracks
+            .iter_mut()
+            .for_each(|track| update(&mut track.phrase_playback.instrument));
+
+        // Channels past the channel count are left silent.
+        let sfx_output: [f32; SFX_CHANNELS_MAX] = std::array::from_fn(|index| {
+            self.sfx
+                .get_mut(index)
+                .map(|sfx| sfx.tick() * sfx.gain * sfx_gain * self.mixer.sfx_gain(index))
+                .unwrap_or_default()
+        });
+        let bgm_output = self.bgm.tick();
+        let bgm_output = std::array::from_fn(|index| {
+            bgm_output[index] * music_gain * self.mixer.bgm_gain(index)
+        });
+
+        let delay = self.delay.definition();
+        let sfx_send: f32 = sfx_output
+            .iter()
+            .zip(self.sfx.iter())
+            .map(|(output, sfx)| output * sfx.delay_send)
+            .sum();
+        let delay_input =
+            bgm_output.iter().sum::<f32>() * delay.music_send + sfx_send * delay.sfx_send;
+
         SoundOutputChannels {
-            sfx_output: std::array::from_fn(|index| self.sfx[index].tick()),
-            bgm_output: self.bgm.tick(),
+            sfx_output,
+            sfx_pan: std::array::from_fn(|index| {
+                self.sfx.get(index).map(|sfx| sfx.pan).unwrap_or_default()
+            }),
+            sfx_channels: self.sfx.len(),
+            bgm_output,
+            delay_output: self.delay.tick(delay_input),
+        }
+    }
+
+    /// How many sfx channels are available.
+    pub fn channel_count(&self) -> usize {
+        self.sfx.len()
+    }
+
+    /// Changes how many sfx channels are available, clamped to between one
+    /// and SFX_CHANNELS_MAX. Existing channels keep playing, while any
+    /// removed channels are stopped.
+    pub fn set_channel_count(&mut self, channel_count: usize) {
+        let channel_count = clamp_sfx_channels(channel_count);
+
+        if channel_count == self.sfx.len() {
+            return;
         }
+
+        let mut sfx = std::mem::take(&mut self.sfx).into_vec();
+        sfx.resize_with(channel_count, || {
+            new_sfx_channel(&self.rom, self.output_sample_rate)
+        });
+        self.sfx = sfx.into_boxed_slice();
+    }
+
+    /// Sets the runtime gain of the music bus. Negative or invalid values are ignored.
+    pub fn set_music_gain(&mut self, gain: f32) {
+        if gain.is_finite() && gain >= 0.0 {
+            self.music_gain = gain;
+        }
+    }
+
+    /// Sets the runtime gain of the sfx bus. Negative or invalid values are ignored.
+    pub fn set_sfx_gain(&mut self, gain: f32) {
+        if gain.is_finite() && gain >= 0.0 {
+            self.sfx_gain = gain;
+        }
+    }
+
+    /// Sets the volume of the whole output, from 0.0 (silent) to 1.0 (full).
+    /// Values are clamped to this range, and invalid values are ignored.
+    pub fn set_master_volume(&mut self, volume: f32) {
+        if volume.is_finite() {
+            self.master_volume = volume.clamp(0.0, 1.0);
+        }
+    }
+
+    /// Returns the master volume to apply to the next sample, fading
+    /// towards the set value over a few milliseconds.
+    pub fn tick_master_volume(&mut self) -> f32 {
+        let difference = self.master_volume - self.master_volume_output;
+        self.master_volume_output +=
+            difference.clamp(-self.master_volume_step, self.master_volume_step);
+        self.master_volume_output
+    }
+
+    /// Mixes a tick's output down to the final stereo sample. The master volume is
+    /// applied, followed by the limiter, and the result is clamped so loud mixes don't
+    /// wrap around or clip the device. Shared by the realtime and offline outputs.
+    pub fn mix_output(
+        &mut self,
+        output: &SoundOutputChannels,
+        limiter: &mut Limiter,
+    ) -> (f32, f32) {
+        let scale = self.tick_master_volume() / (self.channel_count() + SONG_TRACK_CHANNELS) as f32;
+        let (left, right) = output.get_stereo_output();
+        let (left, right) = limiter.process(left * scale, right * scale);
+        (left.clamp(-1.0, 1.0), right.clamp(-1.0, 1.0))
+    }
+
+    /// Sets the stereo position of the sfx channel, from -1.0 (left) to 1.0 (right).
+    /// Invalid channels or values are ignored.
+    pub fn set_pan(&mut self, channel: usize, pan: f32) {
+        if let (Some(sfx), true) = (self.sfx.get_mut(channel), pan.is_finite()) {
+            sfx.pan = pan.clamp(-1.0, 1.0);
+        }
+    }
+
+    /// Sets how much of the sfx channel is sent into the delay, on top of
+    /// the sfx bus send. Invalid channels or values are ignored.
+    pub fn set_delay_send(&mut self, channel: usize, send: f32) {
+        if let (Some(sfx), true) = (self.sfx.get_mut(channel), send.is_finite()) {
+            sfx.delay_send = send.clamp(0.0, 1.0);
+        }
+    }
+
+    pub fn frame_rate(&self) -> FrameRate {
+        self.frame_rate
+    }
+
+    pub fn transpose(&self) -> i32 {
+        self.transpose
+    }
+
+    /// Shifts the pitch of every note by steps of the rom's tuning, which are semitones
+    /// unless it has a custom tuning. Clamped to TRANSPOSE_MAX_SEMITONES either way.
+    pub fn set_transpose(&mut self, semitones: i32) {
+        self.transpose = semitones.clamp(-TRANSPOSE_MAX_SEMITONES, TRANSPOSE_MAX_SEMITONES);
+    }
+
+    pub fn tuning_reference(&self) -> f32 {
+        self.tuning_reference
+    }
+
+    /// Sets the frequency of A4 in Hz, which every other note is tuned relative to.
+    /// Values are clamped to the tuning reference range, and invalid values are ignored.
+    pub fn set_tuning_reference(&mut self, frequency: f32) {
+        if frequency.is_finite() {
+            self.tuning_reference = frequency.clamp(TUNING_REFERENCE_MIN, TUNING_REFERENCE_MAX);
+        }
+    }
+
+    /// How much every frequency is multiplied by, from the transpose and tuning reference.
+    /// Scales of ratios are transposed by their intervals from the root of the scale.
+    pub fn tuning_ratio(&self) -> f32 {
+        (self.tuning_reference / DEFAULT_TUNING_REFERENCE)
+            * self.rom.tuning.interval(TUNING_ROOT_NOTE, self.transpose)
+    }
+
+    pub fn mixer(&self) -> &ChannelMixer {
+        &self.mixer
+    }
+
+    /// Mutes, solos or changes the volume of a channel, without changing the rom.
+    pub fn set_mixer_strip(&mut self, channel: MixerChannel, strip: MixerStrip) {
+        self.mixer.set_strip(channel, strip);
+    }
+
+    pub fn delay(&self) -> &DelayDefinition {
+        self.delay.definition()
+    }
+
+    /// Changes the delay parameters, which are clamped to their valid ranges.
+    pub fn set_delay(&mut self, delay: DelayDefinition) {
+        self.delay.set_definition(delay);
     }
 
     /// Sets the Bgm to be played. If None is passed in, bgm will be stopped.
@@ -83,19 +329,32 @@ impl SoundEngineData {
     }
 
     /// Sets the Sfx to be played. If None is passed in, the sfx will be stopped.
+    /// Invalid channels are ignored.
     pub fn play_sfx(&mut self, sfx: Option<Sfx>, channel: usize) {
-        self.sfx[channel].set_sfx_id(sfx);
+        if let Some(playback) = self.sfx.get_mut(channel) {
+            playback.set_sfx_id(sfx);
+        }
     }
 
-    pub fn play_note(&mut self, note: i32, instrument_index: usize, channel: usize) {
+    /// Plays a note until released. The velocity scales the volume
+    /// of the note, the same way as a phrase entry's volume.
+    pub fn play_note(
+        &mut self,
+        note: i32,
+        instrument_index: usize,
+        channel: usize,
+        velocity: PhraseVolumeType,
+    ) {
         let instrument = self.rom[InstrumentId(instrument_index)].as_ref();
         let channel = self.sfx.get_mut(channel);
 
         if let (Some(instrument), Some(channel)) = (&instrument, channel) {
+            channel.start_voice(None);
             let target = &mut channel.chain_playback.phrase_playback.instrument;
             target.update_from_instrument(instrument);
+            target.set_volume(velocity);
             target.set_active(true);
-            target.set_note(note);
+            target.set_note(note, &self.rom.tuning);
         }
     }
 
@@ -109,16 +368,91 @@ impl SoundEngineData {
         }
     }
 
-    pub fn trigger_note(&mut self, note: i32, instrument_index: usize, channel: usize) {
+    pub fn trigger_note(
+        &mut self,
+        note: i32,
+        instrument_index: usize,
+        channel: usize,
+        velocity: PhraseVolumeType,
+    ) {
         let instrument = self.rom[InstrumentId(instrument_index)].as_ref();
         let channel = self.sfx.get_mut(channel);
 
         if let (Some(instrument), Some(channel)) = (&instrument, channel) {
+            channel.start_voice(None);
             let target = &mut channel.chain_playback.phrase_playback.instrument;
             target.update_from_instrument(instrument);
+            target.set_volume(velocity);
             target.trigger();
-            target.set_note(note);
+            target.set_note(note, &self.rom.tuning);
+        }
+    }
+
+    pub fn set_voice_steal_policy(&mut self, policy: VoiceStealPolicy) {
+        self.voice_steal_policy = policy;
+    }
+
+    /// Picks a channel for a new voice. Free channels are used first, and once
+    /// every channel is busy, one is stolen according to the policy.
+    pub fn allocate_voice_channel(&mut self) -> usize {
+        let channel = match self.voice_steal_policy {
+            VoiceStealPolicy::RoundRobin => self.next_voice_channel % self.sfx.len(),
+            VoiceStealPolicy::Oldest => self.find_voice_channel(|sfx| sfx.voice_age as f32),
+            VoiceStealPolicy::Quietest => self.find_voice_channel(|sfx| -sfx.level),
+        };
+
+        self.next_voice_channel = (channel + 1) % self.sfx.len();
+        channel
+    }
+
+    /// Returns the first free channel, or the one with the highest score.
+    fn find_voice_channel(&self, score: impl Fn(&SfxPlayback) -> f32) -> usize {
+        if let Some(channel) = self.sfx.iter().position(SfxPlayback::is_free) {
+            return channel;
         }
+
+        self.sfx
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| score(a).total_cmp(&score(b)))
+            .map(|(channel, _)| channel)
+            .unwrap_or_default()
+    }
+
+    /// Plays a note on a newly allocated channel until it is released with
+    /// the same voice id. Returns the channel which was used.
+    pub fn play_voice(
+        &mut self,
+        note: i32,
+        instrument_index: usize,
+        voice: usize,
+        velocity: PhraseVolumeType,
+    ) -> usize {
+        let channel = self.allocate_voice_channel();
+        self.play_note(note, instrument_index, channel, velocity);
+        self.sfx[channel].voice = Some(voice);
+        channel
+    }
+
+    /// Releases the voice, if it's still playing. Voices which
+    /// were stolen by another note are left alone.
+    pub fn release_voice(&mut self, voice: usize) {
+        if let Some(channel) = self.sfx.iter().position(|sfx| sfx.voice == Some(voice)) {
+            self.sfx[channel].voice = None;
+            self.set_key_active(false, channel);
+        }
+    }
+
+    /// Triggers a note on a newly allocated channel. Returns the channel which was used.
+    pub fn trigger_voice(
+        &mut self,
+        note: i32,
+        instrument_index: usize,
+        velocity: PhraseVolumeType,
+    ) -> usize {
+        let channel = self.allocate_voice_channel();
+        self.trigger_note(note, instrument_index, channel, velocity);
+        channel
     }
 
     pub fn play_frequency(&mut self, frequency: f32, instrument_index: usize, channel: usize) {
@@ -133,28 +467,54 @@ impl SoundEngineData {
         }
     }
 
+    /// Advances by a single frame. The leftover samples are carried over to the next one,
+    /// so every second of frames generates exactly a second of samples instead of drifting.
+    pub(crate) fn fast_forward_frame(&mut self) {
+        let samples = self.samples_in_next_frame();
+        self.fast_forward(samples);
+    }
+
+    fn samples_in_next_frame(&mut self) -> usize {
+        let frames_per_second = self.frame_rate.frames_per_second();
+        let samples = self.output_sample_rate + self.frame_sample_remainder;
+        self.frame_sample_remainder = samples % frames_per_second;
+        samples / frames_per_second
+    }
+
     pub(crate) fn fast_forward(&mut self, frames: usize) {
         (0..frames).for_each(|_| {
             self.bgm.tick();
             self.sfx.iter_mut().for_each(|sfx| {
                 sfx.tick();
             });
+            self.tick_master_volume();
         });
     }
 
     pub fn replace_sound_rom_instance(&mut self, new_rom: &Arc<SoundRomInstance>) {
         self.rom = new_rom.clone();
+        self.delay.set_definition(new_rom.delay);
 
         self.bgm.replace_sound_rom_instance(new_rom);
         self.sfx
             .iter_mut()
             .for_each(|sfx| sfx.replace_sound_rom_instance(new_rom));
+        self.set_channel_count(new_rom.sfx_channels);
     }
 }
 
+fn new_sfx_channel(rom: &Arc<SoundRomInstance>, output_sample_rate: usize) -> SfxPlayback {
+    SfxPlayback::new(
+        None,
+        rom,
+        InstrumentInstance::no_sound(output_sample_rate),
+        output_sample_rate,
+    )
+}
+
 pub struct SoundEngine {
     _stream: Stream,
-    sound_frames_per_render_frame: usize,
+    frame_rate: FrameRate,
     sound_thread_producer: Producer<SoundEngineChannelType>,
     output_sample_rate: usize,
 }
@@ -164,7 +524,16 @@ impl SoundEngine {
         self.output_sample_rate
     }
 
-    pub fn new(fps: usize, rom: &Arc<SoundRomInstance>, message_buffer_size: usize) -> Self {
+    /// How often the game advances the sound, once per frame.
+    pub fn frame_rate(&self) -> FrameRate {
+        self.frame_rate
+    }
+
+    pub fn new(
+        frame_rate: FrameRate,
+        rom: &Arc<SoundRomInstance>,
+        message_buffer_size: usize,
+    ) -> Self {
         initialize_globals();
         let mut device = default_host().default_output_device().unwrap();
 
@@ -172,6 +541,7 @@ impl SoundEngine {
         let output_sample_rate = supported_config.sample_rate().0 as usize;
 
         let (stream, producer) = SoundEngineRunner::initialize_stream(
+            frame_rate,
             rom,
             &mut device,
             supported_config,
@@ -181,7 +551,7 @@ impl SoundEngine {
         stream.play().unwrap();
 
         Self {
-            sound_frames_per_render_frame: output_sample_rate / fps,
+            frame_rate,
             output_sample_rate,
             _stream: stream,
             sound_thread_producer: producer,
@@ -191,7 +561,7 @@ impl SoundEngine {
     /// Fast-forwards the the SoundEngineData by generating one frame worth samples
     /// This keeps it somewhat in sync with the audio that's actually being played
     pub fn fast_forward(&mut self, data: &mut SoundEngineData) {
-        data.fast_forward(self.sound_frames_per_render_frame);
+        data.fast_forward_frame();
     }
 
     pub fn sync_audio_thread(&mut self, data: &SoundEngineData) {
@@ -205,6 +575,12 @@ impl SoundEngine {
     pub fn send(&mut self, message: SoundEngineChannelType) {
         self.sound_thread_producer.push(message).unwrap();
     }
+
+    /// Enables or disables the output limiter. This only affects what
+    /// is heard, not the state of the SoundEngineData.
+    pub fn set_limiter_enabled(&mut self, enabled: bool) {
+        self.send(SoundEngineChannelType::SetLimiterEnabled(enabled))
+    }
 }
 
 struct SoundEngineRunner {
@@ -213,10 +589,12 @@ struct SoundEngineRunner {
     consumer: Consumer<SoundEngineChannelType>,
     data: SoundEngineData,
     sound_output_producer: Option<Producer<SoundOutputChannels>>,
+    limiter: Limiter,
 }
 
 impl SoundEngineRunner {
     fn initialize_stream(
+        frame_rate: FrameRate,
         rom: &Arc<SoundRomInstance>,
         device: &mut Device,
         config: SupportedStreamConfig,
@@ -230,7 +608,7 @@ impl SoundEngineRunner {
         println!("Output Sample Rate: {}", output_sample_rate);
         println!("Output channels: {}", channels);
 
-        let data = SoundEngineData::new(output_sample_rate, rom);
+        let data = SoundEngineData::new(output_sample_rate, frame_rate, rom);
 
         (
             Self {
@@ -239,6 +617,7 @@ impl SoundEngineRunner {
                 consumer,
                 data,
                 sound_output_producer: None,
+                limiter: Limiter::new(output_sample_rate),
             }
             .build_stream(device, config),
             producer,
@@ -299,16 +678,21 @@ impl SoundEngineRunner {
                         SoundEngineChannelType::PianoKeyPressed {
                             note_index,
                             instrument_index,
-                            channel,
-                        } => data.play_note(note_index as i32, instrument_index, channel),
-                        SoundEngineChannelType::PianoKeyReleased { channel } => {
-                            data.set_key_active(false, channel)
+                            voice,
+                            velocity,
+                        } => {
+                            data.play_voice(note_index as i32, instrument_index, voice, velocity);
+                        }
+                        SoundEngineChannelType::PianoKeyReleased { voice } => {
+                            data.release_voice(voice)
                         }
                         SoundEngineChannelType::TriggerNote {
                             note_index,
                             instrument_index,
-                            channel,
-                        } => data.trigger_note(note_index as i32, instrument_index, channel),
+                            velocity,
+                        } => {
+                            data.trigger_voice(note_index as i32, instrument_index, velocity);
+                        }
                         SoundEngineChannelType::UpdateOutputProducer(new_producer) => {
                             self.sound_output_producer = new_producer
                         }
@@ -340,6 +724,30 @@ impl SoundEngineRunner {
                             data.play_bgm(Some(SongId(bgm)));
                         }
                         SoundEngineChannelType::StopBgm => data.play_bgm(None),
+                        SoundEngineChannelType::SetBgmLoop(region) => {
+                            data.bgm.set_loop_region(region)
+                        }
+                        SoundEngineChannelType::SetPan { channel, pan } => {
+                            data.set_pan(channel, pan)
+                        }
+                        SoundEngineChannelType::SetMasterVolume(volume) => {
+                            data.set_master_volume(volume)
+                        }
+                        SoundEngineChannelType::SetLimiterEnabled(enabled) => {
+                            self.limiter.enabled = enabled
+                        }
+                        SoundEngineChannelType::SetVoiceStealPolicy(policy) => {
+                            data.set_voice_steal_policy(policy)
+                        }
+                        SoundEngineChannelType::SetMixerStrip { channel, strip } => {
+                            data.set_mixer_strip(channel, strip)
+                        }
+                        SoundEngineChannelType::SetTranspose(semitones) => {
+                            data.set_transpose(semitones)
+                        }
+                        SoundEngineChannelType::SetTuningReference(frequency) => {
+                            data.set_tuning_reference(frequency)
+                        }
                     };
                 }
 
@@ -351,16 +759,312 @@ impl SoundEngineRunner {
                     }
                 }
 
-                let bgm_frame = output.get_bgm_output();
-                let sfx_frame = output.get_sfx_output();
-                let output = (bgm_frame + sfx_frame) / (SFX_CHANNELS + SONG_TRACK_CHANNELS) as f32;
+                let (left, right) = data.mix_output(&output, &mut self.limiter);
 
-                frame.iter_mut().for_each(|channel| {
-                    *channel = cpal::Sample::from::<f32>(&output);
-                });
+                // Mono devices get both sides mixed down, any channels
+                // past the first two are left silent.
+                match frame {
+                    [mono] => *mono = cpal::Sample::from::<f32>(&((left + right) * 0.5)),
+                    [left_out, right_out, rest @ ..] => {
+                        *left_out = cpal::Sample::from::<f32>(&left);
+                        *right_out = cpal::Sample::from::<f32>(&right);
+                        rest.iter_mut()
+                            .for_each(|channel| *channel = cpal::Sample::from::<f32>(&0.0));
+                    }
+                    [] => (),
+                }
             });
 
             buffer_written = true;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gamercade_audio::{Chain, ChainId, Song, SoundRom, Tuning, DEFAULT_BPM};
+
+    use super::*;
+    use crate::STEAL_FADE_SECONDS;
+
+    fn render_sfx(rom: &Arc<SoundRomInstance>, gain: f32) -> Vec<f32> {
+        let mut data = SoundEngineData::new(48_000, FrameRate::default(), rom);
+        data.set_sfx_gain(gain);
+        data.play_sfx(
+            Some(Sfx {
+                bpm: DEFAULT_BPM,
+                ..Default::default()
+            }),
+            0,
+        );
+
+        (0..4_800).map(|_| data.tick().get_sfx_output()).collect()
+    }
+
+    #[test]
+    fn test_sfx_gain_ratio() {
+        initialize_globals();
+        let mut chain = Chain::default();
+        chain.entries[0] = Some(PhraseId(0));
+
+        let rom = SoundRom {
+            chains: vec![Some(chain)].into_boxed_slice(),
+            ..Default::default()
+        };
+        let rom = Arc::new(SoundRomInstance::new(&rom));
+
+        let full = render_sfx(&rom, 1.0);
+        let half = render_sfx(&rom, 0.5);
+
+        assert!(full.iter().any(|sample| *sample != 0.0));
+        full.iter().zip(half.iter()).for_each(|(full, half)| {
+            assert_eq!(*full * 0.5, *half);
+        });
+    }
+
+    #[test]
+    fn test_song_gain_ratio() {
+        initialize_globals();
+        let mut chain = Chain::default();
+        chain.entries[0] = Some(PhraseId(0));
+
+        let mut tracks = [None; SONG_TRACK_CHANNELS];
+        tracks[0] = Some(ChainId(0));
+        let song = Song {
+            tracks: vec![tracks].into_boxed_slice(),
+            gain: 0.5,
+            ..Default::default()
+        };
+
+        let rom = SoundRom {
+            chains: vec![Some(chain)].into_boxed_slice(),
+            songs: vec![song].into_boxed_slice(),
+            ..Default::default()
+        };
+        let rom = Arc::new(SoundRomInstance::new(&rom));
+
+        // The same chain played as a song and as an sfx, so
+        // only the song's gain sets them apart.
+        let mut data = SoundEngineData::new(48_000, FrameRate::default(), &rom);
+        data.play_bgm(Some(SongId(0)));
+        data.play_sfx(Some(Sfx::default()), 0);
+
+        let output = (0..4_800).map(|_| data.tick()).collect::<Vec<_>>();
+        assert!(output.iter().any(|output| output.get_sfx_output() != 0.0));
+        output.iter().for_each(|output| {
+            assert_eq!(output.get_sfx_output() * 0.5, output.get_bgm_output());
+        });
+    }
+
+    #[test]
+    fn test_frames_dont_drift_from_sample_rate() {
+        initialize_globals();
+        let rom = Arc::new(SoundRomInstance::new(&SoundRom::default()));
+        let mut data = SoundEngineData::new(44_100, FrameRate::SuperFast, &rom);
+
+        // 44100 / 240 = 183.75 samples per frame
+        let samples = (0..240)
+            .map(|_| data.samples_in_next_frame())
+            .collect::<Vec<_>>();
+
+        assert_eq!(samples.iter().sum::<usize>(), 44_100);
+        assert!(samples.iter().all(|samples| (183..=184).contains(samples)));
+    }
+
+    #[test]
+    fn test_master_volume_fades() {
+        initialize_globals();
+        let rom = Arc::new(SoundRomInstance::new(&SoundRom::default()));
+        let mut data = SoundEngineData::new(48_000, FrameRate::default(), &rom);
+        let fade_samples = (48_000.0 * MASTER_VOLUME_FADE_SECONDS) as usize;
+
+        data.set_master_volume(f32::NAN);
+        assert_eq!(data.tick_master_volume(), 1.0);
+
+        data.set_master_volume(-1.0);
+        let fade = (0..fade_samples)
+            .map(|_| data.tick_master_volume())
+            .collect::<Vec<_>>();
+
+        assert!(fade[0] > 0.99);
+        assert!(fade.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(data.tick_master_volume().abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_note_velocity_scales_output() {
+        initialize_globals();
+        let rom = Arc::new(SoundRomInstance::new(&SoundRom::default()));
+
+        let render = |velocity| {
+            let mut data = SoundEngineData::new(48_000, FrameRate::default(), &rom);
+            data.play_note(48, 0, 0, velocity);
+            (0..4_800)
+                .map(|_| data.tick().get_sfx_output())
+                .collect::<Vec<_>>()
+        };
+
+        let full = render(PhraseVolumeType::MAX);
+        let quiet = render(PhraseVolumeType::MAX / 2);
+
+        assert!(full.iter().any(|sample| *sample != 0.0));
+        full.iter().zip(quiet.iter()).for_each(|(full, quiet)| {
+            assert!(quiet.abs() <= full.abs());
+        });
+        assert!(quiet.iter().map(|x| x.abs()).sum::<f32>() < full.iter().map(|x| x.abs()).sum());
+    }
+
+    #[test]
+    fn test_transpose_retunes_notes() {
+        initialize_globals();
+        let rom = Arc::new(SoundRomInstance::new(&SoundRom::default()));
+
+        let render = |note, transpose| {
+            let mut data = SoundEngineData::new(48_000, FrameRate::default(), &rom);
+            data.set_transpose(transpose);
+            data.play_note(note, 0, 0, PhraseVolumeType::MAX);
+            (0..4_800)
+                .map(|_| data.tick().get_sfx_output())
+                .collect::<Vec<_>>()
+        };
+
+        let octave_up = render(36, 12);
+        assert!(octave_up
+            .iter()
+            .zip(render(48, 0).iter())
+            .all(|(a, b)| (a - b).abs() < 1e-3));
+        assert_ne!(octave_up, render(36, 0));
+    }
+
+    #[test]
+    fn test_transpose_follows_tuning() {
+        let rom = Arc::new(SoundRomInstance::new(&SoundRom {
+            tuning: Tuning::EqualDivision(19),
+            ..Default::default()
+        }));
+        let mut data = SoundEngineData::new(48_000, FrameRate::default(), &rom);
+
+        data.set_transpose(19);
+        assert!((data.tuning_ratio() - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_tuning_is_clamped() {
+        let rom = Arc::new(SoundRomInstance::new(&SoundRom::default()));
+        let mut data = SoundEngineData::new(48_000, FrameRate::default(), &rom);
+        assert_eq!(data.tuning_ratio(), 1.0);
+
+        data.set_tuning_reference(432.0);
+        assert_eq!(data.tuning_ratio(), 432.0 / 440.0);
+
+        data.set_tuning_reference(f32::NAN);
+        assert_eq!(data.tuning_reference(), 432.0);
+        data.set_tuning_reference(1.0);
+        assert_eq!(data.tuning_reference(), TUNING_REFERENCE_MIN);
+
+        data.set_transpose(i32::MAX);
+        assert_eq!(data.transpose(), TRANSPOSE_MAX_SEMITONES);
+    }
+
+    #[test]
+    fn test_channel_count_follows_rom() {
+        initialize_globals();
+        let rom = Arc::new(SoundRomInstance::new(&SoundRom {
+            sfx_channels: 4,
+            ..Default::default()
+        }));
+        let mut data = SoundEngineData::new(48_000, FrameRate::default(), &rom);
+        assert_eq!(data.channel_count(), 4);
+
+        // Channels past the count are ignored.
+        data.play_sfx(Some(Sfx::default()), 4);
+        assert_eq!(data.tick().sfx_channels, 4);
+
+        let rom = Arc::new(SoundRomInstance::new(&SoundRom {
+            sfx_channels: SFX_CHANNELS_MAX + 1,
+            ..Default::default()
+        }));
+        data.replace_sound_rom_instance(&rom);
+        assert_eq!(data.channel_count(), SFX_CHANNELS_MAX);
+
+        data.set_channel_count(0);
+        assert_eq!(data.channel_count(), 1);
+    }
+
+    #[test]
+    fn test_voice_stealing() {
+        initialize_globals();
+        let rom = Arc::new(SoundRomInstance::new(&SoundRom {
+            sfx_channels: 2,
+            ..Default::default()
+        }));
+        let mut data = SoundEngineData::new(48_000, FrameRate::default(), &rom);
+        data.set_voice_steal_policy(VoiceStealPolicy::Oldest);
+
+        assert_eq!(data.play_voice(48, 0, 0, PhraseVolumeType::MAX), 0);
+        (0..100).for_each(|_| {
+            data.tick();
+        });
+        assert_eq!(data.play_voice(50, 0, 1, PhraseVolumeType::MAX), 1);
+        (0..100).for_each(|_| {
+            data.tick();
+        });
+
+        // Every channel is busy, so the oldest voice is stolen.
+        assert_eq!(data.play_voice(52, 0, 2, PhraseVolumeType::MAX), 0);
+
+        // Releasing the stolen voice doesn't cut off the new one.
+        data.release_voice(0);
+        assert_eq!(data.sfx[0].voice, Some(2));
+        data.release_voice(2);
+        assert_eq!(data.sfx[0].voice, None);
+
+        // Round robin ignores what is playing.
+        data.set_voice_steal_policy(VoiceStealPolicy::RoundRobin);
+        assert_eq!(data.play_voice(48, 0, 3, PhraseVolumeType::MAX), 1);
+        assert_eq!(data.play_voice(48, 0, 4, PhraseVolumeType::MAX), 0);
+    }
+
+    #[test]
+    fn test_stolen_voice_fades_out() {
+        initialize_globals();
+        let rom = Arc::new(SoundRomInstance::new(&SoundRom {
+            sfx_channels: 1,
+            ..Default::default()
+        }));
+        let mut data = SoundEngineData::new(48_000, FrameRate::default(), &rom);
+        data.set_voice_steal_policy(VoiceStealPolicy::Quietest);
+        let mut reference = data.clone();
+
+        [&mut data, &mut reference].into_iter().for_each(|data| {
+            data.play_voice(84, 0, 0, PhraseVolumeType::MAX);
+            (0..4_800).for_each(|_| {
+                data.tick();
+            });
+        });
+
+        // A silent note steals the voice, so only the old one is heard
+        data.play_voice(60, 0, 1, 0);
+        let fade_samples = (48_000.0 * STEAL_FADE_SECONDS) as usize;
+        let faded = (0..fade_samples)
+            .map(|_| data.tick().get_sfx_output())
+            .collect::<Vec<_>>();
+        let unfaded = (0..fade_samples)
+            .map(|_| reference.tick().get_sfx_output())
+            .collect::<Vec<_>>();
+
+        // The old voice keeps playing as it fades, rather than fading a frozen sample.
+        faded
+            .iter()
+            .zip(unfaded.iter())
+            .enumerate()
+            .for_each(|(index, (faded, unfaded))| {
+                let fade = 1.0 - (index + 1) as f32 / fade_samples as f32;
+                assert!((faded - unfaded * fade).abs() < 1e-3);
+            });
+        assert!(unfaded
+            .iter()
+            .any(|sample| sample.signum() != unfaded[0].signum()));
+        assert!(faded.last().unwrap().abs() < 1e-3);
+    }
+}
@@ -1,9 +1,14 @@
-use gamercade_audio::{SFX_CHANNELS, SONG_TRACK_CHANNELS};
+use gamercade_audio::{SFX_CHANNELS_MAX, SONG_TRACK_CHANNELS};
 
 #[derive(Clone)]
 pub struct SoundOutputChannels {
-    pub sfx_output: [f32; SFX_CHANNELS],
+    /// Sized for the most channels a rom can have, so this never allocates
+    /// on the audio thread. Only the first sfx_channels entries are used.
+    pub sfx_output: [f32; SFX_CHANNELS_MAX],
+    pub sfx_pan: [f32; SFX_CHANNELS_MAX],
+    pub sfx_channels: usize,
     pub bgm_output: [f32; SONG_TRACK_CHANNELS],
+    pub delay_output: f32,
 }
 
 impl SoundOutputChannels {
@@ -14,4 +19,49 @@ impl SoundOutputChannels {
     pub fn get_bgm_output(&self) -> f32 {
         self.bgm_output.iter().sum()
     }
+
+    /// Mixes all channels into a left and right output. Bgm and the delay are
+    /// centered, while each sfx channel is placed according to its pan.
+    pub fn get_stereo_output(&self) -> (f32, f32) {
+        let bgm = self.get_bgm_output() + self.delay_output;
+
+        self.sfx_output.iter().zip(self.sfx_pan.iter()).fold(
+            (bgm, bgm),
+            |(left, right), (output, pan)| {
+                let (left_gain, right_gain) = pan_gains(*pan);
+                (left + output * left_gain, right + output * right_gain)
+            },
+        )
+    }
+}
+
+/// Returns the left and right gains for the pan. A centered pan
+/// plays at full volume on both sides, matching mono output.
+fn pan_gains(pan: f32) -> (f32, f32) {
+    ((1.0 - pan).min(1.0), (1.0 + pan).min(1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stereo_output() {
+        let mut output = SoundOutputChannels {
+            sfx_output: [0.0; SFX_CHANNELS_MAX],
+            sfx_pan: [0.0; SFX_CHANNELS_MAX],
+            sfx_channels: 2,
+            bgm_output: [0.0; SONG_TRACK_CHANNELS],
+            delay_output: 0.0,
+        };
+        output.sfx_output[0] = 1.0;
+        output.sfx_output[1] = 0.5;
+        output.sfx_pan[1] = -1.0;
+        output.bgm_output[0] = 0.25;
+
+        assert_eq!(output.get_stereo_output(), (1.75, 1.25));
+
+        output.sfx_pan[0] = 0.5;
+        assert_eq!(output.get_stereo_output(), (1.25, 1.25));
+    }
 }
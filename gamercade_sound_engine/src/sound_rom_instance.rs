@@ -1,11 +1,12 @@
 use std::{ops::Index, sync::Arc};
 
 use gamercade_audio::{
-    Chain, ChainId, InstrumentDataDefinition, InstrumentId, PatchDefinition, Phrase, PhraseId,
-    SampleDefinition, Song, SoundRom,
+    clamp_sfx_channels, BusGains, Chain, ChainId, DelayDefinition, InstrumentDataDefinition,
+    InstrumentId, PatchDefinition, Phrase, PhraseId, SampleDefinition, Song, SoundRom, Tuning,
+    WavetableDefinition,
 };
 
-use crate::{Sfx, SongId, WavetableDefinition};
+use crate::{Sfx, SongId};
 
 /// An engine loaded in memory, ready to use.
 #[derive(Debug)]
@@ -15,6 +16,10 @@ pub struct SoundRomInstance {
     pub phrases: Box<[Option<Phrase>]>,
     pub instrument_bank: Box<[Option<InstrumentDefinition>]>,
     pub sfx: Box<[Sfx]>,
+    pub bus_gains: BusGains,
+    pub delay: DelayDefinition,
+    pub sfx_channels: usize,
+    pub tuning: Tuning,
 }
 
 /// An instrument stored in memory, ready to generate the pieces
@@ -68,6 +73,10 @@ impl SoundRomInstance {
                 .collect::<Vec<_>>()
                 .into_boxed_slice(),
             sfx: rom.sfx.clone(),
+            bus_gains: rom.bus_gains,
+            delay: rom.delay,
+            sfx_channels: clamp_sfx_channels(rom.sfx_channels),
+            tuning: rom.tuning.clone(),
         }
     }
 }
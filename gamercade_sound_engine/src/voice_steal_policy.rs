@@ -0,0 +1,17 @@
+/// How a channel is chosen when a note is played without one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VoiceStealPolicy {
+    /// Cycles through the channels, regardless of what is playing on them.
+    RoundRobin,
+    /// Uses a free channel if there is one, otherwise
+    /// steals the voice which started the longest time ago.
+    #[default]
+    Oldest,
+    /// Uses a free channel if there is one, otherwise
+    /// steals the voice which is currently the quietest.
+    Quietest,
+}
+
+impl VoiceStealPolicy {
+    pub const ALL: [Self; 3] = [Self::Oldest, Self::Quietest, Self::RoundRobin];
+}